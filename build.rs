@@ -0,0 +1,50 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    let git_commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|stdout| stdout.trim().to_string())
+        .unwrap_or_else(|| "unknown".into());
+
+    let build_timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let features = env::vars()
+        .filter_map(|(key, _)| {
+            key.strip_prefix("CARGO_FEATURE_")
+                .map(|feature| feature.to_lowercase().replace('_', "-"))
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+
+    println!("cargo:rustc-env=OWLGO_GIT_COMMIT={}", git_commit);
+    println!("cargo:rustc-env=OWLGO_BUILD_TIMESTAMP={}", build_timestamp);
+    println!("cargo:rustc-env=OWLGO_FEATURES={}", features);
+
+    // a commit moves the branch HEAD points to (or, once packed, packed-refs), not HEAD itself
+    let mut git_files = vec![".git/HEAD".to_string(), ".git/packed-refs".to_string()];
+
+    if let Some(head_ref) = fs::read_to_string(".git/HEAD")
+        .ok()
+        .and_then(|head| head.trim().strip_prefix("ref: ").map(str::to_string))
+    {
+        git_files.push(format!(".git/{}", head_ref));
+    }
+
+    for git_file in git_files
+        .iter()
+        .filter(|git_file| Path::new(git_file).exists())
+    {
+        println!("cargo:rerun-if-changed={}", git_file);
+    }
+}
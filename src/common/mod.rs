@@ -1,3 +1,5 @@
 pub mod owl_error;
+pub mod owl_warning;
 
 pub use owl_error::{OwlError, Result};
+pub use owl_warning::{OwlWarning, take_warnings, warn};
@@ -1,17 +1,26 @@
 use std::fmt;
 
+/// Result type used throughout owlgo.
 pub type Result<T> = std::result::Result<T, OwlError>;
 
+/// Errors raised by owlgo, each carrying a message and (for most variants) extra detail.
 #[derive(Debug)]
 pub enum OwlError {
     CommandNotFound(String),
     FileError(String, String),
+    Interrupted(String),
     LlmError(String, String),
+    NeedsInput(String),
     NetworkError(String, String),
     ProcessError(String, String),
+    StrictMode(String),
     TestFailure(String),
+    /// A run killed once it went past its deadline.
+    TimedOut(String),
     TomlError(String, String),
     TuiError(String, String),
+    /// A name that could not be resolved, with each step that was tried and a hint.
+    Unresolved(String, Vec<String>, String),
     Unsupported(String),
     UriError(String, String),
 }
@@ -26,6 +35,16 @@ macro_rules! check_info {
     };
 }
 
+impl OwlError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            OwlError::Interrupted(_) => 130,
+            OwlError::NeedsInput(_) => 3,
+            _ => 1,
+        }
+    }
+}
+
 impl fmt::Display for OwlError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -33,22 +52,35 @@ impl fmt::Display for OwlError {
             OwlError::FileError(expr, err_info) => {
                 write!(f, "{} (info: {})", expr, check_info!(err_info))
             }
+            OwlError::Interrupted(expr) => write!(f, "{}", expr),
             OwlError::LlmError(expr, err_info) => {
                 write!(f, "{} (info: {})", expr, check_info!(err_info))
             }
+            OwlError::NeedsInput(expr) => write!(f, "{}", expr),
             OwlError::NetworkError(expr, err_info) => {
                 write!(f, "{} (info: {})", expr, check_info!(err_info))
             }
             OwlError::ProcessError(expr, err_info) => {
                 write!(f, "{} (info: {})", expr, check_info!(err_info))
             }
+            OwlError::StrictMode(expr) => write!(f, "{}", expr),
             OwlError::TestFailure(expr) => write!(f, "{}", expr),
+            OwlError::TimedOut(expr) => write!(f, "{}", expr),
             OwlError::TomlError(expr, err_info) => {
                 write!(f, "{} (info: {})", expr, check_info!(err_info))
             }
             OwlError::TuiError(expr, err_info) => {
                 write!(f, "{} (info: {})", expr, check_info!(err_info))
             }
+            OwlError::Unresolved(expr, tried, hint) => {
+                write!(f, "{}", expr)?;
+
+                for step in tried {
+                    write!(f, "\n  - {}", step)?;
+                }
+
+                write!(f, "\n  (hint: {})", check_info!(hint))
+            }
             OwlError::Unsupported(expr) => write!(f, "{}", expr),
             OwlError::UriError(expr, err_info) => {
                 write!(f, "{} (info: {})", expr, check_info!(err_info))
@@ -0,0 +1,45 @@
+use std::fmt;
+use std::sync::Mutex;
+
+static WARNINGS: Mutex<Vec<OwlWarning>> = Mutex::new(Vec::new());
+
+/// Non-fatal issues collected while a command runs and reported once it finishes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OwlWarning {
+    HookFailed(String),
+    IgnoredFile(String),
+    MissingFeedback(String),
+    Recovered(String),
+    Skipped(String),
+    ToolchainChanged(String),
+    UnknownKey(String, String),
+    UnmatchedCase(String),
+}
+
+impl fmt::Display for OwlWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OwlWarning::HookFailed(expr) => write!(f, "{}", expr),
+            OwlWarning::IgnoredFile(file) => write!(f, "'{}': ignored by quests", file),
+            OwlWarning::MissingFeedback(file) => write!(f, "'{}': no feedback file", file),
+            OwlWarning::Recovered(expr) => write!(f, "{}", expr),
+            OwlWarning::Skipped(expr) => write!(f, "{}", expr),
+            OwlWarning::ToolchainChanged(expr) => write!(f, "{}", expr),
+            OwlWarning::UnknownKey(key, path) => write!(f, "'{}': unknown key in '{}'", key, path),
+            OwlWarning::UnmatchedCase(expr) => write!(f, "{}", expr),
+        }
+    }
+}
+
+/// Records a warning, ignoring exact duplicates.
+pub fn warn(warning: OwlWarning) {
+    let mut warnings = WARNINGS.lock().expect("warnings lock");
+
+    if !warnings.contains(&warning) {
+        warnings.push(warning);
+    }
+}
+
+pub fn take_warnings() -> Vec<OwlWarning> {
+    std::mem::take(&mut *WARNINGS.lock().expect("warnings lock"))
+}
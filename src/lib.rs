@@ -0,0 +1,64 @@
+//! Core library behind the `owlgo` CLI.
+//!
+//! Quest running, the manifest, language toolchains, and stash operations live here so that
+//! editor plugins and graders can embed them without spawning the CLI.
+
+pub mod common;
+pub mod owl_core;
+pub mod owl_utils;
+
+pub const ATTEST_KEY: &str = ".attest_key";
+pub const BUILD_CACHE: &str = ".last_build.log";
+pub const CACHE_DIR: &str = ".cache";
+pub const CHAT_DIR: &str = ".chat";
+pub const EXT_DIR: &str = ".ext";
+pub const EXT_MANIFEST: &str = "manifest.toml";
+pub const GIST_API_URL: &str = "https://api.github.com/gists";
+pub const GIT_DIR: &str = ".git";
+pub const HIDDEN_DIR: &str = "hidden";
+pub const HISTORY_DIR: &str = ".history";
+pub const LAST_FAILURE: &str = "last_failure.md";
+pub const LAST_RUN: &str = "last_run.toml";
+pub const LAST_RUN_DIR: &str = ".last_run";
+pub const MANIFEST: &str = ".manifest.toml";
+pub const MANIFEST_HEAD_URL: &str = "https://gist.githubusercontent.com/latenitecoding/84c043f4c9092998773640a2202f2d36/raw/owl_manifest_short";
+pub const MANIFEST_URL: &str = "https://gist.githubusercontent.com/latenitecoding/b6fdd8656c0b6a60795581f84d0f2fa4/raw/owlgo_manifest";
+pub const MERGED_DIR: &str = ".merged";
+pub const OWL_DIR: &str = ".owlgo";
+pub const POOL_DIR: &str = ".pool";
+pub const PROGRESS: &str = ".progress.toml";
+pub const PROJECT_CONFIG: &str = ".owlgo.toml";
+pub const PROMPT_DIR: &str = ".prompt";
+pub const PROMPT_FILE: &str = ".prompt.md";
+pub const QUEST_META: &str = ".quest.toml";
+pub const REMOTE_HEADER: &str = "remote_header.toml";
+pub const ROSTER_DIR: &str = "roster";
+pub const SCRIPT_DIR: &str = "scripts";
+pub const SERVE_PORT: &str = "7878";
+pub const TEMPLATE_STEM: &str = ".template";
+pub const TMP_ARCHIVE: &str = ".tmp.archive";
+pub const TODOS_SUFFIX: &str = ".todos.md";
+pub const STASH_DIR: &str = ".stash";
+
+// it must be that [manifest] is at the top
+pub const TOML_TEMPLATE: &str = r#"
+[manifest]
+version = "0.1.7"
+timestamp = "0.0.0"
+ai_sdk = "claude"
+api_key = ""
+
+[extensions]
+
+[ext_uri]
+
+[ext_keys]
+
+[personal_prompts]
+
+[personal_quests]
+
+[prompts]
+
+[quests]
+"#;
@@ -1,68 +1,65 @@
-use clap::{Arg, ArgAction, Command, arg};
-use std::cmp::Ordering;
+use clap::{Arg, ArgAction, ArgMatches, Command, arg};
 use std::ffi::OsStr;
+use std::io::{self, IsTerminal};
 use std::path::{Path, PathBuf};
 use std::process;
+use std::time::Duration;
 use url::Url;
 
-mod common;
-use common::OwlError;
-
-mod owl_core;
-use owl_core::ReviewPrompt;
-
-mod owl_utils;
-use owl_utils::{
-    FileExplorerApp, PromptMode, Uri, cmd_utils, fs_utils, prog_utils, toml_utils, tui_utils,
+use owlgo_core::common::{self, OwlError, OwlWarning};
+use owlgo_core::owl_core::{self, ReviewPrompt};
+use owlgo_core::owl_utils::judge_utils::{CompareMode, OutputFilter, OutputFormat, Shard};
+use owlgo_core::owl_utils::progress_utils::{self, ProgressMode};
+use owlgo_core::owl_utils::solve_utils::PinAction;
+use owlgo_core::owl_utils::toml_utils::ChatRetention;
+use owlgo_core::owl_utils::{
+    FileApp, FileExplorerApp, OwlConfig, PromptMode, TuiTheme, Uri, WorkspaceScope, cmd_utils,
+    fs_utils, git_utils, prog_utils, signal_utils, term_utils, toml_utils, tui_utils,
+};
+use owlgo_core::{
+    BUILD_CACHE, CACHE_DIR, CHAT_DIR, LAST_FAILURE, MANIFEST, MANIFEST_HEAD_URL, MANIFEST_URL,
+    OWL_DIR, PROJECT_CONFIG, PROMPT_DIR, REMOTE_HEADER, ROSTER_DIR, SERVE_PORT, STASH_DIR,
+    TMP_ARCHIVE, TOML_TEMPLATE,
 };
-
-use crate::owl_utils::{FileApp, git_utils};
-
-const CHAT_DIR: &str = ".chat";
-const GIT_DIR: &str = ".git";
-const MANIFEST: &str = ".manifest.toml";
-const MANIFEST_HEAD_URL: &str = "https://gist.githubusercontent.com/latenitecoding/84c043f4c9092998773640a2202f2d36/raw/owl_manifest_short";
-const MANIFEST_URL: &str = "https://gist.githubusercontent.com/latenitecoding/b6fdd8656c0b6a60795581f84d0f2fa4/raw/owlgo_manifest";
-const OWL_DIR: &str = ".owlgo";
-const PROMPT_DIR: &str = ".prompt";
-const PROMPT_FILE: &str = ".prompt.md";
-const TEMPLATE_STEM: &str = ".template";
-const TMP_ARCHIVE: &str = ".tmp.archive";
-const STASH_DIR: &str = ".stash";
-
-// it must be that [manifest] is at the top
-const TOML_TEMPLATE: &str = r#"
-[manifest]
-version = "0.1.7"
-timestamp = "0.0.0"
-ai_sdk = "claude"
-api_key = ""
-
-[extensions]
-
-[ext_uri]
-
-[personal_prompts]
-
-[personal_quests]
-
-[prompts]
-
-[quests]
-"#;
 
 macro_rules! report_owl_err {
     ($expr:expr) => {
-        eprintln!("\x1b[31m[owlgo error]\x1b[0m: {}", $expr);
-        process::exit(1);
+        let owl_err = $expr;
+        report_warnings(&common::take_warnings());
+        eprintln!("\x1b[31m[owlgo error]\x1b[0m: {}", owl_err);
+        process::exit(owl_err.exit_code());
     };
 }
 
 fn cli() -> Command {
     Command::new("owlgo")
         .about("A lightweight CLI to assist in solving CP problems")
+        .after_help(concat!(
+            "Environment (flags > environment > .owlgo.toml > manifest):\n",
+            "  OWLGO_MANIFEST_URL  The manifest 'update' downloads, in place of the official one\n",
+            "  OWLGO_HEADER_URL    The manifest header 'update' checks, in place of the official one\n",
+            "  OWLGO_AI_SDK        The LLM backend for 'review' (claude or ollama)\n",
+            "  OWLGO_API_KEY       The API key for the LLM backend\n",
+            "  OWLGO_JOBS          How many cases 'quest' runs at once when --jobs isn't given",
+        ))
         .subcommand_required(true)
         .arg_required_else_help(true)
+        .disable_help_subcommand(true)
+        .arg(
+            arg!(--strict "Treat warnings as errors")
+                .global(true)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--docker "Builds and runs programs in the Docker images pinned in the manifest's [docker] table (timings include container startup)")
+                .global(true)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(-y --yes "Never prompt: accepts confirmations, and fails (exit code 3) on anything else needing input or a TUI")
+                .global(true)
+                .action(ArgAction::SetTrue),
+        )
         .subcommand(
             Command::new("add")
                 .about("adds new personal quest/extension/prompt to the manifest")
@@ -76,6 +73,19 @@ fn cli() -> Command {
                     .conflicts_with("prompt")
                 )
                 .arg(arg!(-F --fetch "Fetches test cases and prompts"))
+                .arg(arg!(--git "The URI is a git repo (with push access) to clone and read the extension from")
+                    .requires("extension")
+                )
+                .arg(arg!(--"local-tests" "The PATH is an unpacked folder of tests to pair and import")
+                    .conflicts_with_all(["extension", "prompt", "fetch"])
+                )
+                .arg(Arg::new("pubkey")
+                    .short('k')
+                    .long("pubkey")
+                    .value_name("KEY")
+                    .help("Pins a minisign public key the extension must be signed with")
+                    .requires("extension")
+                )
                 .arg(Arg::new("prompt")
                     .short('P')
                     .long("prompt")
@@ -85,6 +95,50 @@ fn cli() -> Command {
                 )
                 .arg_required_else_help(true),
         )
+        .subcommand(
+            Command::new("attest")
+                .about("judges a program and writes a signed attestation of its verdicts")
+                .arg(arg!([QUEST] "The name of the quest")
+                    .required_unless_present("verify")
+                )
+                .arg(arg!([PROG] "The path to the program")
+                    .required_unless_present("verify")
+                )
+                .arg(arg!(-o --output <FILE> "Where to write the attestation (defaults to <QUEST>.attest.toml)")
+                    .conflicts_with("verify")
+                )
+                .arg(arg!(--verify <FILE> "Checks the signature of an attestation instead")
+                    .conflicts_with_all(["QUEST", "PROG"])
+                )
+                .arg(arg!(-k --pubkey <KEY> "The public key the attestation must be signed with (without one, it is only checked against the key it carries)")
+                    .requires("verify")
+                )
+                .arg(arg!(-s --source <PROG> "The source the attestation must have judged")
+                    .requires("verify")
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("times a program on every case, optionally against its stashed version")
+                .arg(arg!(<PROG> "The program to benchmark"))
+                .arg(arg!([CASES] "The quest or directory with the '.in' files (defaults to the program's name)"))
+                .arg(arg!(--"against-stash" "Also times the stashed version and prints the speedup per case"))
+                .arg(arg!(-r --runs <N> "How many times each case is run, keeping the best time")
+                    .value_parser(clap::value_parser!(usize))
+                    .default_value("3")
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("check")
+                .about("checks a program for syntax/type errors without building or testing it")
+                .arg(arg!(<PROG> "The program to check"))
+                .arg(arg!(--format <FORMAT> "The output format for check failures")
+                    .value_parser(["pretty", "vscode"])
+                )
+                .arg_required_else_help(true),
+        )
         .subcommand(
             Command::new("clear")
                 .about("removes test cases and/or stashed files")
@@ -101,6 +155,9 @@ fn cli() -> Command {
                     .help("Removes LLM chat history")
                     .conflicts_with_all(["all", "prompt", "stash"])
                 )
+                .arg(arg!(-n --"dry-run" "Lists the chats that would be removed without removing them")
+                    .requires("chat")
+                )
                 .arg(arg!(-k --keep "Tests are not cleared"))
                 .arg(Arg::new("manifest")
                     .short('m')
@@ -109,6 +166,9 @@ fn cli() -> Command {
                     .help("Removes all stashed programs")
                     .conflicts_with("all")
                 )
+                .arg(arg!(--"older-than" <AGE> "Only removes chats older than AGE (e.g., '30d', '12h')")
+                    .requires("chat")
+                )
                 .arg(Arg::new("program")
                     .short('p')
                     .long("program")
@@ -116,6 +176,7 @@ fn cli() -> Command {
                     .help("Removes all stashed programs")
                     .conflicts_with("all")
                 )
+                .arg(arg!(--progress "Removes the solve history (kept by default, even with '--all')"))
                 .arg(Arg::new("prompt")
                     .short('P')
                     .long("prompt")
@@ -131,6 +192,94 @@ fn cli() -> Command {
                     .conflicts_with_all(["all", "chat", "prompt"])
                 ),
         )
+        .subcommand(
+            Command::new("collect")
+                .about("collects students' stash remotes and grades them against a quest")
+                .arg(arg!(<REMOTES> "A file listing '<name> <url>' (or just '<url>') per line"))
+                .arg(arg!(-q --quest <NAME> "The quest to grade each solution against").required(true))
+                .arg(arg!(-p --prog <FILE> "The solution in each stash (defaults to '<quest>.<ext>')"))
+                .arg(arg!(-o --out <DIR> "The directory to clone stashes into (defaults to 'roster')"))
+                .arg(arg!(--csv "Writes the grade sheet as CSV instead of markdown"))
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("compare")
+                .about("runs two programs on every input and compares their outputs")
+                .arg(arg!(<PROG_A> "The first program (e.g., a fast solution)"))
+                .arg(arg!(<PROG_B> "The second program (e.g., a brute force)"))
+                .arg(arg!(<CASES> "The quest or directory with the '.in' files"))
+                .arg(arg!(--compare <MODE> "How outputs are compared (overrides .owlgo.toml)")
+                    .value_parser(["exact", "lineset", "lineset-tokens", "tokens", "trim"])
+                )
+                .arg(arg!(--"show-build" "Shows the compiler's output and warnings on successful builds"))
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("config")
+                .about("reads and edits settings in the manifest")
+                .subcommand(
+                    Command::new("get")
+                        .about("prints the value of a setting")
+                        .arg(arg!(<KEY> "The setting")
+                            .value_parser(owl_core::config_subcommand::CONFIG_KEYS.to_vec())
+                        )
+                        .arg_required_else_help(true),
+                )
+                .subcommand(
+                    Command::new("list")
+                        .about("prints every setting in the manifest")
+                )
+                .subcommand(
+                    Command::new("set")
+                        .about("validates and updates a setting")
+                        .arg(arg!(<KEY> "The setting")
+                            .value_parser(owl_core::config_subcommand::CONFIG_KEYS.to_vec())
+                        )
+                        .arg(arg!(<VALUE> "The new value"))
+                        .arg_required_else_help(true),
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("debug")
+                .about("steps through a quest's cases in a TUI, running the program on demand")
+                .arg(arg!(<QUEST> "The name of the quest"))
+                .arg(arg!(<PROG> "The path to the program"))
+                .arg(arg!(--"show-build" "Shows the compiler's output and warnings on successful builds"))
+                .arg(arg!(--filter <FILTER> ... "Filters both outputs before comparing (ignore-case, ignore-prefix:<P>, or s/<REGEX>/<REP>/)"))
+                .arg(arg!(--compare <MODE> "How outputs are compared (overrides .owlgo.toml)")
+                    .value_parser(["exact", "lineset", "lineset-tokens", "tokens", "trim"])
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("bundles the manifest, stash, prompts, and scripts into one archive")
+                .arg(arg!(<ARCHIVE> "The archive to write (e.g. owlgo_env.tar.gz)"))
+                .arg(arg!(--chats "Also bundles recorded chats"))
+                .arg(arg!(--quests "Also bundles fetched and local quests"))
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("ext")
+                .about("manages extensions shared through a git repo")
+                .subcommand(
+                    Command::new("publish")
+                        .about("commits a personal quest to a git extension and pushes it")
+                        .arg(arg!(<QUEST> "The personal quest to publish"))
+                        .arg(arg!(--ext <NAME> "The git extension to publish to (if there is more than one)"))
+                        .arg_required_else_help(true),
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("extract-samples")
+                .about("turns the sample blocks of a problem statement into test cases")
+                .arg(arg!(<QUEST> "The quest to add the samples to"))
+                .arg(arg!([STATEMENT] "The statement (markdown/HTML) to extract from (defaults to the quest's)"))
+                .arg(arg!(-f --force "Overwrites existing sample cases"))
+                .arg_required_else_help(true),
+        )
         .subcommand(
             Command::new("fetch")
                 .about("fetches quests/extensions/prompts to your machine")
@@ -149,8 +298,28 @@ fn cli() -> Command {
                     .help("The name is a prompt")
                     .conflicts_with("extension")
                 )
+                .arg(arg!(--"no-intro" "Skips showing the quest's README/statement after fetching"))
+                .arg(arg!(--format <FORMAT> "The output format for progress")
+                    .value_parser(["pretty", "jsonl"])
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("fmt")
+                .about("formats the program with the formatter for its language (see [formatters] in the manifest)")
+                .arg(arg!(<PROG> "The program to format"))
+                .arg(Arg::new("force-lang")
+                    .long("lang")
+                    .value_name("LANG")
+                    .help("Formats the program as LANG (e.g. 'cpp' or 'python') instead of detecting it")
+                )
                 .arg_required_else_help(true),
         )
+        .subcommand(
+            Command::new("gc")
+                .about("removes pooled quest data that no quest links to anymore")
+                .arg(arg!(-n --"dry-run" "Reports what would be removed without removing it")),
+        )
         .subcommand(
             Command::new("git")
                 .about("provides git integration from within the stash directory")
@@ -173,16 +342,48 @@ fn cli() -> Command {
                 .subcommand(
                     Command::new("sync")
                         .about("syncs the stash directory to match the remote")
-                        .arg(arg!(-f --force "Removes all local changes")),
+                        .arg(arg!(-f --force "Removes all local changes"))
+                        .arg(arg!(--auto <INTERVAL> "Also syncs from any command once the last sync is older than INTERVAL (e.g., '6h', or 'off')")),
                 )
                 .arg_required_else_help(true),
         )
+        .subcommand(
+            Command::new("import")
+                .about("restores an archive made by 'owlgo export'")
+                .arg(arg!(<ARCHIVE> "The archive to restore"))
+                .arg(Arg::new("only")
+                    .long("only")
+                    .value_name("PART")
+                    .action(ArgAction::Append)
+                    .value_parser(owl_core::ENV_PARTS.to_vec())
+                    .help("Restores only this part (repeatable)")
+                )
+                .arg(arg!(-f --force "Overwrites existing files and skips the version check"))
+                .arg_required_else_help(true),
+        )
         .subcommand(
             Command::new("init")
                 .about("creates a local file from a stashed template")
                 .arg(arg!(<PROG> "The program to initialize from the template"))
+                .arg(arg!(--merge "Merges the template into an existing file, marking conflicts"))
+                .arg(arg!(--overwrite "Replaces an existing file with the template")
+                    .conflicts_with("merge")
+                )
                 .arg_required_else_help(true),
         )
+        .subcommand(
+            Command::new("help")
+                .about("prints help and worked examples for a command or topic")
+                .arg(arg!([TOPIC] "The command or topic (personal-quest, stress, review)"))
+                .arg(Arg::new("man")
+                    .long("man")
+                    .value_name("DIR")
+                    .num_args(0..=1)
+                    .default_missing_value(".")
+                    .help("Generates roff man pages into DIR (defaults to the current dir)")
+                    .conflicts_with("TOPIC")
+                ),
+        )
         .subcommand(
             Command::new("list")
                 .about("outputs information on stashed files")
@@ -207,13 +408,73 @@ fn cli() -> Command {
                     .help("List starting from the root of the owlgo directory")
                     .conflicts_with_all(["chat", "prompt"])
                 )
+                .arg(Arg::new("quests")
+                    .short('Q')
+                    .long("quests")
+                    .action(ArgAction::SetTrue)
+                    .help("List the quests in the manifest with their descriptions")
+                    .conflicts_with_all(["chat", "prompt", "root", "tui"])
+                )
                 .arg(arg!(-I --tui "Enters an interactive TUI to preview files"))
+                .arg(arg!(--theme <THEME> "The TUI theme (dark, light, or a syntax highlighting theme)"))
+                .arg(arg!(--format <FORMAT> "The output format (json nests entries with their kind, size, and mtime)")
+                    .value_parser(["plain", "json"])
+                    .default_value("plain")
+                    .conflicts_with_all(["quests", "tui"])
+                )
+        )
+        .subcommand(
+            Command::new("matrix")
+                .about("builds and tests every solution in a directory against a quest")
+                .arg(arg!(<QUEST> "The name of the quest"))
+                .arg(arg!(-s --solutions <DIR> "The directory of solutions (defaults to the current dir)"))
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("merge")
+                .about("combines the test cases of several quests into a new personal quest")
+                .arg(arg!(<NEW_NAME> "The name of the merged quest"))
+                .arg(Arg::new("QUESTS")
+                    .help("The quests to merge (colliding case names are prefixed by their quest)")
+                    .required(true)
+                    .num_args(2..)
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("minimize")
+                .about("shrinks a failing case's input while the failure persists")
+                .arg(arg!(<QUEST> "The name of the quest"))
+                .arg(arg!(<CASE> "The name of the failing case"))
+                .arg(arg!(<PROG> "The program that fails the case"))
+                .arg(arg!(-r --reference <REF> "A known-good solution to compare outputs against (otherwise only crashes count)"))
+                .arg(arg!(--compare <MODE> "How outputs are compared (overrides .owlgo.toml)")
+                    .value_parser(["exact", "lineset", "lineset-tokens", "tokens", "trim"])
+                )
+                .arg(arg!(--"show-build" "Shows the compiler's output and warnings on successful builds"))
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("pack")
+                .about("packs the test cases in a directory into quest archives to share")
+                .arg(arg!([DIR] "The directory of test cases to pack (defaults to the current dir)"))
+                .arg(arg!(-n --name <NAME> "The name of the archive (defaults to the dir name)"))
+                .arg(Arg::new("strip_feedback")
+                    .long("strip-feedback")
+                    .action(ArgAction::SetTrue)
+                    .help("Also packs a student archive without hint/feedback '.md' files")
+                )
+                .arg(Arg::new("strip_answers")
+                    .long("strip-answers")
+                    .action(ArgAction::SetTrue)
+                    .help("Also packs a student archive without '.ans'/'.out' files")
+                ),
         )
         .subcommand(
             Command::new("quest")
                 .about("tests program against all test cases in the selected quest")
-                .arg(arg!(<NAME> "The name of the quest"))
-                .arg(arg!(<PROG> "The program to test"))
+                .arg(arg!([NAME] "The name of the quest (defaults to the quest in .owlgo.toml)"))
+                .arg(arg!([PROG] "The program to test"))
                 .arg(Arg::new("CASE")
                     .short('c')
                     .long("case")
@@ -236,8 +497,61 @@ fn cli() -> Command {
                     .action(ArgAction::SetTrue)
                     .conflicts_with_all(["CASE", "TEST"])
                 )
+                .arg(Arg::new("seed")
+                    .long("seed")
+                    .help("Seeds the random test case selection (implies --rand)")
+                    .conflicts_with_all(["CASE", "TEST"])
+                    .value_parser(clap::value_parser!(u64))
+                )
+                .arg(arg!(--shard <SHARD> "Runs only shard K of N (e.g. 2/4) of the test cases")
+                    .conflicts_with_all(["CASE", "rand", "seed", "TEST"])
+                )
+                .arg(Arg::new("jobs")
+                    .short('j')
+                    .long("jobs")
+                    .help("Runs up to N test cases at once with a live view of the run")
+                    .conflicts_with_all(["CASE", "rand", "seed", "TEST"])
+                    .value_parser(clap::value_parser!(usize))
+                )
+                .arg(arg!(--"no-top" "Prints each result instead of the live view when using --jobs")
+                    .requires("jobs")
+                )
+                .arg(arg!(--script <NAME> "Passes the results to a rhai script in ~/.owlgo/scripts"))
+                .arg(arg!(--results <FILE> "Writes the results as versioned JSON (see 'Results JSON' in the README)")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .conflicts_with("batch")
+                )
+                .arg(arg!(--format <FORMAT> "The output format for build and test failures")
+                    .value_parser(["pretty", "vscode", "jsonl"])
+                )
+                .arg(arg!(--filter <FILTER> ... "Filters both outputs before comparing (ignore-case, ignore-prefix:<P>, or s/<REGEX>/<REP>/)"))
+                .arg(arg!(--compare <MODE> "How outputs are compared (overrides .owlgo.toml)")
+                    .value_parser(["exact", "lineset", "lineset-tokens", "tokens", "trim"])
+                )
+                .arg(arg!(--scratch "Runs the program in a temporary scratch directory"))
+                .arg(arg!(--"show-build" "Shows the compiler's output and warnings on successful builds"))
+                .arg(Arg::new("force-lang")
+                    .long("lang")
+                    .value_name("LANG")
+                    .help("Builds and runs the program as LANG (e.g. 'cpp' or 'python') instead of detecting it")
+                )
+                .arg(arg!(--"no-intro" "Skips showing the quest's README/statement on first use"))
+                .arg(arg!(--pin "Pins the quest to the current toolchain version for the program's language")
+                    .conflicts_with_all(["unpin", "batch"])
+                )
+                .arg(arg!(--unpin "Stops checking the quest's toolchain version for the program's language")
+                    .conflicts_with("batch")
+                )
+                .arg(arg!(--batch <LIST> "Runs quest/program pairs from a TOML/CSV list, a directory, or a pattern")
+                    .conflicts_with_all(["NAME", "PROG", "CASE", "TEST", "rand", "seed"])
+                )
                 .arg_required_else_help(true),
         )
+        .subcommand(
+            Command::new("rerun")
+                .about("repeats the most recent failing quest case with the same program")
+                .arg(arg!(-v --verbose "Shows the program's stderr for the case")),
+        )
         .subcommand(
             Command::new("restore")
                 .about("restores the file/program to the version stashed away")
@@ -247,8 +561,49 @@ fn cli() -> Command {
         .subcommand(
             Command::new("review")
                 .about("submits the program to an LLM for a code review")
-                .arg(arg!(<PROG> "The program to review"))
+                .arg(arg!([PROG] "The program to review").required_unless_present("no_prog"))
                 .arg(arg!([PROMPT] "The prompt or description to give"))
+                .arg(Arg::new("no_prog")
+                    .long("no-prog")
+                    .action(ArgAction::SetTrue)
+                    .help("Chat about the prompt/desc alone before any program exists")
+                    .conflicts_with_all(["debug", "default", "explore", "optimize", "test"])
+                )
+                .arg(Arg::new("all_stashed")
+                    .long("all-stashed")
+                    .action(ArgAction::SetTrue)
+                    .help("Compares all stashed programs for the quest in a single report")
+                    .requires("quest")
+                    .conflicts_with_all([
+                        "no_prog", "tui", "debug", "default", "explain", "explore", "optimize",
+                        "test",
+                    ])
+                )
+                .arg(Arg::new("since_stash")
+                    .long("since-stash")
+                    .action(ArgAction::SetTrue)
+                    .help("Reviews only the changes since the program was last stashed")
+                    .requires("PROG")
+                    .conflicts_with_all([
+                        "no_prog", "all_stashed", "tui", "debug", "default", "explain", "explore",
+                        "optimize", "test",
+                    ])
+                )
+                .arg(Arg::new("include")
+                    .long("include")
+                    .value_name("PATH")
+                    .num_args(1..)
+                    .action(ArgAction::Append)
+                    .help("Files or dirs to send along as context (ignoring generated and gitignored files)")
+                    .conflicts_with_all(["all_stashed", "since_stash"])
+                )
+                .arg(Arg::new("budget")
+                    .long("budget")
+                    .value_name("TOKENS")
+                    .help("Roughly how many tokens of workspace files to send (most recently modified first)")
+                    .value_parser(clap::value_parser!(usize))
+                    .conflicts_with_all(["all_stashed", "since_stash"])
+                )
                 .arg(arg!(--sdk <SDK> "Updates the chosen LLM sdk (e.g, 'claude')"))
                 .arg(arg!(--key <KEY> "Updates the API key for the chosen LLM"))
                 .arg(Arg::new("file")
@@ -316,14 +671,42 @@ fn cli() -> Command {
                     .conflicts_with_all(["debug", "default", "explain", "explore", "optimize"])
                 )
                 .arg(arg!(-I --tui "Enters an interactive TUI to chat with chosen LLM"))
+                .arg(arg!(--theme <THEME> "The TUI theme (dark, light, or a syntax highlighting theme)"))
                 .arg_required_else_help(true),
         )
         .subcommand(
             Command::new("run")
                 .about("builds and executes target program")
                 .arg(arg!(<PROG> "The program to run"))
+                .arg(arg!(--scratch "Runs the program in a temporary scratch directory"))
+                .arg(arg!(--"show-build" "Shows the compiler's output and warnings on successful builds"))
+                .arg(Arg::new("force-lang")
+                    .long("lang")
+                    .value_name("LANG")
+                    .help("Builds and runs the program as LANG (e.g. 'cpp' or 'python') instead of detecting it")
+                )
                 .arg_required_else_help(true),
         )
+        .subcommand(
+            Command::new("serve")
+                .about("serves a local HTTP API, guarded by a token printed at startup, to list quests and run quest or stashed programs against them")
+                .arg(Arg::new("port")
+                    .short('p')
+                    .long("port")
+                    .help("The port to listen on (localhost only)")
+                    .default_value(SERVE_PORT)
+                    .value_parser(clap::value_parser!(u16))
+                ),
+        )
+        .subcommand(
+            Command::new("share")
+                .about("uploads a file, stashed program, or chat record as a secret gist")
+                .arg(arg!(<FILE> "The file path, stashed program, or chat record to share"))
+                .arg(arg!(-d --description <TEXT> "The gist description"))
+                .arg(arg!(--public "Makes the gist public instead of secret")
+                    .action(ArgAction::SetTrue)
+                ),
+        )
         .subcommand(
             Command::new("show")
                 .about("prints test input/expected or stashed files")
@@ -371,13 +754,25 @@ fn cli() -> Command {
                     .action(ArgAction::SetTrue)
                     .conflicts_with_all(["CASE", "manifest", "program", "prompt", "TEST"])
                 )
+                .arg(Arg::new("seed")
+                    .long("seed")
+                    .help("Seeds the random test case selection (implies --rand)")
+                    .conflicts_with_all(["CASE", "manifest", "program", "prompt", "TEST"])
+                    .value_parser(clap::value_parser!(u64))
+                )
+                .arg(arg!(-F --full "Show the whole file even if it is large"))
                 .arg(arg!(-I --tui "Show the file in a TUI (redirects to list if no other args are provided)"))
+                .arg(arg!(--theme <THEME> "The TUI theme (dark, light, or a syntax highlighting theme)"))
                 .arg_required_else_help(true),
         )
         .subcommand(
             Command::new("stash")
                 .about("stashes the program/prompt/file away for later")
                 .arg(arg!(<PROG> "The program/prompt/file to stash"))
+                .arg(arg!(-f --force "Overwrites a different stashed file of the same name")
+                    .conflicts_with("history")
+                )
+                .arg(arg!(-H --history "Moves a different stashed file of the same name into the stash history"))
                 .arg(Arg::new("prompt")
                     .short('P')
                     .long("prompt")
@@ -394,25 +789,326 @@ fn cli() -> Command {
                 )
                 .arg_required_else_help(true),
         )
+        .subcommand(
+            Command::new("sync")
+                .about("backs up the stash, prompts, and chats to S3 or WebDAV (or restores them)")
+                .arg(arg!(<REMOTE> "The remote (e.g. s3://bucket/prefix or https://dav.example.com/owlgo)"))
+                .arg(arg!(--restore "Downloads the remote's files into the stash instead"))
+                .arg(arg!(-f --force "Overwrites local changes when restoring")
+                    .requires("restore")
+                )
+                .arg_required_else_help(true),
+        )
         .subcommand(
             Command::new("test")
                 .about("runs program against sample test case")
                 .arg(arg!(<PROG> "The program to test"))
                 .arg(arg!(<IN> "The input file for the test case"))
-                .arg(arg!(<ANS> "The answer file to the test case"))
+                .arg(arg!([ANS] "The answer file to the test case (omit to only print the output)"))
+                .arg(arg!(--format <FORMAT> "The output format for build and test failures")
+                    .value_parser(["pretty", "vscode"])
+                )
+                .arg(arg!(--filter <FILTER> ... "Filters both outputs before comparing (ignore-case, ignore-prefix:<P>, or s/<REGEX>/<REP>/)"))
+                .arg(arg!(--compare <MODE> "How outputs are compared (overrides .owlgo.toml)")
+                    .value_parser(["exact", "lineset", "lineset-tokens", "tokens", "trim"])
+                )
+                .arg(arg!(--scratch "Runs the program in a temporary scratch directory"))
+                .arg(arg!(--"show-build" "Shows the compiler's output and warnings on successful builds"))
+                .arg(Arg::new("force-lang")
+                    .long("lang")
+                    .value_name("LANG")
+                    .help("Builds and runs the program as LANG (e.g. 'cpp' or 'python') instead of detecting it")
+                )
                 .arg_required_else_help(true),
         )
-        .subcommand(Command::new("update").about("checks owlgo and its manifest for updates"))
+        .subcommand(
+            Command::new("todos")
+                .about("shows the checklist extracted from a review and checks off items")
+                .arg(arg!([CHAT] "The chat record (defaults to the most recent review)"))
+                .arg(arg!(-d --done <N> ... "Checks off the numbered todo items")
+                    .value_parser(clap::value_parser!(usize))
+                    .num_args(1..)
+                )
+                .arg(arg!(-u --undo <N> ... "Reopens the numbered todo items")
+                    .value_parser(clap::value_parser!(usize))
+                    .num_args(1..)
+                ),
+        )
+        .subcommand(
+            Command::new("update")
+                .about("checks owlgo and its manifest for updates")
+                .arg(arg!(--review "Asks which new or changed quests to accept from each extension"))
+                .arg(arg!(--format <FORMAT> "The output format for progress")
+                    .value_parser(["pretty", "jsonl"])
+                ),
+        )
         .subcommand(
             Command::new("version")
                 .about("outputs the current version")
-                .arg(arg!(-L --lang <EXT> "Outputs the system version of the language")),
+                .arg(arg!(-L --lang <EXT> "Outputs the system version of the language"))
+                .arg(arg!(--format <FORMAT> "The output format")
+                    .value_parser(["plain", "json"])
+                    .default_value("plain")
+                    .conflicts_with("lang")
+                ),
+        )
+        .subcommand(
+            Command::new("where")
+                .about("outputs the resolved paths of owlgo's files and directories")
+                .arg(arg!([KEY] "Outputs only the path for this key")
+                    .value_parser(owl_core::where_subcommand::WHERE_KEYS.to_vec())
+                )
+                .arg(arg!(-q --quest <NAME> "Includes the directory of the quest"))
+                .arg(arg!(-p --prog <FILE> "Includes the stashed copy of the program"))
+                .arg(arg!(--format <FORMAT> "The output format")
+                    .value_parser(["plain", "json"])
+                    .default_value("plain")
+                ),
+        )
+        .subcommand(
+            Command::new("which-lang")
+                .about("outputs the language a program is built and run as, and why")
+                .arg(arg!(<PROG> "The program to check"))
+                .arg(Arg::new("force-lang")
+                    .long("lang")
+                    .value_name("LANG")
+                    .help("Checks LANG (e.g. 'cpp' or 'python') as an override")
+                )
+                .arg_required_else_help(true),
         )
 }
 
+/// A quest name from the command line, resolved to the quest it matches.
+fn quest_arg(quest_name: &str) -> String {
+    match owl_core::resolve_quest_name(quest_name) {
+        Ok(quest_name) => quest_name,
+        Err(e) => {
+            report_owl_err!(e);
+        }
+    }
+}
+
+fn report_warnings(warnings: &[OwlWarning]) {
+    if warnings.is_empty() {
+        return;
+    }
+
+    eprintln!(
+        "\x1b[33m[owlgo warnings]\x1b[0m: {} warning(s)",
+        warnings.len()
+    );
+
+    for warning in warnings {
+        eprintln!("  - {}", warning);
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let matches = cli().get_matches();
+    let strict = matches.get_one::<bool>("strict").is_some_and(|&f| f);
+
+    if matches.get_one::<bool>("yes").is_some_and(|&f| f) {
+        term_utils::set_non_interactive(true);
+    }
+
+    let manifest_path =
+        fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST)).expect("owlgo dir exists");
+
+    let mut config = match OwlConfig::load(PROJECT_CONFIG)
+        .and_then(OwlConfig::with_env)
+        .and_then(|config| config.with_manifest(&manifest_path))
+    {
+        Ok(config) => config,
+        Err(e) => {
+            report_owl_err!(e);
+        }
+    };
+
+    if let Some(("review", sub_matches)) = matches.subcommand() {
+        if let Some(ai_sdk) = sub_matches.get_one::<String>("sdk") {
+            config.ai_sdk = Some(ai_sdk.to_owned());
+        }
+
+        if let Some(api_key) = sub_matches.get_one::<String>("key") {
+            config.api_key = Some(api_key.to_owned());
+        }
+    }
+
+    let checks_updates = !matches!(matches.subcommand_name(), Some("update" | "version"));
+
+    let header_url = config.header_url.clone();
+    let header_refresh =
+        checks_updates.then(|| tokio::spawn(owl_core::refresh_remote_header(header_url)));
+
+    run_command(&matches, config).await;
+
+    if matches.subcommand_name() != Some("git") {
+        match owl_core::auto_sync_git() {
+            Ok(true) => eprintln!("\x1b[2mauto-synced the stash with its git remote\x1b[0m"),
+            Ok(false) => {}
+            Err(e) => common::warn(OwlWarning::Recovered(format!("stash auto-sync: {}", e))),
+        }
+    }
+
+    // the refresh rewrites the cache the hints read and mark, so it gets a few seconds to land
+    // and is cancelled after that instead of racing them
+    if let Some(mut header_refresh) = header_refresh
+        && tokio::time::timeout(Duration::from_secs(3), &mut header_refresh)
+            .await
+            .is_err()
+    {
+        header_refresh.abort();
+        let _ = header_refresh.await;
+    }
+
+    if checks_updates && let Err(e) = owl_core::hint_update() {
+        common::warn(OwlWarning::Recovered(e.to_string()));
+    }
+
+    let warnings = common::take_warnings();
+    report_warnings(&warnings);
+
+    if strict && !warnings.is_empty() {
+        report_owl_err!(OwlError::StrictMode(format!(
+            "{} warning(s) treated as errors (--strict)",
+            warnings.len()
+        )));
+    }
+}
+
+async fn run_command(matches: &ArgMatches, mut config: OwlConfig) {
+    if matches.get_one::<bool>("docker").is_some_and(|&f| f) {
+        config.docker = true;
+    }
+
+    if let Some((_, sub_matches)) = matches.subcommand()
+        && let Ok(Some(true)) = sub_matches.try_get_one::<bool>("scratch")
+    {
+        config.scratch = true;
+    }
+
+    if let Some((_, sub_matches)) = matches.subcommand()
+        && let Ok(Some(true)) = sub_matches.try_get_one::<bool>("show-build")
+    {
+        config.show_build = true;
+    }
+
+    if let Some((_, sub_matches)) = matches.subcommand()
+        && let Ok(Some(no_intro)) = sub_matches.try_get_one::<bool>("no-intro")
+    {
+        config.show_intro = !no_intro;
+    }
+
+    if let Some(("quest", sub_matches)) = matches.subcommand() {
+        if sub_matches.get_one::<bool>("pin").is_some_and(|&f| f) {
+            config.pin = Some(PinAction::Pin);
+        } else if sub_matches.get_one::<bool>("unpin").is_some_and(|&f| f) {
+            config.pin = Some(PinAction::Unpin);
+        }
+    }
+
+    if config.scratch {
+        cmd_utils::set_scratch(Some(config.aux_files.clone()));
+    }
+
+    if let Some((_, sub_matches)) = matches.subcommand()
+        && let Ok(Some(filters)) = sub_matches.try_get_many::<String>("filter")
+    {
+        for filter in filters {
+            match OutputFilter::try_from(filter.as_str()) {
+                Ok(filter) => config.filters.push(filter),
+                Err(e) => {
+                    report_owl_err!(e);
+                }
+            }
+        }
+    }
+
+    if let Some((_, sub_matches)) = matches.subcommand()
+        && let Ok(Some(lang)) = sub_matches.try_get_one::<String>("force-lang")
+    {
+        if let Err(e) = prog_utils::find_prog_lang(lang) {
+            report_owl_err!(e);
+        }
+
+        config.force_lang = Some(lang.to_string());
+    }
+
+    if let Some((_, sub_matches)) = matches.subcommand()
+        && let Ok(Some(mode)) = sub_matches.try_get_one::<String>("compare")
+    {
+        match CompareMode::try_from(mode.as_str()) {
+            Ok(mode) => config.compare = mode,
+            Err(e) => {
+                report_owl_err!(e);
+            }
+        }
+    }
+
+    config.build_cache =
+        fs_utils::ensure_path_from_home(&[OWL_DIR, CACHE_DIR], Some(BUILD_CACHE)).ok();
+    config.failure_log = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(LAST_FAILURE)).ok();
+
+    if let Some((_, sub_matches)) = matches.subcommand()
+        && let Ok(Some(script_name)) = sub_matches.try_get_one::<String>("script")
+    {
+        config.script = Some(script_name.to_owned());
+    }
+
+    if let Some(("quest", sub_matches)) = matches.subcommand() {
+        config.results = sub_matches.get_one::<PathBuf>("results").cloned();
+    }
+
+    if let Some(("check" | "quest" | "test", sub_matches)) = matches.subcommand()
+        && let Some(format) = sub_matches.get_one::<String>("format")
+    {
+        match OutputFormat::try_from(format.as_str()) {
+            Ok(format) => config.format = format,
+            Err(e) => {
+                report_owl_err!(e);
+            }
+        }
+    }
+
+    if let Some(("fetch" | "quest" | "update", sub_matches)) = matches.subcommand() {
+        match sub_matches.get_one::<String>("format").map(String::as_str) {
+            Some("jsonl") => progress_utils::set_progress(ProgressMode::Jsonl),
+            _ if io::stderr().is_terminal() => progress_utils::set_progress(ProgressMode::Bar),
+            _ => {}
+        }
+    }
+
+    if let Some(("quest", sub_matches)) = matches.subcommand() {
+        if let Some(&jobs) = sub_matches.get_one::<usize>("jobs") {
+            config.jobs = jobs;
+        }
+
+        config.show_top = !sub_matches.get_one::<bool>("no-top").is_some_and(|&f| f)
+            && io::stdout().is_terminal();
+    }
+
+    if let Some(("quest", sub_matches)) = matches.subcommand()
+        && let Some(shard) = sub_matches.get_one::<String>("shard")
+    {
+        match Shard::try_from(shard.as_str()) {
+            Ok(shard) => config.shard = Some(shard),
+            Err(e) => {
+                report_owl_err!(e);
+            }
+        }
+    }
+
+    if let Some((_, sub_matches)) = matches.subcommand()
+        && let Ok(Some(theme_name)) = sub_matches.try_get_one::<String>("theme")
+    {
+        match TuiTheme::try_from(theme_name.as_str()) {
+            Ok(theme) => config.theme = theme,
+            Err(e) => {
+                report_owl_err!(e);
+            }
+        }
+    }
 
     match matches.subcommand() {
         Some(("add", sub_matches)) => {
@@ -421,13 +1117,23 @@ async fn main() {
             let is_extension = sub_matches.get_one::<bool>("extension").is_some_and(|&f| f);
             let and_fetch = sub_matches.get_one::<bool>("fetch").is_some_and(|&f| f);
             let is_prompt = sub_matches.get_one::<bool>("prompt").is_some_and(|&f| f);
+            let is_git = sub_matches.get_one::<bool>("git").is_some_and(|&f| f);
+            let pub_key = sub_matches.get_one::<String>("pubkey");
+            let local_tests = sub_matches
+                .get_one::<bool>("local-tests")
+                .is_some_and(|&f| f);
 
             let uri = Uri::try_from(uri_str.as_str()).expect("provided URI is valid");
 
-            let action = if is_extension {
-                owl_core::add_extension(name, &uri, and_fetch).await
+            let action = if is_git {
+                owl_core::add_git_extension(name, uri_str, pub_key.map(String::as_str), and_fetch)
+                    .await
+            } else if is_extension {
+                owl_core::add_extension(name, &uri, pub_key.map(String::as_str), and_fetch).await
             } else if is_prompt {
                 owl_core::add_prompt(name, &uri, and_fetch).await
+            } else if local_tests {
+                owl_core::add_local_quest(name, Path::new(uri_str)).await
             } else {
                 owl_core::add_quest(name, &uri, and_fetch).await
             };
@@ -436,14 +1142,97 @@ async fn main() {
                 report_owl_err!(e);
             }
         }
+        Some(("attest", sub_matches)) => {
+            let action = match sub_matches.get_one::<String>("verify") {
+                Some(attest_path) => owl_core::verify_attestation(
+                    Path::new(attest_path),
+                    sub_matches.get_one::<String>("pubkey").map(String::as_str),
+                    sub_matches.get_one::<String>("source").map(Path::new),
+                ),
+                None => {
+                    let quest_name =
+                        quest_arg(sub_matches.get_one::<String>("QUEST").expect("required"));
+                    let prog = sub_matches.get_one::<String>("PROG").expect("required");
+
+                    signal_utils::install_ctrl_c_handler();
+
+                    owl_core::attest_quest(
+                        &quest_name,
+                        Path::new(prog),
+                        sub_matches.get_one::<String>("output").map(Path::new),
+                        &config,
+                    )
+                    .await
+                }
+            };
+
+            if let Err(e) = action {
+                report_owl_err!(e);
+            }
+        }
+        Some(("bench", sub_matches)) => {
+            let prog = sub_matches.get_one::<String>("PROG").expect("required");
+            let cases = sub_matches.get_one::<String>("CASES");
+            let against_stash = sub_matches
+                .get_one::<bool>("against-stash")
+                .is_some_and(|&f| f);
+            let runs = sub_matches.get_one::<usize>("runs").copied().unwrap_or(3);
+
+            signal_utils::install_ctrl_c_handler();
+
+            if let Err(e) = owl_core::bench_program(
+                Path::new(prog),
+                cases.map(String::as_str),
+                against_stash,
+                runs,
+                &config,
+            )
+            .await
+            {
+                report_owl_err!(e);
+            }
+        }
+        Some(("check", sub_matches)) => {
+            let prog = sub_matches.get_one::<String>("PROG").expect("required");
+
+            if let Err(e) = owl_core::check_program(Path::new(prog), &config) {
+                report_owl_err!(e);
+            }
+        }
         Some(("clear", sub_matches)) => {
             let do_all = sub_matches.get_one::<bool>("all").is_some_and(|&f| f);
             let do_chat = sub_matches.get_one::<bool>("chat").is_some_and(|&f| f);
             let keep_tests = sub_matches.get_one::<bool>("keep").is_some_and(|&f| f);
             let do_manif = sub_matches.get_one::<bool>("manifest").is_some_and(|&f| f);
             let do_programs = sub_matches.get_one::<bool>("program").is_some_and(|&f| f);
+            let do_progress = sub_matches.get_one::<bool>("progress").is_some_and(|&f| f);
             let do_prompts = sub_matches.get_one::<bool>("prompt").is_some_and(|&f| f);
             let do_stash = sub_matches.get_one::<bool>("stash").is_some_and(|&f| f);
+            let dry_run = sub_matches.get_one::<bool>("dry-run").is_some_and(|&f| f);
+            let older_than = sub_matches.get_one::<String>("older-than");
+
+            if dry_run || older_than.is_some() {
+                let retention =
+                    match older_than.map(|age| owl_core::clear_subcommand::parse_age(age)) {
+                        Some(Ok(age)) => ChatRetention {
+                            max_age: Some(age),
+                            ..ChatRetention::default()
+                        },
+                        Some(Err(e)) => {
+                            report_owl_err!(e);
+                        }
+                        None => ChatRetention {
+                            max_count: Some(0),
+                            ..ChatRetention::default()
+                        },
+                    };
+
+                if let Err(e) = owl_core::clear_chats(&retention, dry_run) {
+                    report_owl_err!(e);
+                }
+
+                return;
+            }
 
             let action = fs_utils::ensure_path_from_home(&[OWL_DIR], None)
                 .and_then(|owl_dir| {
@@ -491,6 +1280,10 @@ async fn main() {
                         owl_core::clear_programs()?
                     }
 
+                    if do_progress {
+                        owl_core::clear_progress()?;
+                    }
+
                     if !keep_tests {
                         owl_core::clear_quests()?;
                     }
@@ -502,6 +1295,106 @@ async fn main() {
                 report_owl_err!(e);
             }
         }
+        Some(("collect", sub_matches)) => {
+            let remotes = sub_matches.get_one::<String>("REMOTES").expect("required");
+            let quest_name = quest_arg(sub_matches.get_one::<String>("quest").expect("required"));
+            let prog_name = sub_matches.get_one::<String>("prog");
+            let out_dir = sub_matches
+                .get_one::<String>("out")
+                .map(String::as_str)
+                .unwrap_or(ROSTER_DIR);
+            let as_csv = sub_matches.get_one::<bool>("csv").is_some_and(|&f| f);
+
+            signal_utils::install_ctrl_c_handler();
+
+            if let Err(e) = owl_core::collect_roster(
+                Path::new(remotes),
+                &quest_name,
+                prog_name.map(String::as_str),
+                Path::new(out_dir),
+                as_csv,
+                &config,
+            )
+            .await
+            {
+                report_owl_err!(e);
+            }
+        }
+        Some(("compare", sub_matches)) => {
+            let prog_a = sub_matches.get_one::<String>("PROG_A").expect("required");
+            let prog_b = sub_matches.get_one::<String>("PROG_B").expect("required");
+            let cases = sub_matches.get_one::<String>("CASES").expect("required");
+
+            signal_utils::install_ctrl_c_handler();
+
+            if let Err(e) =
+                owl_core::compare_programs(Path::new(prog_a), Path::new(prog_b), cases, &config)
+                    .await
+            {
+                report_owl_err!(e);
+            }
+        }
+        Some(("config", sub_matches)) => {
+            let action = match sub_matches.subcommand() {
+                Some(("get", sub_matches)) => {
+                    let key = sub_matches.get_one::<String>("KEY").expect("required");
+
+                    owl_core::get_config(key)
+                }
+                Some(("list", _)) => owl_core::list_config(),
+                Some(("set", sub_matches)) => {
+                    let key = sub_matches.get_one::<String>("KEY").expect("required");
+                    let value = sub_matches.get_one::<String>("VALUE").expect("required");
+
+                    owl_core::set_config(key, value)
+                }
+                _ => unreachable!(),
+            };
+
+            if let Err(e) = action {
+                report_owl_err!(e);
+            }
+        }
+        Some(("debug", sub_matches)) => {
+            let quest_name = quest_arg(sub_matches.get_one::<String>("QUEST").expect("required"));
+            let prog = sub_matches.get_one::<String>("PROG").expect("required");
+
+            if let Err(e) = owl_core::debug_quest(&quest_name, Path::new(prog), &config).await {
+                report_owl_err!(e);
+            }
+        }
+        Some(("export", sub_matches)) => {
+            let archive = sub_matches.get_one::<String>("ARCHIVE").expect("required");
+            let with_chats = sub_matches.get_one::<bool>("chats").is_some_and(|&f| f);
+            let with_quests = sub_matches.get_one::<bool>("quests").is_some_and(|&f| f);
+
+            if let Err(e) = owl_core::export_env(Path::new(archive), with_chats, with_quests) {
+                report_owl_err!(e);
+            }
+        }
+        Some(("ext", sub_matches)) => match sub_matches.subcommand() {
+            Some(("publish", sub_matches)) => {
+                let quest_name =
+                    quest_arg(sub_matches.get_one::<String>("QUEST").expect("required"));
+                let ext_name = sub_matches.get_one::<String>("ext");
+
+                if let Err(e) =
+                    owl_core::publish_quest(&quest_name, ext_name.map(String::as_str)).await
+                {
+                    report_owl_err!(e);
+                }
+            }
+            _ => unreachable!(),
+        },
+        Some(("extract-samples", sub_matches)) => {
+            let quest_name = quest_arg(sub_matches.get_one::<String>("QUEST").expect("required"));
+            let statement = sub_matches.get_one::<String>("STATEMENT").map(Path::new);
+            let force = sub_matches.get_one::<bool>("force").is_some_and(|&f| f);
+
+            if let Err(e) = owl_core::extract_samples(&quest_name, statement, force) {
+                report_owl_err!(e);
+            }
+        }
         Some(("fetch", sub_matches)) => {
             let name = sub_matches.get_one::<String>("NAME").expect("required");
             let is_ext = sub_matches.get_one::<bool>("extension").is_some_and(|&f| f);
@@ -512,13 +1405,36 @@ async fn main() {
             } else if is_prompt {
                 owl_core::fetch_prompt(name).await
             } else {
-                owl_core::fetch_quest(name).await
+                let name = quest_arg(name);
+
+                owl_core::fetch_quest(&name).await.map(|_| {
+                    if config.show_intro
+                        && let Err(e) = fs_utils::quest_path(&name)
+                            .and_then(|quest_path| owl_core::show_intro(&quest_path))
+                    {
+                        common::warn(OwlWarning::Recovered(e.to_string()));
+                    }
+                })
             };
 
             if let Err(e) = action {
                 report_owl_err!(e);
             }
         }
+        Some(("fmt", sub_matches)) => {
+            let prog = sub_matches.get_one::<String>("PROG").expect("required");
+
+            if let Err(e) = owl_core::format_program(Path::new(prog), &config) {
+                report_owl_err!(e);
+            }
+        }
+        Some(("gc", sub_matches)) => {
+            let dry_run = sub_matches.get_one::<bool>("dry-run").is_some_and(|&f| f);
+
+            if let Err(e) = owl_core::gc_pool(dry_run) {
+                report_owl_err!(e);
+            }
+        }
         Some(("git", sub_matches)) => match sub_matches.subcommand() {
             Some(("push", sub_matches)) => {
                 let use_force = sub_matches.get_one::<bool>("force").is_some_and(|&f| f);
@@ -547,41 +1463,52 @@ async fn main() {
             Some(("sync", sub_matches)) => {
                 let use_force = sub_matches.get_one::<bool>("force").is_some_and(|&f| f);
 
+                if let Some(interval) = sub_matches.get_one::<String>("auto")
+                    && let Err(e) = owl_core::set_auto_sync(interval)
+                {
+                    report_owl_err!(e);
+                }
+
                 if let Err(e) = owl_core::sync_git_remote(use_force) {
                     report_owl_err!(e);
                 }
             }
             _ => unreachable!(),
         },
+        Some(("import", sub_matches)) => {
+            let archive = sub_matches.get_one::<String>("ARCHIVE").expect("required");
+            let only = sub_matches
+                .get_many::<String>("only")
+                .map(|parts| parts.cloned().collect::<Vec<String>>())
+                .unwrap_or_default();
+            let force = sub_matches.get_one::<bool>("force").is_some_and(|&f| f);
+
+            if let Err(e) = owl_core::import_env(Path::new(archive), &only, force).await {
+                report_owl_err!(e);
+            }
+        }
         Some(("init", sub_matches)) => {
             let prog = sub_matches.get_one::<String>("PROG").expect("required");
+            let do_merge = sub_matches.get_one::<bool>("merge").is_some_and(|&f| f);
+            let do_overwrite = sub_matches.get_one::<bool>("overwrite").is_some_and(|&f| f);
 
-            let prog_path = Path::new(prog);
-
-            if prog_path.exists() {
-                let e = OwlError::FileError(
-                    format!(
-                        "'{}': file already exists in stash",
-                        prog_path.to_string_lossy()
-                    ),
-                    "".into(),
-                );
+            let on_conflict = match (do_merge, do_overwrite) {
+                (true, _) => owl_core::InitConflict::Merge,
+                (_, true) => owl_core::InitConflict::Overwrite,
+                _ => owl_core::InitConflict::Ask,
+            };
 
+            if let Err(e) = owl_core::init_program(Path::new(prog), on_conflict) {
                 report_owl_err!(e);
             }
+        }
+        Some(("help", sub_matches)) => {
+            let topic = sub_matches.get_one::<String>("TOPIC");
 
-            let action = prog_path
-                .extension()
-                .and_then(OsStr::to_str)
-                .ok_or(OwlError::UriError(
-                    format!("'{}': has no file extension", prog_path.to_string_lossy()),
-                    "None".into(),
-                ))
-                .map(|ext| format!("{}.{}", TEMPLATE_STEM, ext))
-                .and_then(|file_str| {
-                    fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], Some(&file_str))
-                })
-                .map(|path| fs_utils::copy_file(&path, prog_path));
+            let action = match sub_matches.get_one::<String>("man") {
+                Some(man_dir) => owl_core::write_man_pages(cli(), Path::new(man_dir)),
+                None => owl_core::show_help(cli(), topic.map(String::as_str)),
+            };
 
             if let Err(e) = action {
                 report_owl_err!(e);
@@ -592,6 +1519,16 @@ async fn main() {
             let start_from_prompt = sub_matches.get_one::<bool>("prompt").is_some_and(|&f| f);
             let start_from_root = sub_matches.get_one::<bool>("root").is_some_and(|&f| f);
             let use_tui = sub_matches.get_one::<bool>("tui").is_some_and(|&f| f);
+            let as_json = sub_matches
+                .get_one::<String>("format")
+                .is_some_and(|format| format == "json");
+
+            if sub_matches.get_one::<bool>("quests").is_some_and(|&f| f) {
+                if let Err(e) = owl_core::list_quests() {
+                    report_owl_err!(e);
+                }
+                return;
+            }
 
             let target_dir = if start_from_root {
                 fs_utils::ensure_path_from_home(&[OWL_DIR], None).expect("owlgo dir exists")
@@ -606,9 +1543,13 @@ async fn main() {
                     .expect("stash dir exists")
             };
 
-            let action = if use_tui {
+            let action = if as_json {
+                owl_core::list_files_json(&target_dir)
+            } else if use_tui {
                 tui_utils::enter_raw_mode().and_then(|_| {
-                    match FileExplorerApp::default().run(&target_dir) {
+                    match FileExplorerApp::new(config.keymap.clone(), config.theme.clone())
+                        .run(&target_dir)
+                    {
                         Ok(_) => tui_utils::exit_raw_mode(),
                         Err(e) => tui_utils::exit_raw_mode().and(Err(e)),
                     }
@@ -638,29 +1579,137 @@ async fn main() {
                 report_owl_err!(e);
             }
         }
-        Some(("quest", sub_matches)) => {
-            let name = sub_matches.get_one::<String>("NAME").expect("required");
+        Some(("matrix", sub_matches)) => {
+            let quest_name = quest_arg(sub_matches.get_one::<String>("QUEST").expect("required"));
+            let solutions_dir = sub_matches
+                .get_one::<String>("solutions")
+                .map(String::as_str)
+                .unwrap_or(".");
+
+            signal_utils::install_ctrl_c_handler();
+
+            if let Err(e) =
+                owl_core::verify_matrix(&quest_name, Path::new(solutions_dir), &config).await
+            {
+                report_owl_err!(e);
+            }
+        }
+        Some(("merge", sub_matches)) => {
+            let new_name = sub_matches.get_one::<String>("NEW_NAME").expect("required");
+            let quest_names = sub_matches
+                .get_many::<String>("QUESTS")
+                .expect("required")
+                .cloned()
+                .collect::<Vec<String>>();
+
+            if let Err(e) = owl_core::merge_quests(new_name, &quest_names).await {
+                report_owl_err!(e);
+            }
+        }
+        Some(("minimize", sub_matches)) => {
+            let quest_name = quest_arg(sub_matches.get_one::<String>("QUEST").expect("required"));
+            let case_name = sub_matches.get_one::<String>("CASE").expect("required");
             let prog = sub_matches.get_one::<String>("PROG").expect("required");
+            let reference = sub_matches.get_one::<String>("reference");
+
+            signal_utils::install_ctrl_c_handler();
+
+            if let Err(e) = owl_core::minimize_case(
+                &quest_name,
+                case_name,
+                Path::new(prog),
+                reference.map(Path::new),
+                &config,
+            )
+            .await
+            {
+                report_owl_err!(e);
+            }
+        }
+        Some(("pack", sub_matches)) => {
+            let dir = sub_matches
+                .get_one::<String>("DIR")
+                .map(String::as_str)
+                .unwrap_or(".");
+            let name = sub_matches.get_one::<String>("name");
+            let strip_feedback = sub_matches
+                .get_one::<bool>("strip_feedback")
+                .is_some_and(|&f| f);
+            let strip_answers = sub_matches
+                .get_one::<bool>("strip_answers")
+                .is_some_and(|&f| f);
+
+            if let Err(e) = owl_core::pack_quest(
+                Path::new(dir),
+                name.map(String::as_str),
+                strip_feedback,
+                strip_answers,
+            ) {
+                report_owl_err!(e);
+            }
+        }
+        Some(("quest", sub_matches)) => {
+            if let Some(batch_src) = sub_matches.get_one::<String>("batch") {
+                signal_utils::install_ctrl_c_handler();
+
+                if let Err(e) = owl_core::run_batch(batch_src, &config).await {
+                    report_owl_err!(e);
+                }
+
+                return;
+            }
+
+            let (name, prog) = match (
+                sub_matches.get_one::<String>("NAME"),
+                sub_matches.get_one::<String>("PROG"),
+            ) {
+                (Some(name), Some(prog)) => (name, prog),
+                (Some(prog), None) => match &config.quest {
+                    Some(name) => (name, prog),
+                    None => {
+                        let e = OwlError::FileError(
+                            "No quest name provided".into(),
+                            format!("no default 'quest' set in '{}'", PROJECT_CONFIG),
+                        );
+
+                        report_owl_err!(e);
+                    }
+                },
+                _ => unreachable!(),
+            };
+            let name = &quest_arg(name);
             let mut case = sub_matches.get_one::<usize>("CASE").map(|u| u.to_owned());
             let test = sub_matches.get_one::<String>("TEST");
             let use_hints = sub_matches.get_one::<bool>("hints").is_some_and(|&f| f);
             let rand = sub_matches.get_one::<bool>("rand").is_some_and(|&f| f);
+            let seed = sub_matches.get_one::<u64>("seed").copied();
 
-            if rand {
-                case = Some(rand::random::<u64>() as usize);
+            if rand || seed.is_some() {
+                case = Some(owl_core::random_case(seed));
             }
 
+            signal_utils::install_ctrl_c_handler();
+
             let action = match test {
                 Some(test_name) => {
-                    owl_core::quest_once(name, Path::new(prog), test_name, use_hints).await
+                    owl_core::quest_once(name, Path::new(prog), test_name, use_hints, &config).await
                 }
-                None => owl_core::quest(name, Path::new(prog), case, use_hints).await,
+                None => owl_core::quest(name, Path::new(prog), case, use_hints, &config).await,
             };
 
             if let Err(e) = action {
                 report_owl_err!(e);
             }
         }
+        Some(("rerun", sub_matches)) => {
+            let verbose = sub_matches.get_one::<bool>("verbose").is_some_and(|&f| f);
+
+            signal_utils::install_ctrl_c_handler();
+
+            if let Err(e) = owl_core::rerun(verbose, &config).await {
+                report_owl_err!(e);
+            }
+        }
         Some(("restore", sub_matches)) => {
             let prog = sub_matches.get_one::<String>("PROG").expect("required");
             let prog_path = Path::new(prog);
@@ -673,8 +1722,7 @@ async fn main() {
                     "None".into(),
                 ))
                 .and_then(|file_name| {
-                    let stash_path =
-                        fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], Some(file_name))?;
+                    let stash_path = fs_utils::stash_path(file_name)?;
 
                     fs_utils::copy_file(&stash_path, prog_path)
                 });
@@ -684,10 +1732,41 @@ async fn main() {
             }
         }
         Some(("review", sub_matches)) => {
-            let prog = sub_matches.get_one::<String>("PROG").expect("required");
-            let prompt = sub_matches
-                .get_one::<String>("PROMPT")
-                .map(String::to_owned);
+            let no_prog = sub_matches.get_one::<bool>("no_prog").is_some_and(|&f| f);
+            let all_stashed = sub_matches
+                .get_one::<bool>("all_stashed")
+                .is_some_and(|&f| f);
+            let since_stash = sub_matches
+                .get_one::<bool>("since_stash")
+                .is_some_and(|&f| f);
+
+            let (prog, prompt) = match (
+                sub_matches.get_one::<String>("PROG"),
+                sub_matches.get_one::<String>("PROMPT"),
+            ) {
+                (Some(prompt), None) if no_prog || all_stashed => (None, Some(prompt.to_owned())),
+                (_, Some(_)) if no_prog || all_stashed => {
+                    let e = OwlError::Unsupported(format!(
+                        "'{}': expected a single prompt/desc and no program",
+                        if no_prog {
+                            "--no-prog"
+                        } else {
+                            "--all-stashed"
+                        }
+                    ));
+
+                    report_owl_err!(e);
+                }
+                (None, None) => {
+                    let e = OwlError::FileError(
+                        "No prompt/desc provided".into(),
+                        "'--no-prog' requires a prompt/desc (e.g., '-q <NAME>')".into(),
+                    );
+
+                    report_owl_err!(e);
+                }
+                (prog, prompt) => (prog.map(Path::new), prompt.map(String::to_owned)),
+            };
 
             let ai_sdk = sub_matches.get_one::<String>("sdk");
             let api_key = sub_matches.get_one::<String>("key");
@@ -740,6 +1819,40 @@ async fn main() {
                 }
             }
 
+            if all_stashed {
+                let quest_name = prompt.expect("quest name is provided");
+
+                if let Err(e) = owl_core::review_stashed(&quest_name, do_forget, &config).await {
+                    report_owl_err!(e);
+                }
+
+                return;
+            }
+
+            let check_prompt = prompt.map(|prompt_str| {
+                if is_file {
+                    ReviewPrompt::IsFile(PathBuf::from(prompt_str))
+                } else if in_stash {
+                    ReviewPrompt::InStash(prompt_str)
+                } else if in_quest {
+                    ReviewPrompt::InQuest(prompt_str)
+                } else {
+                    ReviewPrompt::UserPrompt(prompt_str)
+                }
+            });
+
+            if since_stash {
+                let prog = prog.expect("program is provided");
+
+                if let Err(e) =
+                    owl_core::review_since_stash(prog, check_prompt, do_forget, &config).await
+                {
+                    report_owl_err!(e);
+                }
+
+                return;
+            }
+
             let mode = if use_debug {
                 PromptMode::Debug
             } else if use_explain {
@@ -750,27 +1863,32 @@ async fn main() {
                 PromptMode::Optimize
             } else if use_test {
                 PromptMode::Test
-            } else if prompt.is_some() && !use_default {
+            } else if check_prompt.is_some() && !use_default {
                 PromptMode::Custom
             } else {
                 PromptMode::Default
             };
 
-            let check_prompt = prompt.map(|prompt_str| {
-                if is_file {
-                    ReviewPrompt::IsFile(PathBuf::from(prompt_str))
-                } else if in_stash {
-                    ReviewPrompt::InStash(prompt_str)
-                } else if in_quest {
-                    ReviewPrompt::InQuest(prompt_str)
-                } else {
-                    ReviewPrompt::UserPrompt(prompt_str)
-                }
-            });
-
-            if let Err(e) =
-                owl_core::review_program(Path::new(prog), check_prompt, mode, do_forget, use_tui)
-                    .await
+            let workspace = WorkspaceScope::new(
+                sub_matches
+                    .get_many::<String>("include")
+                    .into_iter()
+                    .flatten()
+                    .map(PathBuf::from)
+                    .collect(),
+                sub_matches.get_one::<usize>("budget").copied(),
+            );
+
+            if let Err(e) = owl_core::review_program(
+                prog,
+                check_prompt,
+                mode,
+                do_forget,
+                use_tui,
+                &workspace,
+                &config,
+            )
+            .await
             {
                 report_owl_err!(e);
             }
@@ -778,7 +1896,27 @@ async fn main() {
         Some(("run", sub_matches)) => {
             let prog = sub_matches.get_one::<String>("PROG").expect("required");
 
-            if let Err(e) = owl_core::run_program(Path::new(prog)) {
+            signal_utils::install_ctrl_c_handler();
+
+            if let Err(e) = owl_core::run_program(Path::new(prog), &config) {
+                report_owl_err!(e);
+            }
+        }
+        Some(("serve", sub_matches)) => {
+            let port = sub_matches.get_one::<u16>("port").expect("has default");
+
+            if let Err(e) = owl_core::serve(*port, &config).await {
+                report_owl_err!(e);
+            }
+        }
+        Some(("share", sub_matches)) => {
+            let file_str = sub_matches.get_one::<String>("FILE").expect("required");
+            let description = sub_matches.get_one::<String>("description");
+            let public = sub_matches.get_one::<bool>("public").is_some_and(|&f| f);
+
+            if let Err(e) =
+                owl_core::share_file(file_str, description.map(String::as_str), public).await
+            {
                 report_owl_err!(e);
             }
         }
@@ -790,48 +1928,58 @@ async fn main() {
             let show_program = sub_matches.get_one::<bool>("program").is_some_and(|&f| f);
             let show_prompt = sub_matches.get_one::<bool>("prompt").is_some_and(|&f| f);
             let rand = sub_matches.get_one::<bool>("rand").is_some_and(|&f| f);
+            let show_full = sub_matches.get_one::<bool>("full").is_some_and(|&f| f);
             let use_tui = sub_matches.get_one::<bool>("tui").is_some_and(|&f| f);
 
             let action = if show_program || show_prompt || show_manifest {
-                let path = if show_manifest {
+                let path_result = if show_manifest {
                     fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))
-                        .expect("manifest exists")
                 } else {
                     let name = sub_matches.get_one::<String>("NAME").expect("required");
 
                     if show_program {
-                        fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], Some(name))
-                            .expect("program exists")
+                        fs_utils::stash_path(name)
                     } else {
-                        fs_utils::ensure_path_from_home(
-                            &[OWL_DIR, STASH_DIR, PROMPT_DIR],
-                            Some(name),
-                        )
-                        .expect("prompt exists")
+                        fs_utils::prompt_path(name)
+                    }
+                };
+
+                let path = match path_result {
+                    Ok(path) => path,
+                    Err(e) => {
+                        report_owl_err!(e);
                     }
                 };
 
                 if use_tui {
-                    tui_utils::enter_raw_mode().and_then(|_| match FileApp::default().run(&path) {
-                        Ok(_) => tui_utils::exit_raw_mode(),
-                        Err(e) => tui_utils::exit_raw_mode().and(Err(e)),
+                    tui_utils::enter_raw_mode().and_then(|_| {
+                        match FileApp::new(config.keymap.clone(), config.theme.clone())
+                            .with_full(show_full)
+                            .run(&path)
+                        {
+                            Ok(_) => tui_utils::exit_raw_mode(),
+                            Err(e) => tui_utils::exit_raw_mode().and(Err(e)),
+                        }
                     })
                 } else if show_manifest || show_program {
-                    owl_core::show_it(&path)
+                    owl_core::show_it(&path, show_full)
                 } else {
                     owl_core::show_and_glow(&path)
                 }
             } else {
-                let name = sub_matches.get_one::<String>("NAME").expect("required");
+                let name = &quest_arg(sub_matches.get_one::<String>("NAME").expect("required"));
 
                 if let Some(test_name) = test {
-                    owl_core::show_test(name, test_name, show_ans, use_tui).await
+                    owl_core::show_test(name, test_name, show_ans, show_full, use_tui, &config)
+                        .await
                 } else {
-                    if rand {
-                        case = Some(rand::random::<u64>() as usize);
+                    let seed = sub_matches.get_one::<u64>("seed").copied();
+
+                    if rand || seed.is_some() {
+                        case = Some(owl_core::random_case(seed));
                     }
 
-                    owl_core::show_quest(name, case, show_ans, use_tui).await
+                    owl_core::show_quest(name, case, show_ans, show_full, use_tui, &config).await
                 }
             };
 
@@ -843,37 +1991,99 @@ async fn main() {
             let prog = sub_matches.get_one::<String>("PROG").expect("required");
             let is_templ = sub_matches.get_one::<bool>("template").is_some_and(|&f| f);
             let is_prompt = sub_matches.get_one::<bool>("prompt").is_some_and(|&f| f);
+            let use_force = sub_matches.get_one::<bool>("force").is_some_and(|&f| f);
+            let use_history = sub_matches.get_one::<bool>("history").is_some_and(|&f| f);
+
+            let on_conflict = match (use_force, use_history) {
+                (true, _) => owl_core::StashConflict::Overwrite,
+                (_, true) => owl_core::StashConflict::Version,
+                _ => owl_core::StashConflict::Refuse,
+            };
+
+            let formatters = config.formatters.on_stash.then_some(&config.formatters);
+
+            if let Err(e) = owl_core::stash_file(
+                Path::new(prog),
+                is_templ,
+                is_prompt,
+                on_conflict,
+                formatters,
+            ) {
+                report_owl_err!(e);
+            }
+        }
+        Some(("sync", sub_matches)) => {
+            let remote = sub_matches.get_one::<String>("REMOTE").expect("required");
+            let restore = sub_matches.get_one::<bool>("restore").is_some_and(|&f| f);
+            let use_force = sub_matches.get_one::<bool>("force").is_some_and(|&f| f);
+
+            let action = if restore {
+                owl_core::restore_stash(remote, use_force).await
+            } else {
+                owl_core::sync_stash(remote).await
+            };
 
-            if let Err(e) = owl_core::stash_file(Path::new(prog), is_templ, is_prompt) {
+            if let Err(e) = action {
                 report_owl_err!(e);
             }
         }
         Some(("test", sub_matches)) => {
             let prog = sub_matches.get_one::<String>("PROG").expect("required");
             let in_file = sub_matches.get_one::<String>("IN").expect("required");
-            let ans_file = sub_matches.get_one::<String>("ANS").expect("required");
-
-            if let Err(e) =
-                owl_core::test_program(Path::new(prog), Path::new(in_file), Path::new(ans_file))
-            {
+            let ans_file = sub_matches.get_one::<String>("ANS");
+
+            if let Err(e) = owl_core::test_program(
+                Path::new(prog),
+                Path::new(in_file),
+                ans_file.map(Path::new),
+                &config,
+            ) {
                 report_owl_err!(e);
             }
         }
-        Some(("update", _)) => {
-            let header_url = Url::parse(MANIFEST_HEAD_URL).expect("remote manifest header is URL");
-            let manifest_url = Url::parse(MANIFEST_URL).expect("remote manifest is URL");
+        Some(("todos", sub_matches)) => {
+            let chat_name = sub_matches.get_one::<String>("CHAT");
+            let done = sub_matches
+                .get_many::<usize>("done")
+                .map(|ns| ns.copied().collect::<Vec<usize>>())
+                .unwrap_or_default();
+            let undo = sub_matches
+                .get_many::<usize>("undo")
+                .map(|ns| ns.copied().collect::<Vec<usize>>())
+                .unwrap_or_default();
+
+            if let Err(e) = owl_core::show_todos(chat_name.map(String::as_str), &done, &undo) {
+                report_owl_err!(e);
+            }
+        }
+        Some(("update", sub_matches)) => {
+            let review = sub_matches.get_one::<bool>("review").is_some_and(|&f| f);
+
+            let header_url = config.header_url.clone().unwrap_or_else(|| {
+                Url::parse(MANIFEST_HEAD_URL).expect("remote manifest header is URL")
+            });
+            let manifest_url = config
+                .manifest_url
+                .clone()
+                .unwrap_or_else(|| Url::parse(MANIFEST_URL).expect("remote manifest is URL"));
             let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))
                 .expect("owlgo dir exists");
             let prompt_dir =
                 fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, PROMPT_DIR], None)
                     .expect("prompt dir exists");
 
+            let header_cache =
+                fs_utils::ensure_path_from_home(&[OWL_DIR, CACHE_DIR], Some(REMOTE_HEADER))
+                    .expect("cache dir exists");
+
             if let Err(e) = toml_utils::update_manifest(
                 &header_url,
+                &header_cache,
                 &manifest_url,
                 &manifest_path,
                 &prompt_dir,
                 Path::new(TMP_ARCHIVE),
+                review,
             )
             .await
             {
@@ -882,40 +2092,41 @@ async fn main() {
         }
         Some(("version", sub_matches)) => {
             let lang = sub_matches.get_one::<String>("lang");
+            let format = sub_matches.get_one::<String>("format").expect("default");
 
             let action = match lang {
                 Some(ext) => prog_utils::try_prog_lang(ext)
-                    .and_then(|prog_lang| prog_lang.version())
+                    .and_then(|prog_lang| prog_lang.version(&config))
                     .map(|stdout| println!("{}", stdout)),
-                None => fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST)).and_then(
-                    |manifest_path| {
-                        if !manifest_path.exists() {
-                            toml_utils::create_toml(&manifest_path, TOML_TEMPLATE)?;
-                        }
-
-                        let version = toml_utils::get_embedded_version(TOML_TEMPLATE)?;
-                        let (manifest_version, timestamp) =
-                            toml_utils::get_manifest_version_timestamp(&manifest_path)?;
-
-                        println!("owlgo version {}", version);
-
-                        if toml_utils::compare_stamps(&manifest_version, &version)?
-                            == Ordering::Less
-                            || timestamp == "0.0.0"
-                        {
-                            println!("\nmanifest out of date...");
-                            println!("run `owlgo update`");
-                        }
-
-                        Ok(())
-                    },
-                ),
+                None => owl_core::show_version(format == "json", &config).await,
             };
 
             if let Err(e) = action {
                 report_owl_err!(e);
             }
         }
+        Some(("where", sub_matches)) => {
+            let key = sub_matches.get_one::<String>("KEY");
+            let quest_name = sub_matches.get_one::<String>("quest");
+            let prog = sub_matches.get_one::<String>("prog");
+            let format = sub_matches.get_one::<String>("format").expect("default");
+
+            if let Err(e) = owl_core::show_paths(
+                key.map(String::as_str),
+                quest_name.map(String::as_str),
+                prog.map(Path::new),
+                format == "json",
+            ) {
+                report_owl_err!(e);
+            }
+        }
+        Some(("which-lang", sub_matches)) => {
+            let prog = sub_matches.get_one::<String>("PROG").expect("required");
+
+            if let Err(e) = owl_core::which_lang(Path::new(prog), &config) {
+                report_owl_err!(e);
+            }
+        }
         _ => unreachable!(),
     }
 }
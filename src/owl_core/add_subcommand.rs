@@ -1,32 +1,46 @@
-use crate::common::{OwlError, Result};
-use crate::owl_utils::{Uri, fs_utils, toml_utils};
-use crate::{MANIFEST, OWL_DIR, PROMPT_DIR, STASH_DIR, TMP_ARCHIVE, TOML_TEMPLATE};
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::fs::manifest;
+use crate::owl_utils::{Manifest, Uri, fs_utils, toml_utils};
+use crate::{OWL_DIR, PROMPT_DIR, STASH_DIR, TMP_ARCHIVE};
 use std::path::Path;
-use toml_edit::{DocumentMut, value};
+use toml_edit::{Item, value};
+
+pub async fn add_extension(
+    ext_name: &str,
+    ext_uri: &Uri,
+    pub_key: Option<&str>,
+    and_fetch: bool,
+) -> Result<()> {
+    fs_utils::check_name(ext_name, "extension")?;
+
+    if let Some(pub_key) = pub_key {
+        toml_utils::parse_public_key(pub_key)?;
+    }
 
-pub async fn add_extension(ext_name: &str, ext_uri: &Uri, and_fetch: bool) -> Result<()> {
-    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
     let prompt_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, PROMPT_DIR], None)?;
+    let mut manifest = Manifest::load()?;
 
-    let mut manifest_doc = if manifest_path.exists() {
-        toml_utils::read_toml(&manifest_path)?
-    } else {
-        TOML_TEMPLATE.parse::<DocumentMut>().map_err(|e| {
-            OwlError::TomlError("Faild to parse TOML template".into(), e.to_string())
-        })?
-    };
+    let ext_doc = toml_utils::read_extension(ext_uri, pub_key).await?;
+    let manifest_doc = manifest.doc_mut();
 
-    let (uri_str, ext_doc) = match ext_uri {
-        Uri::Local(path) => {
-            let uri_str = path
-                .to_str()
-                .ok_or(OwlError::UriError("Invalid URI".into(), "None".into()))?;
-            (uri_str, toml_utils::read_toml(path)?)
+    match pub_key {
+        Some(pub_key) => {
+            manifest::set_entry(manifest_doc, "ext_keys", ext_name, value(pub_key.trim()))
         }
-        Uri::Remote(url) => (url.as_str(), toml_utils::request_toml(url).await?),
-    };
-
-    manifest_doc["extensions"][ext_name] = value(uri_str);
+        None => {
+            let unpinned = manifest_doc
+                .get_mut("ext_keys")
+                .and_then(Item::as_table_like_mut)
+                .and_then(|ext_keys| ext_keys.remove(ext_name));
+
+            if unpinned.is_some() {
+                warn(OwlWarning::Recovered(format!(
+                    "'{}': public key unpinned, signatures are no longer verified",
+                    ext_name
+                )));
+            }
+        }
+    }
 
     let some_tmp_archive = if and_fetch {
         Some(Path::new(TMP_ARCHIVE))
@@ -35,42 +49,25 @@ pub async fn add_extension(ext_name: &str, ext_uri: &Uri, and_fetch: bool) -> Re
     };
 
     toml_utils::commit_extension(
-        &manifest_path,
+        &mut manifest,
         &prompt_dir,
         ext_name,
         ext_uri,
         &ext_doc,
-        &mut manifest_doc,
         some_tmp_archive,
     )
     .await
 }
 
 pub async fn add_prompt(prompt_name: &str, uri: &Uri, and_fetch: bool) -> Result<()> {
-    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
-
-    let mut manifest_doc = if manifest_path.exists() {
-        toml_utils::read_toml(&manifest_path)?
-    } else {
-        TOML_TEMPLATE.parse::<DocumentMut>().map_err(|e| {
-            OwlError::TomlError("Failed to parse TOML template".into(), e.to_string())
-        })?
-    };
+    fs_utils::check_name(prompt_name, "prompt")?;
 
-    let uri_str = match uri {
-        Uri::Local(path) => path
-            .to_str()
-            .ok_or(OwlError::UriError("Invalid URI".into(), "None".into()))?,
-        Uri::Remote(url) => url.as_str(),
-    };
-
-    manifest_doc["personal_prompts"][prompt_name] = value(uri_str);
-
-    toml_utils::write_manifest(&manifest_doc, &manifest_path)?;
+    let mut manifest = Manifest::load()?;
+    manifest.set_personal_prompt(prompt_name, uri)?;
+    manifest.write()?;
 
     if and_fetch {
-        let prompt_dir =
-            fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, PROMPT_DIR], Some(prompt_name))?;
+        let prompt_dir = fs_utils::prompt_path(prompt_name)?;
 
         match uri {
             Uri::Local(path) => fs_utils::copy_file(path, &prompt_dir)?,
@@ -81,35 +78,44 @@ pub async fn add_prompt(prompt_name: &str, uri: &Uri, and_fetch: bool) -> Result
     Ok(())
 }
 
-pub async fn add_quest(quest_name: &str, uri: &Uri, and_fetch: bool) -> Result<()> {
-    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+/// Registers a quest whose tests are an unpacked folder rather than an archive, pairing and
+/// importing them right away.
+pub async fn add_local_quest(quest_name: &str, tests_dir: &Path) -> Result<()> {
+    if !tests_dir.is_dir() {
+        return Err(OwlError::FileError(
+            format!("'{}': no such dir", tests_dir.to_string_lossy()),
+            "'--local-tests' expects an unpacked folder of test files".into(),
+        ));
+    }
 
-    let mut manifest_doc = if manifest_path.exists() {
-        toml_utils::read_toml(&manifest_path)?
-    } else {
-        TOML_TEMPLATE.parse::<DocumentMut>().map_err(|e| {
-            OwlError::TomlError("Failed to parse TOML template".into(), e.to_string())
-        })?
-    };
+    let tests_dir = tests_dir.canonicalize().map_err(|e| {
+        OwlError::FileError(
+            format!("Failed to access dir '{}'", tests_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
 
-    let uri_str = match uri {
-        Uri::Local(path) => path
-            .to_str()
-            .ok_or(OwlError::UriError("Invalid URI".into(), "None".into()))?,
-        Uri::Remote(url) => url.as_str(),
-    };
+    add_quest(quest_name, &Uri::Local(tests_dir), true).await
+}
 
-    manifest_doc["personal_quests"][quest_name] = value(uri_str);
+pub async fn add_quest(quest_name: &str, uri: &Uri, and_fetch: bool) -> Result<()> {
+    fs_utils::check_name(quest_name, "quest")?;
 
-    toml_utils::write_manifest(&manifest_doc, &manifest_path)?;
+    let mut manifest = Manifest::load()?;
+    manifest.set_personal_quest(quest_name, uri)?;
+    manifest.write()?;
 
     if and_fetch {
-        let quest_dir = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(quest_name))?;
+        let quest_dir = fs_utils::quest_path(quest_name)?;
 
         match uri {
-            Uri::Local(path) => fs_utils::extract_archive(path, &quest_dir, false).await?,
+            Uri::Local(path) if path.is_dir() => {
+                let cases = fs_utils::import_local_tests(path, &quest_dir)?;
+                println!("paired {} test cases for '{}'", cases, quest_name);
+            }
+            Uri::Local(path) => fs_utils::extract_archive(path, &quest_dir, false, None).await?,
             Uri::Remote(url) => {
-                fs_utils::download_archive(url, Path::new(TMP_ARCHIVE), &quest_dir).await?
+                fs_utils::download_archive(url, Path::new(TMP_ARCHIVE), &quest_dir, None).await?
             }
         }
     }
@@ -0,0 +1,398 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{OwlConfig, fs_utils, prog_utils};
+use crate::{ATTEST_KEY, OWL_DIR};
+use chrono::Local;
+use ring::rand::SystemRandom;
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use toml_edit::{ArrayOfTables, DocumentMut, Table, value};
+
+const ATTEST_FORMAT: i64 = 2;
+const ATTEST_SUFFIX: &str = ".attest.toml";
+const SIGNATURE_HEADER: &str = "\n[signature]\n";
+
+/// Judges `prog` on every case of a quest and writes a signed record of the verdicts.
+pub async fn attest_quest(
+    quest_name: &str,
+    prog: &Path,
+    out_path: Option<&Path>,
+    config: &OwlConfig,
+) -> Result<()> {
+    let quest_path = fs_utils::quest_path(quest_name)?;
+
+    if !quest_path.exists() {
+        super::fetch_quest(quest_name).await?;
+    }
+
+    if !prog.exists() {
+        return Err(OwlError::FileError(
+            format!("'{}': no such file", prog.to_string_lossy()),
+            "".into(),
+        ));
+    }
+
+    if prog_utils::resolve_prog_lang(prog, config).is_none() {
+        return Err(OwlError::Unsupported(format!(
+            "'{}': unknown language",
+            prog.to_string_lossy()
+        )));
+    }
+
+    let key_pair = attest_key()?;
+    let source_hash = source_sha256(prog)?;
+    let cases_hash = cases_sha256(&quest_path)?;
+
+    let started = Local::now();
+    let summary = super::judge_quest(&quest_path, prog, None, false, config)?;
+    let finished = Local::now();
+
+    let mut attest_doc = DocumentMut::new();
+    attest_doc["format"] = value(ATTEST_FORMAT);
+    attest_doc["quest"] = value(quest_name);
+    attest_doc["program"] = value(
+        prog.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    );
+    attest_doc["source_sha256"] = value(&source_hash);
+    attest_doc["cases_sha256"] = value(&cases_hash);
+    attest_doc["owlgo_version"] = value(env!("CARGO_PKG_VERSION"));
+    attest_doc["started"] = value(started.to_rfc3339());
+    attest_doc["finished"] = value(finished.to_rfc3339());
+    attest_doc["verdict"] = value(if summary.failed == 0 && summary.passed > 0 {
+        "accepted"
+    } else {
+        "failed"
+    });
+    attest_doc["passed"] = value(summary.passed as i64);
+    attest_doc["failed"] = value(summary.failed as i64);
+    attest_doc["elapsed_ms"] = value(summary.elapsed.as_millis() as i64);
+
+    let mut machine = Table::new();
+    machine["os"] = value(env::consts::OS);
+    machine["arch"] = value(env::consts::ARCH);
+    machine["host"] = value(host_name());
+    machine["user"] = value(
+        env::var("USER")
+            .or_else(|_| env::var("USERNAME"))
+            .unwrap_or_default(),
+    );
+    attest_doc["machine"] = machine.into();
+
+    let mut cases = ArrayOfTables::new();
+
+    for case in summary.cases.iter() {
+        let mut case_table = Table::new();
+        case_table["name"] = value(&case.name);
+        case_table["passed"] = value(case.passed());
+
+        if let Some(elapsed) = case.elapsed {
+            case_table["elapsed_ms"] = value(elapsed.as_millis() as i64);
+        }
+
+        cases.push(case_table);
+    }
+
+    attest_doc["cases"] = cases.into();
+
+    let body = attest_doc.to_string();
+    let signature = key_pair.sign(body.as_bytes());
+
+    let attestation = format!(
+        "{}{}public_key = \"{}\"\nsignature = \"{}\"\n",
+        body,
+        SIGNATURE_HEADER,
+        to_hex(key_pair.public_key().as_ref()),
+        to_hex(signature.as_ref())
+    );
+
+    let out_path = out_path
+        .map(Path::to_path_buf)
+        .unwrap_or(PathBuf::from(format!("{}{}", quest_name, ATTEST_SUFFIX)));
+
+    fs::write(&out_path, attestation).map_err(|e| {
+        OwlError::FileError(
+            format!("could not write '{}'", out_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    println!(
+        "attested {}/{} passed for '{}' in '{}'",
+        summary.passed,
+        summary.passed + summary.failed,
+        quest_name,
+        out_path.to_string_lossy()
+    );
+
+    Ok(())
+}
+
+/// Checks an attestation's signature, optionally against a known public key and the source
+/// it claims to have judged.
+pub fn verify_attestation(
+    attest_path: &Path,
+    pub_key: Option<&str>,
+    source: Option<&Path>,
+) -> Result<()> {
+    let attestation = fs::read_to_string(attest_path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read '{}'", attest_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let invalid = |info: &str| {
+        OwlError::FileError(
+            format!("'{}': invalid attestation", attest_path.to_string_lossy()),
+            info.into(),
+        )
+    };
+
+    let body = attestation
+        .rfind(SIGNATURE_HEADER)
+        .map(|i| &attestation[..i])
+        .ok_or_else(|| invalid("missing signature"))?;
+
+    let attest_doc = attestation
+        .parse::<DocumentMut>()
+        .map_err(|e| invalid(&e.to_string()))?;
+
+    let get_item = |table: &str, key: &str| match table {
+        "" => attest_doc.get(key),
+        table => attest_doc.get(table).and_then(|item| item.get(key)),
+    };
+    let get_str = |table: &str, key: &str| {
+        get_item(table, key)
+            .and_then(|item| item.as_str())
+            .map(String::from)
+    };
+    let get_int = |key: &str| {
+        get_item("", key)
+            .and_then(|item| item.as_integer())
+            .unwrap_or_default()
+    };
+
+    if get_int("format") != ATTEST_FORMAT {
+        return Err(OwlError::Unsupported(format!(
+            "attestation format is not supported (expected {})",
+            ATTEST_FORMAT
+        )));
+    }
+
+    let key_hex = get_str("signature", "public_key").ok_or_else(|| invalid("missing key"))?;
+    let sig_hex = get_str("signature", "signature").ok_or_else(|| invalid("missing signature"))?;
+
+    let key_bytes = from_hex(&key_hex).ok_or_else(|| invalid("malformed public key"))?;
+    let sig_bytes = from_hex(&sig_hex).ok_or_else(|| invalid("malformed signature"))?;
+
+    UnparsedPublicKey::new(&ED25519, &key_bytes)
+        .verify(body.as_bytes(), &sig_bytes)
+        .map_err(|_| invalid("signature does not match its contents"))?;
+
+    if let Some(pub_key) = pub_key
+        && !pub_key.trim().eq_ignore_ascii_case(&key_hex)
+    {
+        return Err(invalid("signed by a different key than expected"));
+    }
+
+    if let Some(source) = source {
+        let expected_hash = get_str("", "source_sha256").unwrap_or_default();
+
+        if source_sha256(source)? != expected_hash {
+            return Err(invalid(&format!(
+                "'{}' is not the attested source",
+                source.to_string_lossy()
+            )));
+        }
+    }
+
+    let quest_name = get_str("", "quest").unwrap_or_default();
+    let expected_cases = get_str("", "cases_sha256").ok_or_else(|| invalid("missing cases"))?;
+    let quest_path = fs_utils::quest_path(&quest_name)?;
+    let cases_checked = quest_path.exists();
+
+    if cases_checked && cases_sha256(&quest_path)? != expected_cases {
+        return Err(invalid(&format!(
+            "the cases of '{}' differ from the attested ones",
+            quest_name
+        )));
+    }
+
+    // any key makes a self-consistent signature, so only a pinned key says who signed it
+    let status = match pub_key {
+        Some(_) => "\x1b[32mvalid attestation\x1b[0m",
+        None => "\x1b[33mself-signed attestation, not independently verified\x1b[0m",
+    };
+
+    println!(
+        "{}: '{}' {} ({}/{} passed) at {}",
+        status,
+        quest_name,
+        get_str("", "verdict").unwrap_or_default(),
+        get_int("passed"),
+        get_int("passed") + get_int("failed"),
+        get_str("", "finished").unwrap_or_default()
+    );
+    println!(
+        "program: {} (sha256 {})",
+        get_str("", "program").unwrap_or_default(),
+        get_str("", "source_sha256").unwrap_or_default()
+    );
+    println!(
+        "cases: sha256 {}{}",
+        expected_cases,
+        if cases_checked {
+            ""
+        } else {
+            " (quest not found locally, so not checked)"
+        }
+    );
+    println!(
+        "machine: {}{} ({}/{})",
+        get_str("machine", "user")
+            .filter(|user| !user.is_empty())
+            .map(|user| format!("{}@", user))
+            .unwrap_or_default(),
+        get_str("machine", "host").unwrap_or_default(),
+        get_str("machine", "os").unwrap_or_default(),
+        get_str("machine", "arch").unwrap_or_default()
+    );
+    println!("key: {}", key_hex);
+
+    if pub_key.is_none() {
+        println!("(pass the signer's '--pubkey' to verify who signed it)");
+    }
+
+    Ok(())
+}
+
+/// Loads the signing key from the owlgo dir, creating it on first use.
+fn attest_key() -> Result<Ed25519KeyPair> {
+    let key_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(ATTEST_KEY))?;
+
+    if !key_path.exists() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).map_err(|e| {
+            OwlError::FileError("could not generate attestation key".into(), e.to_string())
+        })?;
+
+        let mut key_options = OpenOptions::new();
+        key_options.write(true).create_new(true);
+
+        // readable by the owner only from the moment it exists
+        #[cfg(unix)]
+        key_options.mode(0o600);
+
+        key_options
+            .open(&key_path)
+            .and_then(|mut key_file| key_file.write_all(pkcs8.as_ref()))
+            .map_err(|e| {
+                OwlError::FileError(
+                    format!("could not write '{}'", key_path.to_string_lossy()),
+                    e.to_string(),
+                )
+            })?;
+
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).map_err(|e| {
+            OwlError::FileError("could not load attestation key".into(), e.to_string())
+        })?;
+
+        eprintln!(
+            "created attestation key '{}'; share its public key with your instructor:\n{}\n",
+            key_path.to_string_lossy(),
+            to_hex(key_pair.public_key().as_ref())
+        );
+
+        return Ok(key_pair);
+    }
+
+    let pkcs8 = fs::read(&key_path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read '{}'", key_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    Ed25519KeyPair::from_pkcs8(&pkcs8).map_err(|e| {
+        OwlError::FileError(
+            format!("'{}': invalid attestation key", key_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })
+}
+
+fn source_sha256(prog: &Path) -> Result<String> {
+    let source = fs::read(prog).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read '{}'", prog.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    Ok(format!("{:x}", Sha256::digest(&source)))
+}
+
+/// A digest of a quest's cases (each input and its answers, by path), so an attestation
+/// can't be passed off for a quest whose cases have changed.
+fn cases_sha256(quest_path: &Path) -> Result<String> {
+    let mut test_cases = if quest_path.is_dir() {
+        fs_utils::find_by_ext(quest_path, "in")?
+    } else {
+        vec![quest_path.to_path_buf()]
+    };
+    test_cases.sort();
+
+    let mut hasher = Sha256::new();
+
+    for test_case in test_cases {
+        let case_files = std::iter::once(test_case.clone()).chain(super::find_answers(&test_case)?);
+
+        for case_file in case_files {
+            let contents = fs::read(&case_file).map_err(|e| {
+                OwlError::FileError(
+                    format!("could not read '{}'", case_file.to_string_lossy()),
+                    e.to_string(),
+                )
+            })?;
+            let rel_path = case_file.strip_prefix(quest_path).unwrap_or(&case_file);
+
+            // lengths keep one file's end from passing for the next one's start
+            for part in [rel_path.to_string_lossy().as_bytes(), &contents] {
+                hasher.update((part.len() as u64).to_le_bytes());
+                hasher.update(part);
+            }
+        }
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn host_name() -> String {
+    env::var("HOSTNAME")
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .or_else(|| env::var("COMPUTERNAME").ok())
+        .map(|host| host.trim().to_string())
+        .unwrap_or_default()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,285 @@
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::{Manifest, OwlConfig, fs_utils, prog_utils, signal_utils, toml_utils};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+struct BatchRow {
+    quest: String,
+    prog: String,
+    verdict: String,
+    passed: usize,
+    total: usize,
+    elapsed_ms: u128,
+}
+
+pub async fn run_batch(batch_src: &str, config: &OwlConfig) -> Result<()> {
+    let pairs = batch_pairs(batch_src)?;
+
+    if pairs.is_empty() {
+        return Err(OwlError::FileError(
+            format!("'{}': no quest/program pairs found", batch_src),
+            "expected a TOML/CSV list, a directory, or a pattern like 'sols/*.cpp'".into(),
+        ));
+    }
+
+    let mut rows = Vec::new();
+
+    for (quest_name, prog) in pairs.iter() {
+        if signal_utils::is_interrupted() {
+            break;
+        }
+
+        let prog_str = prog.to_string_lossy().to_string();
+
+        println!("\x1b[36m[{}]\x1b[0m {}", quest_name, prog_str);
+
+        let mut row = BatchRow {
+            quest: quest_name.clone(),
+            prog: prog_str,
+            verdict: "".into(),
+            passed: 0,
+            total: 0,
+            elapsed_ms: 0,
+        };
+
+        row.verdict = match judge_pair(quest_name, prog, config).await {
+            Ok(summary) => {
+                row.passed = summary.passed;
+                row.total = summary.passed + summary.failed;
+                row.elapsed_ms = summary.elapsed.as_millis();
+
+                if summary.failed > 0 {
+                    "failed".into()
+                } else {
+                    "accepted".into()
+                }
+            }
+            Err(verdict) => verdict,
+        };
+
+        rows.push(row);
+    }
+
+    let cells = rows
+        .iter()
+        .map(|row| {
+            [
+                row.quest.clone(),
+                row.prog.clone(),
+                row.verdict.clone(),
+                format!("{}/{}", row.passed, row.total),
+                format!("{}ms", row.elapsed_ms),
+            ]
+        })
+        .collect::<Vec<[String; 5]>>();
+
+    println!(
+        "{}",
+        super::matrix_subcommand::verdict_table(
+            &format!("batch for '{}'", batch_src),
+            ["quest", "solution", "verdict", "passed", "elapsed"],
+            &cells,
+        )
+    );
+
+    if signal_utils::is_interrupted() {
+        return Err(OwlError::Interrupted(format!(
+            "interrupted after {} quests",
+            rows.len()
+        )));
+    }
+
+    let rejected = rows.iter().filter(|row| row.verdict != "accepted").count();
+
+    if rejected > 0 {
+        Err(OwlError::TestFailure(format!(
+            "{}/{} quests not accepted",
+            rejected,
+            rows.len()
+        )))
+    } else {
+        println!("\x1b[32mall quests accepted\x1b[0m 🏆🏆🏆\n");
+        Ok(())
+    }
+}
+
+async fn judge_pair(
+    quest_name: &str,
+    prog: &Path,
+    config: &OwlConfig,
+) -> std::result::Result<super::quest_subcommand::QuestSummary, String> {
+    let quest_path = fs_utils::quest_path(quest_name).map_err(|e| report(e, "no quest"))?;
+
+    if !quest_path.exists() {
+        super::fetch_quest(quest_name)
+            .await
+            .map_err(|e| report(e, "no quest"))?;
+    }
+
+    if !prog.exists() {
+        return Err("missing program".into());
+    }
+
+    match prog_utils::resolve_prog_lang(prog, config) {
+        Some(lang) if !lang.command_exists(config) => return Err("no toolchain".into()),
+        Some(_) => {}
+        None => return Err("unknown lang".into()),
+    }
+
+    super::judge_quest(&quest_path, prog, None, false, config)
+        .map_err(|e| report(e, "build failed"))
+}
+
+fn report(e: OwlError, verdict: &str) -> String {
+    eprintln!("\x1b[31m{}\x1b[0m", e);
+    verdict.into()
+}
+
+fn batch_pairs(batch_src: &str) -> Result<Vec<(String, PathBuf)>> {
+    let batch_path = Path::new(batch_src);
+    let list_dir = batch_path.parent().unwrap_or(Path::new("."));
+
+    if batch_path.is_file() {
+        return match batch_path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml_pairs(batch_path, list_dir),
+            Some("csv") => csv_pairs(batch_path, list_dir),
+            _ => Err(OwlError::Unsupported(format!(
+                "'{}': expected a '.toml' or '.csv' list",
+                batch_src
+            ))),
+        };
+    }
+
+    let (search_dir, pattern) = if batch_path.is_dir() {
+        (batch_path, "*")
+    } else {
+        let pattern = batch_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or(OwlError::UriError(
+                format!("'{}': has no file name", batch_src),
+                "".into(),
+            ))?;
+
+        (list_dir, pattern)
+    };
+
+    stem_pairs(search_dir, pattern)
+}
+
+fn toml_pairs(list_path: &Path, list_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let list_doc = toml_utils::read_toml(list_path)?;
+
+    let list_table = match list_doc.get("quests") {
+        Some(item) => item.as_table_like().ok_or(OwlError::TomlError(
+            format!(
+                "Invalid entry 'quests' in '{}'",
+                list_path.to_string_lossy()
+            ),
+            "expected a table".into(),
+        ))?,
+        None => list_doc.as_table(),
+    };
+
+    list_table
+        .iter()
+        .map(|(quest_name, item)| match item.as_str() {
+            Some(prog) => Ok((quest_name.to_string(), list_dir.join(prog))),
+            None => Err(OwlError::TomlError(
+                format!(
+                    "Invalid entry '{}' in '{}'",
+                    quest_name,
+                    list_path.to_string_lossy()
+                ),
+                "expected a path to a program".into(),
+            )),
+        })
+        .collect()
+}
+
+fn csv_pairs(list_path: &Path, list_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let list_str = fs::read_to_string(list_path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read list '{}'", list_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    list_str
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| match line.split_once(',') {
+            Some(("quest", _)) => None,
+            Some((quest_name, prog)) => Some(Ok((
+                quest_name.trim().to_string(),
+                list_dir.join(prog.trim()),
+            ))),
+            None => Some(Err(OwlError::FileError(
+                format!(
+                    "Invalid line '{}' in '{}'",
+                    line,
+                    list_path.to_string_lossy()
+                ),
+                "expected 'quest,program'".into(),
+            ))),
+        })
+        .collect()
+}
+
+fn stem_pairs(search_dir: &Path, pattern: &str) -> Result<Vec<(String, PathBuf)>> {
+    let quest_names = Manifest::load()?
+        .quests()?
+        .into_iter()
+        .map(|quest| quest.name)
+        .collect::<Vec<String>>();
+
+    let mut progs = fs_utils::dir_tree(search_dir)?
+        .into_iter()
+        .filter(|path| prog_utils::check_prog_lang(path).is_some())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| wildcard_match(pattern, name))
+        })
+        .collect::<Vec<PathBuf>>();
+    progs.sort();
+
+    let mut pairs = Vec::new();
+
+    for prog in progs {
+        let Some(quest_name) = prog.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+
+        let is_quest = quest_names.iter().any(|name| name == quest_name)
+            || fs_utils::quest_path(quest_name).is_ok_and(|quest_path| quest_path.is_dir());
+
+        if is_quest {
+            pairs.push((quest_name.to_string(), prog));
+        } else {
+            warn(OwlWarning::Skipped(format!(
+                "'{}': no quest named '{}'",
+                prog.to_string_lossy(),
+                quest_name
+            )));
+        }
+    }
+
+    Ok(pairs)
+}
+
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+
+            (0..=name.len())
+                .filter(|&i| name.is_char_boundary(i))
+                .any(|i| wildcard_match(rest, &name[i..]))
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use super::CaseCheck;
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{OwlConfig, fs_utils, prog_utils, signal_utils};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Shorter cases are too noisy to call a speedup or a regression on.
+const NOISE_FLOOR: Duration = Duration::from_millis(5);
+/// Ratios within this much of 1.0 are reported as unchanged.
+const NOISE_RATIO: f64 = 0.05;
+
+struct BenchRow {
+    case: String,
+    working: std::result::Result<Duration, String>,
+    stashed: Option<std::result::Result<Duration, String>>,
+}
+
+/// Times `prog` on every case (its best of `runs`), and when `against_stash` is set, its stashed
+/// version too, printing the speedup or regression per case.
+pub async fn bench_program(
+    prog: &Path,
+    quest_or_dir: Option<&str>,
+    against_stash: bool,
+    runs: usize,
+    config: &OwlConfig,
+) -> Result<()> {
+    if !prog.exists() {
+        return Err(OwlError::FileError(
+            format!("'{}': no such file", prog.to_string_lossy()),
+            "".into(),
+        ));
+    }
+
+    let quest_or_dir = match quest_or_dir {
+        Some(quest_or_dir) => quest_or_dir,
+        None => prog
+            .file_stem()
+            .and_then(OsStr::to_str)
+            .ok_or(OwlError::UriError(
+                format!("'{}': has no file stem", prog.to_string_lossy()),
+                "name the quest or directory with the '.in' files".into(),
+            ))?,
+    };
+
+    let cases_dir = if Path::new(quest_or_dir).is_dir() {
+        PathBuf::from(quest_or_dir)
+    } else {
+        let quest_path = fs_utils::quest_path(quest_or_dir)?;
+
+        if !quest_path.exists() {
+            super::fetch_quest(quest_or_dir).await?;
+        }
+
+        quest_path
+    };
+
+    let mut test_cases: Vec<PathBuf> = fs_utils::find_by_ext(&cases_dir, "in")?;
+    test_cases.sort();
+
+    if test_cases.is_empty() {
+        return Err(OwlError::FileError(
+            format!("'{}': no test cases found", cases_dir.to_string_lossy()),
+            "expected files ending in '.in'".into(),
+        ));
+    }
+
+    // each version is built and timed in its own pass so they never run side by side
+    let stashed = if against_stash {
+        Some(stashed_copy(prog)?)
+    } else {
+        None
+    };
+
+    let working_times = match time_cases(prog, &test_cases, runs, config) {
+        Ok(working_times) => working_times,
+        Err(e) => {
+            if let Some(stashed) = &stashed {
+                remove_stashed_copy(stashed);
+            }
+
+            return Err(e);
+        }
+    };
+
+    let stashed_times = match &stashed {
+        Some(stashed) => {
+            eprintln!("\n>>> timing the stashed version ...");
+
+            let stashed_times = time_cases(stashed, &test_cases, runs, config);
+            remove_stashed_copy(stashed);
+
+            Some(stashed_times?)
+        }
+        None => None,
+    };
+
+    let rows = test_cases
+        .iter()
+        .zip(working_times)
+        .enumerate()
+        .map(|(i, (test_case, working))| BenchRow {
+            case: fs_utils::case_stem(test_case)
+                .unwrap_or_default()
+                .to_string(),
+            working,
+            stashed: stashed_times
+                .as_ref()
+                .and_then(|stashed_times| stashed_times.get(i).cloned()),
+        })
+        .collect::<Vec<BenchRow>>();
+
+    println!("{}", bench_table(prog, runs, &rows));
+
+    if signal_utils::is_interrupted() {
+        return Err(OwlError::Interrupted(format!(
+            "interrupted after {} cases",
+            rows.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Copies the stashed version of `prog` into a scratch dir, keeping its filename so that it
+/// builds the same way (e.g., Java's class name).
+fn stashed_copy(prog: &Path) -> Result<PathBuf> {
+    let prog_file_name = prog
+        .file_name()
+        .and_then(OsStr::to_str)
+        .ok_or(OwlError::UriError(
+            format!("'{}': has no filename", prog.to_string_lossy()),
+            "".into(),
+        ))?;
+
+    let stash_path = fs_utils::stash_path(prog_file_name)?;
+
+    if !stash_path.is_file() {
+        return Err(OwlError::FileError(
+            format!("'{}': not stashed", prog_file_name),
+            format!("run 'owlgo stash {}' first", prog.to_string_lossy()),
+        ));
+    }
+
+    let bench_dir = std::env::temp_dir().join(format!("owlgo-bench-{}", std::process::id()));
+    let copy_path = bench_dir.join(prog_file_name);
+
+    fs::create_dir_all(&bench_dir).map_err(|e| {
+        OwlError::FileError(
+            format!("could not create dir '{}'", bench_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+    fs::copy(&stash_path, &copy_path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not copy '{}'", stash_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    Ok(copy_path)
+}
+
+fn remove_stashed_copy(copy_path: &Path) {
+    if let Some(bench_dir) = copy_path.parent() {
+        let _ = fs::remove_dir_all(bench_dir);
+    }
+}
+
+/// Builds `prog` and times it on each case, stopping early if interrupted.
+fn time_cases(
+    prog: &Path,
+    test_cases: &[PathBuf],
+    runs: usize,
+    config: &OwlConfig,
+) -> Result<Vec<std::result::Result<Duration, String>>> {
+    let (target, build_files) = match prog_utils::build_program(prog, config)? {
+        Some(bl) => (bl.target, bl.build_files),
+        None => (prog.to_path_buf(), None),
+    };
+
+    let mut times = Vec::new();
+
+    for test_case in test_cases.iter() {
+        if signal_utils::is_interrupted() {
+            break;
+        }
+
+        eprintln!(
+            "timing \x1b[36m{}\x1b[0m ...",
+            fs_utils::case_stem(test_case).unwrap_or_default()
+        );
+
+        times.push(time_case(prog, &target, test_case, runs, config));
+    }
+
+    prog_utils::cleanup_program(prog, &target, build_files)?;
+
+    Ok(times)
+}
+
+/// The best time of `runs` runs, or why the case could not be timed.
+fn time_case(
+    prog: &Path,
+    target: &Path,
+    test_case: &Path,
+    runs: usize,
+    config: &OwlConfig,
+) -> std::result::Result<Duration, String> {
+    let ans_files = super::find_answers(test_case).unwrap_or_default();
+
+    let mut best: Option<Duration> = None;
+
+    for _ in 0..runs.max(1) {
+        if signal_utils::is_interrupted() {
+            break;
+        }
+
+        let elapsed = if ans_files.is_empty() {
+            super::run_case(prog, target, test_case, config)
+                .map(|(_, elapsed)| elapsed)
+                .map_err(|_| "error".to_string())?
+        } else {
+            match super::check_case(prog, target, test_case, &ans_files, config) {
+                Ok(CaseCheck::Passed(elapsed, _)) => elapsed,
+                Ok(CaseCheck::Failed(..)) => return Err("wrong answer".into()),
+                Ok(CaseCheck::Exceeded(_, exceeded)) => {
+                    return Err(format!("{} limit", exceeded.kind()));
+                }
+                Err(_) => return Err("error".into()),
+            }
+        };
+
+        best = Some(best.map_or(elapsed, |best| best.min(elapsed)));
+    }
+
+    best.ok_or("interrupted".into())
+}
+
+fn bench_table(prog: &Path, runs: usize, rows: &[BenchRow]) -> String {
+    let format_time = |time: &std::result::Result<Duration, String>| match time {
+        Ok(elapsed) => format!("{:.1}ms", elapsed.as_secs_f64() * 1000.0),
+        Err(reason) => reason.clone(),
+    };
+
+    let with_stash = rows.iter().any(|row| row.stashed.is_some());
+
+    let header: &[&str] = if with_stash {
+        &["case", "working", "stashed", "speedup"]
+    } else {
+        &["case", "working"]
+    };
+
+    let cells = rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![row.case.clone(), format_time(&row.working)];
+
+            if let Some(stashed) = &row.stashed {
+                cells.push(format_time(stashed));
+                cells.push(match (&row.working, stashed) {
+                    (Ok(working), Ok(stashed)) => {
+                        format!("{:.2}x", ratio(*working, *stashed))
+                    }
+                    _ => "-".into(),
+                });
+            }
+
+            cells
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    let widths = (0..header.len())
+        .map(|i| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain([header[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<usize>>();
+
+    let format_row = |row: &[&str]| {
+        row.iter()
+            .zip(widths.iter())
+            .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut table = format!(
+        "\nbench for '{}' (best of {})\n\n",
+        prog.to_string_lossy(),
+        runs.max(1)
+    );
+
+    table.push_str(&format_row(header));
+    table.push('\n');
+
+    for (row, cells) in rows.iter().zip(cells.iter()) {
+        let line = format_row(&cells.iter().map(String::as_str).collect::<Vec<&str>>());
+
+        let color = match (&row.working, &row.stashed) {
+            (Err(_), _) => "\x1b[31m",
+            (Ok(working), Some(Ok(stashed))) => match change(*working, *stashed) {
+                Some(true) => "\x1b[32m",
+                Some(false) => "\x1b[31m",
+                None => "",
+            },
+            _ => "",
+        };
+
+        table.push_str(&format!("{}{}\x1b[0m\n", color, line));
+    }
+
+    if with_stash {
+        let (faster, slower) = rows.iter().fold((0, 0), |(faster, slower), row| {
+            match (&row.working, &row.stashed) {
+                (Ok(working), Some(Ok(stashed))) => match change(*working, *stashed) {
+                    Some(true) => (faster + 1, slower),
+                    Some(false) => (faster, slower + 1),
+                    None => (faster, slower),
+                },
+                _ => (faster, slower),
+            }
+        });
+
+        let timed = rows
+            .iter()
+            .filter_map(|row| match (&row.working, &row.stashed) {
+                (Ok(working), Some(Ok(stashed))) => Some((*working, *stashed)),
+                _ => None,
+            })
+            .collect::<Vec<(Duration, Duration)>>();
+
+        if !timed.is_empty() {
+            let working_total = timed.iter().map(|(working, _)| *working).sum::<Duration>();
+            let stashed_total = timed.iter().map(|(_, stashed)| *stashed).sum::<Duration>();
+
+            table.push_str(&format!(
+                "\n{:.2}x overall, {} faster, {} slower, {} unchanged\n",
+                ratio(working_total, stashed_total),
+                faster,
+                slower,
+                timed.len() - faster - slower
+            ));
+        }
+    }
+
+    table
+}
+
+/// How many times faster the working version is than the stashed one.
+fn ratio(working: Duration, stashed: Duration) -> f64 {
+    stashed.as_secs_f64() / working.as_secs_f64().max(f64::EPSILON)
+}
+
+/// Whether the working version got faster (`true`) or slower (`false`), if measurably either.
+fn change(working: Duration, stashed: Duration) -> Option<bool> {
+    if working.max(stashed) < NOISE_FLOOR {
+        return None;
+    }
+
+    let ratio = ratio(working, stashed);
+
+    if ratio > 1.0 + NOISE_RATIO {
+        Some(true)
+    } else if ratio < 1.0 - NOISE_RATIO {
+        Some(false)
+    } else {
+        None
+    }
+}
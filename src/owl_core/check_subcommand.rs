@@ -0,0 +1,86 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::cmd::judge_utils::OutputFormat;
+use crate::owl_utils::{OwlConfig, fs_utils, prog_utils, vscode_utils};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::time::Instant;
+
+/// Runs only the language's syntax/type check on `prog`, for fast feedback on save.
+pub fn check_program(prog: &Path, config: &OwlConfig) -> Result<()> {
+    if !prog.exists() {
+        return Err(OwlError::FileError(
+            format!("'{}': no such file", prog.to_string_lossy()),
+            "".into(),
+        ));
+    }
+
+    let lang = prog_utils::resolve_prog_lang(prog, config).ok_or(OwlError::Unsupported(
+        format!("'{}': unknown language", prog.to_string_lossy()),
+    ))?;
+
+    let out_dir = env::temp_dir().join(format!("owlgo-check-{}", process::id()));
+
+    let mut check_cmd = lang
+        .check_cmd(prog, &out_dir)
+        .ok_or(OwlError::Unsupported(format!(
+            "no fast check for {} (use 'owlgo test' to build it instead)",
+            lang.name()
+        )))?;
+
+    if lang.should_build() {
+        check_cmd.args(&config.build_args);
+    }
+
+    fs::create_dir_all(&out_dir).map_err(|e| {
+        OwlError::FileError(
+            format!("could not create dir '{}'", out_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let start = Instant::now();
+    let output = check_cmd.output();
+    let elapsed = start.elapsed();
+
+    fs_utils::remove_path(&out_dir)?;
+
+    let output = output.map_err(|e| {
+        OwlError::CommandNotFound(format!(
+            "'{}': {}",
+            check_cmd.get_program().to_string_lossy(),
+            e
+        ))
+    })?;
+
+    if output.status.success() {
+        if config.format == OutputFormat::Pretty {
+            println!(
+                "[{}ms] \x1b[32mcheck passed\x1b[0m ({})",
+                elapsed.as_millis(),
+                lang.name()
+            );
+        }
+
+        return Ok(());
+    }
+
+    let stderr = [output.stderr, output.stdout]
+        .iter()
+        .map(|out| String::from_utf8_lossy(out).trim_end().to_string())
+        .filter(|out| !out.is_empty())
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if config.format == OutputFormat::Vscode {
+        for diagnostic in vscode_utils::build_diagnostics(prog, &stderr) {
+            println!("{}", diagnostic);
+        }
+    }
+
+    Err(OwlError::ProcessError(
+        format!("'{}': check failed", prog.to_string_lossy()),
+        stderr,
+    ))
+}
@@ -1,8 +1,164 @@
+use super::todos_subcommand;
 use crate::common::{OwlError, Result};
-use crate::owl_utils::fs_utils;
-use crate::{CHAT_DIR, GIT_DIR, OWL_DIR, PROMPT_DIR, STASH_DIR};
+use crate::owl_utils::{fs_utils, solve_utils, toml_utils, toml_utils::ChatRetention};
+use crate::{
+    CACHE_DIR, CHAT_DIR, GIT_DIR, HISTORY_DIR, MANIFEST, MERGED_DIR, OWL_DIR, PROMPT_DIR,
+    SCRIPT_DIR, STASH_DIR, TODOS_SUFFIX,
+};
 use std::ffi::OsStr;
 use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Removes chats beyond `retention` (with their todos), or only lists them when `dry_run`.
+pub fn clear_chats(retention: &ChatRetention, dry_run: bool) -> Result<()> {
+    let (pruned, chats) = prune_chats(retention, dry_run)?;
+    let freed = pruned.iter().map(|(_, size)| size).sum::<u64>();
+
+    for (chat_path, _) in pruned.iter() {
+        println!(
+            "{} {}",
+            if dry_run { "would remove" } else { "removed" },
+            chat_path
+                .file_name()
+                .and_then(OsStr::to_str)
+                .unwrap_or_default()
+        );
+    }
+
+    println!(
+        "{} {} of {} chats ({:.1} MB)",
+        if dry_run { "would remove" } else { "removed" },
+        pruned.len(),
+        chats,
+        freed as f64 / (1024.0 * 1024.0)
+    );
+
+    Ok(())
+}
+
+/// Applies the manifest's `[chat]` retention policy, returning how many chats were removed.
+pub fn enforce_chat_retention() -> Result<usize> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+
+    if !manifest_path.exists() {
+        return Ok(0);
+    }
+
+    let retention = toml_utils::get_manifest_chat_retention(&manifest_path)?;
+
+    prune_chats(&retention, false).map(|(pruned, _)| pruned.len())
+}
+
+/// Parses an age like `30d`, `2w`, `12h` or `90m`.
+pub fn parse_age(age: &str) -> Result<Duration> {
+    let invalid = || {
+        OwlError::Unsupported(format!(
+            "'{}': invalid age (expected e.g. '30d', '2w', '12h' or '90m')",
+            age
+        ))
+    };
+
+    let split = age.find(|c: char| !c.is_ascii_digit()).unwrap_or(age.len());
+    let (num, unit) = age.split_at(split);
+    let num = num.parse::<u64>().map_err(|_| invalid())?;
+
+    let secs = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" | "" => DAY_SECS,
+        "w" => 7 * DAY_SECS,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Duration::from_secs(num * secs))
+}
+
+/// Keeps the newest chats that fit every limit; returns the rest with their sizes (todos
+/// included) and the number of chats there were.
+fn prune_chats(retention: &ChatRetention, dry_run: bool) -> Result<(Vec<(PathBuf, u64)>, usize)> {
+    let chat_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, CHAT_DIR], None)?;
+
+    if !chat_dir.exists() {
+        return Ok((Vec::new(), 0));
+    }
+
+    let mut chats = Vec::new();
+
+    for entry in fs::read_dir(&chat_dir)
+        .map_err(|e| OwlError::FileError("could not read chat dir".into(), e.to_string()))?
+    {
+        let path = entry
+            .map_err(|e| {
+                OwlError::FileError("could not read entry in chat dir".into(), e.to_string())
+            })?
+            .path();
+
+        let is_todos = path
+            .file_name()
+            .and_then(OsStr::to_str)
+            .is_some_and(|name| name.ends_with(TODOS_SUFFIX));
+
+        if !path.is_file() || is_todos {
+            continue;
+        }
+
+        let metadata = fs::metadata(&path).map_err(|e| {
+            OwlError::FileError(
+                format!("could not read '{}'", path.to_string_lossy()),
+                e.to_string(),
+            )
+        })?;
+
+        let todos_size = fs::metadata(todos_subcommand::todos_path(&path))
+            .map(|todos| todos.len())
+            .unwrap_or(0);
+
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .unwrap_or_default();
+
+        chats.push((path, metadata.len() + todos_size, age));
+    }
+
+    chats.sort_by_key(|(_, _, age)| *age);
+
+    let mut kept_bytes = 0;
+    let mut over_limit = false;
+    let mut pruned = Vec::new();
+
+    for (i, (path, size, age)) in chats.iter().enumerate() {
+        over_limit = over_limit
+            || retention.max_count.is_some_and(|count| i >= count)
+            || retention.max_age.is_some_and(|max_age| *age > max_age)
+            || retention
+                .max_bytes
+                .is_some_and(|max_bytes| kept_bytes + size > max_bytes);
+
+        if !over_limit {
+            kept_bytes += size;
+            continue;
+        }
+
+        if !dry_run {
+            let todos_path = todos_subcommand::todos_path(path);
+
+            if todos_path.exists() {
+                fs_utils::remove_path(&todos_path)?;
+            }
+
+            fs_utils::remove_path(path)?;
+        }
+
+        pruned.push((path.clone(), *size));
+    }
+
+    Ok((pruned, chats.len()))
+}
 
 pub fn clear_programs() -> Result<()> {
     let stash_dir = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(STASH_DIR))?;
@@ -28,7 +184,13 @@ pub fn clear_programs() -> Result<()> {
                 "".into(),
             ))?;
 
-        if path.is_dir() && (stem == CHAT_DIR || stem == GIT_DIR || stem == PROMPT_DIR) {
+        if path.is_dir()
+            && (stem == CHAT_DIR
+                || stem == GIT_DIR
+                || stem == HISTORY_DIR
+                || stem == MERGED_DIR
+                || stem == PROMPT_DIR)
+        {
             continue;
         }
 
@@ -38,6 +200,18 @@ pub fn clear_programs() -> Result<()> {
     Ok(())
 }
 
+/// Removes the solve history (accepted runs, toolchain pins and revisits) kept across clears.
+pub fn clear_progress() -> Result<()> {
+    let progress_path = solve_utils::progress_path()?;
+
+    if progress_path.exists() {
+        fs_utils::remove_path(&progress_path)?;
+    }
+
+    Ok(())
+}
+
+/// Removes every fetched quest, leaving the manifest, stash and progress store alone.
 pub fn clear_quests() -> Result<()> {
     let owl_dir = fs_utils::ensure_path_from_home(&[OWL_DIR], None)?;
 
@@ -63,7 +237,12 @@ pub fn clear_quests() -> Result<()> {
             ))?;
 
         if path.is_file()
-            || (stem == CHAT_DIR || stem == GIT_DIR || stem == PROMPT_DIR || stem == STASH_DIR)
+            || (stem == CACHE_DIR
+                || stem == CHAT_DIR
+                || stem == GIT_DIR
+                || stem == PROMPT_DIR
+                || stem == SCRIPT_DIR
+                || stem == STASH_DIR)
         {
             continue;
         }
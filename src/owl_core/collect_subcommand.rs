@@ -0,0 +1,259 @@
+use crate::GIT_DIR;
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{OwlConfig, fs_utils, git_utils, prog_utils};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const GRADE_SHEET_STEM: &str = "grades";
+
+struct GradeRow {
+    student: String,
+    prog: String,
+    verdict: String,
+    passed: usize,
+    total: usize,
+    elapsed_ms: u128,
+}
+
+pub async fn collect_roster(
+    remotes_file: &Path,
+    quest_name: &str,
+    prog_name: Option<&str>,
+    out_dir: &Path,
+    as_csv: bool,
+    config: &OwlConfig,
+) -> Result<()> {
+    let quest_path = fs_utils::quest_path(quest_name)?;
+
+    if !quest_path.exists() {
+        super::fetch_quest(quest_name).await?;
+    }
+
+    let remotes = read_remotes(remotes_file)?;
+
+    fs::create_dir_all(out_dir).map_err(|e| {
+        OwlError::FileError(
+            format!(
+                "Failed to create all dirs in '{}'",
+                out_dir.to_string_lossy()
+            ),
+            e.to_string(),
+        )
+    })?;
+
+    let out_dir = out_dir.canonicalize().map_err(|e| {
+        OwlError::FileError(
+            format!("Failed to access dir '{}'", out_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let mut rows = Vec::new();
+
+    for (student, url) in remotes.iter() {
+        println!("\x1b[36m[{}]\x1b[0m {}", student, url);
+
+        let student_dir = out_dir.join(student);
+
+        let mut row = GradeRow {
+            student: student.to_string(),
+            prog: "-".into(),
+            verdict: "".into(),
+            passed: 0,
+            total: 0,
+            elapsed_ms: 0,
+        };
+
+        if let Err(e) = sync_student(&out_dir, &student_dir, url) {
+            eprintln!("\x1b[31m{}\x1b[0m", e);
+            row.verdict = "sync failed".into();
+            rows.push(row);
+            continue;
+        }
+
+        let Some(prog) = find_solution(&student_dir, quest_name, prog_name, config) else {
+            row.verdict = "missing".into();
+            rows.push(row);
+            continue;
+        };
+
+        row.prog = prog
+            .strip_prefix(&student_dir)
+            .unwrap_or(&prog)
+            .to_string_lossy()
+            .to_string();
+
+        match super::judge_quest(&quest_path, &prog, None, false, config) {
+            Ok(summary) => {
+                row.verdict = if summary.failed > 0 {
+                    "failed".into()
+                } else {
+                    "accepted".into()
+                };
+                row.passed = summary.passed;
+                row.total = summary.passed + summary.failed;
+                row.elapsed_ms = summary.elapsed.as_millis();
+            }
+            Err(e) => {
+                eprintln!("\x1b[31m{}\x1b[0m", e);
+                row.verdict = "build failed".into();
+            }
+        }
+
+        rows.push(row);
+    }
+
+    let (grade_sheet, ext) = if as_csv {
+        (csv_sheet(&rows), "csv")
+    } else {
+        (markdown_sheet(quest_name, &rows), "md")
+    };
+
+    let sheet_path = out_dir.join(format!("{}.{}", GRADE_SHEET_STEM, ext));
+
+    fs::write(&sheet_path, &grade_sheet).map_err(|e| {
+        OwlError::FileError(
+            format!(
+                "could not write grade sheet '{}'",
+                sheet_path.to_string_lossy()
+            ),
+            e.to_string(),
+        )
+    })?;
+
+    println!("{}", grade_sheet);
+    println!("grade sheet written to '{}'", sheet_path.to_string_lossy());
+
+    Ok(())
+}
+
+fn csv_sheet(rows: &[GradeRow]) -> String {
+    let mut sheet = String::from("student,program,verdict,passed,total,elapsed_ms\n");
+
+    for row in rows.iter() {
+        sheet.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            csv_field(&row.student),
+            csv_field(&row.prog),
+            row.verdict,
+            row.passed,
+            row.total,
+            row.elapsed_ms
+        ));
+    }
+
+    sheet
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn find_solution(
+    student_dir: &Path,
+    quest_name: &str,
+    prog_name: Option<&str>,
+    config: &OwlConfig,
+) -> Option<PathBuf> {
+    if let Some(prog_name) = prog_name {
+        let prog = student_dir.join(prog_name);
+        return prog.is_file().then_some(prog);
+    }
+
+    let mut candidates = fs::read_dir(student_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.file_stem().and_then(OsStr::to_str) == Some(quest_name)
+                && prog_utils::resolve_prog_lang(path, config).is_some()
+        })
+        .collect::<Vec<PathBuf>>();
+    candidates.sort();
+
+    candidates.into_iter().next()
+}
+
+fn markdown_sheet(quest_name: &str, rows: &[GradeRow]) -> String {
+    let mut sheet = format!(
+        "# Grades for '{}'\n\n| student | program | verdict | passed | elapsed |\n|---|---|---|---|---|\n",
+        quest_name
+    );
+
+    for row in rows.iter() {
+        sheet.push_str(&format!(
+            "| {} | `{}` | {} | {}/{} | {}ms |\n",
+            row.student, row.prog, row.verdict, row.passed, row.total, row.elapsed_ms
+        ));
+    }
+
+    sheet
+}
+
+fn read_remotes(remotes_file: &Path) -> Result<Vec<(String, String)>> {
+    let remotes_str = fs::read_to_string(remotes_file).map_err(|e| {
+        OwlError::FileError(
+            format!(
+                "could not read remote list '{}'",
+                remotes_file.to_string_lossy()
+            ),
+            e.to_string(),
+        )
+    })?;
+
+    let mut remotes = Vec::new();
+
+    for line in remotes_str.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+
+        let remote = match (fields.next(), fields.next()) {
+            (Some(student), Some(url)) => (student.to_string(), url.to_string()),
+            (Some(url), None) => {
+                let student = url
+                    .trim_end_matches('/')
+                    .rsplit(['/', ':'])
+                    .next()
+                    .map(|name| name.trim_end_matches(".git"))
+                    .filter(|name| !name.is_empty())
+                    .ok_or(OwlError::UriError(
+                        format!("'{}': could not determine student name", url),
+                        "use '<name> <url>'".into(),
+                    ))?;
+
+                (student.to_string(), url.to_string())
+            }
+            _ => unreachable!(),
+        };
+
+        remotes.push(remote);
+    }
+
+    if remotes.is_empty() {
+        return Err(OwlError::FileError(
+            format!("'{}': no remotes listed", remotes_file.to_string_lossy()),
+            "expected '<name> <url>' per line".into(),
+        ));
+    }
+
+    Ok(remotes)
+}
+
+fn sync_student(out_dir: &Path, student_dir: &Path, url: &str) -> Result<()> {
+    if student_dir.join(GIT_DIR).exists() {
+        git_utils::git_fetch(student_dir, "origin", "main")?;
+        git_utils::git_reset(student_dir, "origin", "main")?;
+    } else {
+        git_utils::git_clone(out_dir, url, student_dir)?;
+    }
+
+    Ok(())
+}
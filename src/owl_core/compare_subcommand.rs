@@ -0,0 +1,140 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{OwlConfig, fs_utils, judge_utils, prog_utils, signal_utils};
+use std::path::{Path, PathBuf};
+
+pub async fn compare_programs(
+    prog_a: &Path,
+    prog_b: &Path,
+    quest_or_dir: &str,
+    config: &OwlConfig,
+) -> Result<()> {
+    for prog in [prog_a, prog_b] {
+        if !prog.exists() {
+            return Err(OwlError::FileError(
+                format!("'{}': no such file", prog.to_string_lossy()),
+                "".into(),
+            ));
+        }
+    }
+
+    let cases_dir = if Path::new(quest_or_dir).is_dir() {
+        PathBuf::from(quest_or_dir)
+    } else {
+        let quest_path = fs_utils::quest_path(quest_or_dir)?;
+
+        if !quest_path.exists() {
+            super::fetch_quest(quest_or_dir).await?;
+        }
+
+        quest_path
+    };
+
+    let mut test_cases: Vec<PathBuf> = fs_utils::find_by_ext(&cases_dir, "in")?;
+    test_cases.sort();
+
+    let (target_a, build_files_a) = match prog_utils::build_program(prog_a, config)? {
+        Some(bl) => (bl.target, bl.build_files),
+        None => (prog_a.to_path_buf(), None),
+    };
+
+    let (target_b, build_files_b) = match prog_utils::build_program(prog_b, config) {
+        Ok(Some(bl)) => (bl.target, bl.build_files),
+        Ok(None) => (prog_b.to_path_buf(), None),
+        Err(e) => {
+            prog_utils::cleanup_program(prog_a, &target_a, build_files_a)?;
+            return Err(e);
+        }
+    };
+
+    let compare_result = compare_cases(prog_a, &target_a, prog_b, &target_b, &test_cases, config);
+
+    prog_utils::cleanup_program(prog_a, &target_a, build_files_a)?;
+    prog_utils::cleanup_program(prog_b, &target_b, build_files_b)?;
+
+    let matched = compare_result?;
+
+    if signal_utils::is_interrupted() {
+        return Err(OwlError::Interrupted(format!(
+            "interrupted after {} cases",
+            matched
+        )));
+    }
+
+    println!("\x1b[32mall {} outputs match\x1b[0m 🏆🏆🏆\n", matched);
+
+    Ok(())
+}
+
+fn compare_cases(
+    prog_a: &Path,
+    target_a: &Path,
+    prog_b: &Path,
+    target_b: &Path,
+    test_cases: &[PathBuf],
+    config: &OwlConfig,
+) -> Result<usize> {
+    let total = test_cases.len();
+
+    for (count, test_case) in test_cases.iter().enumerate() {
+        if signal_utils::is_interrupted() {
+            return Ok(count);
+        }
+
+        let in_stem = fs_utils::case_stem(test_case).unwrap_or_default();
+
+        let output_a = super::run_case(prog_a, target_a, test_case, config);
+        let output_b = super::run_case(prog_b, target_b, test_case, config);
+
+        match (&output_a, &output_b) {
+            (Ok((actual_a, elapsed_a)), Ok((actual_b, elapsed_b)))
+                if judge_utils::outputs_match(
+                    actual_a,
+                    actual_b,
+                    config.compare,
+                    &config.filters,
+                ) =>
+            {
+                println!(
+                    "({}/{}) [{}ms | {}ms] test_name: \x1b[36m{}\x1b[0m, status: \x1b[32moutputs match\x1b[0m",
+                    count + 1,
+                    total,
+                    elapsed_a.as_millis(),
+                    elapsed_b.as_millis(),
+                    in_stem
+                );
+            }
+            _ => {
+                let is_hidden = fs_utils::is_hidden_case(test_case);
+
+                let render = |output: &Result<(Vec<u8>, _)>| match output {
+                    Ok(_) if is_hidden => "(hidden case, output not shown)\n".into(),
+                    Ok((actual, _)) => judge_utils::preview_output(actual, config.encoding),
+                    Err(e) => format!("\x1b[31m{}\x1b[0m\n", e),
+                };
+
+                eprintln!(
+                    concat!(
+                        "\x1b[31m{}\x1b[0m: {}\n\n",
+                        "\x1b[1;33m>>> {} <<<\x1b[0m\n\n{}\n",
+                        "\x1b[1;35m>>> {} <<<\x1b[0m\n\n{}\n",
+                    ),
+                    "[outputs differ]",
+                    test_case.to_string_lossy(),
+                    prog_a.to_string_lossy(),
+                    render(&output_a),
+                    prog_b.to_string_lossy(),
+                    render(&output_b)
+                );
+
+                return Err(OwlError::TestFailure(format!(
+                    "outputs differ on '{}' ({}/{})",
+                    in_stem,
+                    count + 1,
+                    total
+                )));
+            }
+        }
+    }
+
+    Ok(total)
+}
@@ -0,0 +1,192 @@
+use super::clear_subcommand;
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{OwlConfig, fs_utils, llm_utils, toml_utils};
+use crate::{MANIFEST, OWL_DIR, TOML_TEMPLATE};
+use toml_edit::{DocumentMut, Item, Table};
+
+pub const CONFIG_KEYS: &[&str] = &[
+    "ai_sdk",
+    "api_key",
+    "model",
+    "ollama_url",
+    "github_token",
+    "update_interval_hours",
+    "hooks.pre_build",
+    "hooks.post_test",
+    "hooks.on_accept",
+    "tui.vim",
+    "appearance.preset",
+    "appearance.syntax_theme",
+    "appearance.accent",
+    "appearance.text",
+    "appearance.selection",
+    "appearance.code_fg",
+    "appearance.code_bg",
+    "appearance.quote",
+    "appearance.link",
+    "sync.s3_access_key",
+    "sync.s3_secret_key",
+    "sync.s3_region",
+    "sync.s3_endpoint",
+    "sync.webdav_user",
+    "sync.webdav_password",
+    "chat.max_count",
+    "chat.max_age_days",
+    "chat.max_size_mib",
+    "git.tag_accepted",
+    "git.auto_sync",
+    "docker.enabled",
+    "docker.c",
+    "docker.cpp",
+    "docker.go",
+    "docker.java",
+    "docker.javascript",
+    "docker.python",
+    "docker.ruby",
+    "docker.rust",
+    "docker.bash",
+    "docker.binary",
+    "sandbox.backend",
+    "sandbox.network",
+    "sandbox.seccomp",
+    "formatters.on_stash",
+    "formatters.c",
+    "formatters.cpp",
+    "formatters.go",
+    "formatters.python",
+    "formatters.rust",
+];
+const SECRET_KEYS: &[&str] = &[
+    "api_key",
+    "github_token",
+    "sync.s3_secret_key",
+    "sync.webdav_password",
+];
+
+pub fn list_config() -> Result<()> {
+    let manifest_doc = read_manifest()?;
+
+    for key in CONFIG_KEYS {
+        if let Some(value) = config_value(&manifest_doc, key) {
+            println!("{} = {}", key, value);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn get_config(key: &str) -> Result<()> {
+    let manifest_doc = read_manifest()?;
+
+    match config_value(&manifest_doc, key) {
+        Some(value) => {
+            println!("{}", value);
+            Ok(())
+        }
+        None => Err(OwlError::TomlError(
+            format!("'{}': not set in manifest", key),
+            "run 'owlgo config set' to set it".into(),
+        )),
+    }
+}
+
+pub fn set_config(key: &str, value: &str) -> Result<()> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+    let mut manifest_doc = read_manifest()?;
+
+    let (table_name, entry_name) = table_and_entry(key);
+
+    let item = match key {
+        "ai_sdk" if !llm_utils::AI_SDKS.contains(&value) => {
+            return Err(OwlError::Unsupported(format!(
+                "'{}': unknown ai sdk (expected one of {})",
+                value,
+                llm_utils::AI_SDKS.join(", ")
+            )));
+        }
+        "model" if value.is_empty() => {
+            return Err(OwlError::Unsupported("model must not be empty".into()));
+        }
+        "ollama_url" if url::Url::parse(value).is_err() => {
+            return Err(OwlError::Unsupported(format!(
+                "'{}': expected a URL (e.g., '{}')",
+                value,
+                llm_utils::DEFAULT_OLLAMA_URL
+            )));
+        }
+        "update_interval_hours" => toml_edit::value(value.parse::<u32>().map_err(|_| {
+            OwlError::Unsupported(format!(
+                "'{}': expected a number of hours (0 disables update checks)",
+                value
+            ))
+        })? as i64),
+        "chat.max_count" | "chat.max_age_days" | "chat.max_size_mib" => {
+            toml_edit::value(value.parse::<u32>().map_err(|_| {
+                OwlError::Unsupported(format!("'{}': expected a non-negative number", value))
+            })? as i64)
+        }
+        "git.auto_sync" => {
+            clear_subcommand::parse_age(value)?;
+            toml_edit::value(value)
+        }
+        "tui.vim"
+        | "git.tag_accepted"
+        | "docker.enabled"
+        | "sandbox.network"
+        | "formatters.on_stash" => toml_edit::value(value.parse::<bool>().map_err(|_| {
+            OwlError::Unsupported(format!("'{}': expected 'true' or 'false'", value))
+        })?),
+        _ => toml_edit::value(value),
+    };
+
+    if !manifest_doc.contains_key(table_name) {
+        manifest_doc[table_name] = Item::Table(Table::new());
+    }
+
+    manifest_doc[table_name][entry_name] = item;
+
+    OwlConfig::check_manifest(&manifest_path, &manifest_doc)?;
+
+    toml_utils::write_manifest(&manifest_doc, &manifest_path)?;
+
+    println!(
+        "{} = {}",
+        key,
+        config_value(&manifest_doc, key).unwrap_or_default()
+    );
+
+    Ok(())
+}
+
+fn config_value(manifest_doc: &DocumentMut, key: &str) -> Option<String> {
+    let (table_name, entry_name) = table_and_entry(key);
+    let item = manifest_doc.get(table_name)?.get(entry_name)?;
+
+    let value = match item.as_value()? {
+        toml_edit::Value::String(s) => s.value().to_string(),
+        value => value.to_string().trim().to_string(),
+    };
+
+    if SECRET_KEYS.contains(&key) && !value.is_empty() {
+        let visible = value.chars().rev().take(4).collect::<Vec<char>>();
+        Some(format!("****{}", visible.iter().rev().collect::<String>()))
+    } else {
+        Some(value)
+    }
+}
+
+fn read_manifest() -> Result<DocumentMut> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+
+    if manifest_path.exists() {
+        toml_utils::read_toml(&manifest_path)
+    } else {
+        TOML_TEMPLATE
+            .parse::<DocumentMut>()
+            .map_err(|e| OwlError::TomlError("Failed to parse TOML template".into(), e.to_string()))
+    }
+}
+
+fn table_and_entry(key: &str) -> (&str, &str) {
+    key.split_once('.').unwrap_or(("manifest", key))
+}
@@ -0,0 +1,188 @@
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::tui::{DebugApp, DebugCase, DebugRun};
+use crate::owl_utils::{
+    OwlConfig, QuestMeta, cmd_utils, fs_utils, hook_utils, judge_utils, prog_utils, solve_utils,
+    tui_utils,
+};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DEBUG_LINES: usize = 500;
+
+/// Opens a TUI to step through a quest's cases one at a time, running `prog` on demand and
+/// marking cases to revisit (kept in the progress store).
+pub async fn debug_quest(quest_name: &str, prog: &Path, config: &OwlConfig) -> Result<()> {
+    let quest_path = fs_utils::quest_path(quest_name)?;
+
+    if !quest_path.exists() {
+        super::fetch_quest(quest_name).await?;
+    }
+
+    if !prog.exists() {
+        return Err(OwlError::FileError(
+            format!("'{}': no such file", prog.to_string_lossy()),
+            "".into(),
+        ));
+    }
+
+    let meta = if quest_path.is_dir() {
+        QuestMeta::load(&quest_path)?
+    } else {
+        QuestMeta::default()
+    };
+    let config = &config.for_quest(&meta);
+
+    let mut test_cases: Vec<PathBuf> = fs_utils::find_by_ext(&quest_path, "in")?;
+    test_cases.sort();
+
+    if test_cases.is_empty() {
+        return Err(OwlError::FileError(
+            format!("'{}': has no test cases", quest_name),
+            "".into(),
+        ));
+    }
+
+    let mut progress_doc = solve_utils::read_progress().unwrap_or_else(|e| {
+        warn(OwlWarning::Recovered(e.to_string()));
+        Default::default()
+    });
+    let marked = solve_utils::revisit_cases(&progress_doc, quest_name);
+
+    let mut cases = test_cases
+        .iter()
+        .map(|in_file| debug_case(in_file, &marked, config))
+        .collect::<Result<Vec<DebugCase>>>()?;
+
+    hook_utils::pre_build(&config.hooks, Some(quest_name), prog)?;
+
+    let _file_io = cmd_utils::set_file_io(meta.input_file, meta.output_file);
+
+    let (target, build_files) = match prog_utils::build_program(prog, config)? {
+        Some(bl) => (bl.target, bl.build_files),
+        None => (prog.to_path_buf(), None),
+    };
+
+    let title = format!("debug {} · {}", quest_name, prog.to_string_lossy());
+    let statement = super::fetch_subcommand::read_statement(&quest_path);
+
+    let tui_result = tui_utils::enter_raw_mode().and_then(|_| {
+        let run_result = DebugApp::new(config.keymap.clone(), config.theme.clone())
+            .with_statement(statement)
+            .run(&title, &mut cases, |i| {
+                debug_run(prog, &target, &test_cases[i], config)
+            });
+
+        tui_utils::exit_raw_mode().and(run_result)
+    });
+
+    prog_utils::cleanup_program(prog, &target, build_files)?;
+    tui_result?;
+
+    let marked = cases
+        .iter()
+        .filter(|case| case.marked)
+        .map(|case| case.name.clone())
+        .collect::<Vec<String>>();
+
+    solve_utils::set_revisit_cases(&mut progress_doc, quest_name, &marked);
+
+    if let Err(e) = solve_utils::write_progress(&progress_doc) {
+        warn(OwlWarning::Recovered(e.to_string()));
+    }
+
+    if !marked.is_empty() {
+        println!("marked to revisit: {}", marked.join(", "));
+    }
+
+    Ok(())
+}
+
+fn debug_case(in_file: &Path, marked: &[String], config: &OwlConfig) -> Result<DebugCase> {
+    let name = fs_utils::case_stem(in_file).unwrap_or_default().to_string();
+    let hidden = fs_utils::is_hidden_case(in_file);
+
+    let (input, expected) = if hidden {
+        (String::new(), String::new())
+    } else {
+        let expected = match super::find_answer(in_file) {
+            Ok(ans_file) => clip(&judge_utils::preview_output(
+                &fs_utils::read_case(&ans_file)?,
+                config.encoding,
+            )),
+            Err(_) => "(no answer file)".into(),
+        };
+
+        (
+            clip(&judge_utils::preview_output(
+                &fs_utils::read_case(in_file)?,
+                config.encoding,
+            )),
+            expected,
+        )
+    };
+
+    Ok(DebugCase {
+        marked: marked.contains(&name),
+        name,
+        input,
+        expected,
+        hidden,
+        run: None,
+    })
+}
+
+/// Runs one case quietly, keeping stdout and stderr for the TUI instead of printing them.
+fn debug_run(prog: &Path, target: &Path, in_file: &Path, config: &OwlConfig) -> DebugRun {
+    let run_result = fs_utils::read_case(in_file).and_then(|stdin| {
+        cmd_utils::set_case_args(fs_utils::read_case_args(in_file)?);
+
+        let run_result = match prog_utils::resolve_prog_lang(prog, config) {
+            Some(lang) => lang.run_with_stdin(target, &stdin, config),
+            None => prog_utils::run_binary(target, Some(&stdin), config),
+        };
+
+        cmd_utils::set_case_args(Vec::new());
+        run_result
+    });
+
+    match run_result {
+        Ok((actual, elapsed)) => {
+            let passed = super::find_answers(in_file).is_ok_and(|ans_files| {
+                ans_files.iter().any(|ans_file| {
+                    fs_utils::read_case(ans_file).is_ok_and(|ans| {
+                        judge_utils::outputs_match(&ans, &actual, config.compare, &config.filters)
+                    })
+                })
+            });
+
+            DebugRun {
+                passed,
+                elapsed,
+                stdout: clip(&judge_utils::preview_output(&actual, config.encoding)),
+                stderr: clip(&String::from_utf8_lossy(&cmd_utils::last_stderr())),
+            }
+        }
+        Err(e) => DebugRun {
+            passed: false,
+            elapsed: Duration::ZERO,
+            stdout: String::new(),
+            stderr: clip(&e.to_string()),
+        },
+    }
+}
+
+fn clip(text: &str) -> String {
+    let num_lines = text.lines().count();
+
+    if num_lines <= DEBUG_LINES {
+        return text.to_string();
+    }
+
+    let mut clipped = text
+        .lines()
+        .take(DEBUG_LINES)
+        .collect::<Vec<&str>>()
+        .join("\n");
+    clipped.push_str(&format!("\n… ({} more lines)", num_lines - DEBUG_LINES));
+    clipped
+}
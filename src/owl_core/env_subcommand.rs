@@ -0,0 +1,248 @@
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::{fs_utils, toml_utils};
+use crate::{
+    CACHE_DIR, CHAT_DIR, GIT_DIR, LAST_RUN_DIR, MANIFEST, OWL_DIR, POOL_DIR, PROGRESS, PROMPT_DIR,
+    SCRIPT_DIR, STASH_DIR, TOML_TEMPLATE,
+};
+use chrono::Local;
+use std::cmp::Ordering;
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process;
+use toml_edit::{Array, DocumentMut, value};
+
+const EXPORT_FORMAT: i64 = 1;
+const EXPORT_HEADER: &str = ".owlgo_export.toml";
+
+/// The parts of the owlgo dir that `export` bundles and `import` restores.
+pub const ENV_PARTS: &[&str] = &[
+    "manifest", "progress", "stash", "prompts", "chats", "scripts", "quests",
+];
+
+pub fn export_env(archive_path: &Path, with_chats: bool, with_quests: bool) -> Result<()> {
+    let owl_dir = fs_utils::ensure_path_from_home(&[OWL_DIR], None)?;
+
+    if !owl_dir.exists() {
+        return Err(OwlError::FileError(
+            format!("'{}': no such dir", owl_dir.to_string_lossy()),
+            "nothing to export".into(),
+        ));
+    }
+
+    let parts = ENV_PARTS
+        .iter()
+        .copied()
+        .filter(|&part| (part != "chats" || with_chats) && (part != "quests" || with_quests))
+        .collect::<Vec<&str>>();
+
+    let mut files = fs_utils::dir_tree(&owl_dir)?
+        .into_iter()
+        .filter(|file| {
+            file.strip_prefix(&owl_dir)
+                .ok()
+                .and_then(env_part)
+                .is_some_and(|part| parts.contains(&part))
+        })
+        .collect::<Vec<PathBuf>>();
+    files.sort();
+
+    let mut header_doc = DocumentMut::new();
+    header_doc["format"] = value(EXPORT_FORMAT);
+    header_doc["version"] = value(toml_utils::get_embedded_version(TOML_TEMPLATE)?);
+    header_doc["created"] = value(Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+    header_doc["parts"] = value(parts.iter().copied().collect::<Array>());
+
+    let header_path = owl_dir.join(EXPORT_HEADER);
+
+    fs::write(&header_path, header_doc.to_string()).map_err(|e| {
+        OwlError::FileError(
+            format!("could not write '{}'", header_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    files.insert(0, header_path.clone());
+
+    let archive_result = fs_utils::create_tar_archive(&owl_dir, &files, archive_path);
+    fs_utils::remove_path(&header_path)?;
+    archive_result?;
+
+    println!(
+        "exported {} files ({}) to '{}'",
+        files.len() - 1,
+        parts.join(", "),
+        archive_path.to_string_lossy()
+    );
+
+    Ok(())
+}
+
+pub async fn import_env(archive_path: &Path, only: &[String], force: bool) -> Result<()> {
+    if !archive_path.is_file() {
+        return Err(OwlError::FileError(
+            format!("'{}': no such file", archive_path.to_string_lossy()),
+            "".into(),
+        ));
+    }
+
+    let owl_dir = fs_utils::ensure_path_from_home(&[OWL_DIR], None)?;
+    let import_dir = env::temp_dir().join(format!("owlgo-import-{}", process::id()));
+
+    let import_result = match fs_utils::extract_tar_archive(archive_path, &import_dir, false).await
+    {
+        Ok(()) => restore_env(&import_dir, &owl_dir, only, force),
+        Err(e) => Err(e),
+    };
+
+    if import_dir.exists() {
+        fs_utils::remove_path(&import_dir)?;
+    }
+
+    let (restored, skipped) = import_result?;
+
+    println!(
+        "imported {} files from '{}'",
+        restored,
+        archive_path.to_string_lossy()
+    );
+
+    if skipped > 0 {
+        println!(
+            "skipped {} existing files that differ (use '--force' to overwrite)",
+            skipped
+        );
+    }
+
+    Ok(())
+}
+
+fn restore_env(
+    import_dir: &Path,
+    owl_dir: &Path,
+    only: &[String],
+    force: bool,
+) -> Result<(usize, usize)> {
+    let header_path = import_dir.join(EXPORT_HEADER);
+
+    if !header_path.is_file() {
+        return Err(OwlError::FileError(
+            "archive is not an owlgo export".into(),
+            format!("missing '{}'", EXPORT_HEADER),
+        ));
+    }
+
+    let header_doc = toml_utils::read_toml(&header_path)?;
+
+    let format = header_doc
+        .get("format")
+        .and_then(|item| item.as_integer())
+        .unwrap_or(0);
+
+    if format != EXPORT_FORMAT {
+        return Err(OwlError::Unsupported(format!(
+            "export format {} is not supported (expected {})",
+            format, EXPORT_FORMAT
+        )));
+    }
+
+    let version = toml_utils::get_embedded_version(TOML_TEMPLATE)?;
+    let export_version = header_doc
+        .get("version")
+        .and_then(|item| item.as_str())
+        .unwrap_or("0.0.0");
+
+    match toml_utils::compare_stamps(export_version, &version)? {
+        Ordering::Greater if !force => {
+            return Err(OwlError::Unsupported(format!(
+                "archive was exported by owlgo {}, which is newer than {} (update owlgo or pass '--force')",
+                export_version, version
+            )));
+        }
+        Ordering::Equal => {}
+        _ => warn(OwlWarning::Recovered(format!(
+            "archive was exported by owlgo {} (running {})",
+            export_version, version
+        ))),
+    }
+
+    let export_parts = header_doc
+        .get("parts")
+        .and_then(|item| item.as_array())
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|part| part.as_str().map(String::from))
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+
+    for part in only.iter().filter(|part| !export_parts.contains(part)) {
+        warn(OwlWarning::Skipped(format!(
+            "'{}': not included in the archive",
+            part
+        )));
+    }
+
+    let is_selected = |part: &str| {
+        export_parts.iter().any(|p| p == part)
+            && (only.is_empty() || only.iter().any(|p| p == part))
+    };
+
+    let mut restored = 0;
+    let mut skipped = 0;
+
+    for file in fs_utils::dir_tree(import_dir)? {
+        let Ok(rel_path) = file.strip_prefix(import_dir) else {
+            continue;
+        };
+
+        if !env_part(rel_path).is_some_and(is_selected) {
+            continue;
+        }
+
+        let dst = owl_dir.join(rel_path);
+
+        if dst.exists() && !force {
+            if fs::read(&dst).ok() != fs::read(&file).ok() {
+                skipped += 1;
+            }
+
+            continue;
+        }
+
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                OwlError::FileError(
+                    format!("could not create dir '{}'", parent.to_string_lossy()),
+                    e.to_string(),
+                )
+            })?;
+        }
+
+        fs_utils::copy_file(&file, &dst)?;
+        restored += 1;
+    }
+
+    Ok((restored, skipped))
+}
+
+fn env_part(rel_path: &Path) -> Option<&'static str> {
+    let mut components = rel_path.components().map(|component| match component {
+        Component::Normal(name) => name.to_str(),
+        _ => None,
+    });
+
+    match (components.next()??, components.next()) {
+        (MANIFEST, None) => Some("manifest"),
+        (PROGRESS, None) => Some("progress"),
+        (STASH_DIR, Some(Some(CHAT_DIR))) => Some("chats"),
+        (STASH_DIR, Some(Some(PROMPT_DIR))) => Some("prompts"),
+        (STASH_DIR, Some(Some(GIT_DIR))) => None,
+        (STASH_DIR, Some(_)) => Some("stash"),
+        (SCRIPT_DIR, Some(_)) => Some("scripts"),
+        (CACHE_DIR | LAST_RUN_DIR | POOL_DIR, _) => None,
+        (_, Some(_)) => Some("quests"),
+        _ => None,
+    }
+}
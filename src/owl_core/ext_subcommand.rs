@@ -0,0 +1,222 @@
+use super::pack_subcommand;
+use crate::common::{OwlError, Result};
+use crate::owl_utils::fs::manifest::{self, EXT_GIT_TABLE, QUEST_TABLES};
+use crate::owl_utils::{Manifest, Uri, fs_utils, git_utils, toml_utils};
+use crate::{EXT_DIR, EXT_MANIFEST, OWL_DIR, PROMPT_DIR, STASH_DIR};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, Item, Table, value};
+
+const EXT_QUEST_DIR: &str = "quests";
+const EXT_TEMPLATE: &str = r#"
+[manifest]
+timestamp = "0.0.0"
+
+[quests]
+
+[prompts]
+"#;
+
+/// Clones a team extension's repo into `~/.owlgo/.ext/<NAME>` and adds the manifest in it as a
+/// local extension. An empty repo is started as an empty extension.
+pub async fn add_git_extension(
+    ext_name: &str,
+    repo_url: &str,
+    pub_key: Option<&str>,
+    and_fetch: bool,
+) -> Result<()> {
+    let ext_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, EXT_DIR], None)?;
+    let clone_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, EXT_DIR], Some(ext_name))?;
+    let ext_path = clone_dir.join(EXT_MANIFEST);
+
+    if clone_dir.exists() {
+        return Err(OwlError::FileError(
+            format!("'{}': already cloned", clone_dir.to_string_lossy()),
+            "remove it or pick another name".into(),
+        ));
+    }
+
+    eprintln!(
+        ">>> cloning extension '{}' from '{}' ...",
+        ext_name, repo_url
+    );
+    git_utils::git_clone(&ext_dir, repo_url, &clone_dir)?;
+
+    if !ext_path.exists() {
+        eprintln!(
+            "'{}': has no {}, starting an empty extension",
+            repo_url, EXT_MANIFEST
+        );
+
+        toml_utils::create_toml(&ext_path, EXT_TEMPLATE)?;
+        git_utils::git_add(&clone_dir)?;
+        git_utils::git_commit(&clone_dir, &format!("start extension '{}'", ext_name))?;
+        git_utils::git_push(
+            &clone_dir,
+            "origin",
+            &git_utils::git_branch(&clone_dir)?,
+            false,
+        )?;
+    }
+
+    super::add_extension(ext_name, &Uri::Local(ext_path), pub_key, and_fetch).await?;
+
+    let mut manifest = Manifest::load()?;
+    manifest::set_entry(manifest.doc_mut(), EXT_GIT_TABLE, ext_name, value(repo_url));
+    manifest.write()
+}
+
+/// Contributes a personal quest back to a team extension: archives it into the clone, adds it
+/// to the extension's manifest (with a changelog entry), then commits and pushes.
+pub async fn publish_quest(quest_name: &str, ext_name: Option<&str>) -> Result<()> {
+    let mut manifest = Manifest::open()?;
+    let ext_name = git_extension(&manifest, ext_name)?;
+
+    if toml_utils::extension_key(manifest.doc(), &ext_name).is_some() {
+        return Err(OwlError::Unsupported(format!(
+            "'{}': extension is pinned to a signing key, publishing would break its signature",
+            ext_name
+        )));
+    }
+
+    if !manifest::entries(manifest.doc(), QUEST_TABLES[0]).any(|(name, _)| name == quest_name) {
+        return Err(OwlError::TomlError(
+            format!("'{}': not a personal quest", quest_name),
+            "add it with 'owlgo add <NAME> <URI>' first".into(),
+        ));
+    }
+
+    let quest_dir = fs_utils::quest_path(quest_name)?;
+
+    if !quest_dir.exists() {
+        super::fetch_quest(quest_name).await?;
+    }
+
+    let ext_path = match manifest.extension_uri(&ext_name)? {
+        Uri::Local(ext_path) => ext_path,
+        Uri::Remote(url) => {
+            return Err(OwlError::UriError(
+                format!("'{}': extension is read from '{}'", ext_name, url),
+                "expected a local clone".into(),
+            ));
+        }
+    };
+    let clone_dir = ext_path.parent().unwrap_or(Path::new(".")).to_path_buf();
+
+    toml_utils::pull_git_extension(&ext_path)?;
+
+    let quest_dir = quest_dir.canonicalize().map_err(|e| {
+        OwlError::FileError(
+            format!("Failed to access dir '{}'", quest_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+    let quest_files = pack_subcommand::quest_files(&quest_dir)?;
+
+    let archive = PathBuf::from(EXT_QUEST_DIR).join(format!("{}.tar.gz", quest_name));
+    let archive_dir = clone_dir.join(EXT_QUEST_DIR);
+
+    fs::create_dir_all(&archive_dir).map_err(|e| {
+        OwlError::FileError(
+            format!("could not create dir '{}'", archive_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+    fs_utils::create_tar_archive(&quest_dir, &quest_files, &clone_dir.join(&archive))?;
+
+    let mut ext_doc = toml_utils::read_toml(&ext_path)?;
+
+    let timestamp = toml_utils::next_stamp(
+        ext_doc
+            .get("manifest")
+            .and_then(|header| header.get("timestamp"))
+            .and_then(Item::as_str)
+            .unwrap_or("0.0.0"),
+    )?;
+    let is_new = !manifest::entries(&ext_doc, "quests").any(|(name, _)| name == quest_name);
+
+    let mut changelog_entry = Table::new();
+    changelog_entry.insert(
+        if is_new { "added" } else { "changed" },
+        value(Array::from_iter([quest_name])),
+    );
+
+    manifest::set_entry(&mut ext_doc, "manifest", "timestamp", value(&timestamp));
+    manifest::set_entry(
+        &mut ext_doc,
+        "quests",
+        quest_name,
+        value(archive.to_string_lossy().replace('\\', "/")),
+    );
+    manifest::set_entry(
+        &mut ext_doc,
+        "changelog",
+        &timestamp,
+        Item::Table(changelog_entry),
+    );
+
+    toml_utils::write_manifest(&ext_doc, &ext_path)?;
+
+    git_utils::git_add(&clone_dir)?;
+    git_utils::git_commit(
+        &clone_dir,
+        &format!(
+            "{} quest '{}'",
+            if is_new { "publish" } else { "update" },
+            quest_name
+        ),
+    )?;
+    git_utils::git_push(
+        &clone_dir,
+        "origin",
+        &git_utils::git_branch(&clone_dir)?,
+        false,
+    )?;
+
+    let ext_uri = Uri::Local(ext_path);
+    let ext_doc = toml_utils::read_extension(&ext_uri, None).await?;
+    let prompt_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, PROMPT_DIR], None)?;
+
+    toml_utils::commit_extension(
+        &mut manifest,
+        &prompt_dir,
+        &ext_name,
+        &ext_uri,
+        &ext_doc,
+        None,
+    )
+    .await?;
+
+    println!(
+        "published '{}' to extension '{}' ({})",
+        quest_name, ext_name, timestamp
+    );
+
+    Ok(())
+}
+
+/// The team extension to publish to: the one named, or else the only one there is.
+fn git_extension(manifest: &Manifest, ext_name: Option<&str>) -> Result<String> {
+    let git_exts = manifest::entries(manifest.doc(), EXT_GIT_TABLE)
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<String>>();
+
+    match (ext_name, git_exts.as_slice()) {
+        (Some(ext_name), _) if git_exts.iter().any(|name| name == ext_name) => {
+            Ok(ext_name.to_string())
+        }
+        (Some(ext_name), _) => Err(OwlError::TomlError(
+            format!("'{}': not a git extension", ext_name),
+            "add it with 'owlgo add <NAME> <REPO> --ext --git'".into(),
+        )),
+        (None, [ext_name]) => Ok(ext_name.clone()),
+        (None, []) => Err(OwlError::TomlError(
+            "No git extensions to publish to".into(),
+            "add one with 'owlgo add <NAME> <REPO> --ext --git'".into(),
+        )),
+        (None, _) => Err(OwlError::TomlError(
+            "More than one git extension to publish to".into(),
+            format!("pass '--ext' with one of {}", git_exts.join(", ")),
+        )),
+    }
+}
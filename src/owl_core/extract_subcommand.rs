@@ -0,0 +1,267 @@
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::fs_utils;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+
+const SAMPLE_STEM: &str = "sample";
+const STATEMENT_NAMES: &[&str] = &[
+    "readme.md",
+    "statement.md",
+    "problem.md",
+    "statement.html",
+    "problem.html",
+    "index.html",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SampleKind {
+    Input,
+    Output,
+}
+
+enum Chunk {
+    /// A heading for the next block; `true` when it names a sample/example explicitly.
+    Label(SampleKind, bool),
+    Block(String),
+}
+
+/// Turns the sample blocks of a problem statement into `.in`/`.ans` files in the quest dir.
+pub fn extract_samples(quest_name: &str, statement: Option<&Path>, force: bool) -> Result<()> {
+    let quest_dir = fs_utils::quest_path(quest_name)?;
+
+    let statement_path = match statement {
+        Some(statement) => statement.to_path_buf(),
+        None if quest_dir.is_dir() => {
+            fs_utils::find_by_names(&quest_dir, STATEMENT_NAMES)?.ok_or(OwlError::FileError(
+                format!("'{}': no statement found", quest_name),
+                format!("expected one of {}", STATEMENT_NAMES.join(", ")),
+            ))?
+        }
+        None => {
+            return Err(OwlError::FileError(
+                format!("'{}': no such quest", quest_name),
+                "pass the statement to extract from".into(),
+            ));
+        }
+    };
+
+    let statement_str = fs::read_to_string(&statement_path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read '{}'", statement_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let is_html = statement_path
+        .extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"))
+        || statement_str.trim_start().starts_with('<');
+
+    let chunks = if is_html {
+        html_chunks(&statement_str)
+    } else {
+        markdown_chunks(&statement_str)
+    };
+
+    let samples = pair_samples(chunks);
+
+    if samples.is_empty() {
+        return Err(OwlError::FileError(
+            format!("'{}': no samples found", statement_path.to_string_lossy()),
+            "expected code blocks, optionally headed 'Sample Input'/'Sample Output'".into(),
+        ));
+    }
+
+    fs::create_dir_all(&quest_dir).map_err(|e| {
+        OwlError::FileError(
+            format!("could not create dir '{}'", quest_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let mut written = 0;
+
+    for (i, (input, output)) in samples.iter().enumerate() {
+        let in_path = quest_dir.join(format!("{}-{}.in", SAMPLE_STEM, i + 1));
+        let ans_path = in_path.with_extension("ans");
+
+        if !force && (in_path.exists() || ans_path.exists()) {
+            warn(OwlWarning::Skipped(format!(
+                "'{}': already exists (use '--force' to overwrite)",
+                in_path.to_string_lossy()
+            )));
+            continue;
+        }
+
+        for (path, contents) in [(&in_path, input), (&ans_path, output)] {
+            fs::write(path, contents).map_err(|e| {
+                OwlError::FileError(
+                    format!("could not write '{}'", path.to_string_lossy()),
+                    e.to_string(),
+                )
+            })?;
+        }
+
+        written += 1;
+    }
+
+    println!(
+        "extracted {} of {} samples into '{}'",
+        written,
+        samples.len(),
+        quest_dir.to_string_lossy()
+    );
+
+    Ok(())
+}
+
+fn markdown_chunks(statement_str: &str) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut fence: Option<(&str, String)> = None;
+
+    for line in statement_str.lines() {
+        let trimmed = line.trim_start();
+
+        match fence.take() {
+            Some((marker, block)) if trimmed.starts_with(marker) => {
+                chunks.push(Chunk::Block(block));
+            }
+            Some((marker, mut block)) => {
+                block.push_str(line);
+                block.push('\n');
+                fence = Some((marker, block));
+            }
+            None if trimmed.starts_with("```") => fence = Some(("```", String::new())),
+            None if trimmed.starts_with("~~~") => fence = Some(("~~~", String::new())),
+            None => chunks.extend(sample_label(line)),
+        }
+    }
+
+    chunks
+}
+
+fn html_chunks(statement_str: &str) -> Vec<Chunk> {
+    let lower = statement_str.to_ascii_lowercase();
+    let mut chunks = Vec::new();
+    let mut pos = 0;
+
+    while let Some(start) = lower[pos..].find("<pre").map(|i| pos + i) {
+        let text = strip_tags(&statement_str[pos..start].replace('>', ">\n"));
+        chunks.extend(text.lines().filter_map(sample_label));
+
+        let Some(body_start) = lower[start..].find('>').map(|i| start + i + 1) else {
+            break;
+        };
+        let body_end = lower[body_start..]
+            .find("</pre>")
+            .map(|i| body_start + i)
+            .unwrap_or(lower.len());
+
+        let body = statement_str[body_start..body_end]
+            .replace("<br>", "\n")
+            .replace("<br/>", "\n")
+            .replace("<br />", "\n")
+            .replace("</div>", "\n");
+
+        let block = decode_entities(&strip_tags(&body));
+        let block = block.trim_start_matches(['\n', '\r']).trim_end();
+        chunks.push(Chunk::Block(format!("{}\n", block)));
+
+        pos = (body_end + "</pre>".len()).min(lower.len());
+    }
+
+    chunks
+}
+
+fn pair_samples(chunks: Vec<Chunk>) -> Vec<(String, String)> {
+    let has_labels = chunks.iter().any(|chunk| matches!(chunk, Chunk::Label(..)));
+    let has_sample_labels = chunks
+        .iter()
+        .any(|chunk| matches!(chunk, Chunk::Label(_, true)));
+
+    let mut samples = Vec::new();
+    let mut label = None;
+    let mut pending_input = None;
+
+    for chunk in chunks {
+        let block = match chunk {
+            // a bare "Input" heading usually describes the format once samples are labelled
+            Chunk::Label(kind, is_sample) if is_sample || !has_sample_labels => {
+                label = Some(kind);
+                continue;
+            }
+            Chunk::Label(..) => continue,
+            Chunk::Block(block) => block,
+        };
+
+        let kind = match (has_labels, &pending_input) {
+            (true, _) => label.take(),
+            (false, Some(_)) => Some(SampleKind::Output),
+            (false, None) => Some(SampleKind::Input),
+        };
+
+        match kind {
+            Some(SampleKind::Input) => pending_input = Some(block),
+            Some(SampleKind::Output) => {
+                if let Some(input) = pending_input.take() {
+                    samples.push((input, block));
+                }
+            }
+            None => {}
+        }
+    }
+
+    samples
+}
+
+/// Recognizes headings like "Sample Input 1", "Example output:", or a bare "Input".
+fn sample_label(line: &str) -> Option<Chunk> {
+    let text = line
+        .trim()
+        .trim_start_matches(['#', '*', '_', ' '])
+        .trim_end_matches(['*', '_', ':', ' '])
+        .to_ascii_lowercase();
+
+    let words = text.split_whitespace().collect::<Vec<&str>>();
+
+    let (kind_word, is_sample) = match words.as_slice() {
+        ["sample" | "example", kind] => (kind, true),
+        ["sample" | "example", kind, n] if n.chars().all(|c| c.is_ascii_digit()) => (kind, true),
+        [kind] => (kind, false),
+        [kind, n] if n.chars().all(|c| c.is_ascii_digit()) => (kind, false),
+        _ => return None,
+    };
+
+    match *kind_word {
+        "input" => Some(Chunk::Label(SampleKind::Input, is_sample)),
+        "output" => Some(Chunk::Label(SampleKind::Output, is_sample)),
+        _ => None,
+    }
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
@@ -1,90 +1,97 @@
-use crate::common::{OwlError, Result};
-use crate::owl_utils::{Uri, fs_utils, toml_utils};
-use crate::{MANIFEST, OWL_DIR, PROMPT_DIR, STASH_DIR, TMP_ARCHIVE};
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::fs::QuestEntry;
+use crate::owl_utils::fs::manifest::{self, QUEST_TABLES};
+use crate::owl_utils::progress_utils::{self, ProgressMode};
+use crate::owl_utils::{Manifest, Uri, fs_utils, toml_utils};
+use crate::{OWL_DIR, PROMPT_DIR, STASH_DIR, TMP_ARCHIVE};
 use futures::prelude::*;
+use std::fs;
 use std::path::Path;
 
-pub async fn fetch_extension(ext_name: &str) -> Result<()> {
-    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
-
-    if !manifest_path.exists() {
-        return Err(OwlError::FileError(
-            "The manifest does not exist".into(),
-            "".into(),
-        ));
-    }
+const INTRO_MARKER: &str = ".intro_shown";
+const INTRO_NAMES: &[&str] = &["readme.md", "statement.md", "problem.md"];
 
-    let manifest_doc = toml_utils::read_toml(&manifest_path)?;
+pub async fn fetch_extension(ext_name: &str) -> Result<()> {
+    fs_utils::check_name(ext_name, "extension")?;
 
-    let uri = match manifest_doc["ext_uri"].get(ext_name) {
-        Some(uri_item) => {
-            let uri_str = uri_item.as_str().ok_or(OwlError::TomlError(
-                format!("Invalid URI entry '{}' in manifest", ext_name),
-                "None".into(),
-            ))?;
-            Uri::try_from(uri_str)?
-        }
-        None => {
-            return Err(OwlError::TomlError(
-                format!("'{}': no such entry found manifest", ext_name),
-                "None".into(),
-            ));
-        }
-    };
+    let mut manifest = Manifest::open()?;
+    let uri = manifest.extension_uri(ext_name)?;
+    let is_git = manifest.extension_git(ext_name).is_some();
 
-    let ext_doc = match uri {
-        Uri::Local(path) => {
-            eprintln!(
-                "reading extension '{}' at '{}'",
-                ext_name,
-                path.to_string_lossy()
-            );
-            toml_utils::read_toml(&path)?
+    match &uri {
+        Uri::Local(path) if is_git => {
+            eprintln!(">>> pulling extension '{}' ...", ext_name);
+            toml_utils::pull_git_extension(path)?;
         }
+        Uri::Local(path) => eprintln!(
+            "reading extension '{}' at '{}'",
+            ext_name,
+            path.to_string_lossy()
+        ),
         Uri::Remote(url) => {
-            eprintln!(">>> requesting extension '{}' from '{}' ...", ext_name, url);
-            toml_utils::request_toml(&url).await?
+            eprintln!(">>> requesting extension '{}' from '{}' ...", ext_name, url)
         }
-    };
+    }
+
+    let ext_doc = toml_utils::read_extension(
+        &uri,
+        toml_utils::extension_key(manifest.doc(), ext_name).as_deref(),
+    )
+    .await?;
+
+    // a team extension may have gained quests since it was added
+    if is_git {
+        let prompt_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, PROMPT_DIR], None)?;
+        toml_utils::commit_extension(&mut manifest, &prompt_dir, ext_name, &uri, &ext_doc, None)
+            .await?;
+    }
 
-    let owl_path = manifest_path.parent().expect("owlgo directory to exist");
+    let owl_path = manifest.path().parent().expect("owlgo directory to exist");
 
     let tmp_archive = Path::new(TMP_ARCHIVE);
 
-    let quest_futures = ext_doc["quests"]
-        .as_table()
-        .into_iter()
-        .flat_map(|quests_table| quests_table.iter())
-        .map(|(quest_name, quest_uri)| async move {
+    let quest_futures =
+        manifest::entries(&ext_doc, "quests").map(|(quest_name, quest_uri)| async move {
             let mut quest_path = owl_path.to_path_buf();
             quest_path.push(quest_name);
 
-            let quest_uri_str = quest_uri.as_str().ok_or(OwlError::TomlError(
-                format!("Invalid entry '{}' in extension '{}'", quest_name, ext_name),
-                "None".into(),
-            ))?;
+            let quest_entry = QuestEntry::from_item(quest_name, quest_uri).map_err(|e| {
+                OwlError::TomlError(
+                    format!("Invalid entry '{}' in extension '{}'", quest_name, ext_name),
+                    e.to_string(),
+                )
+            })?;
 
-            match Uri::try_from(quest_uri_str)? {
-                Uri::Local(path) => {
-                    eprintln!(
-                        ">>> extracting quest '{}' at '{}' ...",
-                        quest_name,
-                        path.to_string_lossy()
-                    );
-                    fs_utils::extract_archive(&path, &quest_path, false).await
-                }
-                Uri::Remote(url) => {
-                    eprintln!(">>> downloading quest '{}' from '{}' ...", quest_name, url);
-                    fs_utils::download_archive(&url, tmp_archive, &quest_path).await
+            let is_text = progress_utils::progress_mode() == ProgressMode::Text;
+
+            let fetch = async {
+                match Uri::try_from(quest_entry.url.as_str())? {
+                    Uri::Local(path) => {
+                        if is_text {
+                            eprintln!(
+                                ">>> extracting quest '{}' at '{}' ...",
+                                quest_name,
+                                path.to_string_lossy()
+                            );
+                        }
+                        let sha256 = quest_entry.sha256.as_deref();
+                        fs_utils::extract_archive(&path, &quest_path, false, sha256).await
+                    }
+                    Uri::Remote(url) => {
+                        if is_text {
+                            eprintln!(">>> downloading quest '{}' from '{}' ...", quest_name, url);
+                        }
+                        let sha256 = quest_entry.sha256.as_deref();
+                        fs_utils::download_archive(&url, tmp_archive, &quest_path, sha256).await
+                    }
                 }
-            }
+            };
+
+            progress_utils::track("download", quest_name, fetch).await
         });
 
-    let prompt_futures = ext_doc["prompts"]
-        .as_table()
-        .into_iter()
-        .flat_map(|prompts_table| prompts_table.iter())
-        .map(|(prompt_name, prompt_uri)| async move {
+    let prompt_futures =
+        manifest::entries(&ext_doc, "prompts").map(|(prompt_name, prompt_uri)| async move {
             let mut prompt_path = owl_path.to_path_buf();
             prompt_path.push(STASH_DIR);
             prompt_path.push(PROMPT_DIR);
@@ -132,38 +139,14 @@ pub async fn fetch_extension(ext_name: &str) -> Result<()> {
 }
 
 pub async fn fetch_prompt(prompt_name: &str) -> Result<()> {
-    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
-    let prompt_path =
-        fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, PROMPT_DIR], Some(prompt_name))?;
-
-    if !manifest_path.exists() {
-        return Err(OwlError::FileError(
-            "The manifest does not exist".into(),
-            "".into(),
-        ));
-    }
+    let prompt_path = fs_utils::prompt_path(prompt_name)?;
 
-    let manifest_doc = toml_utils::read_toml(&manifest_path)?;
-
-    let prompt_entry = manifest_doc["personal_prompts"]
-        .get(prompt_name)
-        .or(manifest_doc["prompts"].get(prompt_name));
-
-    let uri = match prompt_entry {
-        Some(uri_item) => {
-            let uri_str = uri_item.as_str().ok_or(OwlError::TomlError(
-                format!("Invalid entry '{}' in manifest", prompt_name),
-                "None".into(),
-            ))?;
-            Uri::try_from(uri_str)?
-        }
-        None => {
-            return Err(OwlError::TomlError(
-                format!("'{}': no such entry found manifest", prompt_name),
-                "None".into(),
-            ));
-        }
-    };
+    let uri = Manifest::open()?
+        .lookup_prompt(prompt_name)?
+        .ok_or(OwlError::TomlError(
+            format!("'{}': no such entry found manifest", prompt_name),
+            "None".into(),
+        ))?;
 
     match uri {
         Uri::Local(path) => fs_utils::copy_file(&path, &prompt_path),
@@ -171,42 +154,224 @@ pub async fn fetch_prompt(prompt_name: &str) -> Result<()> {
     }
 }
 
+/// Fetches a quest by its manifest entry. Failing that, the error lists where the quest was
+/// looked for (personal quests, quests, then extensions) and what to try next.
 pub async fn fetch_quest(quest_name: &str) -> Result<()> {
-    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
-    let quest_dir = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(quest_name))?;
+    let quest_dir = fs_utils::quest_path(quest_name)?;
+    let not_fetched = format!("'{}': could not fetch quest", quest_name);
+
+    let manifest = Manifest::open().map_err(|e| {
+        OwlError::Unresolved(
+            not_fetched.clone(),
+            vec![match e {
+                OwlError::FileError(expr, _) => format!("manifest: {}", expr),
+                e => format!("manifest: {}", e),
+            }],
+            "run 'owlgo update' to download the manifest".into(),
+        )
+    })?;
+
+    let Some(quest_entry) = manifest.lookup_quest(quest_name)? else {
+        let mut tried = QUEST_TABLES
+            .iter()
+            .map(|table_name| {
+                format!(
+                    "[{}]: no entry among {} quest(s)",
+                    table_name,
+                    manifest::entries(manifest.doc(), table_name).count()
+                )
+            })
+            .collect::<Vec<String>>();
 
-    if !manifest_path.exists() {
-        return Err(OwlError::FileError(
-            "The manifest does not exist".into(),
-            "".into(),
+        let ext_names = manifest
+            .iter_extensions()
+            .filter_map(|ext_entry| ext_entry.ok().map(|ext_entry| ext_entry.name))
+            .collect::<Vec<String>>();
+
+        tried.push(if ext_names.is_empty() {
+            "[extensions]: none added".into()
+        } else {
+            format!(
+                "[extensions]: not listed by {} as of their last fetch",
+                ext_names.join(", ")
+            )
+        });
+
+        tried.extend(
+            manifest
+                .quests()?
+                .into_iter()
+                .filter(|quest| is_similar(quest_name, &quest.name))
+                .map(|quest| format!("did you mean {}?", quest.describe())),
+        );
+
+        return Err(OwlError::Unresolved(
+            not_fetched,
+            tried,
+            "run 'owlgo list' to see every quest, or 'owlgo update' to refresh the manifest and its extensions".into(),
         ));
-    }
+    };
 
-    let manifest_doc = toml_utils::read_toml(&manifest_path)?;
-    let quest_entry = manifest_doc["personal_quests"]
-        .get(quest_name)
-        .or(manifest_doc["quests"].get(quest_name));
+    let table_name = QUEST_TABLES
+        .iter()
+        .find(|table_name| {
+            manifest::entries(manifest.doc(), table_name).any(|(name, _)| name == quest_name)
+        })
+        .unwrap_or(&QUEST_TABLES[0]);
+    let found = format!("[{}]: found '{}'", table_name, quest_entry.url);
 
-    let uri = match quest_entry {
-        Some(uri_item) => {
-            let uri_str = uri_item.as_str().ok_or(OwlError::TomlError(
-                format!("Invalid entry '{}' in manifest", quest_name),
-                "None".into(),
-            ))?;
-            Uri::try_from(uri_str)?
+    let uri = Uri::try_from(quest_entry.url.as_str()).map_err(|e| {
+        OwlError::Unresolved(
+            not_fetched.clone(),
+            vec![found.clone(), format!("uri: {}", e)],
+            "run 'owlgo update' in case the entry has been fixed".into(),
+        )
+    })?;
+
+    let sha256 = quest_entry.sha256.as_deref();
+
+    let fetch = async {
+        match uri {
+            Uri::Local(path) if path.is_dir() => {
+                fs_utils::import_local_tests(&path, &quest_dir).map(|_| ())
+            }
+            Uri::Local(path) => fs_utils::extract_archive(&path, &quest_dir, false, sha256).await,
+            Uri::Remote(url) => {
+                fs_utils::download_archive(&url, Path::new(TMP_ARCHIVE), &quest_dir, sha256).await
+            }
         }
-        None => {
-            return Err(OwlError::TomlError(
-                format!("'{}': no such entry found manifest", quest_name),
-                "None".into(),
-            ));
+    };
+
+    progress_utils::track("download", quest_name, fetch)
+        .await
+        .map_err(|e| match e {
+            OwlError::Interrupted(_) => e,
+            e => OwlError::Unresolved(
+                not_fetched,
+                vec![found, format!("fetch: {}", e)],
+                "check your connection, or run 'owlgo update' in case the quest has moved".into(),
+            ),
+        })
+}
+
+/// The quest `quest_name` refers to: itself when a local quest or manifest entry has exactly that
+/// name, else the one it matches ignoring case and `-`/`_`. Local quests are checked first and the
+/// manifest only when none match. A match is echoed so that the same quest doesn't end up fetched
+/// (and tracked) under two spellings, and more than one match is an error.
+pub fn resolve_quest_name(quest_name: &str) -> Result<String> {
+    if let Some(canonical) = match_quest_name(quest_name, fs_utils::local_quests()?)? {
+        return Ok(canonical);
+    }
+
+    let manifest_quests = match Manifest::open() {
+        Ok(manifest) => manifest
+            .quests()?
+            .into_iter()
+            .map(|quest| quest.name)
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    Ok(match_quest_name(quest_name, manifest_quests)?.unwrap_or(quest_name.to_string()))
+}
+
+/// The name in `known` that `quest_name` is, or else the only one it normalizes the same as.
+fn match_quest_name(quest_name: &str, mut known: Vec<String>) -> Result<Option<String>> {
+    if known.iter().any(|name| name == quest_name) {
+        return Ok(Some(quest_name.to_string()));
+    }
+
+    let normalized = fs_utils::normalize_quest_name(quest_name);
+
+    known.retain(|name| fs_utils::normalize_quest_name(name) == normalized);
+    known.sort();
+    known.dedup();
+
+    match known.as_slice() {
+        [] => Ok(None),
+        [canonical] => {
+            eprintln!("resolved quest '{}' to '{}'", quest_name, canonical);
+            Ok(Some(canonical.clone()))
         }
+        candidates => Err(OwlError::Unresolved(
+            format!("quest '{}' is ambiguous", quest_name),
+            candidates
+                .iter()
+                .map(|name| format!("matches '{}'", name))
+                .collect(),
+            "use the exact name of the quest you mean".into(),
+        )),
+    }
+}
+
+pub fn show_intro(quest_dir: &Path) -> Result<()> {
+    let marker_path = quest_dir.join(INTRO_MARKER);
+
+    if marker_path.exists() {
+        return Ok(());
+    }
+
+    let Some(intro_path) = fs_utils::find_by_names(quest_dir, INTRO_NAMES)? else {
+        return Ok(());
     };
 
-    match uri {
-        Uri::Local(path) => fs_utils::extract_archive(&path, &quest_dir, false).await,
-        Uri::Remote(url) => {
-            fs_utils::download_archive(&url, Path::new(TMP_ARCHIVE), &quest_dir).await
+    super::show_and_glow(&intro_path)?;
+
+    fs::write(&marker_path, "").map_err(|e| {
+        OwlError::FileError(
+            format!("could not record intro '{}'", marker_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })
+}
+
+/// The quest's statement to show beside its cases, if it has a markdown one.
+pub(super) fn read_statement(quest_path: &Path) -> Option<String> {
+    if !quest_path.is_dir() {
+        return None;
+    }
+
+    let statement_path = fs_utils::find_by_names(quest_path, INTRO_NAMES).unwrap_or_else(|e| {
+        warn(OwlWarning::Recovered(e.to_string()));
+        None
+    })?;
+
+    fs::read_to_string(&statement_path)
+        .inspect_err(|e| {
+            warn(OwlWarning::Skipped(format!(
+                "could not read '{}': {}",
+                statement_path.to_string_lossy(),
+                e
+            )))
+        })
+        .ok()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars = b.chars().collect::<Vec<char>>();
+    let mut prev_row = (0..=b_chars.len()).collect::<Vec<usize>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1; b_chars.len() + 1];
+
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let cost = if a_char == *b_char { 0 } else { 1 };
+            row[j + 1] = (prev_row[j] + cost)
+                .min(prev_row[j + 1] + 1)
+                .min(row[j] + 1);
         }
+
+        prev_row = row;
     }
+
+    prev_row[b_chars.len()]
+}
+
+fn is_similar(query: &str, quest_name: &str) -> bool {
+    let query = fs_utils::normalize_quest_name(query);
+    let quest_name = fs_utils::normalize_quest_name(quest_name);
+
+    quest_name.contains(&query)
+        || query.contains(&quest_name)
+        || edit_distance(&query, &quest_name) <= query.chars().count().div_ceil(4)
 }
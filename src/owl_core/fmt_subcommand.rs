@@ -0,0 +1,26 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{OwlConfig, fmt_utils, prog_utils};
+use std::path::Path;
+
+/// Formats `prog` in place with the formatter for its language.
+pub fn format_program(prog: &Path, config: &OwlConfig) -> Result<()> {
+    if !prog.exists() {
+        return Err(OwlError::FileError(
+            format!("'{}': program not found", prog.to_string_lossy()),
+            "".into(),
+        ));
+    }
+
+    let lang =
+        prog_utils::resolve_prog_lang(prog, config).ok_or(OwlError::Unsupported(format!(
+            "'{}': no language detected (pass '--lang' to pick one)",
+            prog.to_string_lossy()
+        )))?;
+
+    match fmt_utils::format_file(prog, lang.name(), &config.formatters)? {
+        true => println!("'{}': formatted", prog.to_string_lossy()),
+        false => println!("'{}': already formatted", prog.to_string_lossy()),
+    }
+
+    Ok(())
+}
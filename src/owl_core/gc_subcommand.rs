@@ -0,0 +1,16 @@
+use crate::common::Result;
+use crate::owl_utils::pool_utils;
+
+pub fn gc_pool(dry_run: bool) -> Result<()> {
+    let summary = pool_utils::gc_pool(dry_run)?;
+
+    println!(
+        "{} {} of {} blobs ({:.1} MB)",
+        if dry_run { "would remove" } else { "removed" },
+        summary.removed,
+        summary.blobs,
+        summary.freed as f64 / (1024.0 * 1024.0)
+    );
+
+    Ok(())
+}
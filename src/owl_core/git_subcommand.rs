@@ -1,6 +1,13 @@
+use super::clear_subcommand;
 use crate::common::{OwlError, Result};
-use crate::owl_utils::{fs_utils, git_utils};
-use crate::{GIT_DIR, OWL_DIR, STASH_DIR};
+use crate::owl_utils::fmt_utils::Formatters;
+use crate::owl_utils::{fs_utils, git_utils, toml_utils};
+use crate::{GIT_DIR, MANIFEST, OWL_DIR, STASH_DIR};
+use chrono::{DateTime, Local};
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Table, TableLike, value};
+
+const AUTO_SYNC_MESSAGE: &str = "owlgo auto-sync";
 
 pub fn push_git_remote(use_force: bool) -> Result<()> {
     let git_path = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], Some(GIT_DIR))?;
@@ -18,7 +25,7 @@ pub fn push_git_remote(use_force: bool) -> Result<()> {
         .and_then(|stdout| {
             println!("{}", stdout);
 
-            git_utils::git_commit(stash_dir)
+            git_utils::git_commit(stash_dir, "\"owlgo CLI submission\"")
         })
         .and_then(|stdout| {
             println!("{}", stdout);
@@ -100,5 +107,156 @@ pub fn sync_git_remote(use_force: bool) -> Result<()> {
 
             git_utils::git_status(stash_dir)
         })
-        .map(|stdout| println!("{}", stdout))
+        .map(|stdout| println!("{}", stdout))?;
+
+    record_last_sync()
+}
+
+/// Schedules (or with `off`, stops) syncing the stash whenever the last sync is older than
+/// `interval`, e.g. `6h` or `1d`.
+pub fn set_auto_sync(interval: &str) -> Result<()> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+    let mut manifest_doc = toml_utils::read_toml(&manifest_path)?;
+
+    let git_table = git_table(&mut manifest_doc, &manifest_path)?;
+
+    if interval == "off" || clear_subcommand::parse_age(interval)?.is_zero() {
+        git_table.remove("auto_sync");
+        println!("stash auto-sync is off");
+    } else {
+        git_table.insert("auto_sync", value(interval));
+        println!("stash auto-sync every {}", interval);
+    }
+
+    toml_utils::write_manifest(&manifest_doc, &manifest_path)
+}
+
+/// Commits, pulls and pushes the stash when `git.auto_sync` is set and the last sync is older
+/// than it, returning whether a sync was attempted.
+pub fn auto_sync_git() -> Result<bool> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+    let git_path = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], Some(GIT_DIR))?;
+
+    if !manifest_path.exists() || !git_path.exists() {
+        return Ok(false);
+    }
+
+    let manifest_doc = toml_utils::read_toml(&manifest_path)?;
+    let git_item = |key: &str| {
+        manifest_doc
+            .get("git")
+            .and_then(|git| git.get(key))
+            .and_then(Item::as_str)
+    };
+
+    let Some(interval) = git_item("auto_sync") else {
+        return Ok(false);
+    };
+    let interval = clear_subcommand::parse_age(interval)?;
+
+    let is_due = git_item("last_sync")
+        .and_then(|last_sync| DateTime::parse_from_rfc3339(last_sync).ok())
+        .and_then(|last_sync| (Local::now().fixed_offset() - last_sync).to_std().ok())
+        .is_none_or(|elapsed| elapsed >= interval);
+
+    if !is_due {
+        return Ok(false);
+    }
+
+    // recorded up front so an unreachable remote isn't retried by every command
+    record_last_sync()?;
+
+    let stash_dir = git_path.parent().expect("stash directory to exist");
+
+    git_utils::git_add(stash_dir)?;
+
+    if let Err(e) = git_utils::git_commit(stash_dir, AUTO_SYNC_MESSAGE)
+        && !git_utils::git_status(stash_dir)
+            .is_ok_and(|status| status.contains("nothing to commit"))
+    {
+        return Err(e);
+    }
+
+    git_utils::git_pull(stash_dir, "origin", "main")?;
+    git_utils::git_push(stash_dir, "origin", "main", false)?;
+
+    Ok(true)
+}
+
+fn record_last_sync() -> Result<()> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+
+    if !manifest_path.exists() {
+        return Ok(());
+    }
+
+    let mut manifest_doc = toml_utils::read_toml(&manifest_path)?;
+    git_table(&mut manifest_doc, &manifest_path)?
+        .insert("last_sync", value(Local::now().to_rfc3339()));
+
+    toml_utils::write_manifest(&manifest_doc, &manifest_path)
+}
+
+/// The manifest's `[git]` table (or inline `git = { ... }`), added when missing.
+fn git_table<'a>(
+    manifest_doc: &'a mut DocumentMut,
+    manifest_path: &Path,
+) -> Result<&'a mut dyn TableLike> {
+    if !manifest_doc.contains_key("git") {
+        manifest_doc["git"] = Item::Table(Table::new());
+    }
+
+    manifest_doc["git"]
+        .as_table_like_mut()
+        .ok_or(OwlError::TomlError(
+            format!(
+                "Invalid entry 'git' in '{}'",
+                manifest_path.to_string_lossy()
+            ),
+            "expected a table".into(),
+        ))
+}
+
+/// Stashes an accepted solution, commits it and tags the commit `accepted/<quest>/<timestamp>`
+/// so the stash keeps every accepted version.
+pub fn tag_accepted(
+    quest_name: &str,
+    prog: &Path,
+    formatters: Option<&Formatters>,
+) -> Result<String> {
+    let git_path = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], Some(GIT_DIR))?;
+
+    if !git_path.exists() {
+        return Err(OwlError::FileError(
+            "No .git directory in stash".into(),
+            "run 'owlgo git <REMOTE>' to track the stash first".into(),
+        ));
+    }
+
+    let stash_dir = git_path.parent().expect("stash directory to exist");
+
+    // a different stashed copy is refused, as with 'owlgo stash', rather than overwritten
+    let stash_path =
+        super::stash_file(prog, false, false, super::StashConflict::Refuse, formatters)?;
+
+    let prog_name = prog.file_name().unwrap_or_default().to_string_lossy();
+    let tag = format!(
+        "accepted/{}/{}",
+        quest_name,
+        Local::now().format("%Y%m%d-%H%M%S")
+    );
+
+    git_utils::git_add_path(stash_dir, &stash_path)?;
+
+    // an unchanged solution has nothing to commit, so its last commit is tagged again
+    if git_utils::git_has_staged(stash_dir)? {
+        git_utils::git_commit(
+            stash_dir,
+            &format!("accepted '{}' with {}", quest_name, prog_name),
+        )?;
+    }
+
+    git_utils::git_tag(stash_dir, &tag)?;
+
+    Ok(tag)
 }
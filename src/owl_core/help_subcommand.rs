@@ -0,0 +1,236 @@
+use crate::common::{OwlError, Result};
+use clap::Command;
+use clap_mangen::Man;
+use std::fs;
+use std::path::Path;
+
+struct HelpTopic {
+    name: &'static str,
+    summary: &'static str,
+    subcommands: &'static [&'static str],
+    examples: &'static [(&'static str, &'static str)],
+}
+
+const HELP_TOPICS: &[HelpTopic] = &[
+    HelpTopic {
+        name: "personal-quest",
+        summary: "adding your own quest and testing against it",
+        subcommands: &["add", "fetch", "merge", "quest", "pack"],
+        examples: &[
+            (
+                "Pack a directory of .in/.ans files into an archive",
+                "owlgo pack ./my_quest -n my_quest",
+            ),
+            (
+                "Add the archive to the manifest as a personal quest",
+                "owlgo add my_quest ./my_quest.tar.gz",
+            ),
+            (
+                "Add a quest hosted remotely and fetch it right away",
+                "owlgo add my_quest https://example.com/my_quest.zip -F",
+            ),
+            (
+                "Combine several quests into one review set",
+                "owlgo merge week_review two_sum three_sum",
+            ),
+            (
+                "Test a program against every case in the quest",
+                "owlgo quest my_quest main.cpp",
+            ),
+            (
+                "Test against a single case by name",
+                "owlgo quest my_quest main.cpp -t 01",
+            ),
+        ],
+    },
+    HelpTopic {
+        name: "stress",
+        summary: "validating a fast solution against a brute force",
+        subcommands: &["compare", "matrix", "minimize", "quest"],
+        examples: &[
+            (
+                "Compare two programs on every input of a quest",
+                "owlgo compare fast.cpp brute.py my_quest",
+            ),
+            (
+                "Compare two programs on a directory of generated inputs",
+                "owlgo compare fast.cpp brute.py ./inputs",
+            ),
+            (
+                "Test against a random case from the quest",
+                "owlgo quest my_quest fast.cpp --rand",
+            ),
+            (
+                "Verify every solution in a directory against a quest",
+                "owlgo matrix my_quest -s ./solutions",
+            ),
+            (
+                "Shrink a failing case against the brute force",
+                "owlgo minimize my_quest 07 fast.cpp -r brute.py",
+            ),
+        ],
+    },
+    HelpTopic {
+        name: "review",
+        summary: "asking an LLM to review, debug, or explain",
+        subcommands: &["review"],
+        examples: &[
+            (
+                "Set the LLM sdk and API key",
+                "owlgo review --sdk claude --key <KEY>",
+            ),
+            (
+                "Ask for help debugging a program",
+                "owlgo review main.cpp -D",
+            ),
+            (
+                "Ask for help understanding the quest's description",
+                "owlgo review main.cpp -x -q my_quest",
+            ),
+            ("Ask for edge cases to test", "owlgo review main.cpp -t"),
+            (
+                "Brainstorm before writing any code",
+                "owlgo review --no-prog \"find two numbers that sum to k\"",
+            ),
+            (
+                "Compare all stashed programs for a quest",
+                "owlgo review --all-stashed -q my_quest",
+            ),
+            ("Chat in the TUI", "owlgo review main.cpp -I"),
+        ],
+    },
+];
+
+pub fn show_help(mut cmd: Command, topic: Option<&str>) -> Result<()> {
+    let Some(topic) = topic else {
+        cmd.print_help()
+            .map_err(|e| OwlError::FileError("Failed to print help".into(), e.to_string()))?;
+
+        println!("\nTopics:");
+
+        for help_topic in HELP_TOPICS.iter() {
+            println!("  {:<16}{}", help_topic.name, help_topic.summary);
+        }
+
+        println!("\nRun 'owlgo help <TOPIC|COMMAND>' for examples");
+
+        return Ok(());
+    };
+
+    if let Some(help_topic) = HELP_TOPICS
+        .iter()
+        .find(|help_topic| help_topic.name == topic)
+    {
+        println!("{}\n", help_topic.summary);
+        print_examples(help_topic);
+
+        return Ok(());
+    }
+
+    let sub_cmd = cmd
+        .find_subcommand_mut(topic)
+        .ok_or(OwlError::Unsupported(format!(
+            "'{}': no such topic or command (expected one of {})",
+            topic,
+            HELP_TOPICS
+                .iter()
+                .map(|help_topic| help_topic.name)
+                .collect::<Vec<&str>>()
+                .join(", ")
+        )))?;
+
+    sub_cmd
+        .print_long_help()
+        .map_err(|e| OwlError::FileError("Failed to print help".into(), e.to_string()))?;
+
+    for help_topic in topics_for(topic) {
+        println!();
+        print_examples(help_topic);
+    }
+
+    Ok(())
+}
+
+pub fn write_man_pages(mut cmd: Command, out_dir: &Path) -> Result<()> {
+    fs::create_dir_all(out_dir).map_err(|e| {
+        OwlError::FileError(
+            format!(
+                "Failed to create all dirs in '{}'",
+                out_dir.to_string_lossy()
+            ),
+            e.to_string(),
+        )
+    })?;
+
+    cmd.build();
+
+    let all_topics = HELP_TOPICS.iter().collect::<Vec<&HelpTopic>>();
+    write_man_page(&cmd, "owlgo", &all_topics, out_dir)?;
+
+    for sub_cmd in cmd.get_subcommands() {
+        let topics = topics_for(sub_cmd.get_name());
+        let page_name = format!("owlgo-{}", sub_cmd.get_name());
+
+        write_man_page(sub_cmd, &page_name, &topics, out_dir)?;
+    }
+
+    println!("man pages written to '{}'", out_dir.to_string_lossy());
+
+    Ok(())
+}
+
+fn print_examples(help_topic: &HelpTopic) {
+    println!("\x1b[1mExamples ({}):\x1b[0m", help_topic.name);
+
+    for (desc, example) in help_topic.examples.iter() {
+        println!("  # {}\n  $ {}\n", desc, example);
+    }
+}
+
+fn roff_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('-', "\\-")
+}
+
+fn topics_for(cmd_name: &str) -> Vec<&'static HelpTopic> {
+    HELP_TOPICS
+        .iter()
+        .filter(|help_topic| help_topic.subcommands.contains(&cmd_name))
+        .collect()
+}
+
+fn write_man_page(
+    cmd: &Command,
+    page_name: &str,
+    topics: &[&HelpTopic],
+    out_dir: &Path,
+) -> Result<()> {
+    let man_path = out_dir.join(format!("{}.1", page_name));
+
+    let mut buffer: Vec<u8> = Vec::new();
+
+    Man::new(cmd.clone()).render(&mut buffer).map_err(|e| {
+        OwlError::FileError(
+            format!("Failed to render man page '{}'", page_name),
+            e.to_string(),
+        )
+    })?;
+
+    if !topics.is_empty() {
+        buffer.extend_from_slice(b".SH EXAMPLES\n");
+
+        for help_topic in topics.iter() {
+            for (desc, example) in help_topic.examples.iter() {
+                buffer.extend_from_slice(
+                    format!(".TP\n{}\n.B {}\n", roff_escape(desc), roff_escape(example)).as_bytes(),
+                );
+            }
+        }
+    }
+
+    fs::write(&man_path, buffer).map_err(|e| {
+        OwlError::FileError(
+            format!("could not write man page '{}'", man_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })
+}
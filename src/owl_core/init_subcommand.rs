@@ -0,0 +1,144 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{fs_utils, term_utils};
+use crate::{OWL_DIR, STASH_DIR, TEMPLATE_STEM};
+use similar::{DiffTag, TextDiff};
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{IsTerminal, stdin};
+use std::path::Path;
+
+const DIFF_CONTEXT: usize = 3;
+
+/// What `init` does when the program it would create already exists.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InitConflict {
+    Ask,
+    Merge,
+    Overwrite,
+    Abort,
+}
+
+/// Creates `prog` from the stashed template for its language, showing a diff against an
+/// existing file and merging, overwriting or aborting as chosen.
+pub fn init_program(prog: &Path, on_conflict: InitConflict) -> Result<()> {
+    let ext = prog
+        .extension()
+        .and_then(OsStr::to_str)
+        .ok_or(OwlError::UriError(
+            format!("'{}': has no file extension", prog.to_string_lossy()),
+            "None".into(),
+        ))?;
+
+    let template_name = format!("{}.{}", TEMPLATE_STEM, ext);
+    let template_path =
+        fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], Some(&template_name))?;
+
+    if !template_path.exists() {
+        return Err(OwlError::FileError(
+            format!("no '{}' template in the stash", ext),
+            format!("stash one as '{}'", template_name),
+        ));
+    }
+
+    if !prog.exists() {
+        return fs_utils::copy_file(&template_path, prog);
+    }
+
+    let read = |path: &Path| {
+        fs::read_to_string(path).map_err(|e| {
+            OwlError::FileError(
+                format!("could not read '{}'", path.to_string_lossy()),
+                e.to_string(),
+            )
+        })
+    };
+
+    let prog_str = read(prog)?;
+    let template_str = read(&template_path)?;
+    let prog_name = prog.to_string_lossy();
+
+    let diff = TextDiff::from_lines(&prog_str, &template_str);
+
+    if diff.ratio() == 1.0 {
+        println!("'{}': already matches the template", prog_name);
+        return Ok(());
+    }
+
+    print!(
+        "{}",
+        diff.unified_diff()
+            .context_radius(DIFF_CONTEXT)
+            .header(&prog_name, &format!("stash/{}", template_name))
+    );
+
+    if let InitConflict::Ask = on_conflict {
+        term_utils::require_interactive(&format!(
+            "choosing to merge or overwrite '{}'",
+            prog_name
+        ))?;
+    }
+
+    let choice = match on_conflict {
+        InitConflict::Ask if stdin().is_terminal() => {
+            match term_utils::ask("[m]erge, [o]verwrite or [a]bort?").as_str() {
+                "m" | "merge" => InitConflict::Merge,
+                "o" | "overwrite" => InitConflict::Overwrite,
+                _ => InitConflict::Abort,
+            }
+        }
+        choice => choice,
+    };
+
+    match choice {
+        InitConflict::Merge => {
+            let mut merged = String::new();
+            let mut conflicts = 0;
+
+            for op in diff.ops() {
+                let local = diff.old_slices()[op.old_range()].concat();
+                let template = diff.new_slices()[op.new_range()].concat();
+
+                match op.tag() {
+                    DiffTag::Equal | DiffTag::Delete => merged.push_str(&local),
+                    DiffTag::Insert => merged.push_str(&template),
+                    DiffTag::Replace => {
+                        conflicts += 1;
+                        merged.push_str(&format!(
+                            "<<<<<<< {}\n{}=======\n{}>>>>>>> template\n",
+                            prog_name,
+                            with_newline(&local),
+                            with_newline(&template)
+                        ));
+                    }
+                }
+            }
+
+            fs::write(prog, merged).map_err(|e| {
+                OwlError::FileError(format!("could not write '{}'", prog_name), e.to_string())
+            })?;
+
+            match conflicts {
+                0 => println!("merged the template into '{}'", prog_name),
+                n => println!(
+                    "merged the template into '{}' with {} conflict(s) to resolve",
+                    prog_name, n
+                ),
+            }
+
+            Ok(())
+        }
+        InitConflict::Overwrite => fs_utils::copy_file(&template_path, prog),
+        InitConflict::Ask | InitConflict::Abort => Err(OwlError::FileError(
+            format!("'{}': already exists", prog_name),
+            "rerun with '--merge' or '--overwrite' to keep the template".into(),
+        )),
+    }
+}
+
+fn with_newline(lines: &str) -> String {
+    if lines.is_empty() || lines.ends_with('\n') {
+        lines.to_string()
+    } else {
+        format!("{}\n", lines)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::Manifest;
+use crate::owl_utils::fs_utils::{self, EntryKind, TreeEntry};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{Value, json};
+use std::path::Path;
+
+pub fn list_quests() -> Result<()> {
+    let manifest = Manifest::open()?;
+    let owl_dir = manifest.path().parent().expect("owlgo directory to exist");
+
+    for quest in manifest.quests()? {
+        let marker = if owl_dir.join(&quest.name).is_dir() {
+            "\x1b[32m✓\x1b[0m"
+        } else {
+            " "
+        };
+
+        println!("{} {}", marker, quest.describe());
+    }
+
+    Ok(())
+}
+
+/// Prints `dir` as a JSON tree of entries with their kind, size (in bytes), and mtime.
+pub fn list_files_json(dir: &Path) -> Result<()> {
+    let tree = fs_utils::file_tree(dir)?;
+
+    serde_json::to_string_pretty(&tree_json(&tree))
+        .map(|json_str| println!("{}", json_str))
+        .map_err(|e| OwlError::FileError("Failed to serialize file tree".into(), e.to_string()))
+}
+
+fn tree_json(entry: &TreeEntry) -> Value {
+    let mut entry_json = json!({
+        "name": entry.name(),
+        "path": entry.path.to_string_lossy(),
+        "kind": entry.kind.name(),
+        "size": entry.size,
+        "modified": entry
+            .modified
+            .map(|modified| DateTime::<Utc>::from(modified).to_rfc3339_opts(SecondsFormat::Secs, true)),
+    });
+
+    if entry.kind == EntryKind::Dir {
+        entry_json["children"] = entry.children.iter().map(tree_json).collect();
+    }
+
+    entry_json
+}
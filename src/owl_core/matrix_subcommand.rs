@@ -0,0 +1,176 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{OwlConfig, fs_utils, prog_utils, signal_utils};
+use std::path::{Path, PathBuf};
+
+struct MatrixRow {
+    prog: String,
+    lang: String,
+    verdict: String,
+    passed: usize,
+    total: usize,
+    elapsed_ms: u128,
+}
+
+pub async fn verify_matrix(
+    quest_name: &str,
+    solutions_dir: &Path,
+    config: &OwlConfig,
+) -> Result<()> {
+    let quest_path = fs_utils::quest_path(quest_name)?;
+
+    if !quest_path.exists() {
+        super::fetch_quest(quest_name).await?;
+    }
+
+    let mut solutions = fs_utils::dir_tree(solutions_dir)?
+        .into_iter()
+        .filter(|path| prog_utils::check_prog_lang(path).is_some())
+        .collect::<Vec<PathBuf>>();
+    solutions.sort();
+
+    if solutions.is_empty() {
+        return Err(OwlError::FileError(
+            format!("'{}': no solutions found", solutions_dir.to_string_lossy()),
+            format!(
+                "expected files ending in one of {}",
+                prog_utils::LANG_EXTS.join(", ")
+            ),
+        ));
+    }
+
+    let mut rows = Vec::new();
+
+    for prog in solutions.iter() {
+        if signal_utils::is_interrupted() {
+            break;
+        }
+
+        let lang = prog_utils::check_prog_lang(prog).expect("solution has a known language");
+
+        let prog_str = prog
+            .strip_prefix(solutions_dir)
+            .unwrap_or(prog)
+            .to_string_lossy()
+            .to_string();
+
+        println!("\x1b[36m[{}]\x1b[0m {}", lang.name(), prog_str);
+
+        let mut row = MatrixRow {
+            prog: prog_str,
+            lang: lang.name().to_string(),
+            verdict: "".into(),
+            passed: 0,
+            total: 0,
+            elapsed_ms: 0,
+        };
+
+        if !lang.command_exists(config) {
+            row.verdict = "no toolchain".into();
+            rows.push(row);
+            continue;
+        }
+
+        match super::judge_quest(&quest_path, prog, None, false, config) {
+            Ok(summary) => {
+                row.verdict = if summary.failed > 0 {
+                    "failed".into()
+                } else {
+                    "accepted".into()
+                };
+                row.passed = summary.passed;
+                row.total = summary.passed + summary.failed;
+                row.elapsed_ms = summary.elapsed.as_millis();
+            }
+            Err(e) => {
+                eprintln!("\x1b[31m{}\x1b[0m", e);
+                row.verdict = "build failed".into();
+            }
+        }
+
+        rows.push(row);
+    }
+
+    println!("{}", matrix_table(quest_name, &rows));
+
+    if signal_utils::is_interrupted() {
+        return Err(OwlError::Interrupted(format!(
+            "interrupted after {} solutions",
+            rows.len()
+        )));
+    }
+
+    let rejected = rows.iter().filter(|row| row.verdict != "accepted").count();
+
+    if rejected > 0 {
+        Err(OwlError::TestFailure(format!(
+            "{}/{} solutions not accepted",
+            rejected,
+            rows.len()
+        )))
+    } else {
+        println!("\x1b[32mall solutions accepted\x1b[0m 🏆🏆🏆\n");
+        Ok(())
+    }
+}
+
+fn matrix_table(quest_name: &str, rows: &[MatrixRow]) -> String {
+    let cells = rows
+        .iter()
+        .map(|row| {
+            [
+                row.prog.clone(),
+                row.lang.clone(),
+                row.verdict.clone(),
+                format!("{}/{}", row.passed, row.total),
+                format!("{}ms", row.elapsed_ms),
+            ]
+        })
+        .collect::<Vec<[String; 5]>>();
+
+    verdict_table(
+        &format!("matrix for '{}'", quest_name),
+        ["solution", "lang", "verdict", "passed", "elapsed"],
+        &cells,
+    )
+}
+
+pub(super) fn verdict_table(title: &str, header: [&str; 5], cells: &[[String; 5]]) -> String {
+    let widths = (0..header.len())
+        .map(|i| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain([header[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<usize>>();
+
+    let format_row = |row: &[&str]| {
+        row.iter()
+            .zip(widths.iter())
+            .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut table = format!("\n{}\n\n", title);
+
+    table.push_str(&format_row(&header));
+    table.push('\n');
+
+    for row in cells.iter() {
+        let line = format_row(&row.iter().map(String::as_str).collect::<Vec<&str>>());
+
+        let color = match row[2].as_str() {
+            "accepted" => "\x1b[32m",
+            _ => "\x1b[31m",
+        };
+
+        table.push_str(&format!("{}{}\x1b[0m\n", color, line));
+    }
+
+    table
+}
@@ -0,0 +1,182 @@
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::{Uri, fs_utils, pool_utils};
+use crate::{MERGED_DIR, OWL_DIR, QUEST_META, STASH_DIR};
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Combines the cases of several quests into a new personal quest, prefixing a case with its
+/// quest's name when its stem is already taken. The merged cases are archived in the stash so
+/// the quest can be refetched after a `clear`.
+pub async fn merge_quests(new_name: &str, quest_names: &[String]) -> Result<()> {
+    let quest_dir = fs_utils::quest_path(new_name)?;
+
+    if quest_dir.exists() || quest_names.iter().any(|name| name == new_name) {
+        return Err(OwlError::UriError(
+            format!("'{}': quest already exists", new_name),
+            "pick a new name for the merged quest".into(),
+        ));
+    }
+
+    for quest_name in quest_names {
+        if !fs_utils::quest_path(quest_name)?.exists() {
+            super::fetch_quest(quest_name).await?;
+        }
+    }
+
+    let merged = merge_cases(&quest_dir, quest_names);
+
+    let (cases, renamed) = match merged {
+        Ok(counts) => counts,
+        Err(e) => {
+            fs_utils::remove_path(&quest_dir)?;
+            return Err(e);
+        }
+    };
+
+    let merged_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, MERGED_DIR], None)?;
+
+    fs::create_dir_all(&merged_dir).map_err(|e| {
+        OwlError::FileError(
+            format!("could not create dir '{}'", merged_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let mut files = fs_utils::dir_tree(&quest_dir)?;
+    files.sort();
+
+    let archive = merged_dir.join(format!("{}.tar.gz", new_name));
+    fs_utils::create_tar_archive(&quest_dir, &files, &archive)?;
+
+    super::add_quest(new_name, &Uri::Local(archive), false).await?;
+
+    if let Err(e) = pool_utils::pool_quest_dir(&quest_dir) {
+        warn(OwlWarning::Recovered(e.to_string()));
+    }
+
+    println!(
+        "merged {} test cases from {} quests into '{}' ({} renamed)",
+        cases,
+        quest_names.len(),
+        new_name,
+        renamed
+    );
+
+    Ok(())
+}
+
+/// Copies every case file of each quest into `quest_dir`, returning how many cases were
+/// merged and how many had to be renamed.
+fn merge_cases(quest_dir: &Path, quest_names: &[String]) -> Result<(usize, usize)> {
+    fs::create_dir_all(quest_dir).map_err(|e| {
+        OwlError::FileError(
+            format!("could not create dir '{}'", quest_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let mut taken = HashSet::new();
+    let mut cases = 0;
+    let mut renamed = 0;
+
+    for quest_name in quest_names {
+        let src_dir = fs_utils::quest_path(quest_name)?;
+
+        if src_dir.join(QUEST_META).exists() {
+            warn(OwlWarning::Skipped(format!(
+                "'{}': {} is not merged (limits and file I/O come from neither quest)",
+                quest_name, QUEST_META
+            )));
+        }
+
+        // case files grouped by their dir (relative to the quest) and stem
+        let mut quest_cases: BTreeMap<(PathBuf, String), Vec<PathBuf>> = BTreeMap::new();
+
+        for file in fs_utils::dir_tree(&src_dir)? {
+            let Ok(rel_path) = file.strip_prefix(&src_dir) else {
+                continue;
+            };
+
+            let is_dotted = rel_path
+                .components()
+                .any(|component| component.as_os_str().to_string_lossy().starts_with('.'));
+
+            if is_dotted || !is_case_file(&file) {
+                continue;
+            }
+
+            let Some(stem) = fs_utils::case_stem(&file) else {
+                continue;
+            };
+
+            let rel_dir = rel_path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+            quest_cases
+                .entry((rel_dir, stem.to_string()))
+                .or_default()
+                .push(file.clone());
+        }
+
+        if !quest_cases.values().flatten().any(|file| is_input(file)) {
+            return Err(OwlError::FileError(
+                format!("'{}': has no test cases", quest_name),
+                "expected '.in' files to merge".into(),
+            ));
+        }
+
+        for ((rel_dir, stem), files) in quest_cases {
+            let mut new_stem = stem.clone();
+            let mut n = 2;
+
+            while taken.contains(&(rel_dir.clone(), new_stem.clone())) {
+                new_stem = match n {
+                    2 => format!("{}_{}", quest_name, stem),
+                    n => format!("{}_{}-{}", quest_name, stem, n - 1),
+                };
+                n += 1;
+            }
+
+            if new_stem != stem {
+                renamed += 1;
+            }
+
+            let dst_dir = quest_dir.join(&rel_dir);
+
+            fs::create_dir_all(&dst_dir).map_err(|e| {
+                OwlError::FileError(
+                    format!("could not create dir '{}'", dst_dir.to_string_lossy()),
+                    e.to_string(),
+                )
+            })?;
+
+            for file in files.iter() {
+                let file_name = file.file_name().and_then(OsStr::to_str).unwrap_or_default();
+
+                fs_utils::copy_file(
+                    file,
+                    &dst_dir.join(format!("{}{}", new_stem, &file_name[stem.len()..])),
+                )?;
+            }
+
+            if files.iter().any(|file| is_input(file)) {
+                cases += 1;
+            }
+
+            taken.insert((rel_dir, new_stem));
+        }
+    }
+
+    Ok((cases, renamed))
+}
+
+fn is_input(file: &Path) -> bool {
+    fs_utils::case_ext(file) == Some("in")
+}
+
+fn is_case_file(file: &Path) -> bool {
+    fs_utils::case_ext(file).is_some_and(|ext| {
+        ext == "in" || ext == "md" || ext == "args" || fs_utils::is_answer_ext(ext)
+    })
+}
@@ -0,0 +1,312 @@
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::{
+    OwlConfig, QuestMeta, cmd_utils, fs_utils, judge_utils, prog_utils, signal_utils,
+};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+const MIN_SUFFIX: &str = "min";
+
+/// A built program to run candidate inputs through.
+struct Runner<'a> {
+    prog: &'a Path,
+    target: PathBuf,
+}
+
+/// Shrinks a failing case by deleting lines (then tokens) in halving chunks for as long as
+/// `prog` still crashes or disagrees with `reference`, saving the result as a new case.
+pub async fn minimize_case(
+    quest_name: &str,
+    case_name: &str,
+    prog: &Path,
+    reference: Option<&Path>,
+    config: &OwlConfig,
+) -> Result<()> {
+    let quest_path = fs_utils::quest_path(quest_name)?;
+
+    if !quest_path.exists() {
+        super::fetch_quest(quest_name).await?;
+    }
+
+    for prog in [Some(prog), reference].into_iter().flatten() {
+        if !prog.exists() {
+            return Err(OwlError::FileError(
+                format!("'{}': no such file", prog.to_string_lossy()),
+                "".into(),
+            ));
+        }
+    }
+
+    let in_path = fs_utils::find_by_stem_and_ext(&quest_path, case_name, "in")?;
+
+    let meta = QuestMeta::load(&quest_path)?;
+    let config = &config.for_quest(&meta);
+    let _file_io = cmd_utils::set_file_io(meta.input_file, meta.output_file);
+
+    let work_dir = env::temp_dir().join(format!("owlgo-minimize-{}", process::id()));
+
+    fs::create_dir_all(&work_dir).map_err(|e| {
+        OwlError::FileError(
+            format!("could not create dir '{}'", work_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let candidate = work_dir.join(format!("{}.in", case_name));
+    let args_path = in_path.with_file_name(format!("{}.args", case_name));
+
+    if args_path.is_file() {
+        fs_utils::copy_file(&args_path, &work_dir.join(format!("{}.args", case_name)))?;
+    }
+
+    let (target, build_files) = match prog_utils::build_program(prog, config)? {
+        Some(bl) => (bl.target, bl.build_files),
+        None => (prog.to_path_buf(), None),
+    };
+
+    let ref_build = match reference.map(|reference| prog_utils::build_program(reference, config)) {
+        Some(Ok(Some(bl))) => Some((bl.target, bl.build_files)),
+        Some(Ok(None)) => reference.map(|reference| (reference.to_path_buf(), None)),
+        Some(Err(e)) => {
+            prog_utils::cleanup_program(prog, &target, build_files)?;
+            fs_utils::remove_path(&work_dir)?;
+            return Err(e);
+        }
+        None => None,
+    };
+
+    let runner = Runner {
+        prog,
+        target: target.clone(),
+    };
+    let ref_runner = reference
+        .zip(ref_build.as_ref())
+        .map(|(reference, (target, _))| Runner {
+            prog: reference,
+            target: target.clone(),
+        });
+
+    let minimized = minimize(&in_path, &candidate, &runner, ref_runner.as_ref(), config);
+
+    prog_utils::cleanup_program(prog, &target, build_files)?;
+
+    if let (Some(reference), Some((ref_target, ref_files))) = (reference, ref_build) {
+        prog_utils::cleanup_program(reference, &ref_target, ref_files)?;
+    }
+
+    fs_utils::remove_path(&work_dir)?;
+
+    let (input, answer, runs) = minimized?;
+
+    let case_dir = in_path.parent().unwrap_or(&quest_path);
+    let mut min_stem = format!("{}-{}", case_name, MIN_SUFFIX);
+    let mut n = 2;
+
+    while case_dir.join(format!("{}.in", min_stem)).exists() {
+        min_stem = format!("{}-{}{}", case_name, MIN_SUFFIX, n);
+        n += 1;
+    }
+
+    let write = |path: PathBuf, contents: &[u8]| {
+        fs::write(&path, contents).map_err(|e| {
+            OwlError::FileError(
+                format!("could not write '{}'", path.to_string_lossy()),
+                e.to_string(),
+            )
+        })
+    };
+
+    write(case_dir.join(format!("{}.in", min_stem)), input.as_bytes())?;
+
+    if args_path.is_file() {
+        fs_utils::copy_file(&args_path, &case_dir.join(format!("{}.args", min_stem)))?;
+    }
+
+    match answer {
+        Some(answer) => write(case_dir.join(format!("{}.ans", min_stem)), &answer)?,
+        None => warn(OwlWarning::Skipped(format!(
+            "'{}': no answer written (pass '--reference' to record one)",
+            min_stem
+        ))),
+    }
+
+    println!(
+        "minimized '{}' from {} to {} bytes ({} lines) in {} runs as case '{}'",
+        case_name,
+        fs_utils::read_case(&in_path)?.len(),
+        input.len(),
+        input.lines().count(),
+        runs,
+        min_stem
+    );
+
+    Ok(())
+}
+
+/// The smallest failing input found, the reference's answer for it (if any), and how many
+/// candidates were run.
+fn minimize(
+    in_path: &Path,
+    candidate: &Path,
+    runner: &Runner,
+    reference: Option<&Runner>,
+    config: &OwlConfig,
+) -> Result<(String, Option<Vec<u8>>, usize)> {
+    let original = String::from_utf8_lossy(&fs_utils::read_case(in_path)?).to_string();
+    let mut runs = 0;
+
+    let mut fails = |input: &str| -> Result<bool> {
+        runs += 1;
+        still_fails(input, candidate, runner, reference, config)
+    };
+
+    if !fails(&original)? {
+        let case_name = fs_utils::case_stem(in_path).unwrap_or_default();
+
+        return Err(OwlError::TestFailure(match reference {
+            Some(_) => format!(
+                "'{}': the program agrees with the reference on this case",
+                case_name
+            ),
+            None => format!(
+                "'{}': the program does not crash on this case (pass '--reference' to compare outputs)",
+                case_name
+            ),
+        }));
+    }
+
+    let lines = original.lines().map(String::from).collect::<Vec<String>>();
+    let mut lines = reduce(lines, |lines| fails(&join_lines(lines)))?;
+
+    for i in 0..lines.len() {
+        if signal_utils::is_interrupted() {
+            break;
+        }
+
+        let tokens = lines[i]
+            .split_whitespace()
+            .map(String::from)
+            .collect::<Vec<String>>();
+
+        if tokens.len() < 2 {
+            continue;
+        }
+
+        let before = lines[..i].to_vec();
+        let after = lines[i + 1..].to_vec();
+
+        let tokens = reduce(tokens, |tokens| {
+            let line = tokens.join(" ");
+            let input = before
+                .iter()
+                .chain([&line])
+                .chain(after.iter())
+                .cloned()
+                .collect::<Vec<String>>();
+
+            fails(&join_lines(&input))
+        })?;
+
+        lines[i] = tokens.join(" ");
+    }
+
+    if signal_utils::is_interrupted() {
+        warn(OwlWarning::Recovered(
+            "interrupted, keeping the smallest failing input so far".into(),
+        ));
+    }
+
+    let input = join_lines(&lines);
+
+    let answer = match reference {
+        Some(reference) => {
+            fs::write(candidate, &input).map_err(|e| {
+                OwlError::FileError(
+                    format!("could not write '{}'", candidate.to_string_lossy()),
+                    e.to_string(),
+                )
+            })?;
+
+            Some(super::run_case(reference.prog, &reference.target, candidate, config)?.0)
+        }
+        None => None,
+    };
+
+    Ok((input, answer, runs))
+}
+
+/// Delta debugging: drops ever smaller chunks of `units` while `fails` holds for what's left.
+fn reduce<F>(mut units: Vec<String>, mut fails: F) -> Result<Vec<String>>
+where
+    F: FnMut(&[String]) -> Result<bool>,
+{
+    let mut chunk = units.len().div_ceil(2).max(1);
+
+    while !units.is_empty() && !signal_utils::is_interrupted() {
+        let mut start = 0;
+        let mut removed = false;
+
+        while start < units.len() && !signal_utils::is_interrupted() {
+            let end = (start + chunk).min(units.len());
+            let rest = [&units[..start], &units[end..]].concat();
+
+            if fails(&rest)? {
+                units = rest;
+                removed = true;
+            } else {
+                start = end;
+            }
+        }
+
+        match (removed, chunk) {
+            (false, 1) => break,
+            (false, _) => chunk = chunk.div_ceil(2),
+            (true, _) => chunk = chunk.min(units.len().div_ceil(2)).max(1),
+        }
+    }
+
+    Ok(units)
+}
+
+/// Whether `prog` still crashes on `input`, or disagrees with a reference that accepts it.
+fn still_fails(
+    input: &str,
+    candidate: &Path,
+    runner: &Runner,
+    reference: Option<&Runner>,
+    config: &OwlConfig,
+) -> Result<bool> {
+    fs::write(candidate, input).map_err(|e| {
+        OwlError::FileError(
+            format!("could not write '{}'", candidate.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let Ok((actual, _)) = super::run_case(runner.prog, &runner.target, candidate, config) else {
+        return Ok(!signal_utils::is_interrupted());
+    };
+
+    let Some(reference) = reference else {
+        return Ok(false);
+    };
+
+    // an input the reference rejects is no longer a valid case
+    Ok(
+        match super::run_case(reference.prog, &reference.target, candidate, config) {
+            Ok((expected, _)) => {
+                !judge_utils::outputs_match(&actual, &expected, config.compare, &config.filters)
+            }
+            Err(_) => false,
+        },
+    )
+}
+
+fn join_lines(lines: &[String]) -> String {
+    match lines.is_empty() {
+        true => String::new(),
+        false => format!("{}\n", lines.join("\n")),
+    }
+}
@@ -1,21 +1,88 @@
 pub mod add_subcommand;
+pub mod attest_subcommand;
+pub mod batch_subcommand;
+pub mod bench_subcommand;
+pub mod check_subcommand;
 pub mod clear_subcommand;
+pub mod collect_subcommand;
+pub mod compare_subcommand;
+pub mod config_subcommand;
+pub mod debug_subcommand;
+pub mod env_subcommand;
+pub mod ext_subcommand;
+pub mod extract_subcommand;
 pub mod fetch_subcommand;
+pub mod fmt_subcommand;
+pub mod gc_subcommand;
 pub mod git_subcommand;
+pub mod help_subcommand;
+pub mod init_subcommand;
+pub mod list_subcommand;
+pub mod matrix_subcommand;
+pub mod merge_subcommand;
+pub mod minimize_subcommand;
+pub mod pack_subcommand;
 pub mod quest_subcommand;
+pub mod rerun_subcommand;
 pub mod review_subcommand;
 pub mod run_subcommand;
+pub mod serve_subcommand;
+pub mod share_subcommand;
 pub mod show_subcommand;
 pub mod stash_subcommand;
+pub mod sync_subcommand;
 pub mod test_subcommand;
+pub mod todos_subcommand;
+pub mod version_subcommand;
+pub mod where_subcommand;
+pub mod which_lang_subcommand;
 
-pub use add_subcommand::{add_extension, add_prompt, add_quest};
-pub use clear_subcommand::{clear_programs, clear_quests};
-pub use fetch_subcommand::{fetch_extension, fetch_prompt, fetch_quest};
-pub use git_subcommand::{push_git_remote, set_git_remote, sync_git_remote};
-pub use quest_subcommand::{quest, quest_once};
-pub use review_subcommand::{ReviewPrompt, review_program};
+pub use add_subcommand::{add_extension, add_local_quest, add_prompt, add_quest};
+pub use attest_subcommand::{attest_quest, verify_attestation};
+pub use batch_subcommand::run_batch;
+pub use bench_subcommand::bench_program;
+pub use check_subcommand::check_program;
+pub use clear_subcommand::{
+    clear_chats, clear_programs, clear_progress, clear_quests, enforce_chat_retention,
+};
+pub use collect_subcommand::collect_roster;
+pub use compare_subcommand::compare_programs;
+pub use config_subcommand::{get_config, list_config, set_config};
+pub use debug_subcommand::debug_quest;
+pub use env_subcommand::{ENV_PARTS, export_env, import_env};
+pub use ext_subcommand::{add_git_extension, publish_quest};
+pub use extract_subcommand::extract_samples;
+pub use fetch_subcommand::{
+    fetch_extension, fetch_prompt, fetch_quest, resolve_quest_name, show_intro,
+};
+pub use fmt_subcommand::format_program;
+pub use gc_subcommand::gc_pool;
+pub use git_subcommand::{
+    auto_sync_git, push_git_remote, set_auto_sync, set_git_remote, sync_git_remote, tag_accepted,
+};
+pub use help_subcommand::{show_help, write_man_pages};
+pub use init_subcommand::{InitConflict, init_program};
+pub use list_subcommand::{list_files_json, list_quests};
+pub use matrix_subcommand::verify_matrix;
+pub use merge_subcommand::merge_quests;
+pub use minimize_subcommand::minimize_case;
+pub use pack_subcommand::pack_quest;
+pub use quest_subcommand::{
+    find_answer, find_answers, judge_quest, quest, quest_once, random_case,
+};
+pub use rerun_subcommand::{record_failed_case, rerun};
+pub use review_subcommand::{ReviewPrompt, review_program, review_since_stash, review_stashed};
 pub use run_subcommand::run_program;
+pub use serve_subcommand::serve;
+pub use share_subcommand::share_file;
 pub use show_subcommand::{show_and_glow, show_it, show_quest, show_test};
-pub use stash_subcommand::stash_file;
-pub use test_subcommand::{test_it, test_program};
+pub use stash_subcommand::{StashConflict, stash_file};
+pub use sync_subcommand::{restore_stash, sync_stash};
+pub use test_subcommand::{
+    CaseCheck, check_case, report_exceeded_case, report_failed_case, run_case, test_it,
+    test_program,
+};
+pub use todos_subcommand::{show_todos, write_todos};
+pub use version_subcommand::{hint_update, refresh_remote_header, show_version};
+pub use where_subcommand::show_paths;
+pub use which_lang_subcommand::which_lang;
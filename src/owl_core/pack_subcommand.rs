@@ -0,0 +1,80 @@
+use crate::QUEST_META;
+use crate::common::{OwlError, Result};
+use crate::owl_utils::fs_utils;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+const FEEDBACK_EXT: &str = "md";
+const QUEST_EXTS: &[&str] = &["in", "md", "args"];
+
+pub fn pack_quest(
+    quest_dir: &Path,
+    archive_name: Option<&str>,
+    strip_feedback: bool,
+    strip_answers: bool,
+) -> Result<()> {
+    let quest_dir = quest_dir.canonicalize().map_err(|e| {
+        OwlError::FileError(
+            format!("Failed to access dir '{}'", quest_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let archive_stem = match archive_name {
+        Some(name) => name.to_string(),
+        None => quest_dir
+            .file_name()
+            .and_then(OsStr::to_str)
+            .map(String::from)
+            .ok_or(OwlError::UriError(
+                format!("'{}': has no dir name", quest_dir.to_string_lossy()),
+                "".into(),
+            ))?,
+    };
+
+    let quest_files = quest_files(&quest_dir)?;
+
+    let full_archive = PathBuf::from(format!("{}.tar.gz", archive_stem));
+    fs_utils::create_tar_archive(&quest_dir, &quest_files, &full_archive)?;
+    println!("packed '{}'", full_archive.to_string_lossy());
+
+    if strip_feedback || strip_answers {
+        let student_files = quest_files
+            .into_iter()
+            .filter(|file| !(strip_feedback && has_ext(file, &[FEEDBACK_EXT])))
+            .filter(|file| !(strip_answers && is_answer(file)))
+            .collect::<Vec<PathBuf>>();
+
+        let student_archive = PathBuf::from(format!("{}.student.tar.gz", archive_stem));
+        fs_utils::create_tar_archive(&quest_dir, &student_files, &student_archive)?;
+        println!("packed '{}'", student_archive.to_string_lossy());
+    }
+
+    Ok(())
+}
+
+/// The cases, answers, feedback, and settings of a quest, which is what gets archived.
+pub(super) fn quest_files(quest_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut quest_files = fs_utils::dir_tree(quest_dir)?
+        .into_iter()
+        .filter(|file| has_ext(file, QUEST_EXTS) || is_answer(file) || file.ends_with(QUEST_META))
+        .collect::<Vec<PathBuf>>();
+    quest_files.sort();
+
+    if !quest_files.iter().any(|file| has_ext(file, &["in"])) {
+        return Err(OwlError::FileError(
+            format!("'{}': has no test cases", quest_dir.to_string_lossy()),
+            "expected '.in' files to pack".into(),
+        ));
+    }
+
+    Ok(quest_files)
+}
+
+fn is_answer(file: &Path) -> bool {
+    fs_utils::case_ext(file).is_some_and(fs_utils::is_answer_ext)
+}
+
+fn has_ext(file: &Path, exts: &[&str]) -> bool {
+    fs_utils::case_ext(file).is_some_and(|ext| exts.contains(&ext))
+}
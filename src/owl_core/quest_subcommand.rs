@@ -1,23 +1,63 @@
-use crate::OWL_DIR;
-use crate::common::{OwlError, Result};
-use crate::owl_utils::{cmd_utils, fs_utils, prog_utils};
+use super::CaseCheck;
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::cmd::cmd_utils::RunUsage;
+use crate::owl_utils::cmd::judge_utils::{CaseResult, OutputFormat, Verdict};
+use crate::owl_utils::progress_utils::{self, ProgressEvent};
+use crate::owl_utils::results_utils::RunResults;
+use crate::owl_utils::solve_utils::{self, PinAction, ToolchainPin};
+use crate::owl_utils::tui::{TopApp, TopState};
+use crate::owl_utils::{
+    OwlConfig, QuestMeta, cmd_utils, fs_utils, hook_utils, prog_utils, script_utils, signal_utils,
+    term_utils, vscode_utils,
+};
+use crate::{LAST_RUN_DIR, OWL_DIR, SCRIPT_DIR};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
 use std::time::Duration;
 
+const ANS_EXTS: &[&str] = &["ans", "ans.gz", "out", "out.gz"];
+
+/// Totals and per-case results of judging a program against a quest.
+pub struct QuestSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub elapsed: Duration,
+    pub cases: Vec<CaseResult>,
+    pub build_warnings: usize,
+}
+
+pub fn random_case(seed: Option<u64>) -> usize {
+    let seed = seed.unwrap_or_else(rand::random::<u64>);
+    eprintln!("random seed: {} (rerun with '--seed {}')", seed, seed);
+
+    StdRng::seed_from_u64(seed).random_range(1..=usize::MAX)
+}
+
+/// Judges `prog` against a quest, fetching the quest first if it isn't local yet.
 pub async fn quest(
     quest_name: &str,
     prog: &Path,
     case_id: Option<usize>,
     use_hints: bool,
+    config: &OwlConfig,
 ) -> Result<()> {
-    let quest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(quest_name))?;
+    let quest_path = fs_utils::quest_path(quest_name)?;
 
     if !quest_path.exists() {
         super::fetch_quest(quest_name).await?;
     }
 
+    if config.show_intro
+        && let Err(e) = super::show_intro(&quest_path)
+    {
+        warn(OwlWarning::Recovered(e.to_string()));
+    }
+
     if !prog.exists() {
         return Err(OwlError::FileError(
             format!("'{}': no such file", prog.to_string_lossy()),
@@ -25,24 +65,238 @@ pub async fn quest(
         ));
     }
 
-    let (target, build_files) = match prog_utils::build_program(prog)? {
-        Some(bl) => (bl.target, bl.build_files),
-        None => (prog.to_path_buf(), None),
+    hook_utils::pre_build(&config.hooks, Some(quest_name), prog)?;
+
+    let toolchain = check_toolchain(quest_name, prog, config);
+
+    let summary = judge_quest(&quest_path, prog, case_id, use_hints, config)?;
+
+    if let Some(case) = summary.cases.iter().find(|case| !case.passed())
+        && let Err(e) = super::record_failed_case(quest_name, &case.name, prog)
+    {
+        warn(OwlWarning::Recovered(e.to_string()));
+    }
+
+    report_summary(
+        quest_name,
+        summary.passed,
+        summary.failed,
+        summary.elapsed,
+        summary.build_warnings,
+        config,
+    );
+    report_usage(&summary.cases, config);
+    write_results(
+        quest_name,
+        &quest_path,
+        prog,
+        &summary.cases,
+        summary.elapsed,
+        summary.build_warnings,
+        config,
+    );
+
+    if signal_utils::is_interrupted() {
+        return Err(OwlError::Interrupted(format!(
+            "interrupted after {} tests",
+            summary.passed + summary.failed
+        )));
+    }
+
+    let hook_verdict = if summary.failed > 0 {
+        hook_utils::VERDICT_FAILED
+    } else {
+        hook_utils::VERDICT_ACCEPTED
+    };
+    hook_utils::post_test(&config.hooks, Some(quest_name), prog, hook_verdict);
+
+    if let Some(script_name) = &config.script {
+        run_quest_script(script_name, quest_name, prog, &summary.cases)?;
+    }
+
+    if summary.failed > 0 {
+        Err(OwlError::TestFailure("test failures".into()))
+    } else {
+        if case_id.is_none()
+            && config.shard.is_none()
+            && let Some((lang_name, version)) = toolchain
+        {
+            record_accepted(quest_name, &lang_name, version.as_deref());
+        }
+
+        if case_id.is_none() && config.shard.is_none() && config.tag_accepted {
+            let formatters = config.formatters.on_stash.then_some(&config.formatters);
+
+            match super::tag_accepted(quest_name, prog, formatters) {
+                Ok(tag) => eprintln!("tagged '{}' in the stash", tag),
+                Err(e) => warn(OwlWarning::Recovered(e.to_string())),
+            }
+        }
+
+        if config.format == OutputFormat::Pretty {
+            println!("\x1b[32mall tests passed\x1b[0m 🏆🏆🏆\n");
+        }
+        Ok(())
+    }
+}
+
+/// Applies `--pin`/`--unpin` and warns when the toolchain differs from the one pinned on the
+/// quest's first accepted run, returning the language and its version for the progress store.
+fn check_toolchain(
+    quest_name: &str,
+    prog: &Path,
+    config: &OwlConfig,
+) -> Option<(String, Option<String>)> {
+    let lang = prog_utils::resolve_prog_lang(prog, config)?;
+    let lang_name = lang.name().to_string();
+    let version = lang.version(config).ok().and_then(|version| {
+        version
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(String::from)
+    });
+
+    let pin_result = (|| -> Result<()> {
+        let mut progress_doc = solve_utils::read_progress()?;
+
+        match (config.pin, &version) {
+            (Some(PinAction::Pin), Some(version)) => {
+                solve_utils::set_toolchain_pin(
+                    &mut progress_doc,
+                    quest_name,
+                    &lang_name,
+                    Some(version),
+                );
+                solve_utils::write_progress(&progress_doc)?;
+                eprintln!("pinned {} for '{}': {}", lang_name, quest_name, version);
+            }
+            (Some(PinAction::Pin), None) => warn(OwlWarning::Skipped(format!(
+                "could not detect the {} version to pin",
+                lang_name
+            ))),
+            (Some(PinAction::Unpin), _) => {
+                solve_utils::set_toolchain_pin(&mut progress_doc, quest_name, &lang_name, None);
+                solve_utils::write_progress(&progress_doc)?;
+                eprintln!("unpinned {} for '{}'", lang_name, quest_name);
+            }
+            (None, Some(version)) => {
+                if let ToolchainPin::Pinned(pinned) =
+                    solve_utils::toolchain_pin(&progress_doc, quest_name, &lang_name)
+                    && pinned != *version
+                {
+                    warn(OwlWarning::ToolchainChanged(format!(
+                        "'{}': {} is now '{}' but '{}' was pinned (use '--pin' to accept it or '--unpin' to stop checking)",
+                        quest_name, lang_name, version, pinned
+                    )));
+                }
+            }
+            (None, None) => {}
+        }
+
+        Ok(())
+    })();
+
+    if let Err(e) = pin_result {
+        warn(OwlWarning::Recovered(e.to_string()));
+    }
+
+    Some((lang_name, version))
+}
+
+fn record_accepted(quest_name: &str, lang_name: &str, version: Option<&str>) {
+    let record_result = solve_utils::read_progress().and_then(|mut progress_doc| {
+        let pinned =
+            solve_utils::record_accepted(&mut progress_doc, quest_name, lang_name, version);
+        solve_utils::write_progress(&progress_doc)?;
+
+        if pinned && let Some(version) = version {
+            eprintln!("pinned {} for '{}': {}", lang_name, quest_name, version);
+        }
+
+        Ok(())
+    });
+
+    if let Err(e) = record_result {
+        warn(OwlWarning::Recovered(e.to_string()));
+    }
+}
+
+/// Builds `prog` and runs it against every case in `quest_path` (or only `case_id`).
+pub fn judge_quest(
+    quest_path: &Path,
+    prog: &Path,
+    case_id: Option<usize>,
+    use_hints: bool,
+    config: &OwlConfig,
+) -> Result<QuestSummary> {
+    let meta = if quest_path.is_dir() {
+        for warning in fs_utils::unmatched_cases(quest_path)? {
+            warn(warning);
+        }
+
+        QuestMeta::load(quest_path)?
+    } else {
+        QuestMeta::default()
+    };
+    let config = &config.for_quest(&meta);
+
+    let _file_io = cmd_utils::set_file_io(meta.input_file, meta.output_file);
+
+    if let Some(last_run) = last_run_dir(quest_path)?
+        && last_run.exists()
+    {
+        fs_utils::remove_path(&last_run)?;
+    }
+
+    let mut test_cases: Vec<PathBuf> = fs_utils::find_by_ext(quest_path, "in")?;
+
+    if let Some(shard) = config.shard {
+        test_cases.sort();
+        test_cases = test_cases
+            .into_iter()
+            .enumerate()
+            .filter(|(position, _)| shard.contains(*position))
+            .map(|(_, test_case)| test_case)
+            .collect();
+    }
+
+    let (target, build_files, build_warnings) = match prog_utils::build_program(prog, config)? {
+        Some(bl) => (bl.target, bl.build_files, bl.warnings),
+        None => (prog.to_path_buf(), None, 0),
     };
 
-    let test_cases: Vec<PathBuf> = fs_utils::find_by_ext(&quest_path, "in")?;
     let total = test_cases.len();
 
     let mut passed = 0;
     let mut failed = 0;
     let mut total_duration: Option<Duration> = None;
+    let mut cases = Vec::new();
+
+    let case_id = case_id.filter(|_| total > 0).map(|d| (d - 1) % total + 1);
+
+    if config.jobs > 1 && case_id.is_none() {
+        let parallel_result = judge_parallel(prog, &target, &test_cases, use_hints, config);
+        prog_utils::cleanup_program(prog, &target, build_files)?;
+
+        return parallel_result.map(|summary| QuestSummary {
+            build_warnings,
+            ..summary
+        });
+    }
 
     let (start, end, mut count) = match case_id {
         Some(d) => (d, d + 1, d - 1),
         None => (0, total, 0),
     };
 
+    cmd_utils::take_last_usage();
+
     for test_case in test_cases.iter().skip(count).take(end - start) {
+        if signal_utils::is_interrupted() {
+            break;
+        }
+
         count += 1;
 
         if let Some(d) = case_id
@@ -51,121 +305,512 @@ pub async fn quest(
             continue;
         }
 
-        match quest_it(&target, test_case, count, total, use_hints) {
-            Ok((true, elapsed)) => {
+        let case_name = fs_utils::case_stem(test_case)
+            .unwrap_or_default()
+            .to_string();
+
+        let quest_result = quest_it(prog, &target, test_case, count, total, use_hints, config);
+        let usage = cmd_utils::take_last_usage();
+
+        match quest_result {
+            Ok((Verdict::Passed, elapsed, _)) => {
                 passed += 1;
                 total_duration = match (total_duration, elapsed) {
                     (Some(d), Some(elap_time)) => Some(d + elap_time),
                     (Some(d), _) => Some(d),
                     _ => elapsed,
                 };
+                cases.push(CaseResult {
+                    name: case_name,
+                    verdict: Verdict::Passed,
+                    elapsed,
+                    usage,
+                    artifacts: None,
+                });
+            }
+            _ if signal_utils::is_interrupted() => break,
+            Ok((verdict, _, artifacts)) => {
+                failed += 1;
+                cases.push(CaseResult {
+                    name: case_name,
+                    verdict,
+                    elapsed: None,
+                    usage,
+                    artifacts,
+                });
+            }
+            Err(_) => {
+                failed += 1;
+                cases.push(CaseResult {
+                    name: case_name,
+                    verdict: Verdict::Error,
+                    elapsed: None,
+                    usage,
+                    artifacts: None,
+                });
             }
-            Ok((false, _)) | Err(_) => failed += 1,
         }
     }
 
-    println!(
-        "passed: {}, failed: {}, elapsed: {}ms",
+    prog_utils::cleanup_program(prog, &target, build_files)?;
+
+    Ok(QuestSummary {
         passed,
         failed,
-        total_duration.map(|d| d.as_millis()).unwrap_or(0)
-    );
+        elapsed: total_duration.unwrap_or_default(),
+        cases,
+        build_warnings,
+    })
+}
 
-    prog_utils::cleanup_program(prog, &target, build_files)?;
+pub fn find_answer(test_case: &Path) -> Result<PathBuf> {
+    let in_stem = fs_utils::case_stem(test_case).ok_or(OwlError::UriError(
+        format!("'{}': has no file stem", test_case.to_string_lossy()),
+        "".into(),
+    ))?;
 
-    if failed > 0 {
-        Err(OwlError::TestFailure("test failures".into()))
+    let parent_dir = test_case.parent().ok_or(OwlError::FileError(
+        format!(
+            "Failed to determine parent dir of '{}'",
+            test_case.to_string_lossy()
+        ),
+        "None".into(),
+    ))?;
+
+    ANS_EXTS
+        .iter()
+        .map(|ext| parent_dir.join(format!("{}.{}", in_stem, ext)))
+        .find(|ans_path| ans_path.exists())
+        .ok_or(OwlError::FileError(
+            format!(
+                "Failed to find answer for '{}' using stem '{}.ans' or '{}.out'",
+                test_case.to_string_lossy(),
+                in_stem,
+                in_stem
+            ),
+            "".into(),
+        ))
+}
+
+/// The primary answer for `test_case` followed by any alternates (`stem.ans2`, `stem.ans3`, ...).
+pub fn find_answers(test_case: &Path) -> Result<Vec<PathBuf>> {
+    let mut ans_paths = vec![find_answer(test_case)?];
+
+    let (Some(parent_dir), Some(in_stem)) = (test_case.parent(), fs_utils::case_stem(test_case))
+    else {
+        return Ok(ans_paths);
+    };
+
+    for n in 2.. {
+        let Some(alt_path) = [
+            format!("{}.ans{}", in_stem, n),
+            format!("{}.ans{}.gz", in_stem, n),
+        ]
+        .iter()
+        .map(|file_name| parent_dir.join(file_name))
+        .find(|alt_path| alt_path.exists()) else {
+            break;
+        };
+
+        ans_paths.push(alt_path);
+    }
+
+    Ok(ans_paths)
+}
+
+/// Runs the cases on `config.jobs` threads, drawing a live view when stdout is a terminal.
+fn judge_parallel(
+    prog: &Path,
+    target: &Path,
+    test_cases: &[PathBuf],
+    use_hints: bool,
+    config: &OwlConfig,
+) -> Result<QuestSummary> {
+    let total = test_cases.len();
+    let workers = config.jobs.min(total);
+    let show_top = config.show_top && config.format == OutputFormat::Pretty;
+
+    let queue = Mutex::new(test_cases.iter().enumerate());
+    let state = Mutex::new(TopState::new(total, workers));
+    let results = Mutex::new(Vec::new());
+
+    let view_result = thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    if signal_utils::is_interrupted() {
+                        break;
+                    }
+
+                    let Some((index, test_case)) = queue.lock().expect("queue lock").next() else {
+                        break;
+                    };
+
+                    let case_name = fs_utils::case_stem(test_case)
+                        .unwrap_or_default()
+                        .to_string();
+
+                    state.lock().expect("top state lock").start(&case_name);
+
+                    let (verdict, elapsed, artifacts, error) = if show_top {
+                        match find_answers(test_case).and_then(|ans_paths| {
+                            super::check_case(prog, target, test_case, &ans_paths, config)
+                        }) {
+                            Ok(CaseCheck::Passed(elapsed, _)) => {
+                                (Verdict::Passed, Some(elapsed), None, None)
+                            }
+                            Ok(CaseCheck::Failed(_, ans, actual)) => (
+                                Verdict::Failed,
+                                None,
+                                save_artifacts(test_case, Some(&ans), Some(&actual)),
+                                Some("failed test".into()),
+                            ),
+                            Ok(CaseCheck::Exceeded(_, exceeded)) => {
+                                (exceeded.verdict(), None, None, Some(exceeded.to_string()))
+                            }
+                            Err(e) => (
+                                Verdict::Error,
+                                None,
+                                save_artifacts(test_case, None, None),
+                                Some(e.to_string()),
+                            ),
+                        }
+                    } else {
+                        match quest_it(prog, target, test_case, index + 1, total, use_hints, config)
+                        {
+                            Ok((verdict, elapsed, artifacts)) => {
+                                (verdict, elapsed, artifacts, None)
+                            }
+                            Err(_) => (Verdict::Error, None, None, None),
+                        }
+                    };
+
+                    let passed = verdict == Verdict::Passed;
+
+                    let usage = cmd_utils::take_last_usage();
+
+                    state
+                        .lock()
+                        .expect("top state lock")
+                        .finish(&case_name, passed);
+
+                    results.lock().expect("results lock").push((
+                        index,
+                        CaseResult {
+                            name: case_name,
+                            verdict,
+                            elapsed,
+                            usage,
+                            artifacts,
+                        },
+                        error,
+                    ));
+                }
+
+                state.lock().expect("top state lock").workers -= 1;
+            });
+        }
+
+        if show_top {
+            let title = format!("owlgo top ({} jobs)", workers);
+            TopApp::new(config.theme.clone()).run(&title, &state)
+        } else {
+            Ok(())
+        }
+    });
+
+    let mut results = results.into_inner().expect("results lock");
+    results.sort_by_key(|(index, _, _)| *index);
+
+    for (index, case, error) in results.iter() {
+        if let Some(e) = error {
+            eprintln!(
+                "{}\n",
+                term_utils::fit_text(&format!(
+                    "({}/{}) test_name: \x1b[36m{}\x1b[0m, status: \x1b[31m{}\x1b[0m 😭",
+                    index + 1,
+                    total,
+                    case.name,
+                    e
+                ))
+            );
+        }
+    }
+
+    if results.iter().any(|(_, _, error)| error.is_some())
+        && let Some(last_run) = test_cases
+            .first()
+            .and_then(|tc| last_run_dir(tc).ok().flatten())
+    {
+        eprintln!("artifacts: {}\n", last_run.to_string_lossy());
+    }
+
+    view_result?;
+
+    let cases = results
+        .into_iter()
+        .map(|(_, case, _)| case)
+        .collect::<Vec<CaseResult>>();
+
+    Ok(QuestSummary {
+        passed: cases.iter().filter(|case| case.passed()).count(),
+        failed: cases.iter().filter(|case| !case.passed()).count(),
+        elapsed: cases.iter().filter_map(|case| case.elapsed).sum(),
+        cases,
+        build_warnings: 0,
+    })
+}
+
+fn last_run_dir(quest_path: &Path) -> Result<Option<PathBuf>> {
+    let quest_dir = if quest_path.is_dir() {
+        Some(quest_path)
     } else {
-        println!("\x1b[32mall tests passed\x1b[0m 🏆🏆🏆\n");
-        Ok(())
+        quest_path.parent()
+    };
+
+    match quest_dir.and_then(Path::file_name).and_then(OsStr::to_str) {
+        Some(quest_name) => Ok(Some(fs_utils::ensure_path_from_home(
+            &[OWL_DIR, LAST_RUN_DIR],
+            Some(quest_name),
+        )?)),
+        None => Ok(None),
+    }
+}
+
+/// Writes a failed case's input, expected and actual output, and stderr to
+/// `~/.owlgo/.last_run/<quest>/<case>/`, warning instead of failing the run.
+fn save_artifacts(test_case: &Path, ans: Option<&[u8]>, actual: Option<&[u8]>) -> Option<PathBuf> {
+    let stderr = cmd_utils::last_stderr();
+
+    if fs_utils::is_hidden_case(test_case) {
+        return None;
     }
+
+    let save_result = (|| {
+        let Some(case_dir) = last_run_dir(test_case)?
+            .zip(fs_utils::case_stem(test_case))
+            .map(|(last_run, case_name)| last_run.join(case_name))
+        else {
+            return Ok(None);
+        };
+
+        fs::create_dir_all(&case_dir).map_err(|e| {
+            OwlError::FileError(
+                format!("could not create dir '{}'", case_dir.to_string_lossy()),
+                e.to_string(),
+            )
+        })?;
+
+        let ans = match ans {
+            Some(ans) => Some(ans.to_vec()),
+            None => find_answer(test_case)
+                .and_then(|ans_path| fs_utils::read_case(&ans_path))
+                .ok(),
+        };
+
+        let artifacts = [
+            ("input.txt", Some(fs_utils::read_case(test_case)?)),
+            ("expected.txt", ans),
+            ("actual.txt", actual.map(<[u8]>::to_vec)),
+            ("stderr.txt", Some(stderr)),
+        ];
+
+        for (file_name, contents) in artifacts {
+            let Some(contents) = contents else {
+                continue;
+            };
+
+            let artifact_path = case_dir.join(file_name);
+
+            fs::write(&artifact_path, contents).map_err(|e| {
+                OwlError::FileError(
+                    format!("could not write '{}'", artifact_path.to_string_lossy()),
+                    e.to_string(),
+                )
+            })?;
+        }
+
+        Ok(Some(case_dir))
+    })();
+
+    save_result.unwrap_or_else(|e: OwlError| {
+        warn(OwlWarning::Recovered(e.to_string()));
+        None
+    })
 }
 
 pub fn quest_it(
+    prog: &Path,
     target: &Path,
     test_case: &Path,
     count: usize,
     total: usize,
     use_hints: bool,
-) -> Result<(bool, Option<Duration>)> {
-    let in_stem = test_case
-        .file_stem()
-        .and_then(OsStr::to_str)
-        .ok_or(OwlError::UriError(
-            format!("'{}': has no file stem", test_case.to_string_lossy()),
-            "".into(),
-        ))?;
+    config: &OwlConfig,
+) -> Result<(Verdict, Option<Duration>, Option<PathBuf>)> {
+    let in_stem = fs_utils::case_stem(test_case).ok_or(OwlError::UriError(
+        format!("'{}': has no file stem", test_case.to_string_lossy()),
+        "".into(),
+    ))?;
 
-    let mut ans_path = test_case
-        .parent()
-        .ok_or(OwlError::FileError(
-            format!(
-                "Failed to determine parent dir of '{}'",
-                test_case.to_string_lossy()
-            ),
-            "None".into(),
-        ))?
-        .to_path_buf();
-
-    let ans_str = format!("{}.ans", in_stem);
-    ans_path.push(&ans_str);
+    let ans_paths = find_answers(test_case)?;
+    let step = Some((count.max(1), total));
 
-    if !ans_path.exists() {
-        ans_path.pop();
-        let out_str = format!("{}.out", in_stem);
-        ans_path.push(out_str);
+    if config.format == OutputFormat::Jsonl {
+        progress_utils::report(ProgressEvent::Started {
+            task: "case",
+            name: in_stem,
+            step,
+        });
     }
 
-    if !ans_path.exists() {
-        return Err(OwlError::FileError(
-            format!(
-                "Failed to find answer for '{}' using stem '{}.ans' or '{}.out'",
-                test_case.to_string_lossy(),
-                in_stem,
-                in_stem
-            ),
-            "".into(),
-        ));
-    }
+    let mut exceeded = None;
+    let mut verdict = Verdict::Error;
+    let mut artifacts = None;
+
+    let test_result = match super::check_case(prog, target, test_case, &ans_paths, config) {
+        Ok(CaseCheck::Passed(elapsed, matched)) => Ok((elapsed, matched)),
+        Ok(CaseCheck::Failed(elapsed, ans, actual)) => {
+            verdict = Verdict::Failed;
+            artifacts = save_artifacts(test_case, Some(&ans), Some(&actual));
+
+            if let Some(artifacts) = &artifacts
+                && config.format == OutputFormat::Pretty
+            {
+                eprintln!("artifacts: {}", artifacts.to_string_lossy());
+            }
 
-    match super::test_it(target, test_case, &ans_path) {
-        Ok(elapsed) => {
+            super::report_failed_case(test_case, elapsed, &ans, &actual, config);
+            Err(OwlError::TestFailure("failed test".into()))
+        }
+        Ok(CaseCheck::Exceeded(elapsed, limit_exceeded)) => {
+            exceeded = Some(limit_exceeded);
+            verdict = limit_exceeded.verdict();
+            super::report_exceeded_case(test_case, elapsed, limit_exceeded, config);
+            Err(OwlError::TestFailure(limit_exceeded.to_string()))
+        }
+        Err(e) => {
+            artifacts = save_artifacts(test_case, None, None);
+
+            if let Some(artifacts) = &artifacts
+                && config.format == OutputFormat::Pretty
+            {
+                eprintln!("artifacts: {}", artifacts.to_string_lossy());
+            }
+
+            Err(e)
+        }
+    };
+
+    match test_result {
+        Ok((elapsed, _)) if config.format == OutputFormat::Jsonl => {
+            progress_utils::report(ProgressEvent::Finished {
+                task: "case",
+                name: in_stem,
+                step,
+                ok: true,
+                elapsed: Some(elapsed),
+                exceeded: None,
+            });
+            Ok((Verdict::Passed, Some(elapsed), None))
+        }
+        Err(_) if config.format == OutputFormat::Jsonl => {
+            progress_utils::report(ProgressEvent::Finished {
+                task: "case",
+                name: in_stem,
+                step,
+                ok: false,
+                elapsed: None,
+                exceeded,
+            });
+            Ok((verdict, None, artifacts))
+        }
+        Ok((elapsed, _)) if config.format == OutputFormat::Vscode => {
             println!(
-                "({}/{}) [{}ms] test_name: \x1b[36m{}\x1b[0m, status: \x1b[32mpassed test\x1b[0m 🎉\n",
-                count,
-                total,
-                elapsed.as_millis(),
-                in_stem
+                "{}",
+                vscode_utils::test_event(test_case, "passed", Some(elapsed), None, None, None)
+            );
+            Ok((Verdict::Passed, Some(elapsed), None))
+        }
+        Err(e) if config.format == OutputFormat::Vscode => {
+            if !matches!(e, OwlError::TestFailure(_)) {
+                println!(
+                    "{}",
+                    vscode_utils::test_event(
+                        test_case,
+                        "errored",
+                        None,
+                        Some(&e.to_string()),
+                        None,
+                        None
+                    )
+                );
+            }
+            Ok((verdict, None, artifacts))
+        }
+        Ok((elapsed, matched)) => {
+            let matched_tag = match matched.file_name() {
+                Some(file_name) if ans_paths.len() > 1 => {
+                    format!(" (matched {})", file_name.to_string_lossy())
+                }
+                _ => String::new(),
+            };
+
+            println!(
+                "{}\n",
+                term_utils::fit_text(&format!(
+                    "({}/{}) [{}ms] test_name: \x1b[36m{}\x1b[0m, status: \x1b[32mpassed test\x1b[0m{} 🎉",
+                    count,
+                    total,
+                    elapsed.as_millis(),
+                    in_stem,
+                    matched_tag
+                ))
             );
-            Ok((true, Some(elapsed)))
+            Ok((Verdict::Passed, Some(elapsed), None))
         }
         Err(e) => {
-            if use_hints && let Some(parent_dir) = test_case.parent() {
+            if use_hints
+                && !fs_utils::is_hidden_case(test_case)
+                && let Some(parent_dir) = test_case.parent()
+            {
                 let feedback_file = format!("{}.md", in_stem);
 
                 let mut feedback_path = parent_dir.to_path_buf();
                 feedback_path.push(feedback_file);
 
-                cmd_utils::bat_file(&feedback_path).or_else(|_| {
-                    cmd_utils::glow_file(&feedback_path).or_else(|_| {
-                        fs::read_to_string(&feedback_path)
-                            .map(|contents| eprintln!("{}", contents))
-                            .map_err(|e| {
-                                OwlError::FileError(
-                                    format!("could not read '{}'", feedback_path.to_string_lossy()),
-                                    e.to_string(),
-                                )
-                            })
-                    })
-                })?
+                if !feedback_path.exists() {
+                    warn(OwlWarning::MissingFeedback(
+                        feedback_path.to_string_lossy().to_string(),
+                    ));
+                } else {
+                    cmd_utils::bat_file(&feedback_path).or_else(|_| {
+                        cmd_utils::glow_file(&feedback_path).or_else(|_| {
+                            fs::read_to_string(&feedback_path)
+                                .map(|contents| eprintln!("{}", contents))
+                                .map_err(|e| {
+                                    OwlError::FileError(
+                                        format!(
+                                            "could not read '{}'",
+                                            feedback_path.to_string_lossy()
+                                        ),
+                                        e.to_string(),
+                                    )
+                                })
+                        })
+                    })?
+                }
             }
 
             eprintln!(
-                "({}/{}) test_name: \x1b[36m{}\x1b[0m, status: \x1b[31m{}\x1b[0m 😭\n",
-                count, total, in_stem, e
+                "{}\n",
+                term_utils::fit_text(&format!(
+                    "({}/{}) test_name: \x1b[36m{}\x1b[0m, status: \x1b[31m{}\x1b[0m 😭",
+                    count, total, in_stem, e
+                ))
             );
 
-            Ok((false, None))
+            Ok((verdict, None, artifacts))
         }
     }
 }
@@ -175,13 +820,20 @@ pub async fn quest_once(
     prog: &Path,
     test_name: &str,
     use_hints: bool,
+    config: &OwlConfig,
 ) -> Result<()> {
-    let quest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(quest_name))?;
+    let quest_path = fs_utils::quest_path(quest_name)?;
 
     if !quest_path.exists() {
         super::fetch_quest(quest_name).await?;
     }
 
+    if config.show_intro
+        && let Err(e) = super::show_intro(&quest_path)
+    {
+        warn(OwlWarning::Recovered(e.to_string()));
+    }
+
     if !prog.exists() {
         return Err(OwlError::FileError(
             format!("'{}': no such file", prog.to_string_lossy()),
@@ -189,34 +841,240 @@ pub async fn quest_once(
         ));
     }
 
-    let (target, build_files) = match prog_utils::build_program(prog)? {
-        Some(bl) => (bl.target, bl.build_files),
-        None => (prog.to_path_buf(), None),
-    };
+    hook_utils::pre_build(&config.hooks, Some(quest_name), prog)?;
 
     let in_path = fs_utils::find_by_stem_and_ext(&quest_path, test_name, "in")?;
 
-    let mut passed = 0;
-    let mut check_elapsed: Option<Duration> = None;
+    let meta = if quest_path.is_dir() {
+        QuestMeta::load(&quest_path)?
+    } else {
+        QuestMeta::default()
+    };
+    let config = &config.for_quest(&meta);
 
-    if let Ok((true, some_duration)) = quest_it(&target, &in_path, 0, 1, use_hints) {
-        passed = 1;
-        check_elapsed = some_duration;
-    }
+    let _file_io = cmd_utils::set_file_io(meta.input_file, meta.output_file);
 
-    println!(
-        "passed: {}, failed: {}, elapsed: {}ms",
+    let (target, build_files, build_warnings) = match prog_utils::build_program(prog, config)? {
+        Some(bl) => (bl.target, bl.build_files, bl.warnings),
+        None => (prog.to_path_buf(), None, 0),
+    };
+
+    cmd_utils::take_last_usage();
+
+    let (verdict, check_elapsed, artifacts) = quest_it(
+        prog, &target, &in_path, 0, 1, use_hints, config,
+    )
+    .unwrap_or((Verdict::Error, None, None));
+    let passed = usize::from(verdict == Verdict::Passed);
+
+    let usage = cmd_utils::take_last_usage();
+
+    report_summary(
+        quest_name,
         passed,
         1 - passed,
-        check_elapsed.map(|d| d.as_millis()).unwrap_or(0)
+        check_elapsed.unwrap_or_default(),
+        build_warnings,
+        config,
     );
 
+    let case = CaseResult {
+        name: test_name.to_string(),
+        verdict,
+        elapsed: check_elapsed,
+        usage,
+        artifacts,
+    };
+
+    write_results(
+        quest_name,
+        &quest_path,
+        prog,
+        std::slice::from_ref(&case),
+        check_elapsed.unwrap_or_default(),
+        build_warnings,
+        config,
+    );
+
+    if passed == 0
+        && let Err(e) = super::record_failed_case(quest_name, test_name, prog)
+    {
+        warn(OwlWarning::Recovered(e.to_string()));
+    }
+
     prog_utils::cleanup_program(prog, &target, build_files)?;
 
+    if signal_utils::is_interrupted() {
+        return Err(OwlError::Interrupted("interrupted".into()));
+    }
+
+    let hook_verdict = if passed == 0 {
+        hook_utils::VERDICT_FAILED
+    } else {
+        hook_utils::VERDICT_ACCEPTED
+    };
+    hook_utils::post_test(&config.hooks, Some(quest_name), prog, hook_verdict);
+
+    if let Some(script_name) = &config.script {
+        run_quest_script(script_name, quest_name, prog, &[case])?;
+    }
+
     if passed == 0 {
         Err(OwlError::TestFailure("test failures".into()))
     } else {
-        println!("\x1b[32mall tests passed\x1b[0m 🏆🏆🏆\n");
+        if config.format == OutputFormat::Pretty {
+            println!("\x1b[32mall tests passed\x1b[0m 🏆🏆🏆\n");
+        }
         Ok(())
     }
 }
+
+/// Writes the run's results to `--results <FILE>`, if given, warning instead of failing the run.
+fn write_results(
+    quest_name: &str,
+    quest_path: &Path,
+    prog: &Path,
+    cases: &[CaseResult],
+    elapsed: Duration,
+    build_warnings: usize,
+    config: &OwlConfig,
+) {
+    let Some(results_path) = &config.results else {
+        return;
+    };
+
+    let limits = if quest_path.is_dir() {
+        QuestMeta::load(quest_path)
+            .map(|meta| (meta.time_limit, meta.memory_limit))
+            .unwrap_or_default()
+    } else {
+        (None, None)
+    };
+
+    let results = RunResults::new(quest_name, prog, cases, elapsed, build_warnings, limits);
+
+    if let Err(e) = results.write(results_path) {
+        warn(OwlWarning::Recovered(e.to_string()));
+    }
+}
+
+fn report_summary(
+    quest_name: &str,
+    passed: usize,
+    failed: usize,
+    elapsed: Duration,
+    build_warnings: usize,
+    config: &OwlConfig,
+) {
+    let shard_tag = config
+        .shard
+        .map(|shard| format!("shard {}/{}: ", shard.index, shard.count))
+        .unwrap_or_default();
+
+    let warnings_tag = match build_warnings {
+        0 => String::new(),
+        n if config.show_build => format!(", \x1b[33mbuild warnings: {}\x1b[0m", n),
+        n => format!(
+            ", \x1b[33mbuild warnings: {}\x1b[0m (see them with '--show-build')",
+            n
+        ),
+    };
+
+    match config.format {
+        OutputFormat::Pretty => println!(
+            "{}",
+            term_utils::fit_text(&format!(
+                "{}passed: {}, failed: {}, elapsed: {}ms{}",
+                shard_tag,
+                passed,
+                failed,
+                elapsed.as_millis(),
+                warnings_tag
+            ))
+        ),
+        OutputFormat::Vscode => {
+            println!("{}", vscode_utils::summary_event(passed, failed, elapsed))
+        }
+        OutputFormat::Jsonl => progress_utils::report(ProgressEvent::Summary {
+            task: "quest",
+            name: quest_name,
+            passed,
+            failed,
+            elapsed,
+        }),
+    }
+}
+
+/// Prints the max, median and total of each case's wall time, CPU time and peak memory,
+/// flagging the slowest case.
+fn report_usage(cases: &[CaseResult], config: &OwlConfig) {
+    let usages = cases
+        .iter()
+        .filter_map(|case| case.usage.map(|usage| (case.name.as_str(), usage)))
+        .collect::<Vec<(&str, RunUsage)>>();
+
+    if config.format != OutputFormat::Pretty || usages.len() < 2 {
+        return;
+    }
+
+    let stats = |value: fn(&RunUsage) -> u64| {
+        let mut values = usages
+            .iter()
+            .map(|(name, usage)| (value(usage), *name))
+            .collect::<Vec<(u64, &str)>>();
+        values.sort();
+
+        let (max, max_name) = values[values.len() - 1];
+        let median = values[(values.len() - 1) / 2].0;
+        let total = values.iter().map(|(v, _)| v).sum::<u64>();
+
+        (max, max_name, median, total)
+    };
+
+    let ms = |micros: u64| format!("{}ms", micros / 1000);
+    let mib = |bytes: u64| format!("{:.1}MiB", bytes as f64 / (1024.0 * 1024.0));
+
+    let (wall_max, slowest, wall_median, wall_total) = stats(|u| u.wall.as_micros() as u64);
+    let (cpu_max, cpu_name, cpu_median, cpu_total) = stats(|u| u.cpu.as_micros() as u64);
+    let (mem_max, mem_name, mem_median, _) = stats(|u| u.peak_mem);
+
+    println!(
+        "wall:   max {} ({}), median {}, total {}",
+        ms(wall_max),
+        slowest,
+        ms(wall_median),
+        ms(wall_total)
+    );
+    println!(
+        "cpu:    max {} ({}), median {}, total {}",
+        ms(cpu_max),
+        cpu_name,
+        ms(cpu_median),
+        ms(cpu_total)
+    );
+    println!(
+        "memory: max {} ({}), median {}",
+        mib(mem_max),
+        mem_name,
+        mib(mem_median)
+    );
+    println!(
+        "slowest case: \x1b[36m{}\x1b[0m ({} wall)\n",
+        slowest,
+        ms(wall_max)
+    );
+}
+
+fn run_quest_script(
+    script_name: &str,
+    quest_name: &str,
+    prog: &Path,
+    cases: &[CaseResult],
+) -> Result<()> {
+    let script_path = fs_utils::ensure_path_from_home(
+        &[OWL_DIR, SCRIPT_DIR],
+        Some(&format!("{}.rhai", script_name)),
+    )?;
+
+    script_utils::run_script(&script_path, quest_name, prog, cases)
+}
@@ -0,0 +1,80 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{OwlConfig, cmd_utils, fs_utils, toml_utils};
+use crate::{CACHE_DIR, LAST_RUN, OWL_DIR};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, value};
+
+/// Remembers the failing case so `owlgo rerun` can repeat it.
+pub fn record_failed_case(quest_name: &str, case_name: &str, prog: &Path) -> Result<()> {
+    let last_run = fs_utils::ensure_path_from_home(&[OWL_DIR, CACHE_DIR], Some(LAST_RUN))?;
+
+    let prog_path = std::path::absolute(prog).map_err(|e| {
+        OwlError::UriError(
+            format!("'{}': could not resolve path", prog.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let mut last_run_doc = DocumentMut::new();
+    last_run_doc["quest"] = value(quest_name);
+    last_run_doc["case"] = value(case_name);
+    last_run_doc["prog"] = value(prog_path.to_string_lossy().to_string());
+
+    fs::write(&last_run, last_run_doc.to_string()).map_err(|e| {
+        OwlError::FileError(
+            format!("could not write '{}'", last_run.to_string_lossy()),
+            e.to_string(),
+        )
+    })
+}
+
+pub async fn rerun(verbose: bool, config: &OwlConfig) -> Result<()> {
+    let last_run = fs_utils::ensure_path_from_home(&[OWL_DIR, CACHE_DIR], Some(LAST_RUN))?;
+
+    if !last_run.exists() {
+        return Err(OwlError::FileError(
+            "no failing case to rerun".into(),
+            "run 'owlgo quest' first".into(),
+        ));
+    }
+
+    let last_run_doc = toml_utils::read_toml(&last_run)?;
+
+    let get_entry = |key: &str| {
+        last_run_doc
+            .get(key)
+            .and_then(|item| item.as_str())
+            .map(String::from)
+            .ok_or(OwlError::TomlError(
+                format!(
+                    "Invalid entry '{}' in '{}'",
+                    key,
+                    last_run.to_string_lossy()
+                ),
+                "expected a string".into(),
+            ))
+    };
+
+    let quest_name = get_entry("quest")?;
+    let case_name = get_entry("case")?;
+    let prog = PathBuf::from(get_entry("prog")?);
+
+    eprintln!(
+        "rerunning '{}' of '{}' with '{}'\n",
+        case_name,
+        quest_name,
+        prog.to_string_lossy()
+    );
+
+    let rerun_result = super::quest_once(&quest_name, &prog, &case_name, false, config).await;
+
+    if verbose {
+        eprintln!(
+            "\x1b[1;35m>>> stderr <<<\x1b[0m\n\n{}",
+            String::from_utf8_lossy(&cmd_utils::last_stderr())
+        );
+    }
+
+    rerun_result
+}
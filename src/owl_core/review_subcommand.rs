@@ -1,11 +1,19 @@
-use crate::common::{OwlError, Result};
-use crate::owl_utils::{LlmApp, PromptMode, cmd_utils, fs_utils, llm_utils, tui_utils};
-use crate::{CHAT_DIR, MANIFEST, OWL_DIR, PROMPT_DIR, PROMPT_FILE, STASH_DIR};
+use super::todos_subcommand;
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::{
+    LlmApp, OwlConfig, PromptMode, QuestMeta, WorkspaceScope, cmd_utils, fs_utils, llm_utils,
+    prog_utils, tui_utils,
+};
+use crate::{CHAT_DIR, MANIFEST, OWL_DIR, PROMPT_FILE, STASH_DIR};
 use chrono::{DateTime, Local};
+use similar::TextDiff;
+use std::ffi::OsStr;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
+const DIFF_CONTEXT: usize = 3;
+
 pub enum ReviewPrompt {
     InQuest(String),
     InStash(String),
@@ -14,11 +22,13 @@ pub enum ReviewPrompt {
 }
 
 pub async fn review_program(
-    prog: &Path,
+    prog: Option<&Path>,
     check_prompt: Option<ReviewPrompt>,
     mode: PromptMode,
     forget_chat: bool,
     use_tui: bool,
+    workspace: &WorkspaceScope,
+    config: &OwlConfig,
 ) -> Result<()> {
     let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
 
@@ -31,69 +41,88 @@ pub async fn review_program(
         ));
     }
 
-    let prog_str = fs::read_to_string(prog).map_err(|e| {
-        OwlError::FileError(
-            format!("could not read program '{}'", prog.to_string_lossy()),
-            e.to_string(),
-        )
-    })?;
+    // a project dir is reviewed as a workspace of its own
+    let (prog, workspace) = match prog {
+        Some(prog) if prog.is_dir() => {
+            let mut workspace = workspace.clone();
+            workspace.paths.insert(0, prog.to_path_buf());
 
-    let check_prompt = match check_prompt {
-        Some(review_prompt) => match review_prompt {
-            ReviewPrompt::IsFile(path) => {
-                let prompt_str = fs::read_to_string(&path).map_err(|e| {
-                    OwlError::FileError(
-                        format!("could not read prompt '{}'", path.to_string_lossy()),
-                        e.to_string(),
-                    )
-                })?;
+            (None, workspace)
+        }
+        _ => (prog, workspace.clone()),
+    };
 
-                Some(prompt_str)
-            }
-            ReviewPrompt::InStash(prompt_name) => {
-                let prompt_path = fs_utils::ensure_path_from_home(
-                    &[OWL_DIR, STASH_DIR, PROMPT_DIR],
-                    Some(&prompt_name),
-                )?;
+    let prog_str = match prog {
+        Some(prog) => Some(fs::read_to_string(prog).map_err(|e| {
+            OwlError::FileError(
+                format!("could not read program '{}'", prog.to_string_lossy()),
+                e.to_string(),
+            )
+        })?),
+        None => None,
+    };
 
-                let prompt_str = fs::read_to_string(&prompt_path).map_err(|e| {
-                    OwlError::FileError(
-                        format!("could not read prompt '{}'", prompt_path.to_string_lossy()),
-                        e.to_string(),
-                    )
-                })?;
+    let prog_str = match (prog, prog_str) {
+        (Some(prog), Some(prog_str)) if mode == PromptMode::Debug => {
+            let failure_report = config
+                .failure_log
+                .as_deref()
+                .and_then(|failure_path| prog_utils::cached_failure(failure_path, prog));
+            let build_errors = config
+                .build_cache
+                .as_deref()
+                .and_then(|cache_path| prog_utils::cached_build_errors(cache_path, prog));
 
-                Some(prompt_str)
+            match (failure_report, build_errors) {
+                (Some(failure_report), _) => {
+                    Some(llm_utils::with_failure_report(&prog_str, &failure_report))
+                }
+                (None, Some(build_errors)) => {
+                    Some(llm_utils::with_build_errors(&prog_str, &build_errors))
+                }
+                (None, None) => Some(prog_str),
             }
-            ReviewPrompt::InQuest(quest_name) => {
-                let prompt_path = fs_utils::ensure_path_from_home(
-                    &[OWL_DIR, STASH_DIR, &quest_name],
-                    Some(PROMPT_FILE),
-                )?;
+        }
+        (_, prog_str) => prog_str,
+    };
 
-                let prompt_str = fs::read_to_string(&prompt_path).map_err(|e| {
-                    OwlError::FileError(
-                        format!("could not read prompt '{}'", prompt_path.to_string_lossy()),
-                        e.to_string(),
-                    )
-                })?;
+    let quest_name = match &check_prompt {
+        Some(ReviewPrompt::InQuest(quest_name)) => Some(quest_name.as_str()),
+        _ => config.quest.as_deref(),
+    };
 
-                Some(prompt_str)
-            }
-            ReviewPrompt::UserPrompt(prompt_str) => Some(prompt_str),
-        },
-        None => None,
+    let prog_str = match (
+        prog.and_then(|prog| prog_context(prog, quest_name, config)),
+        prog_str,
+    ) {
+        (Some(context), Some(prog_str)) => Some(llm_utils::with_lang_context(&prog_str, &context)),
+        (_, prog_str) => prog_str,
     };
 
-    let (ai_sdk, client) = llm_utils::try_llm_client(&manifest_path)?;
+    let prog_str = if workspace.is_empty() {
+        prog_str
+    } else {
+        let context = workspace.gather(prog)?;
+        eprintln!("workspace: {}", context.summary());
+
+        match prog_str {
+            Some(prog_str) => Some(llm_utils::with_workspace(&prog_str, &context.files)),
+            None if context.files.is_empty() => None,
+            None => Some(llm_utils::workspace_files(&context.files)),
+        }
+    };
+
+    let check_prompt = check_prompt.map(read_review_prompt).transpose()?;
+
+    let (ai_sdk, client) = llm_utils::try_llm_client(&manifest_path, config)?;
 
     let response = if use_tui {
         tui_utils::enter_raw_mode()?;
-        let response_text = LlmApp::default()
+        let response_text = LlmApp::new(config.keymap.clone(), config.theme.clone())
             .run(
                 &ai_sdk,
                 &client,
-                Some(&prog_str),
+                prog_str.as_deref(),
                 check_prompt.as_deref(),
                 mode,
             )
@@ -105,7 +134,7 @@ pub async fn review_program(
         llm_utils::llm_review_with_client(
             &ai_sdk,
             &client,
-            Some(&prog_str),
+            prog_str.as_deref(),
             check_prompt.as_deref(),
             mode,
         )
@@ -117,8 +146,242 @@ pub async fn review_program(
 
     let chat_file_stem = format!("{}_{}.md", ai_sdk, timestamp);
 
+    record_chat(&chat_file_stem, &response, forget_chat)
+}
+
+pub async fn review_stashed(quest_name: &str, forget_chat: bool, config: &OwlConfig) -> Result<()> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+
+    if !manifest_path.exists() {
+        eprintln!("manifest doesn't exist...");
+        eprintln!("run 'owlgo update'");
+        return Err(OwlError::FileError(
+            "manifest does not exist".into(),
+            "".into(),
+        ));
+    }
+
+    let stash_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], None)?;
+
+    let mut prog_paths = fs::read_dir(&stash_dir)
+        .map_err(|e| {
+            OwlError::FileError(
+                format!("could not read stash '{}'", stash_dir.to_string_lossy()),
+                e.to_string(),
+            )
+        })?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.file_stem().is_some_and(|stem| stem == quest_name))
+        .collect::<Vec<PathBuf>>();
+
+    let quest_stash_dir = stash_dir.join(quest_name);
+
+    if quest_stash_dir.is_dir() {
+        prog_paths.extend(fs_utils::dir_tree(&quest_stash_dir)?);
+    }
+
+    prog_paths.retain(|path| prog_utils::resolve_prog_lang(path, config).is_some());
+    prog_paths.sort();
+
+    if prog_paths.is_empty() {
+        return Err(OwlError::FileError(
+            format!("'{}': no stashed programs found", quest_name),
+            "stash programs named after the quest or in its stash dir".into(),
+        ));
+    }
+
+    let progs = prog_paths
+        .iter()
+        .map(|path| {
+            let prog_name = path
+                .strip_prefix(&stash_dir)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string();
+
+            fs::read_to_string(path)
+                .map(
+                    |prog_str| match prog_context(path, Some(quest_name), config) {
+                        Some(context) => llm_utils::with_lang_context(&prog_str, &context),
+                        None => prog_str,
+                    },
+                )
+                .map(|prog_str| (prog_name, prog_str))
+                .map_err(|e| {
+                    OwlError::FileError(
+                        format!("could not read program '{}'", path.to_string_lossy()),
+                        e.to_string(),
+                    )
+                })
+        })
+        .collect::<Result<Vec<(String, String)>>>()?;
+
+    let prompt_path = quest_stash_dir.join(PROMPT_FILE);
+
+    let check_prompt = if prompt_path.exists() {
+        Some(fs::read_to_string(&prompt_path).map_err(|e| {
+            OwlError::FileError(
+                format!("could not read prompt '{}'", prompt_path.to_string_lossy()),
+                e.to_string(),
+            )
+        })?)
+    } else {
+        None
+    };
+
+    let (ai_sdk, client) = llm_utils::try_llm_client(&manifest_path, config)?;
+
+    let response =
+        llm_utils::llm_compare_with_client(&ai_sdk, &client, &progs, check_prompt.as_deref())
+            .await?;
+
+    let mut report = format!("# Stashed programs for '{}'\n\n", quest_name);
+
+    for (prog_name, _) in progs.iter() {
+        report.push_str(&format!("- `{}`\n", prog_name));
+    }
+
+    report.push_str(&response);
+
+    let now: DateTime<Local> = Local::now();
+    let timestamp = now.format("%Y-%m-%d-%H-%M-%S").to_string();
+
+    let chat_file_stem = format!("{}_{}_{}.md", ai_sdk, quest_name, timestamp);
+
+    record_chat(&chat_file_stem, &report, forget_chat)
+}
+
+/// Reviews only what changed in the program since it was stashed, so large files stay cheap.
+pub async fn review_since_stash(
+    prog: &Path,
+    check_prompt: Option<ReviewPrompt>,
+    forget_chat: bool,
+    config: &OwlConfig,
+) -> Result<()> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+
+    if !manifest_path.exists() {
+        eprintln!("manifest doesn't exist...");
+        eprintln!("run 'owlgo update'");
+        return Err(OwlError::FileError(
+            "manifest does not exist".into(),
+            "".into(),
+        ));
+    }
+
+    let prog_file_name = prog
+        .file_name()
+        .and_then(OsStr::to_str)
+        .ok_or(OwlError::UriError(
+            format!("'{}': has no filename", prog.to_string_lossy()),
+            "".into(),
+        ))?;
+
+    let stash_path = fs_utils::stash_path(prog_file_name)?;
+
+    if !stash_path.is_file() {
+        return Err(OwlError::FileError(
+            format!("'{}': not stashed", prog_file_name),
+            format!("run 'owlgo stash {}' first", prog.to_string_lossy()),
+        ));
+    }
+
+    let stashed_str = fs::read_to_string(&stash_path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read stashed '{}'", stash_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+    let prog_str = fs::read_to_string(prog).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read program '{}'", prog.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let diff = TextDiff::from_lines(&stashed_str, &prog_str);
+
+    if diff.ratio() == 1.0 {
+        println!("'{}': no changes since it was stashed", prog_file_name);
+        return Ok(());
+    }
+
+    let diff_str = diff
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT)
+        .header(&format!("stash/{}", prog_file_name), prog_file_name)
+        .to_string();
+
+    let quest_name = match &check_prompt {
+        Some(ReviewPrompt::InQuest(quest_name)) => Some(quest_name.as_str()),
+        _ => config.quest.as_deref(),
+    };
+    let lang_context = prog_context(prog, quest_name, config);
+
+    let check_prompt = check_prompt.map(read_review_prompt).transpose()?;
+
+    let (ai_sdk, client) = llm_utils::try_llm_client(&manifest_path, config)?;
+
+    let response = llm_utils::llm_diff_with_client(
+        &ai_sdk,
+        &client,
+        &diff_str,
+        lang_context.as_deref(),
+        check_prompt.as_deref(),
+    )
+    .await?;
+
+    let report = format!(
+        "# Changes to '{}' since stash\n\n```diff\n{}```\n{}",
+        prog_file_name, diff_str, response
+    );
+
+    let now: DateTime<Local> = Local::now();
+    let timestamp = now.format("%Y-%m-%d-%H-%M-%S").to_string();
+
+    let chat_file_stem = format!("{}_{}.md", ai_sdk, timestamp);
+
+    record_chat(&chat_file_stem, &report, forget_chat)
+}
+
+/// Describes the program's language and build flags, and the quest's time limit if it has one.
+fn prog_context(prog: &Path, quest_name: Option<&str>, config: &OwlConfig) -> Option<String> {
+    let lang = prog_utils::resolve_prog_lang(prog, config)?;
+    let build_line = lang.build_line(prog, &config.build_args);
+
+    let time_limit = quest_name
+        .and_then(|quest_name| fs_utils::quest_path(quest_name).ok())
+        .and_then(|quest_dir| QuestMeta::load(&quest_dir).ok())
+        .and_then(|meta| meta.time_limit);
+
+    Some(llm_utils::lang_context(
+        lang.name(),
+        build_line.as_deref(),
+        time_limit,
+    ))
+}
+
+fn read_review_prompt(review_prompt: ReviewPrompt) -> Result<String> {
+    let prompt_path = match review_prompt {
+        ReviewPrompt::IsFile(path) => path,
+        ReviewPrompt::InStash(prompt_name) => fs_utils::prompt_path(&prompt_name)?,
+        ReviewPrompt::InQuest(quest_name) => {
+            fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, &quest_name], Some(PROMPT_FILE))?
+        }
+        ReviewPrompt::UserPrompt(prompt_str) => return Ok(prompt_str),
+    };
+
+    fs::read_to_string(&prompt_path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read prompt '{}'", prompt_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })
+}
+
+fn record_chat(chat_file_stem: &str, response: &str, forget_chat: bool) -> Result<()> {
     let chat_path =
-        fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, CHAT_DIR], Some(&chat_file_stem))?;
+        fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, CHAT_DIR], Some(chat_file_stem))?;
 
     let mut chat_file = OpenOptions::new()
         .create(true)
@@ -153,7 +416,24 @@ pub async fn review_program(
         })?;
 
     if forget_chat {
-        fs_utils::remove_path(&chat_path)?;
+        return fs_utils::remove_path(&chat_path);
+    }
+
+    if let Some((todos_path, count)) = todos_subcommand::write_todos(&chat_path, response)? {
+        println!(
+            "{} todos written to '{}' (run 'owlgo todos' to check them off)",
+            count,
+            todos_path.to_string_lossy()
+        );
+    }
+
+    match super::enforce_chat_retention() {
+        Ok(0) => {}
+        Ok(pruned) => eprintln!(
+            "removed {} old chat(s) per the manifest's [chat] limits",
+            pruned
+        ),
+        Err(e) => warn(OwlWarning::Recovered(e.to_string())),
     }
 
     Ok(())
@@ -1,8 +1,8 @@
 use crate::common::{OwlError, Result};
-use crate::owl_utils::{cmd_utils, prog_utils};
+use crate::owl_utils::{OwlConfig, hook_utils, prog_utils, signal_utils};
 use std::path::Path;
 
-pub fn run_program(prog: &Path) -> Result<()> {
+pub fn run_program(prog: &Path, config: &OwlConfig) -> Result<()> {
     if !prog.exists() {
         return Err(OwlError::FileError(
             format!("'{}': program not found", prog.to_string_lossy()),
@@ -10,22 +10,39 @@ pub fn run_program(prog: &Path) -> Result<()> {
         ));
     }
 
-    match prog_utils::check_prog_lang(prog) {
+    match prog_utils::resolve_prog_lang(prog, config) {
         Some(lang) => {
-            let (target, build_files) = match prog_utils::build_program(prog)? {
+            hook_utils::pre_build(&config.hooks, config.quest.as_deref(), prog)?;
+
+            let (target, build_files) = match prog_utils::build_program(prog, config)? {
                 Some(bl) => (bl.target, bl.build_files),
                 None => (prog.to_path_buf(), None),
             };
 
-            let run_result = lang.run(&target);
+            let run_result = lang.run(&target, config);
 
             prog_utils::cleanup_program(prog, &target, build_files)?;
 
-            run_result.map(|(stdout, _)| println!("{}", stdout))
+            if signal_utils::is_interrupted() {
+                return Err(OwlError::Interrupted("interrupted".into()));
+            }
+
+            if let Err(e) = &run_result {
+                prog_utils::report_failure("run", prog, e, config);
+            }
+
+            run_result.map(|(stdout, _)| println!("{}", String::from_utf8_lossy(&stdout)))
         }
         None => {
-            let (stdout, _) = cmd_utils::run_binary(prog)?;
-            println!("{}", stdout);
+            let (stdout, _) = prog_utils::run_binary(prog, None, config).map_err(|e| {
+                if signal_utils::is_interrupted() {
+                    OwlError::Interrupted("interrupted".into())
+                } else {
+                    prog_utils::report_failure("run", prog, &e, config);
+                    e
+                }
+            })?;
+            println!("{}", String::from_utf8_lossy(&stdout));
             Ok(())
         }
     }
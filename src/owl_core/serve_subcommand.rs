@@ -0,0 +1,443 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::cmd::judge_utils::{CaseResult, Verdict};
+use crate::owl_utils::{OwlConfig, QuestMeta, fs_utils, judge_utils};
+use crate::{OWL_DIR, STASH_DIR};
+use encoding_rs::Encoding;
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+const MAX_BODY_LEN: usize = 1024 * 1024;
+const MAX_HEAD_LEN: u64 = 16 * 1024;
+const MAX_HEADERS: usize = 64;
+
+struct ServeState {
+    config: OwlConfig,
+    port: u16,
+    token: String,
+    runs: Mutex<Vec<Value>>,
+    run_lock: Mutex<()>,
+}
+
+struct RequestHead {
+    method: String,
+    path: String,
+    content_len: usize,
+    origin: Option<String>,
+    token: Option<String>,
+}
+
+pub async fn serve(port: u16, config: &OwlConfig) -> Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await.map_err(|e| {
+        OwlError::NetworkError(format!("Failed to bind to port {}", port), e.to_string())
+    })?;
+
+    let token = format!("{:032x}", rand::random::<u128>());
+
+    println!("owlgo serving on http://127.0.0.1:{}", port);
+    println!(
+        "session token: {} (send it as 'Authorization: Bearer <token>')",
+        token
+    );
+
+    let state = Arc::new(ServeState {
+        config: config.clone(),
+        port,
+        token,
+        runs: Mutex::new(Vec::new()),
+        run_lock: Mutex::new(()),
+    });
+
+    loop {
+        let (stream, _) = listener.accept().await.map_err(|e| {
+            OwlError::NetworkError("Failed to accept connection".into(), e.to_string())
+        })?;
+
+        let state = Arc::clone(&state);
+
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, state).await {
+                eprintln!("\x1b[33m[owlgo warning]\x1b[0m: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: TcpStream, state: Arc<ServeState>) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let (status, body) = match read_head(&mut reader).await? {
+        None => (431, json!({ "error": "request headers too large" })),
+        Some(head) if !origin_allowed(head.origin.as_deref(), state.port) => {
+            (403, json!({ "error": "origin not allowed" }))
+        }
+        Some(head) if !token_matches(head.token.as_deref(), &state.token) => {
+            (401, json!({ "error": "missing or wrong session token" }))
+        }
+        Some(head) if head.content_len > MAX_BODY_LEN => {
+            (413, json!({ "error": "request body too large" }))
+        }
+        Some(head) => {
+            let mut body = vec![0; head.content_len];
+            reader.read_exact(&mut body).await.map_err(read_err)?;
+
+            route(&head.method, &head.path, &body, state).await
+        }
+    };
+
+    let body_str = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body_str.len(),
+        body_str
+    );
+
+    reader
+        .get_mut()
+        .write_all(response.as_bytes())
+        .await
+        .map_err(|e| OwlError::NetworkError("Failed to write response".into(), e.to_string()))
+}
+
+fn read_err(e: std::io::Error) -> OwlError {
+    OwlError::NetworkError("Failed to read request".into(), e.to_string())
+}
+
+/// Reads the request line and headers, or `None` if they run past `MAX_HEAD_LEN` bytes or
+/// `MAX_HEADERS` headers.
+async fn read_head<R: AsyncRead + Unpin>(reader: &mut BufReader<R>) -> Result<Option<RequestHead>> {
+    let mut head_reader = reader.take(MAX_HEAD_LEN);
+
+    let mut request_line = String::new();
+    head_reader
+        .read_line(&mut request_line)
+        .await
+        .map_err(read_err)?;
+
+    let mut parts = request_line.split_whitespace();
+    let mut head = RequestHead {
+        method: parts.next().unwrap_or("").to_string(),
+        path: parts.next().unwrap_or("").to_string(),
+        content_len: 0,
+        origin: None,
+        token: None,
+    };
+
+    for _ in 0..=MAX_HEADERS {
+        let mut header = String::new();
+        head_reader.read_line(&mut header).await.map_err(read_err)?;
+
+        if !header.ends_with('\n') {
+            return Ok(None);
+        }
+
+        let header = header.trim_end();
+
+        if header.is_empty() {
+            return Ok(Some(head));
+        }
+
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+
+        if name.eq_ignore_ascii_case("content-length") {
+            head.content_len = value.parse::<usize>().unwrap_or(0);
+        } else if name.eq_ignore_ascii_case("origin") {
+            head.origin = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("authorization") {
+            head.token = value
+                .strip_prefix("Bearer ")
+                .map(|token| token.trim().to_string());
+        }
+    }
+
+    Ok(None)
+}
+
+/// Requests without an `Origin` come from local tools; browsers must be on the served page.
+fn origin_allowed(origin: Option<&str>, port: u16) -> bool {
+    origin.is_none_or(|origin| {
+        origin == format!("http://127.0.0.1:{}", port)
+            || origin == format!("http://localhost:{}", port)
+    })
+}
+
+fn token_matches(token: Option<&str>, expected: &str) -> bool {
+    token.is_some_and(|token| {
+        token.len() == expected.len()
+            && token
+                .bytes()
+                .zip(expected.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    })
+}
+
+async fn route(method: &str, path: &str, body: &[u8], state: Arc<ServeState>) -> (u16, Value) {
+    let segments = path
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<&str>>();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["quests"]) => match fs_utils::local_quests() {
+            Ok(quests) => (200, json!({ "quests": quests })),
+            Err(e) => (500, json!({ "error": e.to_string() })),
+        },
+        ("GET", ["runs"]) => {
+            let runs = state.runs.lock().expect("runs lock is not poisoned");
+
+            let summaries = runs
+                .iter()
+                .map(|run| {
+                    json!({
+                        "id": run["id"],
+                        "quest": run["quest"],
+                        "prog": run["prog"],
+                        "status": run["status"],
+                    })
+                })
+                .collect::<Vec<Value>>();
+
+            (200, json!({ "runs": summaries }))
+        }
+        ("POST", ["runs"]) => start_run(body, state).await,
+        ("GET", ["runs", id]) => match find_run(&state, id) {
+            Some(run) => (200, run),
+            None => (404, json!({ "error": format!("'{}': no such run", id) })),
+        },
+        ("GET", ["runs", id, "diffs"]) => match find_run(&state, id) {
+            Some(run) => {
+                let diffs = run["cases"]
+                    .as_array()
+                    .map(|cases| {
+                        cases
+                            .iter()
+                            .filter(|case| case["verdict"] != "passed")
+                            .cloned()
+                            .collect::<Vec<Value>>()
+                    })
+                    .unwrap_or_default();
+
+                (
+                    200,
+                    json!({ "id": run["id"], "status": run["status"], "diffs": diffs }),
+                )
+            }
+            None => (404, json!({ "error": format!("'{}': no such run", id) })),
+        },
+        _ => (
+            404,
+            json!({ "error": format!("'{} {}': no such route", method, path) }),
+        ),
+    }
+}
+
+async fn start_run(body: &[u8], state: Arc<ServeState>) -> (u16, Value) {
+    let request = match serde_json::from_slice::<Value>(body) {
+        Ok(request) => request,
+        Err(e) => return (400, json!({ "error": e.to_string() })),
+    };
+
+    let (Some(quest_name), Some(prog_str)) = (request["quest"].as_str(), request["prog"].as_str())
+    else {
+        return (
+            400,
+            json!({ "error": "expected 'quest' and 'prog' strings" }),
+        );
+    };
+
+    let prog = match Path::new(prog_str).canonicalize() {
+        Ok(prog) => prog,
+        Err(e) => {
+            return (400, json!({ "error": format!("'{}': {}", prog_str, e) }));
+        }
+    };
+
+    let quest_path = match fs_utils::quest_path(quest_name) {
+        Ok(quest_path) => quest_path,
+        Err(e) => return (500, json!({ "error": e.to_string() })),
+    };
+
+    if !quest_path.exists()
+        && let Err(e) = super::fetch_quest(quest_name).await
+    {
+        return (404, json!({ "error": e.to_string() }));
+    }
+
+    match prog_allowed(&prog, &quest_path) {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                403,
+                json!({
+                    "error": format!(
+                        "'{}': programs must be in the quest or stash dir",
+                        prog_str
+                    )
+                }),
+            );
+        }
+        Err(e) => return (500, json!({ "error": e.to_string() })),
+    }
+
+    let id = {
+        let mut runs = state.runs.lock().expect("runs lock is not poisoned");
+        let id = runs.len();
+
+        runs.push(json!({
+            "id": id,
+            "quest": quest_name,
+            "prog": prog.to_string_lossy(),
+            "status": "running",
+        }));
+
+        id
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let result = {
+            let _run_guard = state.run_lock.lock().expect("run lock is not poisoned");
+            judge_run(&quest_path, &prog, &state.config)
+        };
+
+        let mut runs = state.runs.lock().expect("runs lock is not poisoned");
+        let run = &mut runs[id];
+
+        match result {
+            Ok(cases) => {
+                let passed = cases
+                    .iter()
+                    .filter(|case| case["verdict"] == "passed")
+                    .count();
+                let elapsed_ms = cases
+                    .iter()
+                    .filter_map(|case| case["elapsed_ms"].as_u64())
+                    .sum::<u64>();
+
+                run["status"] = json!("done");
+                run["passed"] = json!(passed);
+                run["failed"] = json!(cases.len() - passed);
+                run["elapsed_ms"] = json!(elapsed_ms);
+                run["cases"] = json!(cases);
+            }
+            Err(e) => {
+                run["status"] = json!("error");
+                run["error"] = json!(e.to_string());
+            }
+        }
+    });
+
+    (202, json!({ "id": id, "status": "running" }))
+}
+
+/// Only programs inside the quest's dir or the stash are run, so a request can't execute
+/// arbitrary files on this machine.
+fn prog_allowed(prog: &Path, quest_path: &Path) -> Result<bool> {
+    let stash_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], None)?;
+
+    Ok([quest_path, &stash_dir]
+        .iter()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .any(|dir| prog.starts_with(dir)))
+}
+
+fn find_run(state: &ServeState, id: &str) -> Option<Value> {
+    let id = id.parse::<usize>().ok()?;
+
+    state
+        .runs
+        .lock()
+        .expect("runs lock is not poisoned")
+        .get(id)
+        .cloned()
+}
+
+fn first_mismatch(expected: &[u8], actual: &[u8]) -> Option<usize> {
+    let mut expected_lines = expected.split(|&b| b == b'\n');
+    let mut actual_lines = actual.split(|&b| b == b'\n');
+
+    let mut line_no = 1;
+
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => return None,
+            (Some(e), Some(a)) if e.trim_ascii_end() == a.trim_ascii_end() => line_no += 1,
+            _ => return Some(line_no),
+        }
+    }
+}
+
+/// Judges `prog` the way `owlgo quest` does, without the live view.
+fn judge_run(quest_path: &Path, prog: &Path, config: &OwlConfig) -> Result<Vec<Value>> {
+    let encoding = QuestMeta::load(quest_path)?.encoding;
+
+    let hidden_cases = fs_utils::find_by_ext(quest_path, "in")?
+        .iter()
+        .filter(|test_case| fs_utils::is_hidden_case(test_case))
+        .filter_map(|test_case| fs_utils::case_stem(test_case).map(str::to_string))
+        .collect::<HashSet<String>>();
+
+    let config = OwlConfig {
+        show_top: false,
+        ..config.clone()
+    };
+    let summary = super::judge_quest(quest_path, prog, None, false, &config)?;
+
+    Ok(summary
+        .cases
+        .iter()
+        .map(|case| case_json(case, hidden_cases.contains(&case.name), encoding))
+        .collect())
+}
+
+/// A case's verdict, with the start of its diff read back from the artifacts the run saved.
+fn case_json(case: &CaseResult, hidden: bool, encoding: Option<&'static Encoding>) -> Value {
+    let mut case_json = json!({
+        "name": case.name,
+        "verdict": case.verdict,
+        "elapsed_ms": case
+            .elapsed
+            .or(case.usage.map(|usage| usage.wall))
+            .map(|elapsed| elapsed.as_millis() as u64),
+    });
+
+    if hidden {
+        case_json["hidden"] = json!(true);
+    } else if case.verdict == Verdict::Failed
+        && let Some(artifacts) = &case.artifacts
+        && let (Ok(ans), Ok(actual)) = (
+            fs::read(artifacts.join("expected.txt")),
+            fs::read(artifacts.join("actual.txt")),
+        )
+    {
+        case_json["first_mismatch_line"] = json!(first_mismatch(&ans, &actual));
+        case_json["expected"] = json!(judge_utils::preview_output(&ans, encoding));
+        case_json["actual"] = json!(judge_utils::preview_output(&actual, encoding));
+    }
+
+    case_json
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
@@ -0,0 +1,121 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{fs_utils, toml_utils};
+use crate::{CHAT_DIR, GIST_API_URL, MANIFEST, OWL_DIR, STASH_DIR};
+use reqwest::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
+use serde_json::{Value, json};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Uploads a file (a path, a stashed program, or a chat record) as a gist, secret unless
+/// `public`, and prints its URL.
+pub async fn share_file(file_str: &str, description: Option<&str>, public: bool) -> Result<()> {
+    let file_path = resolve_shared(file_str)?;
+    let file_name = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or(file_str.into());
+
+    let contents = fs::read_to_string(&file_path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read '{}'", file_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    if contents.trim().is_empty() {
+        return Err(OwlError::FileError(
+            format!("'{}': file is empty", file_path.to_string_lossy()),
+            "gists cannot be empty".into(),
+        ));
+    }
+
+    let body = json!({
+        "description": description.map(String::from).unwrap_or(format!("{} (shared with owlgo)", file_name)),
+        "public": public,
+        "files": { file_name.clone(): { "content": contents } },
+    });
+
+    let response_text = reqwest::Client::new()
+        .post(GIST_API_URL)
+        .header(ACCEPT, "application/vnd.github+json")
+        .header(AUTHORIZATION, format!("Bearer {}", github_token()?))
+        .header(CONTENT_TYPE, "application/json")
+        .header(USER_AGENT, "owlgo")
+        .body(body.to_string())
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|e| {
+            OwlError::NetworkError(format!("could not share '{}'", file_name), e.to_string())
+        })?
+        .text()
+        .await
+        .map_err(|e| {
+            OwlError::NetworkError(
+                format!("could not read response from '{}'", GIST_API_URL),
+                e.to_string(),
+            )
+        })?;
+
+    let gist_url = serde_json::from_str::<Value>(&response_text)
+        .ok()
+        .and_then(|gist| gist["html_url"].as_str().map(String::from))
+        .ok_or(OwlError::NetworkError(
+            format!("unexpected response from '{}'", GIST_API_URL),
+            response_text,
+        ))?;
+
+    println!("{}", gist_url);
+
+    Ok(())
+}
+
+/// The token from the manifest, then `GITHUB_TOKEN`.
+fn github_token() -> Result<String> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+
+    let token = match manifest_path.exists() {
+        true => toml_utils::get_manifest_github_token(&manifest_path)?,
+        false => None,
+    };
+
+    token
+        .or_else(|| {
+            env::var("GITHUB_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty())
+        })
+        .ok_or(OwlError::TomlError(
+            "no GitHub token to create gists with".into(),
+            "run 'owlgo config set github_token <TOKEN>' (needs the 'gist' scope)".into(),
+        ))
+}
+
+fn resolve_shared(file_str: &str) -> Result<PathBuf> {
+    let path = Path::new(file_str);
+
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+
+    let stashed = fs_utils::stash_path(file_str)?;
+
+    if stashed.is_file() {
+        return Ok(stashed);
+    }
+
+    let chat_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, CHAT_DIR], None)?;
+    let chat_name = fs_utils::check_name(file_str, "chat")?;
+
+    [
+        chat_dir.join(chat_name),
+        chat_dir.join(format!("{}.md", chat_name)),
+    ]
+    .into_iter()
+    .find(|chat_path| chat_path.is_file())
+    .ok_or(OwlError::FileError(
+        format!("'{}': no such file", file_str),
+        "expected a path, a stashed program, or a chat record".into(),
+    ))
+}
@@ -1,8 +1,8 @@
-use crate::OWL_DIR;
 use crate::common::{OwlError, Result};
-use crate::owl_utils::{FileApp, FileExplorerApp, cmd_utils, fs_utils, tui_utils};
+use crate::owl_utils::tui::QuestCase;
+use crate::owl_utils::{FileApp, FileExplorerApp, OwlConfig, cmd_utils, fs_utils, tui_utils};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub fn show_and_glow(target_path: &Path) -> Result<()> {
     cmd_utils::bat_file(target_path).or_else(|_| {
@@ -19,7 +19,23 @@ pub fn show_and_glow(target_path: &Path) -> Result<()> {
     })
 }
 
-pub fn show_it(target_path: &Path) -> Result<()> {
+pub fn show_it(target_path: &Path, show_full: bool) -> Result<()> {
+    if !show_full
+        && let Some(preview) =
+            fs_utils::read_preview(target_path, "use --full to show the whole file")?
+    {
+        println!("{}", preview);
+
+        return Ok(());
+    }
+
+    if fs_utils::is_gzipped(target_path) {
+        let contents = fs_utils::read_case(target_path)?;
+        println!("{}", String::from_utf8_lossy(&contents));
+
+        return Ok(());
+    }
+
     cmd_utils::bat_file(target_path).or_else(|_| {
         fs::read_to_string(target_path)
             .map(|contents| println!("{}", contents))
@@ -36,17 +52,29 @@ pub async fn show_quest(
     quest_name: &str,
     case_id: Option<usize>,
     show_ans: bool,
+    show_full: bool,
     use_tui: bool,
+    config: &OwlConfig,
 ) -> Result<()> {
-    let quest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(quest_name))?;
+    let quest_path = fs_utils::quest_path(quest_name)?;
 
     if !quest_path.exists() {
         super::fetch_quest(quest_name).await?;
     }
 
     if use_tui && case_id.is_none() {
+        let statement = super::fetch_subcommand::read_statement(&quest_path);
+        let cases = match statement {
+            Some(_) => quest_cases(&quest_path),
+            None => Vec::new(),
+        };
+
         return tui_utils::enter_raw_mode().and_then(|_| {
-            match FileExplorerApp::default().run(&quest_path) {
+            match FileExplorerApp::new(config.keymap.clone(), config.theme.clone())
+                .with_full(show_full)
+                .with_statement(statement, cases)
+                .run(&quest_path)
+            {
                 Ok(_) => tui_utils::exit_raw_mode(),
                 Err(e) => tui_utils::exit_raw_mode().and(Err(e)),
             }
@@ -59,33 +87,79 @@ pub async fn show_quest(
         fs_utils::find_by_ext(&quest_path, "in")?
     };
 
+    let num_cases = test_cases.len();
+    let test_cases = test_cases
+        .into_iter()
+        .filter(|test_case| !fs_utils::is_hidden_case(test_case))
+        .collect::<Vec<PathBuf>>();
+
+    if test_cases.is_empty() {
+        return Err(OwlError::FileError(
+            format!("'{}': every case is hidden", quest_name),
+            "hidden cases are judged but never shown".into(),
+        ));
+    }
+
+    if test_cases.len() < num_cases {
+        eprintln!("({} hidden cases not shown)", num_cases - test_cases.len());
+    }
+
     if let Some(case_number) = case_id {
         let test_case = &test_cases[(case_number - 1) % test_cases.len()];
 
+        if let Some(case_stem) = fs_utils::case_stem(test_case) {
+            eprintln!("case: {}", case_stem);
+        }
+
         if use_tui {
-            tui_utils::enter_raw_mode().and_then(|_| match FileApp::default().run(test_case) {
-                Ok(_) => tui_utils::exit_raw_mode(),
-                Err(e) => tui_utils::exit_raw_mode().and(Err(e)),
+            tui_utils::enter_raw_mode().and_then(|_| {
+                match FileApp::new(config.keymap.clone(), config.theme.clone())
+                    .with_full(show_full)
+                    .run(test_case)
+                {
+                    Ok(_) => tui_utils::exit_raw_mode(),
+                    Err(e) => tui_utils::exit_raw_mode().and(Err(e)),
+                }
             })
         } else {
-            show_it(test_case)
+            show_it(test_case, show_full)
         }
     } else {
         for test_case in test_cases {
-            show_it(&test_case)?;
+            show_it(&test_case, show_full)?;
         }
 
         Ok(())
     }
 }
 
+/// The cases shown beside the statement in the TUI's split view; hidden ones are left out.
+fn quest_cases(quest_path: &Path) -> Vec<QuestCase> {
+    let mut test_cases = fs_utils::find_by_ext(quest_path, "in").unwrap_or_default();
+    test_cases.sort();
+
+    test_cases
+        .into_iter()
+        .filter(|in_file| !fs_utils::is_hidden_case(in_file))
+        .map(|in_file| QuestCase {
+            name: fs_utils::case_stem(&in_file)
+                .unwrap_or_default()
+                .to_string(),
+            answer: super::find_answer(&in_file).ok(),
+            input: in_file,
+        })
+        .collect()
+}
+
 pub async fn show_test(
     quest_name: &str,
     test_name: &str,
     show_ans: bool,
+    show_full: bool,
     use_tui: bool,
+    config: &OwlConfig,
 ) -> Result<()> {
-    let quest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(quest_name))?;
+    let quest_path = fs_utils::quest_path(quest_name)?;
 
     if !quest_path.exists() {
         super::fetch_quest(quest_name).await?;
@@ -97,12 +171,24 @@ pub async fn show_test(
         fs_utils::find_by_stem_and_ext(&quest_path, test_name, "in")?
     };
 
+    if fs_utils::is_hidden_case(&test_case) {
+        return Err(OwlError::FileError(
+            format!("'{}': is a hidden case", test_name),
+            "hidden cases are judged but never shown".into(),
+        ));
+    }
+
     if use_tui {
-        tui_utils::enter_raw_mode().and_then(|_| match FileApp::default().run(&test_case) {
-            Ok(_) => tui_utils::exit_raw_mode(),
-            Err(e) => tui_utils::exit_raw_mode().and(Err(e)),
+        tui_utils::enter_raw_mode().and_then(|_| {
+            match FileApp::new(config.keymap.clone(), config.theme.clone())
+                .with_full(show_full)
+                .run(&test_case)
+            {
+                Ok(_) => tui_utils::exit_raw_mode(),
+                Err(e) => tui_utils::exit_raw_mode().and(Err(e)),
+            }
         })
     } else {
-        show_it(&test_case)
+        show_it(&test_case, show_full)
     }
 }
@@ -1,10 +1,32 @@
-use crate::common::{OwlError, Result};
-use crate::owl_utils::fs_utils;
-use crate::{OWL_DIR, PROMPT_DIR, STASH_DIR, TEMPLATE_STEM};
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::fmt_utils::{self, Formatters};
+use crate::owl_utils::{fs_utils, prog_utils};
+use crate::{HISTORY_DIR, OWL_DIR, STASH_DIR, TEMPLATE_STEM};
+use chrono::Local;
+use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
 use std::ffi::OsStr;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-pub fn stash_file(prog: &Path, as_templ: bool, as_prompt: bool) -> Result<()> {
+/// What `stash` does when a different file is already stashed under the same name.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StashConflict {
+    Refuse,
+    Overwrite,
+    Version,
+}
+
+/// Stashes a program, template, or prompt in `~/.owlgo/.stash`, formatting programs and
+/// templates with `formatters` (if given) so the stash is diffable across machines, and returns
+/// where it was stashed.
+pub fn stash_file(
+    prog: &Path,
+    as_templ: bool,
+    as_prompt: bool,
+    on_conflict: StashConflict,
+    formatters: Option<&Formatters>,
+) -> Result<PathBuf> {
     let prog_file_name = prog
         .file_name()
         .and_then(OsStr::to_str)
@@ -13,29 +35,143 @@ pub fn stash_file(prog: &Path, as_templ: bool, as_prompt: bool) -> Result<()> {
             "".into(),
         ))?;
 
-    if as_prompt {
-        let stash_path = fs_utils::ensure_path_from_home(
-            &[OWL_DIR, STASH_DIR, PROMPT_DIR],
-            Some(prog_file_name),
-        )?;
+    let stash_path = if as_prompt {
+        fs_utils::prompt_path(prog_file_name)?
+    } else if as_templ {
+        let prog_ext = prog
+            .extension()
+            .and_then(OsStr::to_str)
+            .ok_or(OwlError::UriError(
+                format!("'{}': has no file extension", prog.to_string_lossy()),
+                "".into(),
+            ))?;
+        let stash_file = format!("{}.{}", TEMPLATE_STEM, prog_ext);
 
-        fs_utils::copy_file(prog, &stash_path)
+        fs_utils::ensure_path_from_home(
+            &[OWL_DIR, STASH_DIR],
+            Some(fs_utils::check_name(&stash_file, "template")?),
+        )?
     } else {
-        let stash_path = if as_templ {
-            let prog_ext = prog
-                .extension()
-                .and_then(OsStr::to_str)
-                .ok_or(OwlError::UriError(
-                    format!("'{}': has no file extension", prog.to_string_lossy()),
-                    "".into(),
-                ))?;
-            let stash_file = format!("{}.{}", TEMPLATE_STEM, prog_ext);
-
-            fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], Some(&stash_file))?
-        } else {
-            fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], Some(prog_file_name))?
-        };
-
-        fs_utils::copy_file(prog, &stash_path)
+        fs_utils::stash_path(prog_file_name)?
+    };
+
+    let read = |path: &Path| {
+        fs::read(path).map_err(|e| {
+            OwlError::FileError(
+                format!("could not read '{}'", path.to_string_lossy()),
+                e.to_string(),
+            )
+        })
+    };
+
+    let contents = match (
+        formatters.filter(|_| !as_prompt),
+        prog_utils::check_prog_lang(prog),
+    ) {
+        (Some(formatters), Some(lang)) => {
+            match fmt_utils::formatted_contents(prog, lang.name(), formatters) {
+                Ok(contents) => contents,
+                Err(e) => {
+                    warn(OwlWarning::Skipped(format!(
+                        "'{}': stashing it unformatted ({})",
+                        prog_file_name, e
+                    )));
+                    read(prog)?
+                }
+            }
+        }
+        _ => read(prog)?,
+    };
+
+    if stash_path.is_file() {
+        let stashed = read(&stash_path)?;
+
+        if Sha256::digest(&stashed) == Sha256::digest(&contents) {
+            println!("'{}': already stashed, nothing to do", prog_file_name);
+            return Ok(stash_path);
+        }
+
+        match on_conflict {
+            StashConflict::Refuse => {
+                return Err(OwlError::FileError(
+                    format!(
+                        "'{}': differs from the stashed copy ({})",
+                        prog_file_name,
+                        diff_summary(&stashed, &contents)
+                    ),
+                    "use '--force' to overwrite it or '--history' to keep the old copy".into(),
+                ));
+            }
+            StashConflict::Version => {
+                let history_path = version_stashed(&stash_path)?;
+
+                println!(
+                    "'{}': moved the stashed copy to '{}'",
+                    prog_file_name,
+                    history_path.to_string_lossy()
+                );
+            }
+            StashConflict::Overwrite => {}
+        }
+    }
+
+    fs::write(&stash_path, contents).map_err(|e| {
+        OwlError::FileError(
+            format!("could not write '{}'", stash_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    Ok(stash_path)
+}
+
+/// Moves a stashed file into the stash history under a timestamped name.
+fn version_stashed(stash_path: &Path) -> Result<PathBuf> {
+    let history_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, HISTORY_DIR], None)?;
+
+    fs::create_dir_all(&history_dir).map_err(|e| {
+        OwlError::FileError(
+            format!("could not create dir '{}'", history_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let stem = stash_path
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default();
+    let timestamp = Local::now().format("%Y-%m-%d-%H-%M-%S");
+
+    let history_path = history_dir.join(match stash_path.extension().and_then(OsStr::to_str) {
+        Some(ext) => format!("{}_{}.{}", stem, timestamp, ext),
+        None => format!("{}_{}", stem, timestamp),
+    });
+
+    fs_utils::copy_file(stash_path, &history_path)?;
+
+    Ok(history_path)
+}
+
+fn diff_summary(stashed: &[u8], contents: &[u8]) -> String {
+    let (Ok(stashed), Ok(contents)) = (std::str::from_utf8(stashed), std::str::from_utf8(contents))
+    else {
+        return format!(
+            "{} bytes stashed, {} bytes new",
+            stashed.len(),
+            contents.len()
+        );
+    };
+
+    let diff = TextDiff::from_lines(stashed, contents);
+    let (mut added, mut removed) = (0, 0);
+
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => added += 1,
+            ChangeTag::Delete => removed += 1,
+            ChangeTag::Equal => {}
+        }
     }
+
+    format!("{} lines added, {} removed", added, removed)
 }
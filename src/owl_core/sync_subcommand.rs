@@ -0,0 +1,230 @@
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::{cloud_utils::CloudRemote, fs_utils, toml_utils};
+use crate::{GIT_DIR, MANIFEST, OWL_DIR, STASH_DIR};
+use chrono::Local;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path};
+use toml_edit::{DocumentMut, Table, value};
+
+const SYNC_INDEX: &str = ".owlgo_sync.toml";
+
+/// Mirrors the stash (with its prompts and chats) to S3 or WebDAV, uploading only files whose
+/// contents changed since the remote's index was written.
+pub async fn sync_stash(remote_url: &str) -> Result<()> {
+    let (remote, stash_dir) = open_remote(remote_url)?;
+
+    let mut index = read_index(&remote).await?;
+    let local = local_hashes(&stash_dir)?;
+
+    let changed = local
+        .iter()
+        .filter(|(rel_path, hash)| index.get(*rel_path) != Some(*hash))
+        .collect::<Vec<(&String, &String)>>();
+
+    let dirs = changed
+        .iter()
+        .filter_map(|(rel_path, _)| rel_path.rsplit_once('/').map(|(dir, _)| dir))
+        .collect::<BTreeSet<&str>>();
+
+    for dir in dirs {
+        remote.ensure_dir(dir).await?;
+    }
+
+    for (rel_path, hash) in changed.iter() {
+        let contents = fs::read(stash_dir.join(rel_path)).map_err(|e| {
+            OwlError::FileError(format!("could not read '{}'", rel_path), e.to_string())
+        })?;
+
+        remote.put(rel_path, contents).await?;
+        index.insert(rel_path.to_string(), hash.to_string());
+
+        println!("uploaded {}", rel_path);
+    }
+
+    write_index(&remote, &index).await?;
+
+    println!(
+        "synced {} file(s) to '{}' ({} unchanged)",
+        changed.len(),
+        remote_url,
+        local.len() - changed.len()
+    );
+
+    Ok(())
+}
+
+/// Downloads files from a stash mirror, keeping local edits unless `use_force`.
+pub async fn restore_stash(remote_url: &str, use_force: bool) -> Result<()> {
+    let (remote, stash_dir) = open_remote(remote_url)?;
+
+    let index = read_index(&remote).await?;
+
+    if index.is_empty() {
+        return Err(OwlError::NetworkError(
+            format!("'{}': nothing to restore", remote_url),
+            "run 'owlgo sync <REMOTE>' to back up the stash first".into(),
+        ));
+    }
+
+    let local = local_hashes(&stash_dir)?;
+    let mut restored = 0;
+
+    for (rel_path, hash) in index.iter() {
+        let is_safe = Path::new(rel_path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+
+        if !is_safe {
+            warn(OwlWarning::Skipped(format!(
+                "'{}': unsafe path in the remote index",
+                rel_path
+            )));
+            continue;
+        }
+
+        match local.get(rel_path) {
+            Some(local_hash) if local_hash == hash => continue,
+            Some(_) if !use_force => {
+                warn(OwlWarning::Skipped(format!(
+                    "'{}': has local changes (use '--force' to overwrite them)",
+                    rel_path
+                )));
+                continue;
+            }
+            _ => {}
+        }
+
+        let Some(contents) = remote.get(rel_path).await? else {
+            warn(OwlWarning::Skipped(format!(
+                "'{}': missing from the remote",
+                rel_path
+            )));
+            continue;
+        };
+
+        if format!("{:x}", Sha256::digest(&contents)) != *hash {
+            warn(OwlWarning::Recovered(format!(
+                "'{}': changed on the remote since it was indexed",
+                rel_path
+            )));
+        }
+
+        let local_path = stash_dir.join(rel_path);
+
+        if let Some(parent) = local_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                OwlError::FileError(
+                    format!("could not create dir '{}'", parent.to_string_lossy()),
+                    e.to_string(),
+                )
+            })?;
+        }
+
+        fs::write(&local_path, contents).map_err(|e| {
+            OwlError::FileError(
+                format!("could not write '{}'", local_path.to_string_lossy()),
+                e.to_string(),
+            )
+        })?;
+
+        restored += 1;
+        println!("restored {}", rel_path);
+    }
+
+    println!("restored {} file(s) from '{}'", restored, remote_url);
+
+    Ok(())
+}
+
+fn open_remote(remote_url: &str) -> Result<(CloudRemote, std::path::PathBuf)> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+
+    let manifest_doc = match manifest_path.exists() {
+        true => toml_utils::read_toml(&manifest_path)?,
+        false => DocumentMut::new(),
+    };
+
+    let remote = CloudRemote::from_url(remote_url, &manifest_doc)?;
+    let stash_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], None)?;
+
+    Ok((remote, stash_dir))
+}
+
+/// The sha256 of every stashed file (git metadata aside), keyed by its `/`-separated path.
+fn local_hashes(stash_dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut hashes = BTreeMap::new();
+
+    for file in fs_utils::dir_tree(stash_dir)? {
+        let Ok(rel_path) = file.strip_prefix(stash_dir) else {
+            continue;
+        };
+
+        if rel_path
+            .components()
+            .any(|component| component.as_os_str() == GIT_DIR)
+        {
+            continue;
+        }
+
+        let contents = fs::read(&file).map_err(|e| {
+            OwlError::FileError(
+                format!("could not read '{}'", file.to_string_lossy()),
+                e.to_string(),
+            )
+        })?;
+
+        let rel_str = rel_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        hashes.insert(rel_str, format!("{:x}", Sha256::digest(&contents)));
+    }
+
+    Ok(hashes)
+}
+
+async fn read_index(remote: &CloudRemote) -> Result<BTreeMap<String, String>> {
+    let Some(contents) = remote.get(SYNC_INDEX).await? else {
+        return Ok(BTreeMap::new());
+    };
+
+    let index_doc = String::from_utf8_lossy(&contents)
+        .parse::<DocumentMut>()
+        .map_err(|e| {
+            OwlError::TomlError(format!("invalid remote '{}'", SYNC_INDEX), e.to_string())
+        })?;
+
+    Ok(index_doc
+        .get("files")
+        .and_then(|files| files.as_table_like())
+        .map(|files| {
+            files
+                .iter()
+                .filter_map(|(rel_path, hash)| {
+                    hash.as_str()
+                        .map(|hash| (rel_path.to_string(), hash.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+async fn write_index(remote: &CloudRemote, index: &BTreeMap<String, String>) -> Result<()> {
+    let mut files = Table::new();
+
+    for (rel_path, hash) in index {
+        files[rel_path.as_str()] = value(hash.as_str());
+    }
+
+    let mut index_doc = DocumentMut::new();
+    index_doc["updated"] = value(Local::now().to_rfc3339());
+    index_doc["files"] = files.into();
+
+    remote
+        .put(SYNC_INDEX, index_doc.to_string().into_bytes())
+        .await
+}
@@ -1,31 +1,50 @@
 use crate::common::{OwlError, Result};
-use crate::owl_utils::{cmd_utils, prog_utils};
-use std::fs;
-use std::path::Path;
+use crate::owl_utils::cmd::judge_utils::{LimitExceeded, OutputFormat};
+use crate::owl_utils::{
+    OwlConfig, cmd_utils, fs_utils, hook_utils, judge_utils, prog_utils, signal_utils, term_utils,
+    vscode_utils,
+};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 macro_rules! report_test_failed {
-    ($test_case:expr, $expected:expr, $actual:expr) => {
+    ($test_case:expr, $expected:expr, $actual:expr, $encoding:expr) => {{
+        let expected = judge_utils::preview_output($expected, $encoding);
+        let actual = judge_utils::preview_output($actual, $encoding);
+
         eprintln!(
-            concat!(
-                "\x1b[31m{}\x1b[0m: {}\n\n",
-                "\x1b[1;33m{}\x1b[0m\n\n{}\n",
-                "\x1b[1;35m{}\x1b[0m\n\n{}\n",
+            "{}\n",
+            term_utils::fit_text(&format!(
+                "\x1b[31m{}\x1b[0m: {}",
+                "[test failure]",
+                $test_case
+                    .to_str()
+                    .map(String::from)
+                    .unwrap_or($test_case.to_string_lossy().to_string())
+            ))
+        );
+
+        match term_utils::side_by_side(">>> expected <<<", &expected, ">>> actual <<<", &actual) {
+            Some(view) => eprintln!("{}", view),
+            None => eprintln!(
+                concat!("\x1b[1;33m{}\x1b[0m\n\n{}\n", "\x1b[1;35m{}\x1b[0m\n\n{}\n",),
+                ">>> expected <<<",
+                term_utils::fit_text(&expected),
+                ">>> actual <<<",
+                term_utils::fit_text(&actual)
             ),
-            "[test failure]",
-            $test_case
-                .to_str()
-                .map(String::from)
-                .unwrap_or($test_case.to_string_lossy().to_string()),
-            ">>> expected <<<",
-            $expected,
-            ">>> actual <<<",
-            $actual
-        )
-    };
+        }
+    }};
 }
 
-pub fn test_it(target: &Path, in_file: &Path, ans_file: &Path) -> Result<Duration> {
+/// Runs a built target on a single input and compares its output to the answer.
+pub fn run_case(
+    prog: &Path,
+    target: &Path,
+    in_file: &Path,
+    config: &OwlConfig,
+) -> Result<(Vec<u8>, Duration)> {
     if !target.exists() {
         return Err(OwlError::FileError(
             format!("'{}': no such file", target.to_string_lossy()),
@@ -38,85 +57,295 @@ pub fn test_it(target: &Path, in_file: &Path, ans_file: &Path) -> Result<Duratio
             "".into(),
         ));
     }
-    if !ans_file.exists() {
-        return Err(OwlError::FileError(
-            format!("'{}': no such file", ans_file.to_string_lossy()),
-            "".into(),
-        ));
-    }
 
-    let stdin = fs::read_to_string(in_file).map_err(|e| {
-        OwlError::FileError(
-            format!("could not read from '{}'", in_file.to_string_lossy()),
-            e.to_string(),
-        )
-    })?;
-    let ans = fs::read_to_string(ans_file).map_err(|e| {
-        OwlError::FileError(
-            format!("could not read from '{}'", ans_file.to_string_lossy()),
-            e.to_string(),
-        )
-    })?;
-
-    match prog_utils::check_prog_lang(target) {
+    let stdin = fs_utils::read_case(in_file)?;
+    let case_args = fs_utils::read_case_args(in_file)?;
+
+    let run_result = match prog_utils::resolve_prog_lang(prog, config) {
         Some(lang) => {
-            if !lang.command_exists() {
+            if !lang.command_exists(config) {
                 return Err(OwlError::CommandNotFound(format!(
                     "'{}': command not found",
                     lang.name()
                 )));
             }
 
-            let run_result = lang.run_with_stdin(target, &stdin);
+            cmd_utils::set_case_args(case_args);
+            lang.run_with_stdin(target, &stdin, config)
+        }
+        None => {
+            cmd_utils::set_case_args(case_args);
+            prog_utils::run_binary(target, Some(&stdin), config)
+        }
+    };
 
-            run_result.and_then(|(actual, elapsed)| {
-                if actual == ans {
-                    Ok(elapsed)
-                } else {
-                    report_test_failed!(in_file, ans, actual);
-                    Err(OwlError::TestFailure("failed test".into()))
-                }
-            })
+    cmd_utils::set_case_args(Vec::new());
+
+    run_result.inspect_err(|e| {
+        if !signal_utils::is_interrupted() && !matches!(e, OwlError::TimedOut(_)) {
+            prog_utils::report_failure("run", prog, e, config);
         }
-        None => cmd_utils::run_binary_with_stdin(target, &stdin).and_then(|(actual, elapsed)| {
-            if actual == ans {
-                Ok(elapsed)
-            } else {
-                report_test_failed!(in_file, ans, actual);
-                Err(OwlError::TestFailure("failed test".into()))
-            }
-        }),
+    })
+}
+
+/// How a program's output compared against a case's answers.
+pub enum CaseCheck {
+    Passed(Duration, PathBuf),
+    Failed(Duration, Vec<u8>, Vec<u8>),
+    Exceeded(Duration, LimitExceeded),
+}
+
+/// Runs a case without printing, passing when the output matches any of `ans_files`.
+pub fn check_case(
+    prog: &Path,
+    target: &Path,
+    in_file: &Path,
+    ans_files: &[PathBuf],
+    config: &OwlConfig,
+) -> Result<CaseCheck> {
+    if let Some(ans_file) = ans_files.iter().find(|ans_file| !ans_file.exists()) {
+        return Err(OwlError::FileError(
+            format!("'{}': no such file", ans_file.to_string_lossy()),
+            "".into(),
+        ));
+    }
+
+    let answers = ans_files
+        .iter()
+        .map(|ans_file| fs_utils::read_case(ans_file))
+        .collect::<Result<Vec<Vec<u8>>>>()?;
+
+    let run_result = run_case(prog, target, in_file, config);
+
+    // a run killed at its deadline went over the time limit rather than failing
+    if let (Err(OwlError::TimedOut(_)), Some(limit)) = (&run_result, config.time_limit) {
+        let used = cmd_utils::last_usage().map_or(limit, |usage| usage.wall);
+
+        return Ok(CaseCheck::Exceeded(
+            used,
+            LimitExceeded::Time { used, limit },
+        ));
+    }
+
+    let (actual, elapsed) = run_result?;
+
+    if let Some(exceeded) = judge_utils::check_limits(
+        elapsed,
+        cmd_utils::last_usage(),
+        config.time_limit,
+        config.memory_limit,
+    ) {
+        return Ok(CaseCheck::Exceeded(elapsed, exceeded));
+    }
+
+    let matched = answers
+        .iter()
+        .position(|ans| judge_utils::outputs_match(ans, &actual, config.compare, &config.filters));
+
+    match matched {
+        Some(index) => Ok(CaseCheck::Passed(elapsed, ans_files[index].clone())),
+        None => Ok(CaseCheck::Failed(
+            elapsed,
+            answers.into_iter().next().unwrap_or_default(),
+            actual,
+        )),
     }
 }
 
-pub fn test_program(prog: &Path, in_file: &Path, ans_file: &Path) -> Result<()> {
-    let test_result = match prog_utils::check_prog_lang(prog) {
+/// Passes when the output matches any of `ans_files`, returning the answer that matched.
+pub fn test_it(
+    prog: &Path,
+    target: &Path,
+    in_file: &Path,
+    ans_files: &[PathBuf],
+    config: &OwlConfig,
+) -> Result<(Duration, PathBuf)> {
+    match check_case(prog, target, in_file, ans_files, config)? {
+        CaseCheck::Passed(elapsed, matched) => Ok((elapsed, matched)),
+        CaseCheck::Failed(elapsed, ans, actual) => {
+            report_failed_case(in_file, elapsed, &ans, &actual, config);
+            Err(OwlError::TestFailure("failed test".into()))
+        }
+        CaseCheck::Exceeded(elapsed, exceeded) => {
+            report_exceeded_case(in_file, elapsed, exceeded, config);
+            Err(OwlError::TestFailure(exceeded.to_string()))
+        }
+    }
+}
+
+/// Only VS Code needs telling here; elsewhere the limit is shown as the case's status.
+pub fn report_exceeded_case(
+    in_file: &Path,
+    elapsed: Duration,
+    exceeded: LimitExceeded,
+    config: &OwlConfig,
+) {
+    if config.format == OutputFormat::Vscode {
+        println!(
+            "{}",
+            vscode_utils::test_event(
+                in_file,
+                "failed",
+                Some(elapsed),
+                Some(&exceeded.to_string()),
+                None,
+                None
+            )
+        );
+    }
+}
+
+pub fn report_failed_case(
+    in_file: &Path,
+    elapsed: Duration,
+    ans: &[u8],
+    actual: &[u8],
+    config: &OwlConfig,
+) {
+    let is_hidden = fs_utils::is_hidden_case(in_file);
+
+    if config.format == OutputFormat::Vscode && is_hidden {
+        println!(
+            "{}",
+            vscode_utils::test_event(
+                in_file,
+                "failed",
+                Some(elapsed),
+                Some("hidden case"),
+                None,
+                None
+            )
+        );
+    } else if config.format == OutputFormat::Vscode {
+        println!(
+            "{}",
+            vscode_utils::test_event(
+                in_file,
+                "failed",
+                Some(elapsed),
+                None,
+                Some(&judge_utils::preview_output(ans, config.encoding)),
+                Some(&judge_utils::preview_output(actual, config.encoding))
+            )
+        );
+    } else if is_hidden {
+        eprintln!(
+            "{}\n",
+            term_utils::fit_text(&format!(
+                "\x1b[31m[test failure]\x1b[0m: {} (hidden case, output not shown)",
+                in_file.to_string_lossy()
+            ))
+        );
+    } else {
+        report_test_failed!(in_file, ans, actual, config.encoding);
+    }
+}
+
+/// Judges `prog` on one input, or without `ans_file` just runs it and prints its output.
+pub fn test_program(
+    prog: &Path,
+    in_file: &Path,
+    ans_file: Option<&Path>,
+    config: &OwlConfig,
+) -> Result<()> {
+    let Some(ans_file) = ans_file else {
+        return run_input(prog, in_file, config);
+    };
+
+    let ans_files = [ans_file.to_path_buf()];
+
+    let test_result = match prog_utils::resolve_prog_lang(prog, config) {
         Some(_) => {
-            let (target, build_files) = match prog_utils::build_program(prog)? {
+            hook_utils::pre_build(&config.hooks, config.quest.as_deref(), prog)?;
+
+            let (target, build_files) = match prog_utils::build_program(prog, config)? {
                 Some(bl) => (bl.target, bl.build_files),
                 None => (prog.to_path_buf(), None),
             };
 
-            let test_result = test_it(&target, in_file, ans_file);
+            let test_result = test_it(prog, &target, in_file, &ans_files, config);
 
             prog_utils::cleanup_program(prog, &target, build_files)?;
 
             test_result
         }
-        None => test_it(prog, in_file, ans_file),
+        None => test_it(prog, prog, in_file, &ans_files, config),
     };
 
-    match test_result {
-        Ok(elapsed) => {
+    let verdict = match test_result {
+        Ok((elapsed, _)) if config.format == OutputFormat::Vscode => {
+            println!(
+                "{}",
+                vscode_utils::test_event(in_file, "passed", Some(elapsed), None, None, None)
+            );
+            hook_utils::VERDICT_ACCEPTED
+        }
+        Err(e) if config.format == OutputFormat::Vscode => {
+            if !matches!(e, OwlError::TestFailure(_)) {
+                println!(
+                    "{}",
+                    vscode_utils::test_event(
+                        in_file,
+                        "errored",
+                        None,
+                        Some(&e.to_string()),
+                        None,
+                        None
+                    )
+                );
+            }
+            hook_utils::VERDICT_FAILED
+        }
+        Ok((elapsed, _)) => {
             println!(
                 "[{}ms] \x1b[32mpassed test\x1b[0m 🎉\n",
                 elapsed.as_millis()
             );
-            Ok(())
+            hook_utils::VERDICT_ACCEPTED
         }
         Err(e) => {
             eprintln!("\x1b[31m{}\x1b[0m 😭\n", e);
-            Ok(())
+            hook_utils::VERDICT_FAILED
         }
+    };
+
+    hook_utils::post_test(&config.hooks, config.quest.as_deref(), prog, verdict);
+
+    Ok(())
+}
+
+fn run_input(prog: &Path, in_file: &Path, config: &OwlConfig) -> Result<()> {
+    let (target, build_files) = match prog_utils::resolve_prog_lang(prog, config) {
+        Some(_) => {
+            hook_utils::pre_build(&config.hooks, config.quest.as_deref(), prog)?;
+
+            match prog_utils::build_program(prog, config)? {
+                Some(bl) => (bl.target, bl.build_files),
+                None => (prog.to_path_buf(), None),
+            }
+        }
+        None => (prog.to_path_buf(), None),
+    };
+
+    let run_result = run_case(prog, &target, in_file, config);
+
+    prog_utils::cleanup_program(prog, &target, build_files)?;
+
+    let (stdout, elapsed) = run_result?;
+
+    if fs_utils::is_hidden_case(in_file) {
+        eprintln!("(hidden case, output not shown)");
+    } else {
+        io::stdout()
+            .write_all(&stdout)
+            .map_err(|e| OwlError::FileError("could not write to stdout".into(), e.to_string()))?;
     }
+
+    eprintln!(
+        "\n[{}ms] ran '{}' (no answer given, so the output is not judged)",
+        elapsed.as_millis(),
+        in_file.to_string_lossy()
+    );
+
+    Ok(())
 }
@@ -0,0 +1,152 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{fs_utils, llm_utils};
+use crate::{CHAT_DIR, OWL_DIR, STASH_DIR, TODOS_SUFFIX};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Writes the review's suggestions as a checklist next to the chat record.
+pub fn write_todos(chat_path: &Path, response: &str) -> Result<Option<(PathBuf, usize)>> {
+    let todos = llm_utils::extract_todos(response);
+
+    if todos.is_empty() {
+        return Ok(None);
+    }
+
+    let chat_name = chat_path
+        .file_name()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default();
+    let todos_path = todos_path(chat_path);
+
+    let mut todos_str = format!("# TODOs from {}\n\n", chat_name);
+
+    for todo in todos.iter() {
+        todos_str.push_str(&format!("- [ ] {}\n", todo));
+    }
+
+    fs::write(&todos_path, todos_str).map_err(|e| {
+        OwlError::FileError(
+            format!("could not write '{}'", todos_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    Ok(Some((todos_path, todos.len())))
+}
+
+/// Shows the checklist for a chat (the most recent one by default), checking off or reopening
+/// items by their number first.
+pub fn show_todos(chat_name: Option<&str>, done: &[usize], undo: &[usize]) -> Result<()> {
+    let chat_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, CHAT_DIR], None)?;
+
+    let todos_path = match chat_name {
+        Some(chat_name) => {
+            let chat_stem = chat_name
+                .strip_suffix(TODOS_SUFFIX)
+                .or_else(|| chat_name.strip_suffix(".md"))
+                .unwrap_or(chat_name);
+
+            todos_path(&chat_dir.join(format!("{}.md", fs_utils::check_name(chat_stem, "chat")?)))
+        }
+        None => latest_todos(&chat_dir)?,
+    };
+
+    if !todos_path.is_file() {
+        return Err(OwlError::FileError(
+            format!("'{}': no todos found", todos_path.to_string_lossy()),
+            "todos are written after each 'owlgo review'".into(),
+        ));
+    }
+
+    let todos_str = fs::read_to_string(&todos_path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read '{}'", todos_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let mut lines = todos_str.lines().map(String::from).collect::<Vec<String>>();
+    let item_lines = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.starts_with("- [ ] ") || line.starts_with("- [x] "))
+        .map(|(i, _)| i)
+        .collect::<Vec<usize>>();
+
+    for (&n, mark) in done
+        .iter()
+        .map(|n| (n, "x"))
+        .chain(undo.iter().map(|n| (n, " ")))
+    {
+        let line_index =
+            n.checked_sub(1)
+                .and_then(|i| item_lines.get(i))
+                .ok_or(OwlError::Unsupported(format!(
+                    "'{}': no such todo (expected 1-{})",
+                    n,
+                    item_lines.len()
+                )))?;
+
+        lines[*line_index].replace_range(3..4, mark);
+    }
+
+    if !done.is_empty() || !undo.is_empty() {
+        fs::write(&todos_path, format!("{}\n", lines.join("\n"))).map_err(|e| {
+            OwlError::FileError(
+                format!("could not write '{}'", todos_path.to_string_lossy()),
+                e.to_string(),
+            )
+        })?;
+    }
+
+    println!("{}", todos_path.to_string_lossy());
+
+    let mut completed = 0;
+
+    for (n, &line_index) in item_lines.iter().enumerate() {
+        let line = &lines[line_index];
+
+        if line.starts_with("- [x] ") {
+            completed += 1;
+        }
+
+        println!("{:>3}. {}", n + 1, &line[2..]);
+    }
+
+    println!("{}/{} done", completed, item_lines.len());
+
+    Ok(())
+}
+
+pub fn todos_path(chat_path: &Path) -> PathBuf {
+    let chat_stem = chat_path
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default();
+
+    chat_path.with_file_name(format!("{}{}", chat_stem, TODOS_SUFFIX))
+}
+
+fn latest_todos(chat_dir: &Path) -> Result<PathBuf> {
+    fs::read_dir(chat_dir)
+        .map_err(|e| {
+            OwlError::FileError(
+                format!("could not read '{}'", chat_dir.to_string_lossy()),
+                e.to_string(),
+            )
+        })?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.ends_with(TODOS_SUFFIX))
+        })
+        .max_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok())
+        .map(|entry| entry.path())
+        .ok_or(OwlError::FileError(
+            "no todos found".into(),
+            "todos are written after each 'owlgo review'".into(),
+        ))
+}
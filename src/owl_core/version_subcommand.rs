@@ -0,0 +1,205 @@
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::{ManifestHeader, OwlConfig, fs_utils, prog_utils, toml_utils};
+use crate::{CACHE_DIR, MANIFEST, MANIFEST_HEAD_URL, OWL_DIR, REMOTE_HEADER, TOML_TEMPLATE};
+use chrono::DateTime;
+use serde_json::json;
+use std::cmp::Ordering;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, value};
+use url::Url;
+
+pub async fn show_version(as_json: bool, config: &OwlConfig) -> Result<()> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+
+    if !manifest_path.exists() {
+        toml_utils::create_toml(&manifest_path, TOML_TEMPLATE)?;
+    }
+
+    let version = toml_utils::get_embedded_version(TOML_TEMPLATE)?;
+    let (manifest_version, timestamp) = toml_utils::get_manifest_version_timestamp(&manifest_path)?;
+
+    let out_of_date = toml_utils::compare_stamps(&manifest_version, &version)? == Ordering::Less
+        || timestamp == "0.0.0";
+
+    let header_cache = fs_utils::ensure_path_from_home(&[OWL_DIR, CACHE_DIR], Some(REMOTE_HEADER))?;
+    let remote_doc = refresh_remote_header(config.header_url.clone())
+        .await
+        .ok()
+        .flatten()
+        .or_else(|| toml_utils::cached_remote_header(&header_cache, None));
+
+    let hints = match &remote_doc {
+        Some(remote_doc) => update_hints(remote_doc, &manifest_path).unwrap_or_else(|e| {
+            warn(OwlWarning::Recovered(e.to_string()));
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
+
+    if !as_json {
+        println!("owlgo version {}", version);
+
+        if out_of_date {
+            println!("\nmanifest out of date...");
+            println!("run `owlgo update`");
+        }
+
+        for hint in hints.iter() {
+            println!("\n{}", hint);
+        }
+
+        return Ok(());
+    }
+
+    let build_date = env!("OWLGO_BUILD_TIMESTAMP")
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(|date| date.to_rfc3339())
+        .unwrap_or_else(|| "unknown".into());
+
+    let features = env!("OWLGO_FEATURES")
+        .split(',')
+        .filter(|feature| !feature.is_empty())
+        .collect::<Vec<&str>>();
+
+    let toolchains = prog_utils::LANG_EXTS
+        .iter()
+        .filter_map(|ext| prog_utils::try_prog_lang(ext).ok().map(|lang| (ext, lang)))
+        .map(|(ext, lang)| {
+            let version = lang
+                .version(config)
+                .ok()
+                .and_then(|stdout| stdout.lines().next().map(|line| line.trim().to_string()));
+
+            json!({
+                "lang": lang.name(),
+                "ext": ext,
+                "available": version.is_some(),
+                "version": version,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let version_info = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "git_commit": env!("OWLGO_GIT_COMMIT"),
+        "build_date": build_date,
+        "features": features,
+        "manifest": {
+            "path": manifest_path.to_string_lossy(),
+            "version": manifest_version,
+            "timestamp": timestamp,
+            "out_of_date": out_of_date,
+        },
+        "remote": remote_doc.as_ref().map(|remote_doc| {
+            let remote_header = ManifestHeader::from_doc(remote_doc).unwrap_or_default();
+
+            json!({
+                "version": remote_header.version().ok(),
+                "timestamp": remote_header.timestamp().ok(),
+                "checked": cache_checked(remote_doc)
+                    .and_then(|secs| DateTime::from_timestamp(secs, 0))
+                    .map(|date| date.to_rfc3339()),
+                "hints": hints,
+            })
+        }),
+        "toolchains": toolchains,
+    });
+
+    serde_json::to_string_pretty(&version_info)
+        .map(|json_str| println!("{}", json_str))
+        .map_err(|e| OwlError::FileError("Failed to serialize version info".into(), e.to_string()))
+}
+
+/// Returns the remote manifest header, requesting it only once the cached copy is older than
+/// `update_interval_hours` (0 never requests).
+pub async fn refresh_remote_header(header_url: Option<Url>) -> Result<Option<DocumentMut>> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+
+    if !manifest_path.exists() {
+        return Ok(None);
+    }
+
+    let header_cache = fs_utils::ensure_path_from_home(&[OWL_DIR, CACHE_DIR], Some(REMOTE_HEADER))?;
+    let interval = toml_utils::get_manifest_update_interval(&manifest_path)?;
+
+    if interval == 0 {
+        return Ok(toml_utils::cached_remote_header(&header_cache, None));
+    }
+
+    if let Some(remote_doc) = toml_utils::cached_remote_header(&header_cache, Some(interval)) {
+        return Ok(Some(remote_doc));
+    }
+
+    let header_url = match header_url {
+        Some(header_url) => header_url,
+        None => Url::parse(MANIFEST_HEAD_URL).map_err(|e| {
+            OwlError::UriError("remote manifest header is not a URL".into(), e.to_string())
+        })?,
+    };
+
+    toml_utils::request_remote_header(&header_url, &header_cache)
+        .await
+        .map(Some)
+}
+
+/// Prints update hints from the cached remote header, once per check and without a request.
+pub fn hint_update() -> Result<()> {
+    let manifest_path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+    let header_cache = fs_utils::ensure_path_from_home(&[OWL_DIR, CACHE_DIR], Some(REMOTE_HEADER))?;
+
+    if !manifest_path.exists() {
+        return Ok(());
+    }
+
+    let Some(mut remote_doc) = toml_utils::cached_remote_header(&header_cache, None) else {
+        return Ok(());
+    };
+
+    let checked = cache_checked(&remote_doc).unwrap_or(0);
+
+    if remote_doc
+        .get("cache")
+        .and_then(|cache| cache.get("hinted"))
+        .and_then(Item::as_integer)
+        == Some(checked)
+    {
+        return Ok(());
+    }
+
+    for hint in update_hints(&remote_doc, &manifest_path)? {
+        eprintln!("\x1b[2m{}\x1b[0m", hint);
+    }
+
+    remote_doc["cache"]["hinted"] = value(checked);
+    toml_utils::write_remote_header(&remote_doc, &header_cache)
+}
+
+fn update_hints(remote_doc: &DocumentMut, manifest_path: &Path) -> Result<Vec<String>> {
+    let (version_order, timestamp_order) =
+        toml_utils::compare_remote_header(remote_doc, manifest_path)?;
+
+    let mut hints = Vec::new();
+
+    if version_order == Ordering::Less {
+        hints.push(format!(
+            "owlgo {} is available (run `cargo install --force owlgo`)",
+            ManifestHeader::from_doc(remote_doc)?.version()?
+        ));
+    }
+
+    if timestamp_order == Ordering::Less {
+        hints.push("manifest update available (run `owlgo update`)".into());
+    }
+
+    Ok(hints)
+}
+
+/// When the cached remote header was last requested, in seconds since the epoch.
+fn cache_checked(remote_doc: &DocumentMut) -> Option<i64> {
+    remote_doc
+        .get("cache")
+        .and_then(|cache| cache.get("checked"))
+        .and_then(Item::as_integer)
+}
@@ -0,0 +1,122 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::fs_utils;
+use crate::{
+    CACHE_DIR, CHAT_DIR, LAST_RUN_DIR, MANIFEST, OWL_DIR, POOL_DIR, PROMPT_DIR, SCRIPT_DIR,
+    STASH_DIR,
+};
+use serde_json::{Map, Value};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+pub const WHERE_KEYS: &[&str] = &[
+    "root", "manifest", "stash", "prompts", "chats", "scripts", "cache", "pool", "quest", "prog",
+];
+
+pub fn show_paths(
+    key: Option<&str>,
+    quest_name: Option<&str>,
+    prog: Option<&Path>,
+    as_json: bool,
+) -> Result<()> {
+    let mut paths: Vec<(&str, PathBuf)> = vec![
+        ("root", fs_utils::ensure_path_from_home(&[OWL_DIR], None)?),
+        (
+            "manifest",
+            fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?,
+        ),
+        (
+            "stash",
+            fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR], None)?,
+        ),
+        (
+            "prompts",
+            fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, PROMPT_DIR], None)?,
+        ),
+        (
+            "chats",
+            fs_utils::ensure_path_from_home(&[OWL_DIR, STASH_DIR, CHAT_DIR], None)?,
+        ),
+        (
+            "scripts",
+            fs_utils::ensure_path_from_home(&[OWL_DIR, SCRIPT_DIR], None)?,
+        ),
+        (
+            "cache",
+            fs_utils::ensure_path_from_home(&[OWL_DIR, CACHE_DIR], None)?,
+        ),
+        (
+            "pool",
+            fs_utils::ensure_path_from_home(&[OWL_DIR, POOL_DIR], None)?,
+        ),
+        (
+            "last_run",
+            fs_utils::ensure_path_from_home(&[OWL_DIR, LAST_RUN_DIR], None)?,
+        ),
+    ];
+
+    if let Some(quest_name) = quest_name {
+        paths.push(("quest", fs_utils::quest_path(quest_name)?));
+    }
+
+    if let Some(prog) = prog {
+        let prog_file_name = prog
+            .file_name()
+            .and_then(OsStr::to_str)
+            .ok_or(OwlError::UriError(
+                format!("'{}': has no filename", prog.to_string_lossy()),
+                "".into(),
+            ))?;
+
+        paths.push(("prog", fs_utils::stash_path(prog_file_name)?));
+    }
+
+    if let Some(key) = key {
+        let (_, path) =
+            paths
+                .iter()
+                .find(|(name, _)| *name == key)
+                .ok_or(OwlError::Unsupported(format!(
+                    "'{}': requires --{}",
+                    key,
+                    if key == "quest" {
+                        "quest <NAME>"
+                    } else {
+                        "prog <FILE>"
+                    }
+                )))?;
+
+        paths = vec![(key, path.clone())];
+    }
+
+    if as_json {
+        let paths_json = paths
+            .iter()
+            .map(|(name, path)| (name.to_string(), Value::from(path.to_string_lossy())))
+            .collect::<Map<String, Value>>();
+
+        return serde_json::to_string_pretty(&paths_json)
+            .map(|json_str| println!("{}", json_str))
+            .map_err(|e| OwlError::FileError("Failed to serialize paths".into(), e.to_string()));
+    }
+
+    if key.is_some() {
+        for (_, path) in paths.iter() {
+            println!("{}", path.to_string_lossy());
+        }
+
+        return Ok(());
+    }
+
+    let width = paths.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    for (name, path) in paths.iter() {
+        println!(
+            "{:<width$}  {}",
+            name,
+            path.to_string_lossy(),
+            width = width
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use crate::common::{OwlError, Result};
+use crate::owl_utils::{OwlConfig, prog_utils};
+use std::path::Path;
+
+/// Prints the language owlgo would build and run `prog` as, and what decided it.
+pub fn which_lang(prog: &Path, config: &OwlConfig) -> Result<()> {
+    if !prog.exists() {
+        return Err(OwlError::FileError(
+            format!("'{}': program not found", prog.to_string_lossy()),
+            "".into(),
+        ));
+    }
+
+    match prog_utils::detect_prog_lang(prog, config) {
+        Some((lang, source)) => println!(
+            "'{}': {} (from {})",
+            prog.to_string_lossy(),
+            lang.name(),
+            source
+        ),
+        None => println!(
+            "'{}': no language detected, runs as an executable (pass '--lang' to pick one)",
+            prog.to_string_lossy()
+        ),
+    }
+
+    Ok(())
+}
@@ -1,8 +1,109 @@
-use crate::common::{OwlError, Result};
-use std::io::{BufReader, Read, Write};
-use std::path::Path;
-use std::process::{Child, Command, Stdio};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use super::sandbox_utils::{self, Sandbox};
+use super::signal_utils;
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::io::{self, BufReader, Read, Write};
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{self, Child, Command, ExitStatus, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const DEFAULT_OUTPUT_LIMIT: u64 = 64 * 1024 * 1024;
+
+static SCRATCH: Mutex<Option<Vec<PathBuf>>> = Mutex::new(None);
+static SCRATCH_COUNT: AtomicU64 = AtomicU64::new(0);
+static CONTAINER_COUNT: AtomicU64 = AtomicU64::new(0);
+static FILE_IO: Mutex<(Option<String>, Option<String>)> = Mutex::new((None, None));
+
+thread_local! {
+    static CASE_ARGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static LAST_STDERR: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    static LAST_USAGE: RefCell<Option<RunUsage>> = const { RefCell::new(None) };
+}
+
+/// Resources a finished program used, as reported by the OS when it was reaped.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RunUsage {
+    pub wall: Duration,
+    pub cpu: Duration,
+    pub peak_mem: u64,
+}
+
+/// Where a program is run: in a Docker image if one is given, otherwise in the sandbox (if
+/// any) on the host. Output past `output_limit` bytes (64 MiB by default) fails the run, and
+/// a run still going at its `deadline` is killed.
+#[derive(Clone, Debug, Default)]
+pub struct RunOptions {
+    pub docker_image: Option<String>,
+    pub sandbox: Option<Sandbox>,
+    pub output_limit: Option<u64>,
+    pub deadline: Option<Duration>,
+}
+
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn enter(cmd: &mut Command) -> Result<Option<ScratchDir>> {
+        if !uses_scratch() {
+            return Ok(None);
+        }
+
+        let scratch = SCRATCH.lock().expect("scratch lock is not poisoned");
+        let aux_files = scratch.as_deref().unwrap_or_default();
+
+        let scratch_dir = std::env::temp_dir().join(format!(
+            "owlgo-scratch-{}-{}",
+            process::id(),
+            SCRATCH_COUNT.fetch_add(1, Ordering::SeqCst)
+        ));
+
+        fs::create_dir_all(&scratch_dir).map_err(|e| {
+            OwlError::FileError(
+                format!(
+                    "could not create scratch dir '{}'",
+                    scratch_dir.to_string_lossy()
+                ),
+                e.to_string(),
+            )
+        })?;
+
+        let scratch_dir = ScratchDir(scratch_dir);
+
+        for aux_file in aux_files {
+            let file_name = aux_file.file_name().ok_or(OwlError::UriError(
+                format!("'{}': has no file name", aux_file.to_string_lossy()),
+                "".into(),
+            ))?;
+
+            fs::copy(aux_file, scratch_dir.0.join(file_name)).map_err(|e| {
+                OwlError::FileError(
+                    format!(
+                        "could not copy auxiliary file '{}'",
+                        aux_file.to_string_lossy()
+                    ),
+                    e.to_string(),
+                )
+            })?;
+        }
+
+        cmd.current_dir(&scratch_dir.0);
+
+        Ok(Some(scratch_dir))
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
 
 pub fn bat_file(path: &Path) -> Result<()> {
     if !path.exists() {
@@ -72,29 +173,62 @@ pub fn glow_file(path: &Path) -> Result<()> {
     }
 }
 
-pub fn run_binary(exe: &Path) -> Result<(String, Duration)> {
+pub fn run_binary(exe: &Path, run_options: &RunOptions) -> Result<(Vec<u8>, Duration)> {
     let exe_str = exe.to_str().ok_or(OwlError::UriError(
         "Invalid binary file URI".into(),
         "None".into(),
     ))?;
 
-    run_cmd("./binary", Command::new(format!("./{}", exe_str)))
+    run_cmd(
+        "./binary",
+        Command::new(binary_cmd_str(exe, exe_str)),
+        run_options,
+    )
 }
 
-pub fn run_binary_with_stdin(exe: &Path, input: &str) -> Result<(String, Duration)> {
+pub fn run_binary_with_stdin(
+    exe: &Path,
+    input: &[u8],
+    run_options: &RunOptions,
+) -> Result<(Vec<u8>, Duration)> {
     let exe_str = exe.to_str().ok_or(OwlError::UriError(
         "Invalid binary file URI".into(),
         "None".into(),
     ))?;
 
-    run_cmd_with_stdin("./binary", Command::new(format!("./{}", exe_str)), input)
+    run_cmd_with_stdin(
+        "./binary",
+        Command::new(binary_cmd_str(exe, exe_str)),
+        input,
+        run_options,
+    )
+}
+
+fn binary_cmd_str(exe: &Path, exe_str: &str) -> String {
+    if exe.is_absolute() {
+        exe_str.into()
+    } else {
+        format!("./{}", exe_str)
+    }
 }
 
-pub fn run_cmd(cmd_tag: &'static str, mut cmd: Command) -> Result<(String, Duration)> {
+pub fn run_cmd(
+    cmd_tag: &'static str,
+    mut cmd: Command,
+    run_options: &RunOptions,
+) -> Result<(Vec<u8>, Duration)> {
     let start = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("[run_cmd::start_time] unreachable");
 
+    let _scratch_dir = ScratchDir::enter(&mut cmd)?;
+    CASE_ARGS.with_borrow(|args| cmd.args(args));
+
+    let (mut cmd, container) = isolate(cmd, run_options)?;
+
+    #[cfg(unix)]
+    cmd.process_group(0);
+
     let child = cmd
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -103,7 +237,21 @@ pub fn run_cmd(cmd_tag: &'static str, mut cmd: Command) -> Result<(String, Durat
             OwlError::ProcessError(format!("[{}] failed to spawn", cmd_tag), e.to_string())
         })?;
 
-    stdout_else_stderr(cmd_tag, child).map(|stdout| {
+    let started = Instant::now();
+    let pid = child.id();
+    signal_utils::register_child(pid);
+
+    let run_result = wait_capped(
+        cmd_tag,
+        child,
+        started,
+        run_options.output_limit.unwrap_or(DEFAULT_OUTPUT_LIMIT),
+        run_options.deadline,
+        container.as_deref(),
+    );
+    signal_utils::unregister_child(pid);
+
+    run_result.map(|stdout| {
         let stop = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("[run_cmd::stop_time] unreachable");
@@ -115,12 +263,39 @@ pub fn run_cmd(cmd_tag: &'static str, mut cmd: Command) -> Result<(String, Durat
 pub fn run_cmd_with_stdin(
     cmd_tag: &'static str,
     mut cmd: Command,
-    input: &str,
-) -> Result<(String, Duration)> {
+    input: &[u8],
+    run_options: &RunOptions,
+) -> Result<(Vec<u8>, Duration)> {
     let start = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("[run_cmd_with_stdin::start_time] unreachable");
 
+    let scratch_dir = ScratchDir::enter(&mut cmd)?;
+    CASE_ARGS.with_borrow(|args| cmd.args(args));
+
+    let (input_file, output_file) = FILE_IO
+        .lock()
+        .expect("file io lock is not poisoned")
+        .clone();
+
+    if let (Some(scratch_dir), Some(input_file)) = (&scratch_dir, &input_file) {
+        let input_path = scratch_dir.0.join(input_file);
+
+        fs::write(&input_path, input).map_err(|e| {
+            OwlError::FileError(
+                format!("could not write '{}'", input_path.to_string_lossy()),
+                e.to_string(),
+            )
+        })?;
+    }
+
+    let input = if input_file.is_some() { &[][..] } else { input };
+
+    let (mut cmd, container) = isolate(cmd, run_options)?;
+
+    #[cfg(unix)]
+    cmd.process_group(0);
+
     let mut child = cmd
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
@@ -130,8 +305,12 @@ pub fn run_cmd_with_stdin(
             OwlError::ProcessError(format!("[{}] failed to spawn", cmd_tag), e.to_string())
         })?;
 
+    let started = Instant::now();
+    let pid = child.id();
+    signal_utils::register_child(pid);
+
     let mut stdin = child.stdin.take().expect("[stdin handle] unreachable");
-    let write_result = stdin.write_all(input.as_bytes()).map_err(|e| {
+    let write_result = stdin.write_all(input).map_err(|e| {
         OwlError::FileError(
             "Failed not write to stdin of child process".into(),
             e.to_string(),
@@ -139,20 +318,47 @@ pub fn run_cmd_with_stdin(
     });
 
     if let Err(e) = write_result {
-        child.wait().map_err(|e| {
+        let wait_result = child.wait();
+        signal_utils::unregister_child(pid);
+
+        wait_result.map_err(|e| {
             OwlError::ProcessError(format!("[{}] not running", cmd_tag), e.to_string())
         })?;
 
         return Err(e);
     }
 
-    stdout_else_stderr(cmd_tag, child).map(|stdout| {
-        let stop = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("[run_cmd_with_stdin::stop_time] unreachable");
+    drop(stdin);
 
-        (stdout, stop - start)
-    })
+    let run_result = wait_capped(
+        cmd_tag,
+        child,
+        started,
+        run_options.output_limit.unwrap_or(DEFAULT_OUTPUT_LIMIT),
+        run_options.deadline,
+        container.as_deref(),
+    );
+    signal_utils::unregister_child(pid);
+
+    let stdout = run_result?;
+
+    let stop = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("[run_cmd_with_stdin::stop_time] unreachable");
+
+    match (&scratch_dir, &output_file) {
+        (Some(scratch_dir), Some(output_file)) => {
+            let output = fs::read(scratch_dir.0.join(output_file)).map_err(|e| {
+                OwlError::ProcessError(
+                    format!("[{}] did not write '{}'", cmd_tag, output_file),
+                    e.to_string(),
+                )
+            })?;
+
+            Ok((output, stop - start))
+        }
+        _ => Ok((stdout, stop - start)),
+    }
 }
 
 pub fn stderr_only(cmd_tag: &'static str, mut child: Child) -> Result<String> {
@@ -184,36 +390,252 @@ pub fn stderr_only(cmd_tag: &'static str, mut child: Child) -> Result<String> {
     }
 }
 
-pub fn stdout_else_stderr(cmd_tag: &'static str, mut child: Child) -> Result<String> {
+/// The (absolute) working dir of `cmd`, and the dirs outside it of any absolute paths it is
+/// given, i.e. what must be visible to run it in a container or sandbox.
+pub fn cmd_dirs(cmd: &Command) -> Result<(PathBuf, Vec<PathBuf>)> {
+    let cwd = match cmd.get_current_dir() {
+        Some(dir) => std::path::absolute(dir),
+        None => env::current_dir(),
+    }
+    .map_err(|e| OwlError::FileError("could not resolve working dir".into(), e.to_string()))?;
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+
+    for arg in std::iter::once(cmd.get_program()).chain(cmd.get_args()) {
+        let path = Path::new(arg);
+
+        if !path.is_absolute() || !path.exists() {
+            continue;
+        }
+
+        let dir = match path.is_dir() {
+            true => path,
+            false => path.parent().unwrap_or(path),
+        };
+
+        if !dir.starts_with(&cwd) && !dirs.iter().any(|mount| dir.starts_with(mount)) {
+            dirs.push(dir.to_path_buf());
+        }
+    }
+
+    Ok((cwd, dirs))
+}
+
+/// Rewrites `cmd` to run in a throwaway container of `image` (called `name`, if given), with
+/// its working dir and the dirs of any absolute paths it is given mounted at the same locations.
+pub fn docker_cmd(image: &str, cmd: &Command, name: Option<&str>) -> Result<Command> {
+    let (cwd, dirs) = cmd_dirs(cmd)?;
+    let mounts = std::iter::once(cwd.clone()).chain(dirs);
+
+    let mut docker = Command::new("docker");
+    docker.args(["run", "--rm", "-i", "--network", "none", "-e", "HOME=/tmp"]);
+
+    if let Some(name) = name {
+        docker.arg("--name").arg(name);
+    }
+
+    #[cfg(unix)]
+    {
+        // SAFETY: getuid and getgid always succeed
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        docker.arg("--user").arg(format!("{}:{}", uid, gid));
+    }
+
+    for mount in mounts {
+        docker
+            .arg("-v")
+            .arg(format!("{0}:{0}", mount.to_string_lossy()));
+    }
+
+    for (key, value) in cmd.get_envs() {
+        if let Some(value) = value {
+            docker.arg("-e").arg(format!(
+                "{}={}",
+                key.to_string_lossy(),
+                value.to_string_lossy()
+            ));
+        }
+    }
+
+    docker
+        .arg("-w")
+        .arg(&cwd)
+        .arg(image)
+        .arg(cmd.get_program())
+        .args(cmd.get_args());
+
+    Ok(docker)
+}
+
+/// Wraps `cmd` in the Docker image or sandbox it is run in, if any, along with the name of its
+/// container so it can be stopped.
+fn isolate(cmd: Command, run_options: &RunOptions) -> Result<(Command, Option<String>)> {
+    match &run_options.docker_image {
+        Some(image) => {
+            let container = format!(
+                "owlgo-{}-{}",
+                process::id(),
+                CONTAINER_COUNT.fetch_add(1, Ordering::SeqCst)
+            );
+
+            Ok((docker_cmd(image, &cmd, Some(&container))?, Some(container)))
+        }
+        None => Ok((
+            sandbox_utils::in_sandbox(cmd, run_options.sandbox.as_ref())?,
+            None,
+        )),
+    }
+}
+
+pub fn set_scratch(aux_files: Option<Vec<PathBuf>>) {
+    *SCRATCH.lock().expect("scratch lock is not poisoned") = aux_files;
+}
+
+/// Extra argv appended to programs run on this thread (from a case's `.args` file).
+pub fn set_case_args(args: Vec<String>) {
+    CASE_ARGS.set(args);
+}
+
+/// Runs programs against named input/output files in the scratch dir instead of stdin/stdout,
+/// until the returned guard is dropped.
+pub fn set_file_io(input_file: Option<String>, output_file: Option<String>) -> FileIoGuard {
+    *FILE_IO.lock().expect("file io lock is not poisoned") = (input_file, output_file);
+
+    FileIoGuard
+}
+
+/// Goes back to stdin/stdout when dropped, so a run that ends early doesn't leak its quest's
+/// file I/O into the next one.
+#[must_use = "file I/O is reset as soon as the guard is dropped"]
+pub struct FileIoGuard;
+
+impl Drop for FileIoGuard {
+    fn drop(&mut self) {
+        if let Ok(mut file_io) = FILE_IO.lock() {
+            *file_io = (None, None);
+        }
+    }
+}
+
+/// The stderr of the last program run on this thread.
+pub fn last_stderr() -> Vec<u8> {
+    LAST_STDERR.with_borrow(Clone::clone)
+}
+
+/// The resources used by the last program run on this thread, clearing them so a case that
+/// never ran a program doesn't report the previous one's.
+pub fn take_last_usage() -> Option<RunUsage> {
+    LAST_USAGE.take()
+}
+
+/// The resources used by the last program run on this thread, leaving them to be taken.
+pub fn last_usage() -> Option<RunUsage> {
+    LAST_USAGE.with_borrow(|usage| *usage)
+}
+
+pub fn uses_scratch() -> bool {
+    let file_io = FILE_IO.lock().expect("file io lock is not poisoned");
+
+    file_io.0.is_some()
+        || file_io.1.is_some()
+        || SCRATCH
+            .lock()
+            .expect("scratch lock is not poisoned")
+            .is_some()
+}
+
+pub fn stdout_else_stderr(cmd_tag: &'static str, child: Child) -> Result<String> {
+    stdout_bytes_else_stderr(cmd_tag, child).and_then(|stdout| {
+        String::from_utf8(stdout).map_err(|e| {
+            OwlError::FileError(
+                format!("'{}': failed to read stdout", cmd_tag),
+                e.to_string(),
+            )
+        })
+    })
+}
+
+pub fn stdout_bytes_else_stderr(cmd_tag: &'static str, child: Child) -> Result<Vec<u8>> {
+    wait_capped(
+        cmd_tag,
+        child,
+        Instant::now(),
+        DEFAULT_OUTPUT_LIMIT,
+        None,
+        None,
+    )
+}
+
+fn wait_capped(
+    cmd_tag: &'static str,
+    mut child: Child,
+    started: Instant,
+    limit: u64,
+    deadline: Option<Duration>,
+    container: Option<&str>,
+) -> Result<Vec<u8>> {
     let stdout_pipe = child.stdout.take().expect("[stdout handle] unreachable");
     let stderr_pipe = child.stderr.take().expect("[stderr handle] unreachable");
 
-    let status = child
-        .wait()
+    let pid = child.id();
+
+    let stdout_reader = thread::spawn(move || read_capped(stdout_pipe, limit, pid));
+    let stderr_reader = thread::spawn(move || read_capped(stderr_pipe, limit, pid));
+    let watchdog = deadline.map(|deadline| Watchdog::start(pid, container, deadline));
+
+    let wait_result = wait_with_usage(&mut child);
+    let timed_out = watchdog.is_some_and(Watchdog::stop);
+
+    let (status, usage) = wait_result
         .map_err(|e| OwlError::ProcessError(format!("[{}] not running", cmd_tag), e.to_string()))?;
 
-    if status.success() {
-        let mut buffer = String::new();
+    LAST_USAGE.set(Some(RunUsage {
+        wall: started.elapsed(),
+        ..usage.unwrap_or_default()
+    }));
 
-        let mut reader = BufReader::new(stdout_pipe);
-        reader.read_to_string(&mut buffer).map_err(|e| {
+    signal_utils::reap_orphans(pid);
+
+    let (stdout, stdout_exceeded) = stdout_reader
+        .join()
+        .expect("[stdout reader] unreachable")
+        .map_err(|e| {
             OwlError::FileError(
                 format!("'{}': failed to read stdout", cmd_tag),
                 e.to_string(),
             )
         })?;
-
-        Ok(buffer)
-    } else {
-        let mut buffer = String::new();
-
-        let mut reader = BufReader::new(stderr_pipe);
-        reader.read_to_string(&mut buffer).map_err(|e| {
+    let (stderr, stderr_exceeded) = stderr_reader
+        .join()
+        .expect("[stderr reader] unreachable")
+        .map_err(|e| {
             OwlError::FileError(
                 format!("'{}': failed to read stderr", cmd_tag),
                 e.to_string(),
             )
         })?;
+
+    LAST_STDERR.set(stderr.clone());
+
+    if let Some(deadline) = deadline.filter(|_| timed_out) {
+        return Err(OwlError::TimedOut(format!(
+            "'{}': killed after {:.2}s",
+            cmd_tag,
+            deadline.as_secs_f64()
+        )));
+    }
+
+    if stdout_exceeded || stderr_exceeded {
+        return Err(OwlError::ProcessError(
+            format!("'{}': output limit exceeded", cmd_tag),
+            format!("more than {} bytes", limit),
+        ));
+    }
+
+    if status.success() {
+        Ok(stdout)
+    } else {
+        let mut buffer = String::from_utf8_lossy(&stderr).to_string();
         buffer.push_str("(run program manually for stack trace)");
 
         Err(OwlError::ProcessError(
@@ -223,6 +645,119 @@ pub fn stdout_else_stderr(cmd_tag: &'static str, mut child: Child) -> Result<Str
     }
 }
 
+/// Kills a program's process group (and its container, if any) once its deadline passes,
+/// unless it is stopped first.
+struct Watchdog {
+    stop_tx: mpsc::Sender<()>,
+    handle: thread::JoinHandle<bool>,
+}
+
+impl Watchdog {
+    fn start(pid: u32, container: Option<&str>, deadline: Duration) -> Self {
+        let (stop_tx, stop_rx) = mpsc::channel();
+        let container = container.map(String::from);
+
+        let handle = thread::spawn(move || match stop_rx.recv_timeout(deadline) {
+            Err(RecvTimeoutError::Timeout) => {
+                signal_utils::kill_group(pid);
+
+                // killing the docker client leaves its container running
+                if let Some(container) = container
+                    && let Err(e) = Command::new("docker")
+                        .args(["kill", &container])
+                        .stdout(Stdio::null())
+                        .stderr(Stdio::null())
+                        .status()
+                {
+                    warn(OwlWarning::Recovered(format!(
+                        "could not kill container '{}': {}",
+                        container, e
+                    )));
+                }
+
+                true
+            }
+            _ => false,
+        });
+
+        Watchdog { stop_tx, handle }
+    }
+
+    /// Stops watching, returning whether the program had already been killed.
+    fn stop(self) -> bool {
+        let _ = self.stop_tx.send(());
+
+        self.handle.join().expect("[watchdog] unreachable")
+    }
+}
+
+/// Waits for `child` with `wait4` so its CPU time and peak memory can be read back.
+#[cfg(unix)]
+fn wait_with_usage(child: &mut Child) -> io::Result<(ExitStatus, Option<RunUsage>)> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut status = 0;
+    // SAFETY: an all-zero rusage is valid, and wait4 only writes into it
+    let mut rusage = unsafe { std::mem::zeroed::<libc::rusage>() };
+
+    loop {
+        // SAFETY: `status` and `rusage` outlive the call
+        let reaped = unsafe { libc::wait4(child.id() as libc::pid_t, &mut status, 0, &mut rusage) };
+
+        if reaped >= 0 {
+            break;
+        }
+
+        let e = io::Error::last_os_error();
+
+        if e.kind() != io::ErrorKind::Interrupted {
+            return Err(e);
+        }
+    }
+
+    let as_duration = |tv: libc::timeval| {
+        Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+    };
+
+    // maxrss is in bytes on macOS and KiB elsewhere
+    let peak_mem = if cfg!(target_os = "macos") {
+        rusage.ru_maxrss as u64
+    } else {
+        rusage.ru_maxrss as u64 * 1024
+    };
+
+    Ok((
+        ExitStatus::from_raw(status),
+        Some(RunUsage {
+            wall: Duration::ZERO,
+            cpu: as_duration(rusage.ru_utime) + as_duration(rusage.ru_stime),
+            peak_mem,
+        }),
+    ))
+}
+
+#[cfg(not(unix))]
+fn wait_with_usage(child: &mut Child) -> io::Result<(ExitStatus, Option<RunUsage>)> {
+    child.wait().map(|status| (status, None))
+}
+
+fn read_capped(pipe: impl Read, limit: u64, pid: u32) -> io::Result<(Vec<u8>, bool)> {
+    let mut buffer = Vec::new();
+    let mut reader = BufReader::new(pipe);
+
+    (&mut reader).take(limit + 1).read_to_end(&mut buffer)?;
+
+    let exceeded = buffer.len() as u64 > limit;
+
+    if exceeded {
+        buffer.truncate(limit as usize);
+        signal_utils::reap_orphans(pid);
+        io::copy(&mut reader, &mut io::sink())?;
+    }
+
+    Ok((buffer, exceeded))
+}
+
 pub fn tree_dir(dir: &Path) -> Result<()> {
     let mut child = Command::new("tree")
         .args(["-a", "-s", "-h", "--du", "-I", ".git"])
@@ -0,0 +1,172 @@
+use crate::common::{OwlError, Result};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{self, Command, Stdio};
+use toml_edit::Item;
+
+/// Used for a language unless the manifest's `[formatters]` table names another command.
+const DEFAULT_FORMATTERS: [(&str, &str); 5] = [
+    ("c", "clang-format -i"),
+    ("cpp", "clang-format -i"),
+    ("go", "gofmt -w"),
+    ("python", "black -q"),
+    ("rust", "rustfmt --edition 2024"),
+];
+
+/// Formatter commands by language name (the manifest's `[formatters]` table), each run with the
+/// file to format in place as its last argument, and whether `stash` runs them.
+#[derive(Clone, Debug, Default)]
+pub struct Formatters {
+    pub on_stash: bool,
+    pub commands: Vec<(String, String)>,
+}
+
+impl Formatters {
+    pub fn from_item(config_path: &Path, item: Option<&Item>) -> Result<Self> {
+        let Some(item) = item else {
+            return Ok(Formatters::default());
+        };
+
+        let invalid = |key: &str, expected: &str| {
+            OwlError::TomlError(
+                format!(
+                    "Invalid entry 'formatters.{}' in '{}'",
+                    key,
+                    config_path.to_string_lossy()
+                ),
+                format!("expected {}", expected),
+            )
+        };
+
+        let formatters_table = item.as_table_like().ok_or(OwlError::TomlError(
+            format!(
+                "Invalid entry 'formatters' in '{}'",
+                config_path.to_string_lossy()
+            ),
+            "expected a table".into(),
+        ))?;
+
+        let mut formatters = Formatters::default();
+
+        for (key, value) in formatters_table.iter() {
+            match key {
+                "on_stash" => {
+                    formatters.on_stash =
+                        value.as_bool().ok_or_else(|| invalid(key, "a boolean"))?;
+                }
+                lang_name => {
+                    let cmd_str = value
+                        .as_str()
+                        .filter(|cmd_str| !cmd_str.trim().is_empty())
+                        .ok_or_else(|| invalid(key, "a command"))?;
+
+                    formatters
+                        .commands
+                        .push((lang_name.to_string(), cmd_str.to_string()));
+                }
+            }
+        }
+
+        Ok(formatters)
+    }
+
+    pub fn command(&self, lang_name: &str) -> Option<String> {
+        self.commands
+            .iter()
+            .find(|(name, _)| name == lang_name)
+            .map(|(_, cmd_str)| cmd_str.clone())
+            .or_else(|| {
+                DEFAULT_FORMATTERS
+                    .iter()
+                    .find(|(name, _)| *name == lang_name)
+                    .map(|(_, cmd_str)| cmd_str.to_string())
+            })
+    }
+}
+
+/// Formats `prog` in place with its language's formatter, returning whether it changed.
+pub fn format_file(prog: &Path, lang_name: &str, formatters: &Formatters) -> Result<bool> {
+    let cmd_str = formatters
+        .command(lang_name)
+        .ok_or(OwlError::Unsupported(format!(
+            "'{}': no formatter configured (set 'formatters.{}' in the manifest)",
+            lang_name, lang_name
+        )))?;
+
+    let mut words = cmd_str.split_whitespace();
+    let program = words.next().expect("formatter command is not empty");
+
+    let read = |path: &Path| {
+        fs::read(path).map_err(|e| {
+            OwlError::FileError(
+                format!("could not read '{}'", path.to_string_lossy()),
+                e.to_string(),
+            )
+        })
+    };
+
+    let before = read(prog)?;
+
+    let output = Command::new(program)
+        .args(words)
+        .arg(prog)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|_| OwlError::CommandNotFound(format!("'{}': command not found", program)))?;
+
+    if !output.status.success() {
+        return Err(OwlError::ProcessError(
+            format!(
+                "[{}] failed to format '{}'",
+                program,
+                prog.to_string_lossy()
+            ),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(read(prog)? != before)
+}
+
+/// The contents `prog` would have once formatted, leaving `prog` itself untouched.
+pub fn formatted_contents(
+    prog: &Path,
+    lang_name: &str,
+    formatters: &Formatters,
+) -> Result<Vec<u8>> {
+    let work_dir = env::temp_dir().join(format!("owlgo-fmt-{}", process::id()));
+    let file_name = prog.file_name().ok_or(OwlError::UriError(
+        format!("'{}': has no filename", prog.to_string_lossy()),
+        "".into(),
+    ))?;
+    let copy_path = work_dir.join(file_name);
+
+    fs::create_dir_all(&work_dir).map_err(|e| {
+        OwlError::FileError(
+            format!("could not create dir '{}'", work_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let formatted = fs::copy(prog, &copy_path)
+        .map_err(|e| {
+            OwlError::FileError(
+                format!("could not copy '{}'", prog.to_string_lossy()),
+                e.to_string(),
+            )
+        })
+        .and_then(|_| format_file(&copy_path, lang_name, formatters))
+        .and_then(|_| {
+            fs::read(&copy_path).map_err(|e| {
+                OwlError::FileError(
+                    format!("could not read '{}'", copy_path.to_string_lossy()),
+                    e.to_string(),
+                )
+            })
+        });
+
+    let _ = fs::remove_dir_all(&work_dir);
+
+    formatted
+}
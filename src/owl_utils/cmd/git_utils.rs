@@ -15,6 +15,35 @@ pub fn git_add(dir: &Path) -> Result<String> {
     cmd_utils::stdout_else_stderr("git add -A", child)
 }
 
+/// Stages only `path`, leaving other changes in `dir` alone.
+pub fn git_add_path(dir: &Path, path: &Path) -> Result<String> {
+    let child = Command::new("git")
+        .args(["add", "--"])
+        .arg(path)
+        .current_dir(dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| OwlError::ProcessError("[git add] failed to spawn".into(), e.to_string()))?;
+
+    cmd_utils::stdout_else_stderr("git add", child)
+}
+
+/// The branch checked out in `dir`, even one without commits yet.
+pub fn git_branch(dir: &Path) -> Result<String> {
+    let child = Command::new("git")
+        .args(["symbolic-ref", "--short", "HEAD"])
+        .current_dir(dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            OwlError::ProcessError("[git symbolic-ref] failed to spawn".into(), e.to_string())
+        })?;
+
+    cmd_utils::stdout_else_stderr("git symbolic-ref", child).map(|branch| branch.trim().to_string())
+}
+
 pub fn git_checkout(dir: &Path, branch: &str) -> Result<String> {
     let child = Command::new("git")
         .args(["checkout", "-b", branch])
@@ -29,9 +58,23 @@ pub fn git_checkout(dir: &Path, branch: &str) -> Result<String> {
     cmd_utils::stderr_only("git checkout", child)
 }
 
-pub fn git_commit(dir: &Path) -> Result<String> {
+pub fn git_clone(dir: &Path, url: &str, out_dir: &Path) -> Result<String> {
     let child = Command::new("git")
-        .args(["commit", "-m", "\"owlgo CLI submission\""])
+        .arg("clone")
+        .arg(url)
+        .arg(out_dir)
+        .current_dir(dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| OwlError::ProcessError("[git clone] failed to spawn".into(), e.to_string()))?;
+
+    cmd_utils::stderr_only("git clone", child)
+}
+
+pub fn git_commit(dir: &Path, message: &str) -> Result<String> {
+    let child = Command::new("git")
+        .args(["commit", "-m", message])
         .current_dir(dir)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -43,6 +86,26 @@ pub fn git_commit(dir: &Path) -> Result<String> {
     cmd_utils::stdout_else_stderr("git commit", child)
 }
 
+/// Whether `dir` has staged changes, from the exit code of `git diff --cached --quiet`.
+pub fn git_has_staged(dir: &Path) -> Result<bool> {
+    let output = Command::new("git")
+        .args(["diff", "--cached", "--quiet"])
+        .current_dir(dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| OwlError::ProcessError("[git diff] failed to spawn".into(), e.to_string()))?;
+
+    match output.status.code() {
+        Some(0) => Ok(false),
+        Some(1) => Ok(true),
+        _ => Err(OwlError::ProcessError(
+            "[git diff] failed to check staged changes".into(),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )),
+    }
+}
+
 pub fn git_fetch(dir: &Path, remote: &str, branch: &str) -> Result<String> {
     let child = Command::new("git")
         .args(["fetch", remote, branch])
@@ -143,6 +206,18 @@ pub fn git_reset(dir: &Path, remote: &str, branch: &str) -> Result<String> {
     cmd_utils::stdout_else_stderr("git reset", child)
 }
 
+pub fn git_tag(dir: &Path, tag: &str) -> Result<String> {
+    let child = Command::new("git")
+        .args(["tag", tag])
+        .current_dir(dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| OwlError::ProcessError("[git tag] failed to spawn".into(), e.to_string()))?;
+
+    cmd_utils::stdout_else_stderr("git tag", child)
+}
+
 pub fn git_status(dir: &Path) -> Result<String> {
     let child = Command::new("git")
         .arg("status")
@@ -0,0 +1,62 @@
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::fs::OwlHooks;
+use std::path::Path;
+use std::process::Command;
+
+pub const VERDICT_ACCEPTED: &str = "accepted";
+pub const VERDICT_FAILED: &str = "failed";
+
+pub fn pre_build(hooks: &OwlHooks, quest: Option<&str>, prog: &Path) -> Result<()> {
+    match &hooks.pre_build {
+        Some(hook_cmd) => run_hook("pre_build", hook_cmd, quest, prog, None),
+        None => Ok(()),
+    }
+}
+
+pub fn post_test(hooks: &OwlHooks, quest: Option<&str>, prog: &Path, verdict: &str) {
+    if let Some(hook_cmd) = &hooks.post_test
+        && let Err(e) = run_hook("post_test", hook_cmd, quest, prog, Some(verdict))
+    {
+        warn(OwlWarning::HookFailed(e.to_string()));
+    }
+
+    if verdict == VERDICT_ACCEPTED
+        && let Some(hook_cmd) = &hooks.on_accept
+        && let Err(e) = run_hook("on_accept", hook_cmd, quest, prog, Some(verdict))
+    {
+        warn(OwlWarning::HookFailed(e.to_string()));
+    }
+}
+
+fn run_hook(
+    hook_name: &str,
+    hook_cmd: &str,
+    quest: Option<&str>,
+    prog: &Path,
+    verdict: Option<&str>,
+) -> Result<()> {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", hook_cmd]);
+    cmd.env("PROG", prog);
+
+    if let Some(quest_name) = quest {
+        cmd.env("QUEST", quest_name);
+    }
+
+    if let Some(verdict) = verdict {
+        cmd.env("VERDICT", verdict);
+    }
+
+    let status = cmd.status().map_err(|e| {
+        OwlError::ProcessError(format!("[{}] failed to spawn", hook_name), e.to_string())
+    })?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(OwlError::ProcessError(
+            format!("'{}' hook: exit with status failed", hook_name),
+            hook_cmd.into(),
+        ))
+    }
+}
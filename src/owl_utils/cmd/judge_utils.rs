@@ -0,0 +1,407 @@
+use super::cmd_utils::RunUsage;
+use crate::common::OwlError;
+use encoding_rs::{Encoding, UTF_8};
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CompareMode {
+    #[default]
+    Exact,
+    /// Lines in any order, each compared token by token.
+    LineSet,
+    /// Lines in any order, with their tokens in any order too.
+    LineSetTokens,
+    Tokens,
+    Trim,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Pretty,
+    Vscode,
+    Jsonl,
+}
+
+/// Rewrites expected and actual outputs alike before they are compared.
+#[derive(Clone, Debug)]
+pub enum OutputFilter {
+    IgnoreCase,
+    IgnorePrefix(String),
+    Replace(Regex, String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shard {
+    pub index: usize,
+    pub count: usize,
+}
+
+/// How a test case was judged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    #[default]
+    Passed,
+    Failed,
+    TimeLimit,
+    MemoryLimit,
+    /// The program crashed, couldn't be run, or the case couldn't be read.
+    Error,
+}
+
+/// The outcome of a single test case.
+#[derive(Clone, Debug)]
+pub struct CaseResult {
+    pub name: String,
+    pub verdict: Verdict,
+    pub elapsed: Option<Duration>,
+    pub usage: Option<RunUsage>,
+    /// Where the case's input, outputs and stderr were saved when it failed.
+    pub artifacts: Option<PathBuf>,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.verdict == Verdict::Passed
+    }
+}
+
+impl TryFrom<&str> for CompareMode {
+    type Error = OwlError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "exact" => Ok(CompareMode::Exact),
+            "lineset" => Ok(CompareMode::LineSet),
+            "lineset-tokens" => Ok(CompareMode::LineSetTokens),
+            "tokens" => Ok(CompareMode::Tokens),
+            "trim" => Ok(CompareMode::Trim),
+            _ => Err(OwlError::Unsupported(format!(
+                "'{}': unknown comparison mode (expected 'exact', 'lineset', 'lineset-tokens', 'tokens', or 'trim')",
+                s
+            ))),
+        }
+    }
+}
+
+impl TryFrom<&str> for OutputFormat {
+    type Error = OwlError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "pretty" => Ok(OutputFormat::Pretty),
+            "vscode" => Ok(OutputFormat::Vscode),
+            "jsonl" => Ok(OutputFormat::Jsonl),
+            _ => Err(OwlError::Unsupported(format!(
+                "'{}': unknown output format (expected 'pretty', 'vscode', or 'jsonl')",
+                s
+            ))),
+        }
+    }
+}
+
+impl TryFrom<&str> for OutputFilter {
+    type Error = OwlError;
+
+    /// `ignore-case`, `ignore-prefix:<PREFIX>`, or a sed-like `s/<REGEX>/<REPLACEMENT>/`.
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        if s == "ignore-case" {
+            return Ok(OutputFilter::IgnoreCase);
+        }
+
+        if let Some(prefix) = s.strip_prefix("ignore-prefix:")
+            && !prefix.is_empty()
+        {
+            return Ok(OutputFilter::IgnorePrefix(prefix.into()));
+        }
+
+        let unknown_filter = || {
+            OwlError::Unsupported(format!(
+                "'{}': unknown output filter (expected 'ignore-case', 'ignore-prefix:<PREFIX>', or 's/<REGEX>/<REPLACEMENT>/')",
+                s
+            ))
+        };
+
+        let mut chars = s.chars();
+        let (Some('s'), Some(delim)) = (chars.next(), chars.next()) else {
+            return Err(unknown_filter());
+        };
+
+        if delim.is_alphanumeric() || delim == '\\' {
+            return Err(unknown_filter());
+        }
+
+        let mut parts = vec![String::new()];
+        let mut escaped = false;
+
+        for c in chars {
+            match c {
+                c if escaped && c == delim => parts.last_mut().expect("has a part").push(c),
+                c if escaped => {
+                    let part = parts.last_mut().expect("has a part");
+                    part.push('\\');
+                    part.push(c);
+                }
+                '\\' => {}
+                c if c == delim => parts.push(String::new()),
+                c => parts.last_mut().expect("has a part").push(c),
+            }
+
+            escaped = c == '\\' && !escaped;
+        }
+
+        match parts.as_slice() {
+            [pattern, replacement, flags] if flags.is_empty() => {
+                let regex = Regex::new(&format!("(?m){}", pattern)).map_err(|e| {
+                    OwlError::Unsupported(format!("'{}': invalid regex ({})", s, e))
+                })?;
+
+                Ok(OutputFilter::Replace(regex, replacement.clone()))
+            }
+            _ => Err(unknown_filter()),
+        }
+    }
+}
+
+impl OutputFilter {
+    fn apply<'a>(&self, output: Cow<'a, [u8]>) -> Cow<'a, [u8]> {
+        match self {
+            OutputFilter::IgnoreCase => match std::str::from_utf8(&output) {
+                Ok(s) => Cow::Owned(s.to_lowercase().into_bytes()),
+                Err(_) => Cow::Owned(output.to_ascii_lowercase()),
+            },
+            OutputFilter::IgnorePrefix(prefix) => Cow::Owned(
+                output
+                    .split_inclusive(|&b| b == b'\n')
+                    .filter(|line| !line.starts_with(prefix.as_bytes()))
+                    .flatten()
+                    .copied()
+                    .collect(),
+            ),
+            OutputFilter::Replace(regex, replacement) => {
+                match regex.replace_all(&output, replacement.as_bytes()) {
+                    Cow::Borrowed(_) => output,
+                    Cow::Owned(replaced) => Cow::Owned(replaced),
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<&str> for Shard {
+    type Error = OwlError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let shard = s
+            .split_once('/')
+            .and_then(|(index, count)| Some((index.parse().ok()?, count.parse().ok()?)));
+
+        match shard {
+            Some((index, count)) if index >= 1 && index <= count => Ok(Shard { index, count }),
+            _ => Err(OwlError::Unsupported(format!(
+                "'{}': invalid shard (expected 'K/N' with 1 <= K <= N)",
+                s
+            ))),
+        }
+    }
+}
+
+impl Shard {
+    pub fn contains(&self, position: usize) -> bool {
+        position % self.count == self.index - 1
+    }
+}
+
+const HEXDUMP_PREVIEW: usize = 512;
+
+/// A limit a run went over, with what it was and what the run used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LimitExceeded {
+    Time { used: Duration, limit: Duration },
+    Memory { used: u64, limit: u64 },
+}
+
+impl LimitExceeded {
+    pub fn verdict(&self) -> Verdict {
+        match self {
+            LimitExceeded::Time { .. } => Verdict::TimeLimit,
+            LimitExceeded::Memory { .. } => Verdict::MemoryLimit,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LimitExceeded::Time { .. } => "time",
+            LimitExceeded::Memory { .. } => "memory",
+        }
+    }
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mib = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+
+        match self {
+            // sub-second limits would read "0.00s > 0.00s" in seconds
+            LimitExceeded::Time { used, limit } if limit.as_secs() == 0 => write!(
+                f,
+                "time limit exceeded ({:.1}ms > {:.1}ms limit)",
+                used.as_secs_f64() * 1000.0,
+                limit.as_secs_f64() * 1000.0
+            ),
+            LimitExceeded::Time { used, limit } => write!(
+                f,
+                "time limit exceeded ({:.2}s > {:.2}s limit)",
+                used.as_secs_f64(),
+                limit.as_secs_f64()
+            ),
+            LimitExceeded::Memory { used, limit } => write!(
+                f,
+                "memory limit exceeded ({:.1}MiB > {:.1}MiB limit)",
+                mib(*used),
+                mib(*limit)
+            ),
+        }
+    }
+}
+
+/// The first limit a run that took `elapsed` (and used `usage`, if known) went over.
+pub fn check_limits(
+    elapsed: Duration,
+    usage: Option<RunUsage>,
+    time_limit: Option<Duration>,
+    memory_limit: Option<u64>,
+) -> Option<LimitExceeded> {
+    if let Some(limit) = time_limit
+        && elapsed > limit
+    {
+        return Some(LimitExceeded::Time {
+            used: elapsed,
+            limit,
+        });
+    }
+
+    match (memory_limit, usage) {
+        (Some(limit), Some(usage)) if usage.peak_mem > limit => Some(LimitExceeded::Memory {
+            used: usage.peak_mem,
+            limit,
+        }),
+        _ => None,
+    }
+}
+
+/// Compares outputs by `mode` once `filters` are applied, in order, to both.
+pub fn outputs_match(
+    expected: &[u8],
+    actual: &[u8],
+    mode: CompareMode,
+    filters: &[OutputFilter],
+) -> bool {
+    let (expected, actual) = filters.iter().fold(
+        (Cow::Borrowed(expected), Cow::Borrowed(actual)),
+        |(expected, actual), filter| (filter.apply(expected), filter.apply(actual)),
+    );
+
+    match mode {
+        CompareMode::Exact => expected == actual,
+        CompareMode::LineSet => line_set(&expected, false) == line_set(&actual, false),
+        CompareMode::LineSetTokens => line_set(&expected, true) == line_set(&actual, true),
+        CompareMode::Tokens => tokens(&expected).eq(tokens(&actual)),
+        CompareMode::Trim => trimmed_lines(&expected).eq(trimmed_lines(&actual)),
+    }
+}
+
+/// `output` as text to show, decoded from `encoding` when it isn't UTF-8.
+pub fn preview_output(output: &[u8], encoding: Option<&'static Encoding>) -> String {
+    if let Some(encoding) = encoding.filter(|&encoding| encoding != UTF_8) {
+        let (decoded, _, had_errors) = encoding.decode(output);
+
+        if !had_errors && !is_binary(decoded.as_bytes()) {
+            return decoded.into_owned();
+        }
+    }
+
+    if !is_binary(output) {
+        return String::from_utf8_lossy(output).to_string();
+    }
+
+    let mut preview = String::new();
+
+    for (i, chunk) in output.chunks(16).take(HEXDUMP_PREVIEW / 16).enumerate() {
+        let hex = chunk
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let ascii = chunk
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>();
+
+        preview.push_str(&format!("{:08x}  {:<47}  |{}|\n", i * 16, hex, ascii));
+    }
+
+    if output.len() > HEXDUMP_PREVIEW {
+        preview.push_str(&format!(
+            "... ({} more bytes)\n",
+            output.len() - HEXDUMP_PREVIEW
+        ));
+    }
+
+    preview
+}
+
+fn is_binary(output: &[u8]) -> bool {
+    match std::str::from_utf8(output) {
+        Ok(s) => s
+            .chars()
+            .any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t')),
+        Err(_) => true,
+    }
+}
+
+/// The non-blank lines of `s` as sorted token lists, so that line order doesn't matter.
+fn line_set(s: &[u8], sort_tokens: bool) -> Vec<Vec<&[u8]>> {
+    let mut lines = s
+        .split(|&b| b == b'\n')
+        .map(|line| {
+            let mut line_tokens = tokens(line).collect::<Vec<&[u8]>>();
+
+            if sort_tokens {
+                line_tokens.sort_unstable();
+            }
+
+            line_tokens
+        })
+        .filter(|line_tokens| !line_tokens.is_empty())
+        .collect::<Vec<Vec<&[u8]>>>();
+
+    lines.sort_unstable();
+    lines
+}
+
+fn tokens(s: &[u8]) -> impl Iterator<Item = &[u8]> {
+    s.split(u8::is_ascii_whitespace)
+        .filter(|token| !token.is_empty())
+}
+
+fn trimmed_lines(s: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let trimmed = s.trim_ascii_end();
+
+    trimmed
+        .split(|&b| b == b'\n')
+        .map(<[u8]>::trim_ascii_end)
+        .take(if trimmed.is_empty() { 0 } else { usize::MAX })
+}
@@ -1,3 +1,13 @@
 pub mod cmd_utils;
+pub mod fmt_utils;
 pub mod git_utils;
+pub mod hook_utils;
+pub mod judge_utils;
 pub mod prog_utils;
+pub mod progress_utils;
+pub mod results_utils;
+pub mod sandbox_utils;
+pub mod script_utils;
+pub mod signal_utils;
+pub mod term_utils;
+pub mod vscode_utils;
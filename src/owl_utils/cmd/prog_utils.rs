@@ -1,24 +1,135 @@
-use super::cmd_utils;
-use crate::common::{OwlError, Result};
-use crate::owl_utils::fs::fs_utils;
-use std::ffi::OsStr;
+use super::judge_utils::OutputFormat;
+use super::{cmd_utils, vscode_utils};
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::fs::{OwlConfig, fs_utils};
+use chrono::Local;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 
-pub fn build_program(prog: &Path) -> Result<Option<BuildLog>> {
-    match check_prog_lang(prog) {
+const STACK_TRACE_HINT: &str = "(run program manually for stack trace)";
+
+static ENV_REPORTED: AtomicBool = AtomicBool::new(false);
+static BUILD_COUNT: AtomicU64 = AtomicU64::new(0);
+
+const PY_CHECK: &str = "import sys, traceback
+try:
+    compile(open(sys.argv[1], 'rb').read(), sys.argv[1], 'exec')
+except SyntaxError:
+    traceback.print_exc(limit=0)
+    sys.exit(1)";
+
+/// Syntax/type checks that skip codegen, by language name: the command and its args, where
+/// `{prog}` is the program (appended when absent) and `{out}` a scratch dir for stray output.
+const CHECK_CMDS: &[(&str, &str, &[&str])] = &[
+    ("bash", "bash", &["-n"]),
+    ("c", "gcc", &["-fsyntax-only", "-std=gnu23"]),
+    ("cpp", "g++", &["-fsyntax-only", "-std=gnu++23"]),
+    (
+        "crystal",
+        "crystal",
+        &["build", "--no-codegen", "--no-color"],
+    ),
+    ("dart", "dart", &["analyze"]),
+    ("go", "gofmt", &["-e"]),
+    ("haskell", "ghc", &["-fno-code", "-ferror-spans"]),
+    (
+        "java",
+        "javac",
+        &["-encoding", "UTF-8", "-proc:none", "-d", "{out}"],
+    ),
+    ("javascript", "node", &["--check"]),
+    ("lean", "lean", &[]),
+    ("lua", "luac", &["-p"]),
+    (
+        "odin",
+        "odin",
+        &["check", "{prog}", "-file", "-no-entry-point"],
+    ),
+    ("python", "python3", &["-c", PY_CHECK]),
+    ("ruby", "ruby", &["-c"]),
+    ("rust", "rustc", &["--emit=metadata", "--out-dir", "{out}"]),
+    ("typescript", "tsc", &["--noEmit", "--module", "commonjs"]),
+    ("zig", "zig", &["ast-check"]),
+];
+
+/// Interpreters named in a shebang line, by the extension of their language.
+const SHEBANG_LANGS: &[(&str, &str)] = &[
+    ("bash", "sh"),
+    ("elixir", "ex"),
+    ("escript", "erl"),
+    ("julia", "jl"),
+    ("lua", "lua"),
+    ("node", "js"),
+    ("python", "py"),
+    ("ruby", "rb"),
+    ("sh", "sh"),
+];
+
+pub const LANG_EXTS: &[&str] = &[
+    "adb", "c", "cpp", "cr", "dart", "erl", "ex", "go", "hs", "java", "jl", "js", "kt", "lean",
+    "lua", "ml", "odin", "py", "rb", "rs", "sh", "ts", "zig",
+];
+
+/// Builds `prog` if its language needs a build step, returning the target and build files.
+pub fn build_program(prog: &Path, config: &OwlConfig) -> Result<Option<BuildLog>> {
+    match resolve_prog_lang(prog, config) {
         Some(lang) => {
-            if !lang.command_exists() {
-                return Err(OwlError::CommandNotFound(format!(
-                    "'{}': command not found",
+            if !lang.command_exists(config) {
+                return Err(OwlError::CommandNotFound(
+                    match config.docker_image(lang.name()) {
+                        Some(image) => format!(
+                            "'{}': command not found in Docker image '{}' (is the Docker daemon running?)",
+                            lang.name(),
+                            image
+                        ),
+                        None => format!("'{}': command not found", lang.name()),
+                    },
+                ));
+            }
+
+            // compilers go by the extension, and without one the target takes the source's name
+            if lang.should_build()
+                && ext_prog_lang(prog).is_none_or(|ext_lang| ext_lang.name() != lang.name())
+            {
+                return Err(OwlError::Unsupported(format!(
+                    "'{}': needs a {} file extension to be built",
+                    prog.to_string_lossy(),
                     lang.name()
                 )));
             }
 
             if lang.should_build() {
-                let build_log = lang.build(prog)?;
-                println!("{}", build_log.stdout);
+                let build_result = lang.build(prog, config);
+
+                if let Some(cache_path) = &config.build_cache {
+                    cache_build_result(cache_path, prog, &build_result);
+                }
+
+                if config.format == OutputFormat::Vscode
+                    && let Err(OwlError::ProcessError(_, stderr)) = &build_result
+                {
+                    let stderr = stderr.strip_suffix(STACK_TRACE_HINT).unwrap_or(stderr);
+
+                    for diagnostic in vscode_utils::build_diagnostics(prog, stderr) {
+                        println!("{}", diagnostic);
+                    }
+                }
+
+                if let Err(e) = &build_result {
+                    report_failure("build", prog, e, config);
+                }
+
+                let build_log = build_result?;
+
+                if config.show_build {
+                    show_build_output(&build_log, config);
+                }
 
                 Ok(Some(build_log))
             } else {
@@ -29,7 +140,221 @@ pub fn build_program(prog: &Path) -> Result<Option<BuildLog>> {
     }
 }
 
+/// Prints what the compiler wrote on a successful build, highlighting warnings.
+fn show_build_output(build_log: &BuildLog, config: &OwlConfig) {
+    let stdout = build_log.stdout.trim_end();
+
+    if !stdout.is_empty() {
+        match config.format {
+            OutputFormat::Jsonl => eprintln!("{}", stdout),
+            _ => println!("{}", stdout),
+        }
+    }
+
+    for line in build_log.stderr.lines() {
+        if config.format == OutputFormat::Pretty && is_warning(line) {
+            eprintln!("\x1b[33m{}\x1b[0m", line);
+        } else {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+/// Counts `warning:` diagnostics, skipping totals like rustc's "warning: 2 warnings emitted".
+fn count_warnings(stderr: &str) -> usize {
+    stderr
+        .lines()
+        .filter(|line| is_warning(line) && !line.trim_end().ends_with("emitted"))
+        .count()
+}
+
+fn is_warning(line: &str) -> bool {
+    line.to_lowercase().contains("warning:")
+}
+
+fn cache_build_result(cache_path: &Path, prog: &Path, build_result: &Result<BuildLog>) {
+    let cache_result = match build_result {
+        Err(OwlError::ProcessError(_, stderr)) => {
+            let prog_path = prog.canonicalize().unwrap_or(prog.to_path_buf());
+            let stderr = stderr.strip_suffix(STACK_TRACE_HINT).unwrap_or(stderr);
+
+            fs::write(
+                cache_path,
+                format!("{}\n{}", prog_path.to_string_lossy(), stderr),
+            )
+            .map_err(|e| {
+                OwlError::FileError(
+                    format!(
+                        "could not cache build errors in '{}'",
+                        cache_path.to_string_lossy()
+                    ),
+                    e.to_string(),
+                )
+            })
+        }
+        _ if cache_path.exists() => fs_utils::remove_path(cache_path),
+        _ => Ok(()),
+    };
+
+    if let Err(e) = cache_result {
+        warn(OwlWarning::Recovered(e.to_string()));
+    }
+}
+
+pub fn report_failure(stage: &str, prog: &Path, err: &OwlError, config: &OwlConfig) {
+    let env_block = env_block(prog, config);
+
+    if config.format == OutputFormat::Pretty && !ENV_REPORTED.swap(true, Ordering::SeqCst) {
+        eprintln!("\x1b[2m[owlgo environment]\n{}\x1b[0m", env_block);
+    }
+
+    let Some(failure_path) = &config.failure_log else {
+        return;
+    };
+
+    let prog_path = prog.canonicalize().unwrap_or(prog.to_path_buf());
+    let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+
+    let report = format!(
+        "{}\n# Last failure: {}\n\n- program: `{}`\n- time: {}\n\n## Error\n\n```text\n{}\n```\n\n## Environment\n\n{}\n",
+        prog_path.to_string_lossy(),
+        stage,
+        prog_path.to_string_lossy(),
+        timestamp,
+        err.to_string().trim_end(),
+        env_block
+    );
+
+    if let Err(e) = fs::write(failure_path, report) {
+        warn(OwlWarning::Recovered(format!(
+            "could not write '{}' (info: {})",
+            failure_path.to_string_lossy(),
+            e
+        )));
+    }
+}
+
+pub fn cached_failure(failure_path: &Path, prog: &Path) -> Option<String> {
+    let report_str = fs::read_to_string(failure_path).ok()?;
+    let (failed_prog, report) = report_str.split_once('\n')?;
+
+    let prog_path = prog.canonicalize().unwrap_or(prog.to_path_buf());
+
+    if Path::new(failed_prog) == prog_path {
+        Some(report.to_string())
+    } else {
+        None
+    }
+}
+
+fn env_block(prog: &Path, config: &OwlConfig) -> String {
+    let lang_str = match resolve_prog_lang(prog, config) {
+        Some(lang) => {
+            let version = lang
+                .version(config)
+                .ok()
+                .and_then(|stdout| stdout.lines().next().map(|line| line.trim().to_string()))
+                .unwrap_or_else(|| "version unknown".into());
+
+            format!("{} ({})", lang.name(), version)
+        }
+        None => "binary".into(),
+    };
+
+    let mut env_block = format!(
+        "- owlgo: {}\n- os: {} ({})\n- lang: {}\n- compare: {:?}",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        lang_str,
+        config.compare
+    );
+
+    if !config.build_args.is_empty() {
+        env_block.push_str(&format!("\n- build args: {}", config.build_args.join(" ")));
+    }
+
+    if !config.jvm_args.is_empty() {
+        env_block.push_str(&format!("\n- jvm args: {}", config.jvm_args.join(" ")));
+    }
+
+    env_block
+}
+
+pub fn cached_build_errors(cache_path: &Path, prog: &Path) -> Option<String> {
+    let cache_str = fs::read_to_string(cache_path).ok()?;
+    let (cached_prog, stderr) = cache_str.split_once('\n')?;
+
+    let prog_path = prog.canonicalize().unwrap_or(prog.to_path_buf());
+
+    if Path::new(cached_prog) == prog_path && !stderr.trim().is_empty() {
+        Some(stderr.to_string())
+    } else {
+        None
+    }
+}
+
+fn run_path(path: &Path) -> Result<PathBuf> {
+    if !cmd_utils::uses_scratch() {
+        return Ok(path.to_path_buf());
+    }
+
+    std::path::absolute(path).map_err(|e| {
+        OwlError::UriError(
+            format!("'{}': could not resolve path", path.to_string_lossy()),
+            e.to_string(),
+        )
+    })
+}
+
+/// Runs a program without a language (e.g. a prebuilt binary) in the same container or sandbox
+/// as programs with one.
+pub fn run_binary(
+    exe: &Path,
+    stdin: Option<&[u8]>,
+    config: &OwlConfig,
+) -> Result<(Vec<u8>, Duration)> {
+    let exe = run_path(exe)?;
+    let run_options = config.run_options("binary");
+
+    match stdin {
+        Some(input) => cmd_utils::run_binary_with_stdin(&exe, input, &run_options),
+        None => cmd_utils::run_binary(&exe, &run_options),
+    }
+}
+
+/// A fresh dir for a build's target and intermediate files, so none of them land beside the
+/// source. It is removed with the rest of the build files by `cleanup_program`.
+fn build_out_dir() -> Result<PathBuf> {
+    let out_dir = std::env::temp_dir().join(format!(
+        "owlgo-build-{}-{}",
+        process::id(),
+        BUILD_COUNT.fetch_add(1, Ordering::SeqCst)
+    ));
+
+    fs::create_dir_all(&out_dir).map_err(|e| {
+        OwlError::FileError(
+            format!("could not create build dir '{}'", out_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    Ok(out_dir)
+}
+
+fn build_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// The language of `prog` from its extension, or else from its shebang line.
 pub fn check_prog_lang(prog: &Path) -> Option<Box<dyn ProgLang>> {
+    ext_prog_lang(prog).or_else(|| shebang_lang(prog).map(|(lang, _)| lang))
+}
+
+fn ext_prog_lang(prog: &Path) -> Option<Box<dyn ProgLang>> {
     prog.extension()
         .and_then(OsStr::to_str)
         .and_then(|ext| try_prog_lang(ext).ok())
@@ -53,6 +378,110 @@ pub fn cleanup_program(
     Ok(())
 }
 
+fn in_docker_image(lang_name: &str, cmd: Command, config: &OwlConfig) -> Result<Command> {
+    match config.docker_image(lang_name) {
+        Some(image) => cmd_utils::docker_cmd(&image, &cmd, None),
+        None => Ok(cmd),
+    }
+}
+
+/// Where the language of a program came from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LangSource {
+    Flag,
+    Extension,
+    Shebang(String),
+    Config,
+}
+
+impl fmt::Display for LangSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LangSource::Flag => write!(f, "'--lang'"),
+            LangSource::Extension => write!(f, "its extension"),
+            LangSource::Shebang(line) => write!(f, "its shebang '{}'", line),
+            LangSource::Config => write!(f, "'lang' in .owlgo.toml"),
+        }
+    }
+}
+
+/// Resolves the language of `prog`: `--lang` first, then its extension, its shebang, and
+/// finally the configured `lang`.
+pub fn detect_prog_lang(
+    prog: &Path,
+    config: &OwlConfig,
+) -> Option<(Box<dyn ProgLang>, LangSource)> {
+    if let Some(lang) = config
+        .force_lang
+        .as_deref()
+        .and_then(|lang| find_prog_lang(lang).ok())
+    {
+        return Some((lang, LangSource::Flag));
+    }
+
+    if let Some(lang) = ext_prog_lang(prog) {
+        return Some((lang, LangSource::Extension));
+    }
+
+    if let Some((lang, line)) = shebang_lang(prog) {
+        return Some((lang, LangSource::Shebang(line)));
+    }
+
+    config
+        .lang
+        .as_deref()
+        .and_then(|ext| try_prog_lang(ext).ok())
+        .map(|lang| (lang, LangSource::Config))
+}
+
+pub fn resolve_prog_lang(prog: &Path, config: &OwlConfig) -> Option<Box<dyn ProgLang>> {
+    detect_prog_lang(prog, config).map(|(lang, _)| lang)
+}
+
+/// Looks up a language by extension (`py`) or by name (`python`).
+pub fn find_prog_lang(lang: &str) -> Result<Box<dyn ProgLang>> {
+    try_prog_lang(lang).or_else(|_| {
+        LANG_EXTS
+            .iter()
+            .filter_map(|ext| try_prog_lang(ext).ok())
+            .find(|prog_lang| prog_lang.name() == lang)
+            .ok_or(OwlError::Unsupported(format!(
+                "'{}': unknown language (expected an extension like 'cpp' or a name like 'python')",
+                lang
+            )))
+    })
+}
+
+/// The language of a script's `#!` interpreter, with the shebang line.
+fn shebang_lang(prog: &Path) -> Option<(Box<dyn ProgLang>, String)> {
+    let mut head = [0; 256];
+    let n = File::open(prog)
+        .and_then(|mut file| file.read(&mut head))
+        .ok()?;
+
+    let line = String::from_utf8_lossy(&head[..n])
+        .strip_prefix("#!")?
+        .lines()
+        .next()?
+        .trim()
+        .to_string();
+
+    let mut words = line.split_whitespace();
+    let mut interp = Path::new(words.next()?).file_name()?.to_str()?;
+
+    if interp == "env" {
+        interp = words.find(|word| !word.starts_with('-'))?;
+    }
+
+    let interp = interp.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+    SHEBANG_LANGS
+        .iter()
+        .find(|(name, _)| *name == interp)
+        .and_then(|(_, ext)| try_prog_lang(ext).ok())
+        .map(|lang| (lang, format!("#!{}", line)))
+}
+
 pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
     match lang_ext {
         "adb" | "ads" => {
@@ -63,6 +492,7 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
                 build_cmd_str: "gnatmake",
                 build_args: &["-g", "-O2"],
                 exe_flag: Some(("-o", ArgsPosition::Pre)),
+                out_dir_flag: Some("-D"),
                 fn_build_files: Some(|target_stem| {
                     vec![
                         format!("b~{}.adb", target_stem),
@@ -84,6 +514,7 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
                 build_cmd_str: "gcc",
                 build_args: &["-g", "-O2", "-std=gnu23", "-static", "-lm"],
                 exe_flag: Some(("-o", ArgsPosition::Pre)),
+                out_dir_flag: None,
                 fn_build_files: None,
             };
             Ok(Box::new(c_lang))
@@ -96,6 +527,7 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
                 build_cmd_str: "g++",
                 build_args: &["-g", "-O2", "-std=gnu++23", "-static", "-lrt", "-lpthread"],
                 exe_flag: Some(("-o", ArgsPosition::Pre)),
+                out_dir_flag: None,
                 fn_build_files: None,
             };
             Ok(Box::new(cpp_lang))
@@ -108,6 +540,7 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
                 build_cmd_str: "crystal",
                 build_args: &["build", "-O", "2", "--no-color"],
                 exe_flag: Some(("-o", ArgsPosition::Post)),
+                out_dir_flag: None,
                 fn_build_files: None,
             };
             Ok(Box::new(crystal_lang))
@@ -120,6 +553,7 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
                 build_cmd_str: "dart",
                 build_args: &["compile", "exe"],
                 exe_flag: Some(("-o", ArgsPosition::Pre)),
+                out_dir_flag: None,
                 fn_build_files: None,
             };
             Ok(Box::new(dart_lang))
@@ -142,6 +576,7 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
                 build_cmd_str: "go",
                 build_args: &["build"],
                 exe_flag: Some(("-o", ArgsPosition::Pre)),
+                out_dir_flag: None,
                 fn_build_files: None,
             };
             Ok(Box::new(go_lang))
@@ -152,16 +587,9 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
                 cmd_str: "ghc",
                 ver_arg: "--version",
                 build_cmd_str: "ghc",
-                build_args: &[
-                    "-O2",
-                    "-ferror-spans",
-                    "-threaded",
-                    "-rtsopts",
-                    "-dynamic",
-                    "-outputdir",
-                    ".",
-                ],
+                build_args: &["-O2", "-ferror-spans", "-threaded", "-rtsopts", "-dynamic"],
                 exe_flag: Some(("-o", ArgsPosition::Pre)),
+                out_dir_flag: Some("-outputdir"),
                 fn_build_files: Some(|thread_stem| {
                     vec![
                         "Main.o".into(),
@@ -177,9 +605,12 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
             let java_lang = CustomLang {
                 name: "java",
                 build_cmd_str: "javac",
-                build_args: &["-encoding", "UTF-8", "-d", "."],
+                build_args: &["-encoding", "UTF-8"],
+                out_dir_flag: "-d",
+                classpath_flag: Some("-cp"),
                 run_cmd_str: "java",
                 run_args: &["-Dfile.encoding=UTF-8", "-XX:+UseSerialGC", "-Xss64m"],
+                jvm_arg_prefix: Some(""),
                 ver_arg: "--version",
                 fn_target_name: |target_stem| format!("{}.class", target_stem),
                 fn_build_files: None,
@@ -209,8 +640,11 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
                 name: "kotlin",
                 build_cmd_str: "kotlinc",
                 build_args: &[],
+                out_dir_flag: "-d",
+                classpath_flag: Some("-cp"),
                 run_cmd_str: "kotlin",
                 run_args: &["-J-XX:+UseSerialGC", "-J-Xss64m"],
+                jvm_arg_prefix: Some("-J"),
                 ver_arg: "-version",
                 fn_target_name: |target_stem| {
                     let mut chars = target_stem.chars();
@@ -251,6 +685,7 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
                 build_cmd_str: "odin",
                 build_args: &["build"],
                 exe_flag: Some(("-file -out:", ArgsPosition::Post)),
+                out_dir_flag: None,
                 fn_build_files: None,
             };
             Ok(Box::new(odin_lang))
@@ -281,17 +716,30 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
                 build_cmd_str: "rustc",
                 build_args: &["-C", "opt-level=3", "-C", "target-cpu=native"],
                 exe_flag: Some(("-o", ArgsPosition::Post)),
+                out_dir_flag: None,
                 fn_build_files: None,
             };
             Ok(Box::new(rust_lang))
         }
+        "sh" | "bash" => {
+            let bash_lang = RuntimeLang {
+                name: "bash",
+                cmd_str: "bash",
+                cmd_args: &[],
+                ver_arg: "--version",
+            };
+            Ok(Box::new(bash_lang))
+        }
         "ts" => {
             let ts_lang = CustomLang {
                 name: "typescript",
                 build_cmd_str: "tsc",
-                build_args: &["--module", "commonjs", "-outDir", "."],
+                build_args: &["--module", "commonjs"],
+                out_dir_flag: "-outDir",
+                classpath_flag: None,
                 run_cmd_str: "node",
                 run_args: &[],
+                jvm_arg_prefix: None,
                 ver_arg: "--version",
                 fn_target_name: |target_stem| format!("{}.js", target_stem),
                 fn_build_files: None,
@@ -306,6 +754,7 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
                 build_cmd_str: "zig",
                 build_args: &["build-exe", "-O", "ReleaseFast"],
                 exe_flag: Some(("-femit-bin=", ArgsPosition::Pre)),
+                out_dir_flag: None,
                 fn_build_files: None,
             };
             Ok(Box::new(zig_lang))
@@ -314,18 +763,76 @@ pub fn try_prog_lang(lang_ext: &str) -> Result<Box<dyn ProgLang>> {
     }
 }
 
+/// A supported language toolchain: how to build, run, and version a program.
 pub trait ProgLang {
-    fn build_cmd(&self, path: &Path) -> Result<Command>;
-    fn build_files(&self, parent: &Path, target_stem: &str) -> Option<Vec<PathBuf>>;
+    /// The command building `path`, writing the target and any intermediate files to `out_dir`.
+    fn build_cmd(&self, path: &Path, out_dir: &Path) -> Result<Command>;
+    fn build_files(&self, out_dir: &Path, target_stem: &str) -> Option<Vec<PathBuf>>;
     fn name(&self) -> &str;
-    fn run_it(&self, path: &Path, stdin: Option<&str>) -> Result<(String, Duration)>;
+    fn run_it(
+        &self,
+        path: &Path,
+        stdin: Option<&[u8]>,
+        config: &OwlConfig,
+    ) -> Result<(Vec<u8>, Duration)>;
     fn should_build(&self) -> bool;
-    fn target_path(&self, parent: &Path, target_stem: &str) -> PathBuf;
+    fn target_path(&self, out_dir: &Path, target_stem: &str) -> PathBuf;
     fn version_cmd(&self) -> Result<Command>;
 
-    fn build(&self, path: &Path) -> Result<BuildLog> {
-        let output = self
-            .build_cmd(path)?
+    /// Whether the compiler writes intermediate files beside the source no matter what, so the
+    /// source is copied into the out dir and built there instead.
+    fn builds_beside_source(&self) -> bool {
+        false
+    }
+
+    /// `build_cmd` run from inside `out_dir`, so that stray output lands there too.
+    fn out_of_tree_cmd(&self, path: &Path, out_dir: &Path) -> Result<Command> {
+        let source = if self.builds_beside_source() {
+            let file_name = path.file_name().ok_or(OwlError::UriError(
+                format!("'{}': has no filename", path.to_string_lossy()),
+                "".into(),
+            ))?;
+            let source = out_dir.join(file_name);
+
+            fs::copy(path, &source).map_err(|e| {
+                OwlError::FileError(
+                    format!("could not copy '{}'", path.to_string_lossy()),
+                    e.to_string(),
+                )
+            })?;
+
+            source
+        } else {
+            std::path::absolute(path).map_err(|e| {
+                OwlError::UriError(
+                    format!("'{}': could not resolve path", path.to_string_lossy()),
+                    e.to_string(),
+                )
+            })?
+        };
+
+        let mut cmd = self.build_cmd(&source, out_dir)?;
+        cmd.current_dir(out_dir);
+
+        Ok(cmd)
+    }
+
+    fn build(&self, path: &Path, config: &OwlConfig) -> Result<BuildLog> {
+        let out_dir = build_out_dir()?;
+        let build_result = self.build_in(path, &out_dir, config);
+
+        if build_result.is_err() {
+            let _ = fs::remove_dir_all(&out_dir);
+        }
+
+        build_result
+    }
+
+    fn build_in(&self, path: &Path, out_dir: &Path, config: &OwlConfig) -> Result<BuildLog> {
+        let mut cmd = self.out_of_tree_cmd(path, out_dir)?;
+        cmd.args(&config.build_args);
+
+        let output = in_docker_image(self.name(), cmd, config)?
             .output()
             .expect("[build] failed to spawn");
 
@@ -339,10 +846,8 @@ pub trait ProgLang {
                 })?
                 .to_string();
 
-            let parent = path.parent().ok_or(OwlError::FileError(
-                format!("'{}': has no parent dir", path.to_string_lossy()),
-                "".into(),
-            ))?;
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            let warnings = count_warnings(&stderr);
 
             let target_stem =
                 path.file_stem()
@@ -352,10 +857,19 @@ pub trait ProgLang {
                         "".into(),
                     ))?;
 
+            let build_files = self
+                .build_files(out_dir, target_stem)
+                .into_iter()
+                .flatten()
+                .chain([out_dir.to_path_buf()])
+                .collect();
+
             Ok(BuildLog {
-                target: self.target_path(parent, target_stem),
+                target: self.target_path(out_dir, target_stem),
                 stdout,
-                build_files: self.build_files(parent, target_stem),
+                stderr,
+                warnings,
+                build_files: Some(build_files),
             })
         } else {
             let mut stderr = String::from_utf8(output.stderr)
@@ -367,7 +881,7 @@ pub trait ProgLang {
                 })?
                 .to_string();
 
-            stderr.push_str("(run program manually for stack trace)");
+            stderr.push_str(STACK_TRACE_HINT);
 
             Err(OwlError::ProcessError(
                 "'build': exit with status failed".into(),
@@ -376,15 +890,56 @@ pub trait ProgLang {
         }
     }
 
-    fn command_exists(&self) -> bool {
-        self.version().is_ok()
+    fn command_exists(&self, config: &OwlConfig) -> bool {
+        self.version(config).is_ok()
     }
 
-    fn version(&self) -> Result<String> {
-        let output = self
-            .version_cmd()?
+    /// A command that only checks `prog` for errors without building it, if the language has one.
+    fn check_cmd(&self, prog: &Path, out_dir: &Path) -> Option<Command> {
+        let (_, cmd_str, args) = CHECK_CMDS.iter().find(|(name, ..)| *name == self.name())?;
+
+        let mut cmd = Command::new(cmd_str);
+        cmd.args(args.iter().map(|&arg| match arg {
+            "{prog}" => prog.as_os_str(),
+            "{out}" => out_dir.as_os_str(),
+            arg => OsStr::new(arg),
+        }));
+
+        if !args.contains(&"{prog}") {
+            cmd.arg(prog);
+        }
+
+        Some(cmd)
+    }
+
+    /// The command line used to build `prog`, if the language has a build step.
+    fn build_line(&self, prog: &Path, extra_args: &[String]) -> Option<String> {
+        if !self.should_build() {
+            return None;
+        }
+
+        let prog_name = prog.file_name()?;
+        let mut cmd = self.build_cmd(Path::new(prog_name), Path::new(".")).ok()?;
+        cmd.args(extra_args);
+
+        Some(
+            std::iter::once(cmd.get_program())
+                .chain(cmd.get_args())
+                .map(OsStr::to_string_lossy)
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+
+    fn version(&self, config: &OwlConfig) -> Result<String> {
+        let output = in_docker_image(self.name(), self.version_cmd()?, config)?
             .output()
-            .expect("[version] failed to spawn");
+            .map_err(|e| {
+                OwlError::ProcessError(
+                    format!("'{} version': failed to spawn", self.name()),
+                    e.to_string(),
+                )
+            })?;
 
         if output.status.success() {
             Ok(String::from_utf8(output.stdout)
@@ -412,18 +967,30 @@ pub trait ProgLang {
         }
     }
 
-    fn run(&self, path: &Path) -> Result<(String, Duration)> {
-        self.run_it(path, None)
+    fn run(&self, path: &Path, config: &OwlConfig) -> Result<(Vec<u8>, Duration)> {
+        let path = run_path(path)?;
+
+        self.run_it(&path, None, config)
     }
 
-    fn run_with_stdin(&self, path: &Path, input: &str) -> Result<(String, Duration)> {
-        self.run_it(path, Some(input))
+    fn run_with_stdin(
+        &self,
+        path: &Path,
+        input: &[u8],
+        config: &OwlConfig,
+    ) -> Result<(Vec<u8>, Duration)> {
+        let path = run_path(path)?;
+
+        self.run_it(&path, Some(input), config)
     }
 }
 
 pub struct BuildLog {
     pub target: PathBuf,
     pub stdout: String,
+    /// Diagnostics from a successful build (usually warnings).
+    pub stderr: String,
+    pub warnings: usize,
     pub build_files: Option<Vec<PathBuf>>,
 }
 
@@ -441,14 +1008,20 @@ struct ComptimeLang {
     build_cmd_str: &'static str,
     build_args: &'static [&'static str],
     exe_flag: Option<(&'static str, ArgsPosition)>,
+    out_dir_flag: Option<&'static str>,
     fn_build_files: Option<fn(&str) -> Vec<String>>,
 }
 
 impl ProgLang for ComptimeLang {
-    fn build_cmd(&self, path: &Path) -> Result<Command> {
+    fn build_cmd(&self, path: &Path, out_dir: &Path) -> Result<Command> {
         let mut cmd = Command::new(self.build_cmd_str);
         cmd.args(self.build_args);
 
+        if let Some(flag) = self.out_dir_flag {
+            cmd.arg(flag);
+            cmd.arg(out_dir);
+        }
+
         let target_stem = path
             .file_stem()
             .and_then(OsStr::to_str)
@@ -462,17 +1035,18 @@ impl ProgLang for ComptimeLang {
                 cmd.arg(path);
             }
 
-            if flag.contains('=') || flag.contains(':') {
-                let exe_arg = format!("{}{}", flag, target_stem);
+            let target = out_dir.join(target_stem);
 
-                if exe_arg.contains(' ') {
-                    let split = exe_arg.split(' ').collect::<Vec<&str>>();
-                    cmd.args(split);
-                } else {
-                    cmd.arg(exe_arg);
-                }
+            if flag.ends_with('=') || flag.ends_with(':') {
+                let (pre_args, flag) = flag.rsplit_once(' ').unwrap_or(("", flag));
+                cmd.args(pre_args.split_whitespace());
+
+                let mut exe_arg = OsString::from(flag);
+                exe_arg.push(&target);
+                cmd.arg(exe_arg);
             } else {
-                cmd.args([flag, target_stem]);
+                cmd.arg(flag);
+                cmd.arg(target);
             }
 
             if pos == ArgsPosition::Pre {
@@ -485,13 +1059,13 @@ impl ProgLang for ComptimeLang {
         Ok(cmd)
     }
 
-    fn build_files(&self, _: &Path, target_stem: &str) -> Option<Vec<PathBuf>> {
+    fn build_files(&self, out_dir: &Path, target_stem: &str) -> Option<Vec<PathBuf>> {
         self.fn_build_files
             .map(|get_build_files| (get_build_files)(target_stem))
             .map(|build_names| {
                 build_names
                     .into_iter()
-                    .map(PathBuf::from)
+                    .map(|build_name| out_dir.join(build_name))
                     .collect::<Vec<PathBuf>>()
             })
     }
@@ -500,10 +1074,17 @@ impl ProgLang for ComptimeLang {
         self.name
     }
 
-    fn run_it(&self, path: &Path, stdin: Option<&str>) -> Result<(String, Duration)> {
+    fn run_it(
+        &self,
+        path: &Path,
+        stdin: Option<&[u8]>,
+        config: &OwlConfig,
+    ) -> Result<(Vec<u8>, Duration)> {
+        let run_options = config.run_options(self.name());
+
         match stdin {
-            Some(input) => cmd_utils::run_binary_with_stdin(path, input),
-            None => cmd_utils::run_binary(path),
+            Some(input) => cmd_utils::run_binary_with_stdin(path, input, &run_options),
+            None => cmd_utils::run_binary(path, &run_options),
         }
     }
 
@@ -511,8 +1092,8 @@ impl ProgLang for ComptimeLang {
         true
     }
 
-    fn target_path(&self, _: &Path, target_stem: &str) -> PathBuf {
-        PathBuf::from(target_stem)
+    fn target_path(&self, out_dir: &Path, target_stem: &str) -> PathBuf {
+        out_dir.join(target_stem)
     }
 
     fn version_cmd(&self) -> Result<Command> {
@@ -531,7 +1112,7 @@ pub struct RuntimeLang {
 }
 
 impl ProgLang for RuntimeLang {
-    fn build_cmd(&self, path: &Path) -> Result<Command> {
+    fn build_cmd(&self, path: &Path, _: &Path) -> Result<Command> {
         Err(OwlError::ProcessError(
             format!(
                 "No build command ({}) for '{}'",
@@ -550,14 +1131,23 @@ impl ProgLang for RuntimeLang {
         self.name
     }
 
-    fn run_it(&self, path: &Path, stdin: Option<&str>) -> Result<(String, Duration)> {
+    fn run_it(
+        &self,
+        path: &Path,
+        stdin: Option<&[u8]>,
+        config: &OwlConfig,
+    ) -> Result<(Vec<u8>, Duration)> {
         let mut run_cmd = Command::new(self.cmd_str);
         run_cmd.args(self.cmd_args);
         run_cmd.arg(path);
 
+        let run_options = config.run_options(self.name());
+
         match stdin {
-            Some(input) => cmd_utils::run_cmd_with_stdin(self.cmd_str, run_cmd, input),
-            None => cmd_utils::run_cmd(self.cmd_str, run_cmd),
+            Some(input) => {
+                cmd_utils::run_cmd_with_stdin(self.cmd_str, run_cmd, input, &run_options)
+            }
+            None => cmd_utils::run_cmd(self.cmd_str, run_cmd, &run_options),
         }
     }
 
@@ -584,29 +1174,34 @@ pub struct CustomLang {
     name: &'static str,
     build_cmd_str: &'static str,
     build_args: &'static [&'static str],
+    out_dir_flag: &'static str,
+    classpath_flag: Option<&'static str>,
     run_cmd_str: &'static str,
     run_args: &'static [&'static str],
+    jvm_arg_prefix: Option<&'static str>,
     ver_arg: &'static str,
     fn_build_files: Option<fn(&str) -> Vec<String>>,
     fn_target_name: fn(&str) -> String,
 }
 
 impl ProgLang for CustomLang {
-    fn build_cmd(&self, path: &Path) -> Result<Command> {
+    fn build_cmd(&self, path: &Path, out_dir: &Path) -> Result<Command> {
         let mut cmd = Command::new(self.build_cmd_str);
         cmd.args(self.build_args);
+        cmd.arg(self.out_dir_flag);
+        cmd.arg(out_dir);
         cmd.arg(path);
 
         Ok(cmd)
     }
 
-    fn build_files(&self, _: &Path, target_stem: &str) -> Option<Vec<PathBuf>> {
+    fn build_files(&self, out_dir: &Path, target_stem: &str) -> Option<Vec<PathBuf>> {
         self.fn_build_files
             .map(|get_build_files| (get_build_files)(target_stem))
             .map(|build_names| {
                 build_names
                     .into_iter()
-                    .map(PathBuf::from)
+                    .map(|build_name| out_dir.join(build_name))
                     .collect::<Vec<PathBuf>>()
             })
     }
@@ -615,23 +1210,50 @@ impl ProgLang for CustomLang {
         self.name
     }
 
-    fn run_it(&self, path: &Path, stdin: Option<&str>) -> Result<(String, Duration)> {
+    fn run_it(
+        &self,
+        path: &Path,
+        stdin: Option<&[u8]>,
+        config: &OwlConfig,
+    ) -> Result<(Vec<u8>, Duration)> {
         let mut cmd = Command::new(self.run_cmd_str);
         cmd.args(self.run_args);
 
-        let target_stem = path
-            .file_stem()
-            .and_then(OsStr::to_str)
-            .ok_or(OwlError::UriError(
-                format!("'{}': has no file stem", path.to_string_lossy()),
-                "".into(),
-            ))?;
+        if let Some(prefix) = self.jvm_arg_prefix {
+            cmd.args(
+                config
+                    .jvm_args
+                    .iter()
+                    .map(|arg| format!("{}{}", prefix, arg)),
+            );
+        }
 
-        cmd.arg(target_stem);
+        match self.classpath_flag {
+            Some(flag) => {
+                let target_stem =
+                    path.file_stem()
+                        .and_then(OsStr::to_str)
+                        .ok_or(OwlError::UriError(
+                            format!("'{}': has no file stem", path.to_string_lossy()),
+                            "".into(),
+                        ))?;
+
+                cmd.arg(flag);
+                cmd.arg(build_dir(path));
+                cmd.arg(target_stem);
+            }
+            None => {
+                cmd.arg(path);
+            }
+        }
+
+        let run_options = config.run_options(self.name());
 
         match stdin {
-            Some(input) => cmd_utils::run_cmd_with_stdin(self.run_cmd_str, cmd, input),
-            None => cmd_utils::run_cmd(self.run_cmd_str, cmd),
+            Some(input) => {
+                cmd_utils::run_cmd_with_stdin(self.run_cmd_str, cmd, input, &run_options)
+            }
+            None => cmd_utils::run_cmd(self.run_cmd_str, cmd, &run_options),
         }
     }
 
@@ -639,8 +1261,8 @@ impl ProgLang for CustomLang {
         true
     }
 
-    fn target_path(&self, _: &Path, target_stem: &str) -> PathBuf {
-        PathBuf::from((self.fn_target_name)(target_stem))
+    fn target_path(&self, out_dir: &Path, target_stem: &str) -> PathBuf {
+        out_dir.join((self.fn_target_name)(target_stem))
     }
 
     fn version_cmd(&self) -> Result<Command> {
@@ -676,7 +1298,8 @@ impl ErlLang {
 }
 
 impl ProgLang for ErlLang {
-    fn build_cmd(&self, path: &Path) -> Result<Command> {
+    // `erl -compile` writes the .beam file to the working dir, i.e. the out dir
+    fn build_cmd(&self, path: &Path, _: &Path) -> Result<Command> {
         let mut cmd = Command::new(self.cmd_str);
         cmd.args(self.build_args);
         cmd.arg(path);
@@ -692,8 +1315,15 @@ impl ProgLang for ErlLang {
         self.name
     }
 
-    fn run_it(&self, path: &Path, stdin: Option<&str>) -> Result<(String, Duration)> {
+    fn run_it(
+        &self,
+        path: &Path,
+        stdin: Option<&[u8]>,
+        config: &OwlConfig,
+    ) -> Result<(Vec<u8>, Duration)> {
         let mut cmd = Command::new(self.cmd_str);
+        cmd.arg("-pa");
+        cmd.arg(build_dir(path));
         cmd.args(self.pre_run_args);
 
         let target_stem = path
@@ -707,9 +1337,11 @@ impl ProgLang for ErlLang {
         cmd.arg(target_stem);
         cmd.args(self.post_run_args);
 
+        let run_options = config.run_options(self.name());
+
         match stdin {
-            Some(input) => cmd_utils::run_cmd_with_stdin(self.cmd_str, cmd, input),
-            None => cmd_utils::run_cmd(self.cmd_str, cmd),
+            Some(input) => cmd_utils::run_cmd_with_stdin(self.cmd_str, cmd, input, &run_options),
+            None => cmd_utils::run_cmd(self.cmd_str, cmd, &run_options),
         }
     }
 
@@ -717,8 +1349,8 @@ impl ProgLang for ErlLang {
         true
     }
 
-    fn target_path(&self, _: &Path, target_stem: &str) -> PathBuf {
-        PathBuf::from((self.fn_target_name)(target_stem))
+    fn target_path(&self, out_dir: &Path, target_stem: &str) -> PathBuf {
+        out_dir.join((self.fn_target_name)(target_stem))
     }
 
     fn version_cmd(&self) -> Result<Command> {
@@ -750,7 +1382,7 @@ impl OcamlLang {
 }
 
 impl ProgLang for OcamlLang {
-    fn build_cmd(&self, path: &Path) -> Result<Command> {
+    fn build_cmd(&self, path: &Path, out_dir: &Path) -> Result<Command> {
         let mut cmd = Command::new(self.build_cmd_str);
         cmd.args(self.build_args);
         cmd.arg(path);
@@ -763,12 +1395,13 @@ impl ProgLang for OcamlLang {
                 "".into(),
             ))?;
 
-        cmd.args(["-o", target_stem]);
+        cmd.arg("-o");
+        cmd.arg(out_dir.join(target_stem));
 
         Ok(cmd)
     }
 
-    fn build_files(&self, parent: &Path, target_stem: &str) -> Option<Vec<PathBuf>> {
+    fn build_files(&self, out_dir: &Path, target_stem: &str) -> Option<Vec<PathBuf>> {
         let output_files = vec![
             format!("{}.cmi", target_stem),
             format!("{}.cmx", target_stem),
@@ -777,12 +1410,7 @@ impl ProgLang for OcamlLang {
 
         let output_paths = output_files
             .into_iter()
-            .map(|build_name| {
-                let mut path = parent.to_path_buf();
-                path.push(build_name);
-
-                path
-            })
+            .map(|build_name| out_dir.join(build_name))
             .collect::<Vec<PathBuf>>();
 
         if output_paths.is_empty() {
@@ -796,19 +1424,31 @@ impl ProgLang for OcamlLang {
         self.name
     }
 
-    fn run_it(&self, path: &Path, stdin: Option<&str>) -> Result<(String, Duration)> {
+    fn run_it(
+        &self,
+        path: &Path,
+        stdin: Option<&[u8]>,
+        config: &OwlConfig,
+    ) -> Result<(Vec<u8>, Duration)> {
+        let run_options = config.run_options(self.name());
+
         match stdin {
-            Some(input) => cmd_utils::run_binary_with_stdin(path, input),
-            None => cmd_utils::run_binary(path),
+            Some(input) => cmd_utils::run_binary_with_stdin(path, input, &run_options),
+            None => cmd_utils::run_binary(path, &run_options),
         }
     }
 
+    // ocamlopt writes the .cmi/.cmx/.o files beside the source, whatever `-o` says
+    fn builds_beside_source(&self) -> bool {
+        true
+    }
+
     fn should_build(&self) -> bool {
         true
     }
 
-    fn target_path(&self, _: &Path, target_stem: &str) -> PathBuf {
-        PathBuf::from(target_stem)
+    fn target_path(&self, out_dir: &Path, target_stem: &str) -> PathBuf {
+        out_dir.join(target_stem)
     }
 
     fn version_cmd(&self) -> Result<Command> {
@@ -818,3 +1458,118 @@ impl ProgLang for OcamlLang {
         Ok(cmd)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal program for every language with a build step.
+    const BUILT_SOURCES: &[(&str, &str)] = &[
+        (
+            "hello.adb",
+            "procedure Hello is\nbegin\n   null;\nend Hello;\n",
+        ),
+        ("hello.c", "int main(void) { return 0; }\n"),
+        ("hello.cpp", "int main() { return 0; }\n"),
+        ("hello.cr", "puts 1\n"),
+        ("hello.dart", "void main() {}\n"),
+        (
+            "hello.erl",
+            "-module(hello).\n-export([start/0]).\nstart() -> ok.\n",
+        ),
+        ("hello.go", "package main\n\nfunc main() {}\n"),
+        ("hello.hs", "main = return ()\n"),
+        (
+            "Hello.java",
+            "class Hello { public static void main(String[] args) {} }\n",
+        ),
+        ("hello.kt", "fun main() {}\n"),
+        ("hello.ml", "let () = ()\n"),
+        ("hello.odin", "package main\n\nmain :: proc() {}\n"),
+        ("hello.rs", "fn main() {}\n"),
+        ("hello.ts", "console.log(1);\n"),
+        ("hello.zig", "pub fn main() void {}\n"),
+    ];
+
+    /// A source dir holding only `file_name` and an empty out dir beside it.
+    fn test_dirs(file_name: &str, source: &str) -> (PathBuf, PathBuf, PathBuf) {
+        let test_dir = std::env::temp_dir().join(format!(
+            "owlgo-test-{}-{}",
+            process::id(),
+            file_name.replace('.', "-")
+        ));
+        let src_dir = test_dir.join("src");
+        let out_dir = test_dir.join("out");
+
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::create_dir_all(&out_dir).unwrap();
+        fs::write(src_dir.join(file_name), source).unwrap();
+
+        (test_dir, src_dir, out_dir)
+    }
+
+    #[test]
+    fn builds_are_pointed_out_of_tree() {
+        for (file_name, source) in BUILT_SOURCES {
+            let (test_dir, src_dir, out_dir) = test_dirs(file_name, source);
+            let src_path = src_dir.join(file_name);
+
+            let lang = check_prog_lang(&src_path).unwrap();
+            let target_stem = src_path.file_stem().and_then(OsStr::to_str).unwrap();
+
+            let cmd = lang.out_of_tree_cmd(&src_path, &out_dir).unwrap();
+            assert_eq!(
+                cmd.get_current_dir(),
+                Some(out_dir.as_path()),
+                "{}",
+                file_name
+            );
+
+            for arg in cmd.get_args().map(OsStr::to_string_lossy) {
+                assert!(
+                    arg == src_path.to_string_lossy() || !arg.contains(&*src_dir.to_string_lossy()),
+                    "{}: '{}' points into the source dir",
+                    file_name,
+                    arg
+                );
+            }
+
+            assert!(
+                lang.target_path(&out_dir, target_stem)
+                    .starts_with(&out_dir),
+                "{}",
+                file_name
+            );
+
+            for build_file in lang.build_files(&out_dir, target_stem).unwrap_or_default() {
+                assert!(build_file.starts_with(&out_dir), "{}", file_name);
+            }
+
+            fs::remove_dir_all(&test_dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn build_leaves_the_source_dir_clean() {
+        // rustc is always at hand where the tests run
+        let (test_dir, src_dir, out_dir) = test_dirs("clean.rs", "fn main() {}\n");
+        let src_path = src_dir.join("clean.rs");
+
+        let lang = check_prog_lang(&src_path).unwrap();
+        let build_log = lang
+            .build_in(&src_path, &out_dir, &OwlConfig::default())
+            .unwrap();
+
+        assert!(build_log.target.exists());
+        assert!(build_log.target.starts_with(&out_dir));
+
+        let src_files = fs::read_dir(&src_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<OsString>>();
+        assert_eq!(src_files, [OsString::from("clean.rs")]);
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+use super::judge_utils::LimitExceeded;
+use crate::common::Result;
+use serde_json::json;
+use std::future::Future;
+use std::io::{Write, stderr, stdout};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const BAR_WIDTH: usize = 24;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ProgressMode {
+    #[default]
+    Text,
+    Bar,
+    Jsonl,
+}
+
+/// A step of a long operation (a quest download, a test case, a whole quest).
+pub enum ProgressEvent<'a> {
+    Started {
+        task: &'a str,
+        name: &'a str,
+        step: Option<(usize, usize)>,
+    },
+    Finished {
+        task: &'a str,
+        name: &'a str,
+        step: Option<(usize, usize)>,
+        ok: bool,
+        elapsed: Option<Duration>,
+        /// The limit a failed case went over, if that's why it failed.
+        exceeded: Option<LimitExceeded>,
+    },
+    Summary {
+        task: &'a str,
+        name: &'a str,
+        passed: usize,
+        failed: usize,
+        elapsed: Duration,
+    },
+}
+
+struct Reporter {
+    mode: ProgressMode,
+    started: usize,
+    finished: usize,
+}
+
+static REPORTER: Mutex<Reporter> = Mutex::new(Reporter {
+    mode: ProgressMode::Text,
+    started: 0,
+    finished: 0,
+});
+
+pub fn set_progress(mode: ProgressMode) {
+    REPORTER.lock().expect("progress lock").mode = mode;
+}
+
+pub fn progress_mode() -> ProgressMode {
+    REPORTER.lock().expect("progress lock").mode
+}
+
+pub fn report(event: ProgressEvent) {
+    let mut reporter = REPORTER.lock().expect("progress lock");
+
+    match reporter.mode {
+        ProgressMode::Text => {}
+        ProgressMode::Bar => reporter.draw_bar(&event),
+        ProgressMode::Jsonl => {
+            println!("{}", event_json(&event));
+            let _ = stdout().flush();
+        }
+    }
+}
+
+/// Reports `fut` as a started/finished pair of events.
+pub async fn track<T>(task: &str, name: &str, fut: impl Future<Output = Result<T>>) -> Result<T> {
+    report(ProgressEvent::Started {
+        task,
+        name,
+        step: None,
+    });
+
+    let start = Instant::now();
+    let result = fut.await;
+
+    report(ProgressEvent::Finished {
+        task,
+        name,
+        step: None,
+        ok: result.is_ok(),
+        elapsed: Some(start.elapsed()),
+        exceeded: None,
+    });
+
+    result
+}
+
+impl Reporter {
+    fn draw_bar(&mut self, event: &ProgressEvent) {
+        let (name, done, total) = match event {
+            ProgressEvent::Started { name, step, .. } => {
+                self.started += 1;
+                let (done, total) = step
+                    .map(|(index, total)| (index - 1, total))
+                    .unwrap_or((self.finished, self.started));
+                (name, done, total)
+            }
+            ProgressEvent::Finished { name, step, .. } => {
+                self.finished += 1;
+                let (done, total) = step.unwrap_or((self.finished, self.started));
+                (name, done, total)
+            }
+            ProgressEvent::Summary { .. } => return,
+        };
+
+        let filled = BAR_WIDTH * done / total.max(1);
+
+        eprint!(
+            "\r\x1b[2K[{}{}] {}/{} {}",
+            "#".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled),
+            done,
+            total,
+            name
+        );
+
+        if done == total {
+            eprintln!();
+            self.started = 0;
+            self.finished = 0;
+        }
+
+        let _ = stderr().flush();
+    }
+}
+
+fn event_json(event: &ProgressEvent) -> String {
+    match event {
+        ProgressEvent::Started { task, name, step } => json!({
+            "event": "started",
+            "task": task,
+            "name": name,
+            "index": step.map(|(index, _)| index),
+            "total": step.map(|(_, total)| total),
+        }),
+        ProgressEvent::Finished {
+            task,
+            name,
+            step,
+            ok,
+            elapsed,
+            exceeded,
+        } => json!({
+            "event": "finished",
+            "task": task,
+            "name": name,
+            "index": step.map(|(index, _)| index),
+            "total": step.map(|(_, total)| total),
+            "ok": ok,
+            "elapsed_ms": elapsed.map(|d| d.as_millis() as u64),
+            "exceeded": exceeded.map(|exceeded| match exceeded {
+                LimitExceeded::Time { used, limit } => json!({
+                    "kind": exceeded.kind(),
+                    "used_ms": used.as_millis() as u64,
+                    "limit_ms": limit.as_millis() as u64,
+                    "message": exceeded.to_string(),
+                }),
+                LimitExceeded::Memory { used, limit } => json!({
+                    "kind": exceeded.kind(),
+                    "used_bytes": used,
+                    "limit_bytes": limit,
+                    "message": exceeded.to_string(),
+                }),
+            }),
+        }),
+        ProgressEvent::Summary {
+            task,
+            name,
+            passed,
+            failed,
+            elapsed,
+        } => json!({
+            "event": "summary",
+            "task": task,
+            "name": name,
+            "passed": passed,
+            "failed": failed,
+            "elapsed_ms": elapsed.as_millis() as u64,
+        }),
+    }
+    .to_string()
+}
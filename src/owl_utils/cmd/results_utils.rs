@@ -0,0 +1,254 @@
+use super::judge_utils::{CaseResult, Verdict};
+use crate::common::{OwlError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Bumped only when a field is removed or changes meaning; fields may be added within a version,
+/// so readers should ignore the ones they don't know.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The document `quest --results <FILE>` writes for external tooling.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunResults {
+    pub schema_version: u32,
+    pub quest: String,
+    pub program: PathBuf,
+    pub passed: usize,
+    pub failed: usize,
+    /// The total time of the passed cases.
+    pub elapsed_ms: f64,
+    pub build_warnings: usize,
+    pub limits: RunLimits,
+    pub cases: Vec<CaseReport>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunLimits {
+    pub time_ms: Option<f64>,
+    pub memory_bytes: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CaseReport {
+    /// The case's file stem, e.g. `sample-1` for `sample-1.in`.
+    pub stem: String,
+    pub verdict: Verdict,
+    /// Wall time, also for failed cases when the run got that far.
+    pub elapsed_ms: Option<f64>,
+    pub cpu_ms: Option<f64>,
+    pub peak_memory_bytes: Option<u64>,
+    /// The directory with the failed case's input, expected and actual output, and stderr.
+    pub artifacts: Option<PathBuf>,
+}
+
+impl RunResults {
+    pub fn new(
+        quest_name: &str,
+        prog: &Path,
+        cases: &[CaseResult],
+        elapsed: Duration,
+        build_warnings: usize,
+        limits: (Option<Duration>, Option<u64>),
+    ) -> Self {
+        let passed = cases.iter().filter(|case| case.passed()).count();
+
+        RunResults {
+            schema_version: SCHEMA_VERSION,
+            quest: quest_name.to_string(),
+            program: prog.to_path_buf(),
+            passed,
+            failed: cases.len() - passed,
+            elapsed_ms: millis(elapsed),
+            build_warnings,
+            limits: RunLimits {
+                time_ms: limits.0.map(millis),
+                memory_bytes: limits.1,
+            },
+            cases: cases.iter().map(CaseReport::from).collect(),
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let results = serde_json::from_str::<RunResults>(json)
+            .map_err(|e| OwlError::Unsupported(format!("could not parse run results: {}", e)))?;
+
+        if results.schema_version > SCHEMA_VERSION {
+            return Err(OwlError::Unsupported(format!(
+                "run results have schema version {} (expected at most {})",
+                results.schema_version, SCHEMA_VERSION
+            )));
+        }
+
+        Ok(results)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("run results serialize to JSON")
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json() + "\n").map_err(|e| {
+            OwlError::FileError(
+                format!("could not write '{}'", path.to_string_lossy()),
+                e.to_string(),
+            )
+        })
+    }
+}
+
+impl From<&CaseResult> for CaseReport {
+    fn from(case: &CaseResult) -> Self {
+        CaseReport {
+            stem: case.name.clone(),
+            verdict: case.verdict,
+            elapsed_ms: case
+                .elapsed
+                .or(case.usage.map(|usage| usage.wall))
+                .map(millis),
+            cpu_ms: case.usage.map(|usage| millis(usage.cpu)),
+            peak_memory_bytes: case.usage.map(|usage| usage.peak_mem),
+            artifacts: case.artifacts.clone(),
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::owl_utils::cmd_utils::RunUsage;
+
+    const GOLDEN: &str = r#"{
+  "schema_version": 1,
+  "quest": "two_sum",
+  "program": "two_sum.cpp",
+  "passed": 1,
+  "failed": 1,
+  "elapsed_ms": 3.5,
+  "build_warnings": 0,
+  "limits": {
+    "time_ms": 1000.0,
+    "memory_bytes": null
+  },
+  "cases": [
+    {
+      "stem": "1",
+      "verdict": "passed",
+      "elapsed_ms": 3.5,
+      "cpu_ms": 2.0,
+      "peak_memory_bytes": 3801088,
+      "artifacts": null
+    },
+    {
+      "stem": "2",
+      "verdict": "time_limit",
+      "elapsed_ms": 1001.25,
+      "cpu_ms": 1000.5,
+      "peak_memory_bytes": 3792896,
+      "artifacts": "/tmp/.last_run/two_sum/2"
+    }
+  ]
+}"#;
+
+    fn golden_results() -> RunResults {
+        RunResults {
+            schema_version: 1,
+            quest: "two_sum".into(),
+            program: "two_sum.cpp".into(),
+            passed: 1,
+            failed: 1,
+            elapsed_ms: 3.5,
+            build_warnings: 0,
+            limits: RunLimits {
+                time_ms: Some(1000.0),
+                memory_bytes: None,
+            },
+            cases: vec![
+                CaseReport {
+                    stem: "1".into(),
+                    verdict: Verdict::Passed,
+                    elapsed_ms: Some(3.5),
+                    cpu_ms: Some(2.0),
+                    peak_memory_bytes: Some(3801088),
+                    artifacts: None,
+                },
+                CaseReport {
+                    stem: "2".into(),
+                    verdict: Verdict::TimeLimit,
+                    elapsed_ms: Some(1001.25),
+                    cpu_ms: Some(1000.5),
+                    peak_memory_bytes: Some(3792896),
+                    artifacts: Some("/tmp/.last_run/two_sum/2".into()),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn results_round_trip() {
+        let usage = RunUsage {
+            wall: Duration::from_millis(4),
+            cpu: Duration::from_millis(2),
+            peak_mem: 3801088,
+        };
+        let cases = [
+            CaseResult {
+                name: "sample-1".into(),
+                verdict: Verdict::Passed,
+                elapsed: Some(Duration::from_millis(3)),
+                usage: Some(usage),
+                artifacts: None,
+            },
+            CaseResult {
+                name: "sample-2".into(),
+                verdict: Verdict::MemoryLimit,
+                elapsed: None,
+                usage: Some(usage),
+                artifacts: Some("/tmp/.last_run/add/sample-2".into()),
+            },
+            CaseResult {
+                name: "sample-3".into(),
+                verdict: Verdict::Error,
+                elapsed: None,
+                usage: None,
+                artifacts: None,
+            },
+        ];
+
+        let results = RunResults::new(
+            "add",
+            Path::new("add.cpp"),
+            &cases,
+            Duration::from_millis(3),
+            2,
+            (Some(Duration::from_secs(1)), Some(256 * 1024 * 1024)),
+        );
+
+        assert_eq!((results.passed, results.failed), (1, 2));
+        assert_eq!(RunResults::from_json(&results.to_json()).unwrap(), results);
+    }
+
+    #[test]
+    fn results_match_golden_json() {
+        assert_eq!(golden_results().to_json(), GOLDEN);
+        assert_eq!(RunResults::from_json(GOLDEN).unwrap(), golden_results());
+    }
+
+    #[test]
+    fn newer_schema_is_rejected() {
+        let results = RunResults {
+            schema_version: SCHEMA_VERSION + 1,
+            ..golden_results()
+        };
+
+        assert!(matches!(
+            RunResults::from_json(&results.to_json()),
+            Err(OwlError::Unsupported(_))
+        ));
+    }
+}
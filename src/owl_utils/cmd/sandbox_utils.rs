@@ -0,0 +1,288 @@
+use super::cmd_utils;
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use toml_edit::Item;
+
+static SANDBOX_CHECKED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SandboxBackend {
+    Bubblewrap,
+    Nsjail,
+}
+
+impl SandboxBackend {
+    pub fn program(&self) -> &'static str {
+        match self {
+            SandboxBackend::Bubblewrap => "bwrap",
+            SandboxBackend::Nsjail => "nsjail",
+        }
+    }
+}
+
+impl TryFrom<&str> for SandboxBackend {
+    type Error = OwlError;
+
+    fn try_from(backend: &str) -> Result<Self> {
+        match backend {
+            "bwrap" | "bubblewrap" => Ok(SandboxBackend::Bubblewrap),
+            "nsjail" => Ok(SandboxBackend::Nsjail),
+            _ => Err(OwlError::Unsupported(format!(
+                "'{}': unknown sandbox backend (expected 'bwrap' or 'nsjail')",
+                backend
+            ))),
+        }
+    }
+}
+
+/// How solutions are isolated on Linux (the manifest's `[sandbox]` table): a read-only view of
+/// the filesystem with a private `/tmp`, no network unless allowed, and an optional seccomp
+/// filter (a compiled BPF program for bwrap, a Kafel policy for nsjail).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sandbox {
+    pub backend: SandboxBackend,
+    pub network: bool,
+    pub seccomp: Option<PathBuf>,
+}
+
+impl Sandbox {
+    pub fn from_item(config_path: &Path, item: Option<&Item>) -> Result<Option<Self>> {
+        let Some(item) = item else {
+            return Ok(None);
+        };
+
+        let invalid = |key: &str, expected: &str| {
+            OwlError::TomlError(
+                format!(
+                    "Invalid entry 'sandbox.{}' in '{}'",
+                    key,
+                    config_path.to_string_lossy()
+                ),
+                format!("expected {}", expected),
+            )
+        };
+
+        let sandbox_table = item.as_table_like().ok_or(OwlError::TomlError(
+            format!(
+                "Invalid entry 'sandbox' in '{}'",
+                config_path.to_string_lossy()
+            ),
+            "expected a table".into(),
+        ))?;
+
+        let backend = match sandbox_table.get("backend") {
+            Some(backend) => match backend.as_str() {
+                Some("none") => return Ok(None),
+                Some(backend) => SandboxBackend::try_from(backend)?,
+                None => return Err(invalid("backend", "a string")),
+            },
+            None => return Ok(None),
+        };
+
+        let network = match sandbox_table.get("network") {
+            Some(network) => network
+                .as_bool()
+                .ok_or_else(|| invalid("network", "a boolean"))?,
+            None => false,
+        };
+
+        let seccomp = match sandbox_table.get("seccomp") {
+            Some(seccomp) => Some(PathBuf::from(
+                seccomp
+                    .as_str()
+                    .ok_or_else(|| invalid("seccomp", "a path"))?,
+            )),
+            None => None,
+        };
+
+        if !cfg!(target_os = "linux") {
+            warn(OwlWarning::Skipped(format!(
+                "'{}': sandboxing is only supported on Linux",
+                backend.program()
+            )));
+
+            return Ok(None);
+        }
+
+        Ok(Some(Sandbox {
+            backend,
+            network,
+            seccomp,
+        }))
+    }
+
+    /// Whether the backend is installed, so a missing one isn't reported as the program it wraps.
+    pub fn check(&self) -> Result<()> {
+        Command::new(self.backend.program())
+            .arg("--help")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|_| ())
+            .map_err(|_| {
+                OwlError::CommandNotFound(format!(
+                    "'{}': command not found (needed by the manifest's [sandbox] table)",
+                    self.backend.program()
+                ))
+            })
+    }
+}
+
+/// Wraps `cmd` in `sandbox` (if any), checking once that its backend is installed.
+pub fn in_sandbox(cmd: Command, sandbox: Option<&Sandbox>) -> Result<Command> {
+    match sandbox {
+        Some(sandbox) => {
+            if !SANDBOX_CHECKED.load(Ordering::SeqCst) {
+                sandbox.check()?;
+                SANDBOX_CHECKED.store(true, Ordering::SeqCst);
+            }
+
+            sandbox_cmd(sandbox, &cmd)
+        }
+        None => Ok(cmd),
+    }
+}
+
+/// Rewrites `cmd` to run under the sandbox's backend. Only a scratch dir (an explicit working
+/// dir) is writable; the working dir and the dirs of absolute paths it is given are otherwise
+/// mounted read-only over a private `/tmp`.
+pub fn sandbox_cmd(sandbox: &Sandbox, cmd: &Command) -> Result<Command> {
+    let (cwd, dirs) = cmd_utils::cmd_dirs(cmd)?;
+    let writable = cmd.get_current_dir().is_some();
+
+    let mut wrapped = Command::new(sandbox.backend.program());
+
+    match sandbox.backend {
+        SandboxBackend::Bubblewrap => {
+            wrapped.args([
+                "--ro-bind",
+                "/",
+                "/",
+                "--dev",
+                "/dev",
+                "--proc",
+                "/proc",
+                "--tmpfs",
+                "/tmp",
+                "--unshare-all",
+                "--die-with-parent",
+            ]);
+
+            if sandbox.network {
+                wrapped.arg("--share-net");
+            }
+
+            for dir in dirs {
+                wrapped.arg("--ro-bind").arg(&dir).arg(&dir);
+            }
+
+            wrapped
+                .arg(if writable { "--bind" } else { "--ro-bind" })
+                .arg(&cwd)
+                .arg(&cwd)
+                .arg("--chdir")
+                .arg(&cwd);
+
+            if let Some(seccomp) = &sandbox.seccomp {
+                pass_seccomp_fd(&mut wrapped, seccomp)?;
+            }
+        }
+        SandboxBackend::Nsjail => {
+            // nsjail's default limits (e.g. 1 MB files, 600 s) would preempt owlgo's own
+            wrapped.args([
+                "--mode",
+                "o",
+                "--chroot",
+                "/",
+                "--really_quiet",
+                "--keep_env",
+                "--time_limit",
+                "0",
+                "--rlimit_as",
+                "max",
+                "--rlimit_cpu",
+                "max",
+                "--rlimit_fsize",
+                "max",
+                "--rlimit_nofile",
+                "max",
+                "--tmpfsmount",
+                "/tmp",
+            ]);
+
+            if sandbox.network {
+                wrapped.arg("--disable_clone_newnet");
+            }
+
+            for dir in dirs {
+                wrapped.arg("--bindmount_ro").arg(&dir);
+            }
+
+            wrapped
+                .arg(if writable {
+                    "--bindmount"
+                } else {
+                    "--bindmount_ro"
+                })
+                .arg(&cwd)
+                .arg("--cwd")
+                .arg(&cwd);
+
+            if let Some(seccomp) = &sandbox.seccomp {
+                wrapped.arg("--seccomp_policy").arg(seccomp);
+            }
+        }
+    }
+
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => wrapped.env(key, value),
+            None => wrapped.env_remove(key),
+        };
+    }
+
+    wrapped
+        .current_dir(&cwd)
+        .arg("--")
+        .arg(cmd.get_program())
+        .args(cmd.get_args());
+
+    Ok(wrapped)
+}
+
+/// bwrap reads its seccomp filter from an inherited file descriptor.
+#[cfg(target_os = "linux")]
+fn pass_seccomp_fd(wrapped: &mut Command, seccomp: &Path) -> Result<()> {
+    use std::os::fd::AsRawFd;
+    use std::os::unix::process::CommandExt;
+
+    let filter = std::fs::File::open(seccomp).map_err(|e| {
+        OwlError::FileError(
+            format!("could not open '{}'", seccomp.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    wrapped.arg("--seccomp").arg(filter.as_raw_fd().to_string());
+
+    // SAFETY: fcntl is async-signal-safe, and only clears close-on-exec on the filter's fd
+    unsafe {
+        wrapped.pre_exec(
+            move || match libc::fcntl(filter.as_raw_fd(), libc::F_SETFD, 0) {
+                -1 => Err(std::io::Error::last_os_error()),
+                _ => Ok(()),
+            },
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pass_seccomp_fd(_wrapped: &mut Command, _seccomp: &Path) -> Result<()> {
+    Err(OwlError::Unsupported(
+        "seccomp filters are only supported on Linux".into(),
+    ))
+}
@@ -0,0 +1,87 @@
+use super::judge_utils::CaseResult;
+use crate::common::{OwlError, Result};
+use rhai::{Array, Dynamic, Engine, INT, Map, Scope};
+use std::path::Path;
+
+pub fn run_script(
+    script_path: &Path,
+    quest: &str,
+    prog: &Path,
+    cases: &[CaseResult],
+) -> Result<()> {
+    if !script_path.exists() {
+        return Err(OwlError::FileError(
+            format!("'{}': no such script", script_path.to_string_lossy()),
+            "".into(),
+        ));
+    }
+
+    let engine = Engine::new();
+
+    let ast = engine
+        .compile_file(script_path.to_path_buf())
+        .map_err(|e| {
+            OwlError::ProcessError(
+                format!("'{}': failed to compile", script_path.to_string_lossy()),
+                e.to_string(),
+            )
+        })?;
+
+    let mut scope = Scope::new();
+    scope.push("results", results_map(quest, prog, cases));
+
+    let output = engine
+        .eval_ast_with_scope::<Dynamic>(&mut scope, &ast)
+        .map_err(|e| {
+            OwlError::ProcessError(
+                format!("'{}': script failed", script_path.to_string_lossy()),
+                e.to_string(),
+            )
+        })?;
+
+    if !output.is_unit() {
+        println!("{}", output);
+    }
+
+    Ok(())
+}
+
+fn results_map(quest: &str, prog: &Path, cases: &[CaseResult]) -> Map {
+    let passed = cases.iter().filter(|case| case.passed()).count();
+    let elapsed_ms: u128 = cases
+        .iter()
+        .filter_map(|case| case.elapsed)
+        .map(|elapsed| elapsed.as_millis())
+        .sum();
+
+    let case_maps = cases
+        .iter()
+        .map(|case| {
+            let mut case_map = Map::new();
+            case_map.insert("name".into(), case.name.clone().into());
+            case_map.insert("verdict".into(), verdict(case.passed()).into());
+            case_map.insert(
+                "elapsed_ms".into(),
+                case.elapsed
+                    .map(|elapsed| Dynamic::from(elapsed.as_millis() as INT))
+                    .unwrap_or(Dynamic::UNIT),
+            );
+
+            Dynamic::from_map(case_map)
+        })
+        .collect::<Array>();
+
+    let mut results = Map::new();
+    results.insert("quest".into(), quest.into());
+    results.insert("prog".into(), prog.to_string_lossy().to_string().into());
+    results.insert("passed".into(), (passed as INT).into());
+    results.insert("failed".into(), ((cases.len() - passed) as INT).into());
+    results.insert("elapsed_ms".into(), (elapsed_ms as INT).into());
+    results.insert("cases".into(), case_maps.into());
+
+    results
+}
+
+fn verdict(passed: bool) -> &'static str {
+    if passed { "passed" } else { "failed" }
+}
@@ -0,0 +1,78 @@
+use crate::common::{OwlWarning, warn};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static RUNNING_PIDS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+pub fn install_ctrl_c_handler() {
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            INTERRUPTED.store(true, Ordering::SeqCst);
+            kill_running();
+        }
+    });
+}
+
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+pub fn register_child(pid: u32) {
+    if let Ok(mut pids) = RUNNING_PIDS.lock() {
+        pids.push(pid);
+    }
+
+    if is_interrupted() {
+        kill_running();
+    }
+}
+
+pub fn reap_orphans(pid: u32) {
+    let is_running = RUNNING_PIDS
+        .lock()
+        .map(|pids| pids.contains(&pid))
+        .unwrap_or(false);
+
+    if is_running {
+        kill_group(pid);
+    }
+}
+
+pub fn unregister_child(pid: u32) {
+    if let Ok(mut pids) = RUNNING_PIDS.lock() {
+        pids.retain(|&running| running != pid);
+    }
+}
+
+fn kill_running() {
+    let Ok(pids) = RUNNING_PIDS.lock() else {
+        return;
+    };
+
+    for &pid in pids.iter() {
+        kill_group(pid);
+    }
+}
+
+#[cfg(unix)]
+pub fn kill_group(pgid: u32) {
+    let Ok(pgid) = libc::pid_t::try_from(pgid) else {
+        return;
+    };
+
+    // SAFETY: killpg only sends a signal; a group that already exited fails with ESRCH
+    if unsafe { libc::killpg(pgid, libc::SIGKILL) } == -1 {
+        let e = std::io::Error::last_os_error();
+
+        if e.raw_os_error() != Some(libc::ESRCH) {
+            warn(OwlWarning::Recovered(format!(
+                "could not kill process group {}: {}",
+                pgid, e
+            )));
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn kill_group(_: u32) {}
@@ -0,0 +1,205 @@
+use crate::common::{OwlError, Result};
+use std::env;
+use std::io::{IsTerminal, Write, stderr, stdin, stdout};
+use std::sync::atomic::{AtomicBool, Ordering};
+use unicode_width::UnicodeWidthChar;
+
+const MIN_SIDE_BY_SIDE: usize = 80;
+const COLUMN_GAP: &str = " │ ";
+const ELLIPSIS: char = '…';
+
+static NON_INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// With `--yes`, confirmations are accepted and anything else needing input fails instead.
+pub fn set_non_interactive(non_interactive: bool) {
+    NON_INTERACTIVE.store(non_interactive, Ordering::SeqCst);
+}
+
+pub fn is_non_interactive() -> bool {
+    NON_INTERACTIVE.load(Ordering::SeqCst)
+}
+
+/// Fails when `what` would need input but owlgo was told not to ask for any.
+pub fn require_interactive(what: &str) -> Result<()> {
+    match is_non_interactive() {
+        true => Err(OwlError::NeedsInput(format!(
+            "{} needs input, but '--yes' was given",
+            what
+        ))),
+        false => Ok(()),
+    }
+}
+
+/// The width of the terminal reports go to, or `None` when output is piped somewhere.
+pub fn term_width() -> Option<usize> {
+    if !stdout().is_terminal() || !stderr().is_terminal() {
+        return None;
+    }
+
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse::<usize>().ok())
+        .or_else(|| crossterm::terminal::size().ok().map(|(w, _)| w as usize))
+        .filter(|&width| width > 0)
+}
+
+/// Asks a question on stderr and reads the (trimmed, lowercased) answer from stdin.
+pub fn ask(question: &str) -> String {
+    eprint!("{} ", question);
+    let _ = stderr().flush();
+
+    let mut answer = String::new();
+
+    match stdin().read_line(&mut answer) {
+        Ok(_) => answer.trim().to_lowercase(),
+        Err(_) => String::new(),
+    }
+}
+
+/// Asks a yes/no question (anything but yes is no).
+pub fn confirm(question: &str) -> bool {
+    if is_non_interactive() {
+        eprintln!("{} [y/N] y (--yes)", question);
+        return true;
+    }
+
+    matches!(ask(&format!("{} [y/N]", question)).as_str(), "y" | "yes")
+}
+
+/// Wraps each line to the terminal width, leaving the text alone when it isn't a terminal.
+pub fn fit_text(text: &str) -> String {
+    match term_width() {
+        Some(width) => text
+            .split('\n')
+            .map(|line| wrap_line(line, width).join("\n"))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        None => text.to_string(),
+    }
+}
+
+/// Shows expected and actual output in two columns, marking lines that differ, or `None`
+/// when the terminal is too narrow (or not a terminal) and a stacked view should be used.
+pub fn side_by_side(
+    left_title: &str,
+    left: &str,
+    right_title: &str,
+    right: &str,
+) -> Option<String> {
+    let width = term_width().filter(|&width| width >= MIN_SIDE_BY_SIDE)?;
+    let column = (width - visible_width(COLUMN_GAP) - 2) / 2;
+
+    let left_lines = left.lines().collect::<Vec<&str>>();
+    let right_lines = right.lines().collect::<Vec<&str>>();
+
+    let mut view = format!(
+        "  {}{}{}\n",
+        pad(&truncate(left_title, column), column),
+        COLUMN_GAP,
+        truncate(right_title, column)
+    );
+
+    for i in 0..left_lines.len().max(right_lines.len()) {
+        let l = left_lines.get(i).copied();
+        let r = right_lines.get(i).copied();
+
+        let (marker, color) = if l.map(str::trim_end) == r.map(str::trim_end) {
+            (' ', "")
+        } else {
+            ('!', "\x1b[31m")
+        };
+
+        view.push_str(&format!(
+            "{}{} {}{}{}{}\x1b[0m\n",
+            color,
+            marker,
+            pad(&truncate(l.unwrap_or(""), column), column),
+            COLUMN_GAP,
+            color,
+            truncate(r.unwrap_or(""), column)
+        ));
+    }
+
+    Some(view)
+}
+
+/// Display width of `s`, ignoring ANSI escape sequences.
+pub fn visible_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+
+    for c in s.chars() {
+        match c {
+            '\x1b' => in_escape = true,
+            'm' if in_escape => in_escape = false,
+            _ if in_escape => {}
+            c => width += c.width().unwrap_or(0),
+        }
+    }
+
+    width
+}
+
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+    let mut in_escape = false;
+
+    for c in line.chars() {
+        let c_width = match c {
+            '\x1b' => {
+                in_escape = true;
+                0
+            }
+            'm' if in_escape => {
+                in_escape = false;
+                0
+            }
+            _ if in_escape => 0,
+            c => c.width().unwrap_or(0),
+        };
+
+        if current_width + c_width > width {
+            lines.push(std::mem::take(&mut current));
+            current_width = 0;
+        }
+
+        current.push(c);
+        current_width += c_width;
+    }
+
+    lines.push(current);
+    lines
+}
+
+fn truncate(s: &str, width: usize) -> String {
+    if visible_width(s) <= width {
+        return s.to_string();
+    }
+
+    let mut truncated = String::new();
+    let mut truncated_width = 0;
+
+    for c in s.chars() {
+        let c_width = c.width().unwrap_or(0);
+
+        if truncated_width + c_width + 1 > width {
+            break;
+        }
+
+        truncated.push(c);
+        truncated_width += c_width;
+    }
+
+    truncated.push(ELLIPSIS);
+    truncated
+}
+
+fn pad(s: &str, width: usize) -> String {
+    format!(
+        "{}{}",
+        s,
+        " ".repeat(width.saturating_sub(visible_width(s)))
+    )
+}
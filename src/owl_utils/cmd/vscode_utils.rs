@@ -0,0 +1,172 @@
+use crate::owl_utils::fs::fs_utils;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const SEVERITIES: &[&str] = &["error", "warning", "note", "info"];
+
+pub fn build_diagnostics(prog: &Path, stderr: &str) -> Vec<String> {
+    let mut diagnostics = Vec::new();
+    let mut pending: Option<(String, String)> = None;
+
+    for line in stderr.lines() {
+        let trimmed = line.trim_start();
+
+        if let Some(location) = trimmed.strip_prefix("--> ") {
+            if let Some((severity, message)) = pending.take()
+                && let Some((file, line_no, col, _)) = split_location(location)
+            {
+                diagnostics.push(format_diagnostic(
+                    prog, file, line_no, col, &severity, &message,
+                ));
+            }
+            continue;
+        }
+
+        if let Some((file, line_no, col, rest)) = split_location(trimmed) {
+            let (severity, message) = split_severity(rest);
+            diagnostics.push(format_diagnostic(
+                prog, file, line_no, col, &severity, &message,
+            ));
+            continue;
+        }
+
+        let (severity, message) = split_severity(trimmed);
+
+        if !message.is_empty() && severity_prefix(trimmed).is_some() {
+            pending = Some((severity, message));
+        }
+    }
+
+    if diagnostics.is_empty() {
+        let message = stderr
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("build failed");
+
+        diagnostics.push(format_diagnostic(
+            prog,
+            &prog.to_string_lossy(),
+            1,
+            1,
+            "error",
+            message,
+        ));
+    }
+
+    diagnostics
+}
+
+pub fn test_event(
+    test_case: &Path,
+    verdict: &str,
+    elapsed: Option<Duration>,
+    message: Option<&str>,
+    expected: Option<&str>,
+    actual: Option<&str>,
+) -> String {
+    let name = fs_utils::case_stem(test_case).unwrap_or_default();
+
+    json!({
+        "event": "test",
+        "name": name,
+        "file": absolute_path(test_case).to_string_lossy(),
+        "verdict": verdict,
+        "elapsed_ms": elapsed.map(|d| d.as_millis() as u64),
+        "message": message,
+        "expected": expected,
+        "actual": actual,
+    })
+    .to_string()
+}
+
+pub fn summary_event(passed: usize, failed: usize, elapsed: Duration) -> String {
+    json!({
+        "event": "summary",
+        "passed": passed,
+        "failed": failed,
+        "elapsed_ms": elapsed.as_millis() as u64,
+    })
+    .to_string()
+}
+
+fn absolute_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or(path.to_path_buf())
+}
+
+fn format_diagnostic(
+    prog: &Path,
+    file: &str,
+    line_no: usize,
+    col: usize,
+    severity: &str,
+    message: &str,
+) -> String {
+    let file_path = Path::new(file);
+
+    let file_path = if file_path.exists() {
+        absolute_path(file_path)
+    } else {
+        match prog.parent() {
+            Some(parent) if parent.join(file_path).exists() => {
+                absolute_path(&parent.join(file_path))
+            }
+            _ => file_path.to_path_buf(),
+        }
+    };
+
+    format!(
+        "{}:{}:{}: {}: {}",
+        file_path.to_string_lossy(),
+        line_no,
+        col,
+        severity,
+        message
+    )
+}
+
+fn severity_prefix(s: &str) -> Option<&'static str> {
+    SEVERITIES.iter().copied().find(|severity| {
+        s.strip_prefix(severity)
+            .is_some_and(|rest| rest.starts_with(':') || rest.starts_with('['))
+    })
+}
+
+fn split_location(s: &str) -> Option<(&str, usize, usize, &str)> {
+    let mut parts = s.splitn(3, ':');
+
+    let file = parts
+        .next()
+        .filter(|file| !file.is_empty() && !file.contains(' '))?;
+    let line_no = parts.next()?.trim().parse::<usize>().ok()?;
+    let rest = parts.next().unwrap_or("");
+
+    match rest
+        .split_once(':')
+        .and_then(|(col, message)| col.trim().parse::<usize>().ok().map(|col| (col, message)))
+    {
+        Some((col, message)) => Some((file, line_no, col, message.trim())),
+        None => Some((file, line_no, 1, rest.trim())),
+    }
+}
+
+fn split_severity(s: &str) -> (String, String) {
+    match severity_prefix(s) {
+        Some(severity) => {
+            let message = s
+                .split_once(':')
+                .map(|(_, message)| message.trim())
+                .unwrap_or("");
+
+            let severity = if severity == "note" || severity == "info" {
+                "info"
+            } else {
+                severity
+            };
+
+            (severity.to_string(), message.to_string())
+        }
+        None => ("error".to_string(), s.trim().to_string()),
+    }
+}
@@ -0,0 +1,304 @@
+use crate::common::{OwlError, Result};
+use chrono::Utc;
+use reqwest::header::{AUTHORIZATION, HOST};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use ring::hmac;
+use sha2::{Digest, Sha256};
+use std::env;
+use toml_edit::DocumentMut;
+use url::Url;
+
+const DEFAULT_REGION: &str = "us-east-1";
+
+/// Where `owlgo sync` mirrors the stash: an S3 (or S3-compatible) bucket or a WebDAV folder.
+#[derive(Clone, Debug)]
+pub enum CloudRemote {
+    S3 {
+        bucket: String,
+        prefix: String,
+        region: String,
+        endpoint: Option<Url>,
+        access_key: String,
+        secret_key: String,
+    },
+    WebDav {
+        base: Url,
+        user: Option<String>,
+        password: Option<String>,
+    },
+}
+
+impl CloudRemote {
+    /// Parses `s3://bucket/prefix` or a WebDAV `http(s)://` URL, taking credentials from the
+    /// manifest's `[sync]` table (or the usual `AWS_*` variables for S3).
+    pub fn from_url(remote: &str, manifest_doc: &DocumentMut) -> Result<Self> {
+        let url = Url::parse(remote).map_err(|e| {
+            OwlError::UriError(format!("'{}': invalid remote", remote), e.to_string())
+        })?;
+
+        let setting = |key: &str, env_key: Option<&str>| {
+            manifest_doc
+                .get("sync")
+                .and_then(|sync| sync.get(key))
+                .and_then(|item| item.as_str())
+                .map(String::from)
+                .or_else(|| env_key.and_then(|env_key| env::var(env_key).ok()))
+                .filter(|value| !value.is_empty())
+        };
+
+        match url.scheme() {
+            "s3" => {
+                let bucket = url
+                    .host_str()
+                    .filter(|bucket| !bucket.is_empty())
+                    .ok_or(OwlError::UriError(
+                        format!("'{}': missing bucket", remote),
+                        "expected 's3://<BUCKET>/<PREFIX>'".into(),
+                    ))?
+                    .to_string();
+
+                let missing = |key: &str| {
+                    OwlError::TomlError(
+                        format!("no S3 credentials to sync with ('sync.{}')", key),
+                        format!("run 'owlgo config set sync.{} <VALUE>'", key),
+                    )
+                };
+
+                let endpoint = match setting("s3_endpoint", None) {
+                    Some(endpoint) => Some(Url::parse(&endpoint).map_err(|e| {
+                        OwlError::UriError(
+                            format!("'{}': invalid S3 endpoint", endpoint),
+                            e.to_string(),
+                        )
+                    })?),
+                    None => None,
+                };
+
+                Ok(CloudRemote::S3 {
+                    bucket,
+                    prefix: url.path().trim_matches('/').to_string(),
+                    region: setting("s3_region", Some("AWS_REGION"))
+                        .unwrap_or(DEFAULT_REGION.into()),
+                    endpoint,
+                    access_key: setting("s3_access_key", Some("AWS_ACCESS_KEY_ID"))
+                        .ok_or_else(|| missing("s3_access_key"))?,
+                    secret_key: setting("s3_secret_key", Some("AWS_SECRET_ACCESS_KEY"))
+                        .ok_or_else(|| missing("s3_secret_key"))?,
+                })
+            }
+            "http" | "https" => {
+                let mut base = url.clone();
+
+                if !base.path().ends_with('/') {
+                    base.set_path(&format!("{}/", base.path()));
+                }
+
+                Ok(CloudRemote::WebDav {
+                    base,
+                    user: setting("webdav_user", None),
+                    password: setting("webdav_password", None),
+                })
+            }
+            scheme => Err(OwlError::Unsupported(format!(
+                "'{}': unsupported remote (expected 's3://' or a WebDAV 'https://' URL)",
+                scheme
+            ))),
+        }
+    }
+
+    /// Fetches an object, or `None` when it doesn't exist yet.
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let resp = self
+            .request(Method::GET, key, Vec::new())?
+            .send()
+            .await
+            .map_err(|e| {
+                OwlError::NetworkError(format!("could not fetch '{}'", key), e.to_string())
+            })?;
+
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let body = resp
+            .error_for_status()
+            .map_err(|e| {
+                OwlError::NetworkError(format!("could not fetch '{}'", key), e.to_string())
+            })?
+            .bytes()
+            .await
+            .map_err(|e| {
+                OwlError::NetworkError(format!("could not read '{}'", key), e.to_string())
+            })?;
+
+        Ok(Some(body.to_vec()))
+    }
+
+    pub async fn put(&self, key: &str, body: Vec<u8>) -> Result<()> {
+        self.request(Method::PUT, key, body)?
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| {
+                OwlError::NetworkError(format!("could not upload '{}'", key), e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Creates the folder for `dir` and its parents (WebDAV only; S3 has no folders).
+    pub async fn ensure_dir(&self, dir: &str) -> Result<()> {
+        if let CloudRemote::S3 { .. } = self {
+            return Ok(());
+        }
+
+        let mkcol = Method::from_bytes(b"MKCOL").expect("MKCOL is a valid method");
+        let mut path = String::new();
+
+        for part in dir.split('/').filter(|part| !part.is_empty()) {
+            path.push_str(part);
+            path.push('/');
+
+            let resp = self
+                .request(mkcol.clone(), &path, Vec::new())?
+                .send()
+                .await
+                .map_err(|e| {
+                    OwlError::NetworkError(format!("could not create '{}'", path), e.to_string())
+                })?;
+
+            // 405 means the folder already exists
+            if !resp.status().is_success() && resp.status() != StatusCode::METHOD_NOT_ALLOWED {
+                return Err(OwlError::NetworkError(
+                    format!("could not create '{}'", path),
+                    resp.status().to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn request(&self, method: Method, key: &str, body: Vec<u8>) -> Result<RequestBuilder> {
+        match self {
+            CloudRemote::WebDav {
+                base,
+                user,
+                password,
+            } => {
+                let url = base.join(&encode_path(key)).map_err(|e| {
+                    OwlError::UriError(format!("'{}': invalid remote path", key), e.to_string())
+                })?;
+
+                let request = Client::new().request(method, url).body(body);
+
+                Ok(match user {
+                    Some(user) => request.basic_auth(user, password.as_ref()),
+                    None => request,
+                })
+            }
+            CloudRemote::S3 {
+                bucket,
+                prefix,
+                region,
+                endpoint,
+                access_key,
+                secret_key,
+            } => {
+                let object_key = match prefix.is_empty() {
+                    true => key.to_string(),
+                    false => format!("{}/{}", prefix, key),
+                };
+
+                let (base, path) = match endpoint {
+                    Some(endpoint) => (
+                        endpoint.clone(),
+                        format!(
+                            "{}/{}/{}",
+                            endpoint.path().trim_end_matches('/'),
+                            bucket,
+                            encode_path(&object_key)
+                        ),
+                    ),
+                    None => (
+                        Url::parse(&format!("https://{}.s3.{}.amazonaws.com", bucket, region))
+                            .map_err(|e| {
+                                OwlError::UriError(
+                                    format!("'{}': invalid bucket", bucket),
+                                    e.to_string(),
+                                )
+                            })?,
+                        format!("/{}", encode_path(&object_key)),
+                    ),
+                };
+
+                let mut url = base.clone();
+                url.set_path(&path);
+
+                let host = match url.port() {
+                    Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+                    None => url.host_str().unwrap_or_default().to_string(),
+                };
+
+                let now = Utc::now();
+                let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+                let date = now.format("%Y%m%d").to_string();
+                let payload_hash = format!("{:x}", Sha256::digest(&body));
+
+                let canonical_request = format!(
+                    "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+                    method, path, host, payload_hash, amz_date, payload_hash
+                );
+                let scope = format!("{}/{}/s3/aws4_request", date, region);
+                let string_to_sign = format!(
+                    "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+                    amz_date,
+                    scope,
+                    Sha256::digest(canonical_request.as_bytes())
+                );
+
+                let signing_key = [date.as_str(), region.as_str(), "s3", "aws4_request"]
+                    .iter()
+                    .fold(format!("AWS4{}", secret_key).into_bytes(), |key, part| {
+                        hmac_sha256(&key, part.as_bytes())
+                    });
+                let signature = hmac_sha256(&signing_key, string_to_sign.as_bytes())
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>();
+
+                Ok(Client::new()
+                    .request(method, url)
+                    .header(HOST, host)
+                    .header("x-amz-content-sha256", payload_hash)
+                    .header("x-amz-date", amz_date)
+                    .header(
+                        AUTHORIZATION,
+                        format!(
+                            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+                            access_key, scope, signature
+                        ),
+                    )
+                    .body(body))
+            }
+        }
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data)
+        .as_ref()
+        .to_vec()
+}
+
+/// Percent-encodes each segment of a `/`-separated path (RFC 3986 unreserved characters only).
+fn encode_path(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -1,14 +1,116 @@
-use crate::common::{OwlError, Result};
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::{HIDDEN_DIR, OWL_DIR, PROMPT_DIR, SCRIPT_DIR, STASH_DIR};
+use flate2::Compression;
 use flate2::read::GzDecoder;
-use std::collections::VecDeque;
+use flate2::write::GzEncoder;
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::ffi::OsStr;
 use std::fs::{self, OpenOptions};
-use std::io::{Cursor, copy};
-use std::path::{Path, PathBuf};
-use tar::Archive;
+use std::io::{BufRead, BufReader, Cursor, Read, copy};
+use std::path::{Component, Path, PathBuf};
+use std::process;
+use std::time::SystemTime;
+use tar::{Archive, Builder, EntryType};
 use url::Url;
 use zip::ZipArchive;
 
+const GZ_EXT: &str = "gz";
+const PREVIEW_LIMIT: u64 = 8 * 1024 * 1024;
+const PREVIEW_LINES: usize = 50;
+const NESTED_TMP_DIR: &str = ".owlgo_nested";
+const MAX_EXTRACT_BYTES: u64 = 1024 * 1024 * 1024;
+const MAX_EXTRACT_FILES: usize = 20_000;
+const INPUT_WORDS: &[&str] = &["in", "inp", "input", "inputs"];
+const ANSWER_WORDS: &[&str] = &[
+    "ans", "answer", "answers", "expected", "out", "output", "outputs",
+];
+const FILE_TYPE_WORDS: &[&str] = &["dat", "gz", "txt"];
+
+/// Running totals that cap how much an archive may unpack, since quest archives come from
+/// third-party URLs.
+#[derive(Default)]
+struct ExtractBudget {
+    files: usize,
+    bytes: u64,
+}
+
+impl ExtractBudget {
+    fn admit(&mut self, archive_path: &Path, entry_path: &Path, size: u64) -> Result<()> {
+        let is_enclosed = entry_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+
+        if !is_enclosed {
+            return Err(OwlError::FileError(
+                format!(
+                    "'{}': unsafe entry '{}'",
+                    archive_path.to_string_lossy(),
+                    entry_path.to_string_lossy()
+                ),
+                "entries cannot be absolute or contain '..'".into(),
+            ));
+        }
+
+        self.files += 1;
+        self.bytes = self.bytes.saturating_add(size);
+
+        if self.files > MAX_EXTRACT_FILES || self.bytes > MAX_EXTRACT_BYTES {
+            return Err(OwlError::FileError(
+                format!("'{}': archive is too large", archive_path.to_string_lossy()),
+                format!(
+                    "more than {} files or {} MiB uncompressed",
+                    MAX_EXTRACT_FILES,
+                    MAX_EXTRACT_BYTES / (1024 * 1024)
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+pub fn case_ext(path: &Path) -> Option<&str> {
+    case_name(path)?.rsplit_once('.').map(|(_, ext)| ext)
+}
+
+/// Answer files are `.ans`/`.out`, plus alternates `.ans2`, `.ans3`, ... for inputs with more
+/// than one accepted output.
+pub fn is_answer_ext(ext: &str) -> bool {
+    match ext.strip_prefix("ans") {
+        Some("") => true,
+        Some(n) => n.parse::<u32>().is_ok_and(|n| n >= 2),
+        None => ext == "out",
+    }
+}
+
+/// Cases under a quest's `hidden/` dir are judged but their contents are never shown.
+pub fn is_hidden_case(path: &Path) -> bool {
+    let is_case = case_ext(path)
+        .is_some_and(|ext| ext == "in" || ext == "md" || ext == "args" || is_answer_ext(ext));
+
+    is_case
+        && path
+            .parent()
+            .and_then(Path::file_name)
+            .is_some_and(|dir_name| dir_name == HIDDEN_DIR)
+}
+
+pub fn case_stem(path: &Path) -> Option<&str> {
+    let name = case_name(path)?;
+
+    Some(name.rsplit_once('.').map_or(name, |(stem, _)| stem))
+}
+
+fn case_name(path: &Path) -> Option<&str> {
+    let name = path.file_name()?.to_str()?;
+
+    Some(
+        name.strip_suffix(GZ_EXT)
+            .and_then(|name| name.strip_suffix('.'))
+            .unwrap_or(name),
+    )
+}
+
 pub fn copy_file(src: &Path, dst: &Path) -> Result<()> {
     let mut src_file = OpenOptions::new().read(true).open(src).map_err(|e| {
         OwlError::FileError(
@@ -76,6 +178,57 @@ pub async fn copy_file_async(src: &Path, dst: &Path) -> Result<()> {
     Ok(())
 }
 
+pub fn create_tar_archive(root_dir: &Path, files: &[PathBuf], archive_path: &Path) -> Result<()> {
+    let tar_file = OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(archive_path)
+        .map_err(|e| {
+            OwlError::FileError(
+                format!(
+                    "Failed to open tar archive '{}' for writing",
+                    archive_path.to_string_lossy()
+                ),
+                e.to_string(),
+            )
+        })?;
+
+    let mut tar_builder = Builder::new(GzEncoder::new(tar_file, Compression::default()));
+
+    for file in files.iter() {
+        let archive_name = file.strip_prefix(root_dir).unwrap_or(file);
+
+        tar_builder
+            .append_path_with_name(file, archive_name)
+            .map_err(|e| {
+                OwlError::FileError(
+                    format!(
+                        "Failed to add '{}' to tar archive '{}'",
+                        file.to_string_lossy(),
+                        archive_path.to_string_lossy()
+                    ),
+                    e.to_string(),
+                )
+            })?;
+    }
+
+    tar_builder
+        .into_inner()
+        .and_then(|gz_encoder| gz_encoder.finish())
+        .map_err(|e| {
+            OwlError::FileError(
+                format!(
+                    "Failed to write tar archive '{}'",
+                    archive_path.to_string_lossy()
+                ),
+                e.to_string(),
+            )
+        })?;
+
+    Ok(())
+}
+
 pub fn dir_tree(root_dir: &Path) -> Result<Vec<PathBuf>> {
     if !root_dir.exists() {
         return Err(OwlError::FileError(
@@ -129,17 +282,137 @@ pub fn dir_tree(root_dir: &Path) -> Result<Vec<PathBuf>> {
     Ok(files)
 }
 
-pub async fn download_archive(url: &Url, tmp_archive: &Path, out_dir: &Path) -> Result<()> {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EntryKind {
+    File,
+    Dir,
+    Symlink,
+}
+
+impl EntryKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EntryKind::File => "file",
+            EntryKind::Dir => "dir",
+            EntryKind::Symlink => "symlink",
+        }
+    }
+}
+
+/// A file or dir with its metadata and, when read with `file_tree`, a dir's entries (sorted by
+/// name, with the dir's size being theirs combined).
+#[derive(Clone, Debug)]
+pub struct TreeEntry {
+    pub path: PathBuf,
+    pub kind: EntryKind,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub children: Vec<TreeEntry>,
+}
+
+impl TreeEntry {
+    /// Reads a single entry's metadata without following symlinks or walking dirs.
+    pub fn load(path: &Path) -> Result<Self> {
+        let metadata = fs::symlink_metadata(path).map_err(|e| {
+            OwlError::FileError(
+                format!("Failed to read metadata of '{}'", path.to_string_lossy()),
+                e.to_string(),
+            )
+        })?;
+
+        let kind = if metadata.is_symlink() {
+            EntryKind::Symlink
+        } else if metadata.is_dir() {
+            EntryKind::Dir
+        } else {
+            EntryKind::File
+        };
+
+        Ok(TreeEntry {
+            path: path.to_path_buf(),
+            kind,
+            size: if kind == EntryKind::File {
+                metadata.len()
+            } else {
+                0
+            },
+            modified: metadata.modified().ok(),
+            children: Vec::new(),
+        })
+    }
+
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.to_string_lossy().to_string())
+    }
+}
+
+/// Like `dir_tree`, but keeps the dir structure and each entry's metadata. Symlinked dirs are
+/// listed but not walked.
+pub fn file_tree(root_dir: &Path) -> Result<TreeEntry> {
+    if !root_dir.is_dir() {
+        return Err(OwlError::FileError(
+            format!(
+                "Failed to read entries in dir '{}'",
+                root_dir.to_string_lossy()
+            ),
+            "no such directory".into(),
+        ));
+    }
+
+    let mut root = TreeEntry::load(root_dir)?;
+
+    for entry in fs::read_dir(root_dir).map_err(|e| {
+        OwlError::FileError(
+            format!("Failed to read dir '{}'", root_dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })? {
+        let path = entry
+            .map_err(|e| {
+                OwlError::FileError(
+                    format!(
+                        "Failed to determine path of dir entry '{}'",
+                        root_dir.to_string_lossy()
+                    ),
+                    e.to_string(),
+                )
+            })?
+            .path();
+
+        let mut child = TreeEntry::load(&path)?;
+
+        if child.kind == EntryKind::Dir {
+            child = file_tree(&path)?;
+        }
+
+        root.size += child.size;
+        root.children.push(child);
+    }
+
+    root.children.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(root)
+}
+
+pub async fn download_archive(
+    url: &Url,
+    tmp_archive: &Path,
+    out_dir: &Path,
+    sha256: Option<&str>,
+) -> Result<()> {
     if let Some(mut segments) = url.path_segments()
         && let Some(filename) = segments.next_back()
         && Path::new(filename).extension().is_some()
     {
         let archive_path = Path::new(filename);
         download_file(url, archive_path).await?;
-        extract_archive(archive_path, out_dir, true).await
+        extract_archive(archive_path, out_dir, true, sha256).await
     } else {
         download_file(url, tmp_archive).await?;
-        extract_archive(tmp_archive, out_dir, true).await
+        extract_archive(tmp_archive, out_dir, true, sha256).await
     }
 }
 
@@ -184,7 +457,85 @@ pub async fn download_file(url: &Url, out: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Rejects quest, prompt, program, and extension names that would escape the owlgo dir or shadow the files
+/// owlgo keeps there.
+pub fn check_name<'a>(name: &'a str, kind: &str) -> Result<&'a str> {
+    check_component(name, kind)?;
+
+    if name.starts_with('.') || (kind == "quest" && name == SCRIPT_DIR) {
+        return Err(OwlError::UriError(
+            format!("'{}': reserved {} name", name, kind),
+            "names starting with '.' and 'scripts' are used by owlgo".into(),
+        ));
+    }
+
+    Ok(name)
+}
+
+fn check_component<'a>(name: &'a str, kind: &str) -> Result<&'a str> {
+    let mut components = Path::new(name).components();
+
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) if !name.contains(['/', '\\']) => Ok(name),
+        _ => Err(OwlError::UriError(
+            format!("'{}': invalid {} name", name, kind),
+            "names cannot be empty, absolute, or contain '..' or path separators".into(),
+        )),
+    }
+}
+
+pub fn quest_path(quest_name: &str) -> Result<PathBuf> {
+    ensure_path_from_home(&[OWL_DIR], Some(check_name(quest_name, "quest")?))
+}
+
+/// Quest names compare equal under this, ignoring case and treating `-` as `_`.
+pub fn normalize_quest_name(quest_name: &str) -> String {
+    quest_name.to_lowercase().replace('-', "_")
+}
+
+/// The names of the quests fetched into the owlgo dir, sorted.
+pub fn local_quests() -> Result<Vec<String>> {
+    let owl_dir = ensure_path_from_home(&[OWL_DIR], None)?;
+
+    let mut quests = fs::read_dir(&owl_dir)
+        .map_err(|e| {
+            OwlError::FileError(
+                format!("Failed to read dir '{}'", owl_dir.to_string_lossy()),
+                e.to_string(),
+            )
+        })?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .filter(|name| !name.starts_with('.') && name != SCRIPT_DIR)
+        .collect::<Vec<String>>();
+    quests.sort();
+
+    Ok(quests)
+}
+
+pub fn prompt_path(prompt_name: &str) -> Result<PathBuf> {
+    ensure_path_from_home(
+        &[OWL_DIR, STASH_DIR, PROMPT_DIR],
+        Some(check_name(prompt_name, "prompt")?),
+    )
+}
+
+pub fn stash_path(prog_name: &str) -> Result<PathBuf> {
+    ensure_path_from_home(
+        &[OWL_DIR, STASH_DIR],
+        Some(check_name(prog_name, "program")?),
+    )
+}
+
 pub fn ensure_path_from_home(dirs: &[&str], file_str: Option<&str>) -> Result<PathBuf> {
+    for name in dirs.iter().copied().chain(file_str) {
+        check_component(name, "path")?;
+    }
+
     let mut path = dirs::home_dir().ok_or(OwlError::FileError(
         "Failed to find home dir".into(),
         "None".into(),
@@ -210,20 +561,102 @@ pub fn ensure_path_from_home(dirs: &[&str], file_str: Option<&str>) -> Result<Pa
     Ok(path)
 }
 
+/// Extracts a quest archive into `out_dir`, first checking it against `sha256` when the quest's
+/// entry pins one.
 pub async fn extract_archive(
     archive_path: &Path,
     out_dir: &Path,
     remove_archive: bool,
+    sha256: Option<&str>,
 ) -> Result<()> {
+    if let Some(sha256) = sha256 {
+        let archive_hash = super::pool_utils::hash_file(archive_path)?;
+
+        if !archive_hash.eq_ignore_ascii_case(sha256) {
+            if remove_archive {
+                remove_path(archive_path)?;
+            }
+
+            return Err(OwlError::FileError(
+                format!("'{}': checksum mismatch", archive_path.to_string_lossy()),
+                format!("expected sha256 {}, got {}", sha256, archive_hash),
+            ));
+        }
+    }
+
     let archive_ext = archive_path.extension().and_then(OsStr::to_str);
+    let staging_dir = staging_dir(out_dir)?;
+    remove_path(&staging_dir)?;
 
-    if let Some(ext) = archive_ext
+    let extract_result = if let Some(ext) = archive_ext
         && (ext == "zip" || ext == "archive")
     {
-        extract_zip_archive(archive_path, out_dir, remove_archive).await
+        extract_zip_archive(archive_path, &staging_dir, remove_archive).await
     } else {
-        extract_tar_archive(archive_path, out_dir, remove_archive).await
+        extract_tar_archive(archive_path, &staging_dir, remove_archive).await
+    };
+
+    // a rejected archive never touches the quest, so there's no half-extracted state to undo
+    let move_result = extract_result.and_then(|_| move_into(&staging_dir, out_dir));
+    remove_path(&staging_dir)?;
+    move_result?;
+
+    for warning in normalize_quest_dir(out_dir)? {
+        warn(warning);
+    }
+
+    if let Err(e) = super::pool_utils::pool_quest_dir(out_dir) {
+        warn(OwlWarning::Recovered(e.to_string()));
     }
+
+    Ok(())
+}
+
+/// A sibling of `out_dir`, so moving out of it is a rename on the same filesystem.
+fn staging_dir(out_dir: &Path) -> Result<PathBuf> {
+    let dir_name = out_dir.file_name().ok_or(OwlError::UriError(
+        format!("'{}': has no dir name", out_dir.to_string_lossy()),
+        "".into(),
+    ))?;
+
+    Ok(out_dir.with_file_name(format!(
+        ".{}.extract-{}",
+        dir_name.to_string_lossy(),
+        process::id()
+    )))
+}
+
+/// Renames `src_dir` to `dst_dir`, or merges it into `dst_dir` when that already exists, with
+/// `src_dir`'s files replacing any of the same name.
+fn move_into(src_dir: &Path, dst_dir: &Path) -> Result<()> {
+    let move_err = |e: std::io::Error| {
+        OwlError::FileError(
+            format!(
+                "Failed to move '{}' into '{}'",
+                src_dir.to_string_lossy(),
+                dst_dir.to_string_lossy()
+            ),
+            e.to_string(),
+        )
+    };
+
+    if !dst_dir.exists() {
+        return fs::rename(src_dir, dst_dir).map_err(move_err);
+    }
+
+    for entry in fs::read_dir(src_dir).map_err(move_err)? {
+        let src = entry.map_err(move_err)?.path();
+        let dst = dst_dir.join(src.file_name().expect("dir entry to have a file name"));
+
+        if src.is_dir() && dst.is_dir() {
+            move_into(&src, &dst)?;
+        } else {
+            remove_path(&dst)?;
+            fs::rename(&src, &dst).map_err(move_err)?;
+        }
+    }
+
+    Ok(())
 }
 
 pub async fn extract_tar_archive(
@@ -259,7 +692,7 @@ pub async fn extract_tar_archive(
     let tar = GzDecoder::new(tar_file);
     let mut tar_archive = Archive::new(tar);
 
-    tar_archive.unpack(out_dir).map_err(|e| {
+    let extract_err = |e: std::io::Error| {
         OwlError::FileError(
             format!(
                 "Failed to extract tar archive '{}' into '{}'",
@@ -268,7 +701,32 @@ pub async fn extract_tar_archive(
             ),
             e.to_string(),
         )
-    })?;
+    };
+
+    let mut budget = ExtractBudget::default();
+
+    for entry in tar_archive.entries().map_err(extract_err)? {
+        let mut entry = entry.map_err(extract_err)?;
+        let entry_path = entry.path().map_err(extract_err)?.into_owned();
+
+        match entry.header().entry_type() {
+            EntryType::Regular | EntryType::Directory => {}
+            EntryType::XGlobalHeader => continue,
+            entry_type => {
+                return Err(OwlError::FileError(
+                    format!(
+                        "'{}': unexpected entry '{}'",
+                        archive_path.to_string_lossy(),
+                        entry_path.to_string_lossy()
+                    ),
+                    format!("{:?} entries are not allowed", entry_type),
+                ));
+            }
+        }
+
+        budget.admit(archive_path, &entry_path, entry.size())?;
+        entry.unpack_in(out_dir).map_err(extract_err)?;
+    }
 
     if remove_archive {
         remove_path(archive_path)?;
@@ -317,7 +775,7 @@ pub async fn extract_zip_archive(
         )
     })?;
 
-    zip_archive.extract(out_dir).map_err(|e| {
+    let extract_err = |e: &dyn std::fmt::Display| {
         OwlError::FileError(
             format!(
                 "Failed to extract zip archive '{}' into '{}'",
@@ -326,7 +784,52 @@ pub async fn extract_zip_archive(
             ),
             e.to_string(),
         )
-    })?;
+    };
+
+    let mut budget = ExtractBudget::default();
+
+    for index in 0..zip_archive.len() {
+        let mut zip_entry = zip_archive.by_index(index).map_err(|e| extract_err(&e))?;
+
+        let entry_path = zip_entry.enclosed_name().ok_or(OwlError::FileError(
+            format!(
+                "'{}': unsafe entry '{}'",
+                archive_path.to_string_lossy(),
+                zip_entry.name()
+            ),
+            "entries cannot be absolute or contain '..'".into(),
+        ))?;
+
+        if zip_entry.is_symlink() || !(zip_entry.is_dir() || zip_entry.is_file()) {
+            return Err(OwlError::FileError(
+                format!(
+                    "'{}': unexpected entry '{}'",
+                    archive_path.to_string_lossy(),
+                    entry_path.to_string_lossy()
+                ),
+                "only files and dirs are allowed".into(),
+            ));
+        }
+
+        let size = zip_entry.size();
+        budget.admit(archive_path, &entry_path, size)?;
+
+        let out_path = out_dir.join(&entry_path);
+
+        if zip_entry.is_dir() {
+            fs::create_dir_all(&out_path).map_err(|e| extract_err(&e))?;
+            continue;
+        }
+
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| extract_err(&e))?;
+        }
+
+        let mut out_file = fs::File::create(&out_path).map_err(|e| extract_err(&e))?;
+
+        // never trust the declared size further than the budget already admitted
+        copy(&mut (&mut zip_entry).take(size), &mut out_file).map_err(|e| extract_err(&e))?;
+    }
 
     if remove_archive {
         remove_path(archive_path)?;
@@ -341,15 +844,7 @@ pub fn find_by_ext(root_dir: &Path, target_ext: &str) -> Result<Vec<PathBuf>> {
 
         let matches = files
             .into_iter()
-            .filter(|file| {
-                if let Some(ext) = file.extension()
-                    && ext == target_ext
-                {
-                    true
-                } else {
-                    false
-                }
-            })
+            .filter(|file| case_ext(file) == Some(target_ext))
             .collect::<Vec<PathBuf>>();
 
         if matches.is_empty() {
@@ -367,6 +862,31 @@ pub fn find_by_ext(root_dir: &Path, target_ext: &str) -> Result<Vec<PathBuf>> {
     })
 }
 
+/// Finds the first of `names` (ignoring case) among the files directly in `dir`.
+pub fn find_by_names(dir: &Path, names: &[&str]) -> Result<Option<PathBuf>> {
+    let paths = fs::read_dir(dir)
+        .map_err(|e| {
+            OwlError::FileError(
+                format!("could not read dir '{}'", dir.to_string_lossy()),
+                e.to_string(),
+            )
+        })?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect::<Vec<PathBuf>>();
+
+    Ok(names.iter().find_map(|name_match| {
+        paths
+            .iter()
+            .find(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.eq_ignore_ascii_case(name_match))
+            })
+            .cloned()
+    }))
+}
+
 pub fn find_by_stem_and_ext(
     root_dir: &Path,
     target_stem: &str,
@@ -376,15 +896,7 @@ pub fn find_by_stem_and_ext(
         let n = files.len();
 
         let file_match = files.into_iter().find(|file| {
-            if let Some(stem) = file.file_stem()
-                && stem == target_stem
-                && let Some(ext) = file.extension()
-                && ext == target_ext
-            {
-                true
-            } else {
-                false
-            }
+            case_stem(file) == Some(target_stem) && case_ext(file) == Some(target_ext)
         });
 
         match file_match {
@@ -402,6 +914,347 @@ pub fn find_by_stem_and_ext(
     })
 }
 
+pub fn normalize_quest_dir(quest_dir: &Path) -> Result<Vec<OwlWarning>> {
+    while let Some(nested_dir) = single_nested_dir(quest_dir)? {
+        let mut tmp_dir = quest_dir.to_path_buf();
+        tmp_dir.push(NESTED_TMP_DIR);
+
+        fs::rename(&nested_dir, &tmp_dir).map_err(|e| {
+            OwlError::FileError(
+                format!(
+                    "Failed to move nested dir '{}'",
+                    nested_dir.to_string_lossy()
+                ),
+                e.to_string(),
+            )
+        })?;
+
+        for entry in fs::read_dir(&tmp_dir).map_err(|e| {
+            OwlError::FileError(
+                format!("Failed to read dir '{}'", tmp_dir.to_string_lossy()),
+                e.to_string(),
+            )
+        })? {
+            let entry = entry.map_err(|e| {
+                OwlError::FileError(
+                    format!(
+                        "Failed to read entry in dir '{}'",
+                        tmp_dir.to_string_lossy()
+                    ),
+                    e.to_string(),
+                )
+            })?;
+
+            let mut dst = quest_dir.to_path_buf();
+            dst.push(entry.file_name());
+
+            fs::rename(entry.path(), &dst).map_err(|e| {
+                OwlError::FileError(
+                    format!(
+                        "Failed to move '{}' into '{}'",
+                        entry.path().to_string_lossy(),
+                        quest_dir.to_string_lossy()
+                    ),
+                    e.to_string(),
+                )
+            })?;
+        }
+
+        remove_path(&tmp_dir)?;
+    }
+
+    let mut warnings = unmatched_cases(quest_dir)?;
+
+    for file in dir_tree(quest_dir)?.iter() {
+        let is_hidden = file
+            .file_name()
+            .and_then(OsStr::to_str)
+            .is_some_and(|name| name.starts_with('.'));
+
+        let is_case = case_ext(file)
+            .is_some_and(|ext| ext == "in" || ext == "md" || ext == "args" || is_answer_ext(ext));
+
+        if !is_case && !is_hidden {
+            warnings.push(OwlWarning::IgnoredFile(file.to_string_lossy().to_string()));
+        }
+    }
+
+    Ok(warnings)
+}
+
+pub fn unmatched_cases(quest_dir: &Path) -> Result<Vec<OwlWarning>> {
+    let mut warnings = Vec::new();
+
+    for file in dir_tree(quest_dir)?.iter() {
+        let file_str = file.to_string_lossy();
+
+        match case_ext(file) {
+            Some("in") if !has_sibling_case(file, &["ans", "out"]) => {
+                warnings.push(OwlWarning::UnmatchedCase(format!(
+                    "'{}': has no matching answer file",
+                    file_str
+                )));
+            }
+            Some(ext)
+                if (is_answer_ext(ext) || ext == "args") && !has_sibling_case(file, &["in"]) =>
+            {
+                warnings.push(OwlWarning::UnmatchedCase(format!(
+                    "'{}': has no matching input file",
+                    file_str
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(warnings)
+}
+
+fn has_sibling_case(file: &Path, exts: &[&str]) -> bool {
+    let (Some(parent), Some(stem)) = (file.parent(), case_stem(file)) else {
+        return false;
+    };
+
+    exts.iter().any(|ext| {
+        parent.join(format!("{}.{}", stem, ext)).exists()
+            || parent.join(format!("{}.{}.{}", stem, ext, GZ_EXT)).exists()
+    })
+}
+
+/// Pairs the inputs and answers in an unpacked folder of tests by their names (`x.in`/`x.out`,
+/// `input01.txt`/`output01.txt`, `in/1.txt`/`out/1.txt`, ...) and copies each pair into the
+/// quest dir as `<case>.in`/`<case>.ans`, returning the number of cases.
+pub fn import_local_tests(src_dir: &Path, quest_dir: &Path) -> Result<usize> {
+    let mut files = dir_tree(src_dir)?;
+    files.sort();
+
+    let mut pairs: BTreeMap<String, (Option<PathBuf>, Option<PathBuf>)> = BTreeMap::new();
+
+    for file in files {
+        let rel_path = file.strip_prefix(src_dir).unwrap_or(&file);
+
+        let is_hidden = rel_path.components().any(|component| {
+            component
+                .as_os_str()
+                .to_str()
+                .is_some_and(|name| name.starts_with('.'))
+        });
+
+        if is_hidden {
+            continue;
+        }
+
+        let Some((key, is_input)) = local_test_key(rel_path) else {
+            warn(OwlWarning::IgnoredFile(file.to_string_lossy().to_string()));
+            continue;
+        };
+
+        let pair = pairs.entry(key).or_default();
+        let slot = if is_input { &mut pair.0 } else { &mut pair.1 };
+
+        match slot {
+            Some(paired) => warn(OwlWarning::UnmatchedCase(format!(
+                "'{}': pairs with the same case as '{}'",
+                file.to_string_lossy(),
+                paired.to_string_lossy()
+            ))),
+            None => *slot = Some(file),
+        }
+    }
+
+    let mut matched = Vec::new();
+
+    for (key, pair) in pairs {
+        match pair {
+            (Some(in_file), Some(ans_file)) => matched.push((key, in_file, ans_file)),
+            (Some(in_file), None) => warn(OwlWarning::UnmatchedCase(format!(
+                "'{}': has no matching answer file",
+                in_file.to_string_lossy()
+            ))),
+            (None, Some(ans_file)) => warn(OwlWarning::UnmatchedCase(format!(
+                "'{}': has no matching input file",
+                ans_file.to_string_lossy()
+            ))),
+            (None, None) => {}
+        }
+    }
+
+    if matched.is_empty() {
+        return Err(OwlError::FileError(
+            format!("'{}': no test cases found", src_dir.to_string_lossy()),
+            "expected pairs like 'x.in'/'x.out' or 'input01.txt'/'output01.txt'".into(),
+        ));
+    }
+
+    fs::create_dir_all(quest_dir).map_err(|e| {
+        OwlError::FileError(
+            format!(
+                "Failed to create all dirs in '{}'",
+                quest_dir.to_string_lossy()
+            ),
+            e.to_string(),
+        )
+    })?;
+
+    let keys = matched
+        .iter()
+        .map(|(key, _, _)| key.as_str())
+        .collect::<Vec<&str>>();
+    let names = local_case_names(&keys);
+
+    for ((_, in_file, ans_file), name) in matched.iter().zip(names.iter()) {
+        for (src, ext) in [(in_file, "in"), (ans_file, "ans")] {
+            let dst = match is_gzipped(src) {
+                true => quest_dir.join(format!("{}.{}.{}", name, ext, GZ_EXT)),
+                false => quest_dir.join(format!("{}.{}", name, ext)),
+            };
+
+            copy_file(src, &dst)?;
+        }
+    }
+
+    if let Err(e) = super::pool_utils::pool_quest_dir(quest_dir) {
+        warn(OwlWarning::Recovered(e.to_string()));
+    }
+
+    Ok(matched.len())
+}
+
+/// The name of a test file with its input/answer word blanked out, so an input and its answer
+/// share a key, and whether it is the input.
+fn local_test_key(rel_path: &Path) -> Option<(String, bool)> {
+    let mut tokens = name_tokens(&rel_path.to_string_lossy().to_lowercase());
+
+    let (i, is_input) = tokens.iter().enumerate().rev().find_map(|(i, token)| {
+        if INPUT_WORDS.contains(&token.as_str()) {
+            Some((i, true))
+        } else if ANSWER_WORDS.contains(&token.as_str()) || (token == "a" && i + 1 == tokens.len())
+        {
+            Some((i, false))
+        } else {
+            None
+        }
+    })?;
+
+    tokens[i] = "*".into();
+
+    Some((tokens.join("/"), is_input))
+}
+
+/// Short, unique case names from pairing keys, dropping the parts every key has in common.
+fn local_case_names(keys: &[&str]) -> Vec<String> {
+    let tokens = keys
+        .iter()
+        .map(|key| {
+            key.split('/')
+                .filter(|token| *token != "*" && !FILE_TYPE_WORDS.contains(token))
+                .collect::<Vec<&str>>()
+        })
+        .collect::<Vec<Vec<&str>>>();
+
+    let shortest = tokens.iter().map(Vec::len).min().unwrap_or(0);
+    let common = match tokens.len() {
+        1 => shortest.saturating_sub(1),
+        _ => (0..shortest)
+            .take_while(|&i| tokens.iter().all(|t| t[i] == tokens[0][i]))
+            .count(),
+    };
+
+    let mut seen = HashSet::new();
+
+    tokens
+        .iter()
+        .map(|t| {
+            let base = match t[common..].join("-") {
+                name if name.is_empty() => "case".to_string(),
+                name => name,
+            };
+
+            let mut name = base.clone();
+            let mut n = 2;
+
+            while !seen.insert(name.clone()) {
+                name = format!("{}-{}", base, n);
+                n += 1;
+            }
+
+            name
+        })
+        .collect()
+}
+
+fn name_tokens(s: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut prev: Option<char> = None;
+
+    for c in s.chars() {
+        if !c.is_alphanumeric() {
+            prev = None;
+            continue;
+        }
+
+        match prev {
+            Some(p) if p.is_ascii_digit() == c.is_ascii_digit() => {
+                if let Some(token) = tokens.last_mut() {
+                    token.push(c);
+                }
+            }
+            _ => tokens.push(c.to_string()),
+        }
+
+        prev = Some(c);
+    }
+
+    tokens
+}
+
+pub fn is_gzipped(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == GZ_EXT)
+}
+
+pub fn read_case(path: &Path) -> Result<Vec<u8>> {
+    let read_err = |e: std::io::Error| {
+        OwlError::FileError(
+            format!("could not read from '{}'", path.to_string_lossy()),
+            e.to_string(),
+        )
+    };
+
+    if !is_gzipped(path) {
+        return fs::read(path).map_err(read_err);
+    }
+
+    let mut contents = Vec::new();
+
+    GzDecoder::new(fs::File::open(path).map_err(read_err)?)
+        .read_to_end(&mut contents)
+        .map_err(read_err)?;
+
+    Ok(contents)
+}
+
+/// The whitespace-separated argv in `stem.args` next to `in_file`, if there is one.
+pub fn read_case_args(in_file: &Path) -> Result<Vec<String>> {
+    let (Some(parent), Some(stem)) = (in_file.parent(), case_stem(in_file)) else {
+        return Ok(Vec::new());
+    };
+
+    let args_path = parent.join(format!("{}.args", stem));
+
+    if !args_path.is_file() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&args_path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read from '{}'", args_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    Ok(contents.split_whitespace().map(String::from).collect())
+}
+
 pub fn read_contents(path: &Path) -> Result<String> {
     if !path.exists() {
         Err(OwlError::FileError(
@@ -439,6 +1292,8 @@ pub fn read_contents(path: &Path) -> Result<String> {
                     e.to_string(),
                 )
             })
+    } else if is_gzipped(path) {
+        read_case(path).map(|contents| String::from_utf8_lossy(&contents).to_string())
     } else {
         fs::read_to_string(path).map_err(|e| {
             OwlError::FileError(
@@ -449,6 +1304,106 @@ pub fn read_contents(path: &Path) -> Result<String> {
     }
 }
 
+pub fn read_preview(path: &Path, load_hint: &str) -> Result<Option<String>> {
+    let file_size = match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => metadata.len(),
+        _ => return Ok(None),
+    };
+
+    if file_size <= PREVIEW_LIMIT {
+        return Ok(None);
+    }
+
+    let read_err = |e: std::io::Error| {
+        OwlError::FileError(
+            format!("could not read from '{}'", path.to_string_lossy()),
+            e.to_string(),
+        )
+    };
+
+    let file = fs::File::open(path).map_err(read_err)?;
+    let mut reader: Box<dyn BufRead> = if is_gzipped(path) {
+        Box::new(BufReader::new(GzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+
+    let mut head = Vec::new();
+    let mut tail = VecDeque::new();
+    let mut num_lines = 0;
+    let mut line = Vec::new();
+
+    while reader.read_until(b'\n', &mut line).map_err(read_err)? > 0 {
+        let line_str = String::from_utf8_lossy(&line).trim_end().to_string();
+        num_lines += 1;
+
+        if head.len() < PREVIEW_LINES {
+            head.push(line_str);
+        } else {
+            if tail.len() == PREVIEW_LINES {
+                tail.pop_front();
+            }
+
+            tail.push_back(line_str);
+        }
+
+        line.clear();
+    }
+
+    let mut preview = head;
+
+    if num_lines > 2 * PREVIEW_LINES {
+        preview.push(format!(
+            "... truncated ({:.1} MB, {} lines omitted), {} ...",
+            file_size as f64 / (1024.0 * 1024.0),
+            num_lines - 2 * PREVIEW_LINES,
+            load_hint
+        ));
+    }
+
+    preview.extend(tail);
+
+    Ok(Some(preview.join("\n")))
+}
+
+fn single_nested_dir(dir: &Path) -> Result<Option<PathBuf>> {
+    let mut nested_dir = None;
+
+    for entry in fs::read_dir(dir).map_err(|e| {
+        OwlError::FileError(
+            format!("Failed to read dir '{}'", dir.to_string_lossy()),
+            e.to_string(),
+        )
+    })? {
+        let path = entry
+            .map_err(|e| {
+                OwlError::FileError(
+                    format!("Failed to read entry in dir '{}'", dir.to_string_lossy()),
+                    e.to_string(),
+                )
+            })?
+            .path();
+
+        let is_junk = path
+            .file_name()
+            .and_then(OsStr::to_str)
+            .is_some_and(|name| name.starts_with('.') || name == "__MACOSX");
+
+        if is_junk {
+            continue;
+        }
+
+        // a lone 'hidden/' holds hidden cases, which would be shown if moved out of it
+        if !path.is_dir() || nested_dir.is_some() || path.file_name() == Some(HIDDEN_DIR.as_ref()) {
+            return Ok(None);
+        }
+
+        nested_dir = Some(path);
+    }
+
+    Ok(nested_dir)
+}
+
 pub fn remove_path(path: &Path) -> Result<()> {
     if !path.exists() {
         return Ok(());
@@ -475,3 +1430,63 @@ pub fn remove_path(path: &Path) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let test_dir = std::env::temp_dir().join(format!("owlgo-test-{}-{}", process::id(), name));
+
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&test_dir).unwrap();
+
+        test_dir
+    }
+
+    #[test]
+    fn check_name_rejects_escaping_and_reserved_names() {
+        for name in ["..", "a/b", "", ".manifest.toml", "scripts"] {
+            assert!(
+                check_name(name, "quest").is_err(),
+                "'{}' was accepted",
+                name
+            );
+        }
+
+        assert_eq!(check_name("two_sum", "quest").unwrap(), "two_sum");
+        assert_eq!(check_name("scripts", "prompt").unwrap(), "scripts");
+    }
+
+    #[test]
+    fn normalize_flattens_a_lone_nested_dir() {
+        let quest_dir = test_dir("nested-quest");
+        fs::create_dir_all(quest_dir.join("cases")).unwrap();
+        fs::write(quest_dir.join("cases").join("a.in"), "1\n").unwrap();
+        fs::write(quest_dir.join("cases").join("a.ans"), "1\n").unwrap();
+
+        normalize_quest_dir(&quest_dir).unwrap();
+
+        assert!(quest_dir.join("a.in").is_file());
+        assert!(!quest_dir.join("cases").exists());
+
+        fs::remove_dir_all(&quest_dir).unwrap();
+    }
+
+    #[test]
+    fn normalize_keeps_a_lone_hidden_dir() {
+        let quest_dir = test_dir("hidden-quest");
+        let hidden_dir = quest_dir.join(HIDDEN_DIR);
+        fs::create_dir_all(&hidden_dir).unwrap();
+        fs::write(hidden_dir.join("a.in"), "1\n").unwrap();
+        fs::write(hidden_dir.join("a.ans"), "1\n").unwrap();
+
+        normalize_quest_dir(&quest_dir).unwrap();
+
+        assert!(hidden_dir.join("a.in").is_file());
+        assert!(is_hidden_case(&hidden_dir.join("a.in")));
+        assert!(!quest_dir.join("a.in").exists());
+
+        fs::remove_dir_all(&quest_dir).unwrap();
+    }
+}
@@ -0,0 +1,278 @@
+use super::toml_utils::{self, QuestEntry};
+use super::{Uri, fs_utils};
+use crate::common::{OwlError, Result};
+use crate::{MANIFEST, OWL_DIR, TOML_TEMPLATE};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table, value};
+
+/// Where quests are looked up, in order: personal entries shadow those from extensions.
+pub const QUEST_TABLES: [&str; 2] = ["personal_quests", "quests"];
+pub const PROMPT_TABLES: [&str; 2] = ["personal_prompts", "prompts"];
+/// Extensions read from a clone of a git repo (by name, the repo they were cloned from).
+pub const EXT_GIT_TABLE: &str = "ext_git";
+
+/// The manifest in the owlgo dir, with typed lookups over its tables. Edits go through the
+/// underlying document so comments and formatting survive a round trip.
+pub struct Manifest {
+    path: PathBuf,
+    doc: DocumentMut,
+}
+
+/// The `[manifest]` table of the local manifest, the remote header, or an extension. Any entry
+/// may be missing, so reads go through accessors that fail instead of panicking.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ManifestHeader {
+    version: Option<String>,
+    timestamp: Option<String>,
+    ai_sdk: Option<String>,
+    api_key: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExtensionEntry {
+    pub name: String,
+    pub uri: Uri,
+    pub timestamp: String,
+    pub pub_key: Option<String>,
+    pub git: Option<String>,
+}
+
+impl Manifest {
+    /// Reads the manifest, starting from the template when there is none yet.
+    pub fn load() -> Result<Self> {
+        let path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+
+        let doc = if path.exists() {
+            toml_utils::read_toml(&path)?
+        } else {
+            TOML_TEMPLATE.parse::<DocumentMut>().map_err(|e| {
+                OwlError::TomlError("Failed to parse TOML template".into(), e.to_string())
+            })?
+        };
+
+        Ok(Manifest { path, doc })
+    }
+
+    /// Reads the manifest, failing when there is none yet.
+    pub fn open() -> Result<Self> {
+        let path = fs_utils::ensure_path_from_home(&[OWL_DIR], Some(MANIFEST))?;
+
+        if !path.exists() {
+            return Err(OwlError::FileError(
+                "The manifest does not exist".into(),
+                "run 'owlgo update'".into(),
+            ));
+        }
+
+        let doc = toml_utils::read_toml(&path)?;
+
+        Ok(Manifest { path, doc })
+    }
+
+    /// Wraps a document that is not (yet) the one on disk, e.g. a freshly downloaded manifest.
+    pub fn from_doc(path: &Path, doc: DocumentMut) -> Self {
+        Manifest {
+            path: path.to_path_buf(),
+            doc,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn doc(&self) -> &DocumentMut {
+        &self.doc
+    }
+
+    pub fn doc_mut(&mut self) -> &mut DocumentMut {
+        &mut self.doc
+    }
+
+    pub fn header(&self) -> Result<ManifestHeader> {
+        ManifestHeader::from_doc(&self.doc)
+    }
+
+    pub fn lookup_quest(&self, quest_name: &str) -> Result<Option<QuestEntry>> {
+        lookup(&self.doc, &QUEST_TABLES, quest_name)
+            .map(|item| QuestEntry::from_item(quest_name, item))
+            .transpose()
+    }
+
+    pub fn lookup_prompt(&self, prompt_name: &str) -> Result<Option<Uri>> {
+        lookup(&self.doc, &PROMPT_TABLES, prompt_name)
+            .map(|item| {
+                item.as_str()
+                    .ok_or(OwlError::TomlError(
+                        format!("Invalid entry '{}' in manifest", prompt_name),
+                        "expected a URL".into(),
+                    ))
+                    .and_then(Uri::try_from)
+            })
+            .transpose()
+    }
+
+    /// Every quest in the manifest, personal quests included, sorted by name.
+    pub fn quests(&self) -> Result<Vec<QuestEntry>> {
+        let mut quests: Vec<QuestEntry> = Vec::new();
+
+        for table_name in QUEST_TABLES {
+            for (quest_name, item) in entries(&self.doc, table_name) {
+                if !quests.iter().any(|quest| quest.name == quest_name) {
+                    quests.push(QuestEntry::from_item(quest_name, item)?);
+                }
+            }
+        }
+
+        quests.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(quests)
+    }
+
+    /// Each registered extension with where it is read from and the key it is pinned to.
+    pub fn iter_extensions(&self) -> impl Iterator<Item = Result<ExtensionEntry>> + '_ {
+        entries(&self.doc, "extensions").map(|(ext_name, timestamp)| {
+            Ok(ExtensionEntry {
+                name: ext_name.to_string(),
+                uri: self.extension_uri(ext_name)?,
+                timestamp: timestamp
+                    .as_str()
+                    .ok_or(OwlError::TomlError(
+                        format!(
+                            "Invalid entry for 'timestamp' in table 'manifest' in local extension '{}'",
+                            ext_name
+                        ),
+                        "None".into(),
+                    ))?
+                    .to_string(),
+                pub_key: toml_utils::extension_key(&self.doc, ext_name),
+                git: self.extension_git(ext_name),
+            })
+        })
+    }
+
+    pub fn extension_uri(&self, ext_name: &str) -> Result<Uri> {
+        let uri_str = self
+            .doc
+            .get("ext_uri")
+            .and_then(|ext_uri| ext_uri.get(ext_name))
+            .ok_or(OwlError::TomlError(
+                format!("'{}': no such entry found manifest", ext_name),
+                "None".into(),
+            ))?
+            .as_str()
+            .ok_or(OwlError::TomlError(
+                format!("Invalid URI entry '{}' in manifest", ext_name),
+                "None".into(),
+            ))?;
+
+        Uri::try_from(uri_str)
+    }
+
+    /// The repo a team extension was cloned from, if it is one.
+    pub fn extension_git(&self, ext_name: &str) -> Option<String> {
+        lookup(&self.doc, &[EXT_GIT_TABLE], ext_name)?
+            .as_str()
+            .map(String::from)
+    }
+
+    pub fn set_personal_quest(&mut self, quest_name: &str, uri: &Uri) -> Result<()> {
+        set_entry(&mut self.doc, QUEST_TABLES[0], quest_name, uri_value(uri)?);
+        Ok(())
+    }
+
+    pub fn set_personal_prompt(&mut self, prompt_name: &str, uri: &Uri) -> Result<()> {
+        set_entry(
+            &mut self.doc,
+            PROMPT_TABLES[0],
+            prompt_name,
+            uri_value(uri)?,
+        );
+        Ok(())
+    }
+
+    pub fn write(&self) -> Result<()> {
+        toml_utils::write_manifest(&self.doc, &self.path)
+    }
+}
+
+impl ManifestHeader {
+    pub fn from_doc(doc: &DocumentMut) -> Result<Self> {
+        let header = entries(doc, "manifest")
+            .filter_map(|(key, item)| Some((key.to_string(), item.as_str()?.into())))
+            .collect::<Map<String, Value>>();
+
+        serde_json::from_value(Value::Object(header))
+            .map_err(|e| OwlError::TomlError("Invalid table 'manifest'".into(), e.to_string()))
+    }
+
+    pub fn version(&self) -> Result<&str> {
+        require(&self.version, "version")
+    }
+
+    pub fn timestamp(&self) -> Result<&str> {
+        require(&self.timestamp, "timestamp")
+    }
+
+    pub fn ai_sdk(&self) -> Result<&str> {
+        require(&self.ai_sdk, "ai_sdk")
+    }
+
+    pub fn api_key(&self) -> Result<&str> {
+        require(&self.api_key, "api_key")
+    }
+}
+
+fn require<'a>(entry: &'a Option<String>, key: &str) -> Result<&'a str> {
+    entry.as_deref().ok_or(OwlError::TomlError(
+        format!("Failed to extract '{}' from table 'manifest'", key),
+        "missing or not a string".into(),
+    ))
+}
+
+/// The first entry named `key` across `tables`.
+fn lookup<'a>(doc: &'a DocumentMut, tables: &[&str], key: &str) -> Option<&'a Item> {
+    tables
+        .iter()
+        .find_map(|table_name| doc.get(table_name)?.as_table_like()?.get(key))
+}
+
+/// The entries of a top-level table, or none when it is missing or not a table.
+pub fn entries<'a>(
+    doc: &'a DocumentMut,
+    table_name: &str,
+) -> impl Iterator<Item = (&'a str, &'a Item)> {
+    doc.get(table_name)
+        .and_then(Item::as_table_like)
+        .into_iter()
+        .flat_map(|table| table.iter())
+}
+
+/// Sets `key` in a top-level table, creating the table (rather than an inline one) if needed.
+pub fn set_entry(doc: &mut DocumentMut, table_name: &str, key: &str, item: Item) {
+    if !doc.get(table_name).is_some_and(Item::is_table_like) {
+        doc[table_name] = Item::Table(Table::new());
+    }
+
+    if let Some(table) = doc[table_name].as_table_like_mut() {
+        table.insert(key, item);
+    }
+}
+
+/// Copies every entry of `from`'s table into `doc`'s, overwriting any with the same key.
+pub fn merge_table(doc: &mut DocumentMut, from: &DocumentMut, table_name: &str) {
+    for (key, item) in entries(from, table_name) {
+        set_entry(doc, table_name, key, item.clone());
+    }
+}
+
+fn uri_value(uri: &Uri) -> Result<Item> {
+    Ok(value(match uri {
+        Uri::Local(path) => path
+            .to_str()
+            .ok_or(OwlError::UriError("Invalid URI".into(), "None".into()))?,
+        Uri::Remote(url) => url.as_str(),
+    }))
+}
@@ -1,5 +1,15 @@
+pub mod cloud_utils;
 pub mod fs_utils;
+pub mod manifest;
+pub mod owl_config;
+pub mod pool_utils;
+pub mod solve_utils;
 pub mod toml_utils;
 pub mod uri;
+pub mod workspace_utils;
 
+pub use manifest::{Manifest, ManifestHeader};
+pub use owl_config::{OwlConfig, OwlHooks, QuestMeta};
+pub use toml_utils::QuestEntry;
 pub use uri::Uri;
+pub use workspace_utils::WorkspaceScope;
@@ -0,0 +1,641 @@
+use super::solve_utils::PinAction;
+use super::toml_utils;
+use crate::QUEST_META;
+use crate::common::{OwlError, OwlWarning, Result, warn};
+use crate::owl_utils::cmd::cmd_utils::RunOptions;
+use crate::owl_utils::cmd::fmt_utils::Formatters;
+use crate::owl_utils::cmd::judge_utils::{CompareMode, OutputFilter, OutputFormat, Shard};
+use crate::owl_utils::cmd::sandbox_utils::Sandbox;
+use crate::owl_utils::llm::llm_utils::AI_SDKS;
+use crate::owl_utils::tui::{KeyMap, TuiTheme};
+use encoding_rs::Encoding;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use toml_edit::{DocumentMut, Item, TableLike};
+use url::Url;
+
+const CONFIG_KEYS: &[&str] = &[
+    "quest",
+    "lang",
+    "compare",
+    "filters",
+    "format",
+    "build_args",
+    "jvm_args",
+    "output_limit_mib",
+    "scratch",
+    "aux_files",
+    "script",
+    "hooks",
+    "jobs",
+    "ai_sdk",
+    "api_key",
+    "manifest_url",
+    "header_url",
+];
+/// Images for Docker by language name, unless the manifest's `[docker]` table pins others.
+/// Programs without a language (e.g. prebuilt binaries) run in the `binary` image.
+const DEFAULT_DOCKER_IMAGES: &[(&str, &str)] = &[
+    ("bash", "bash:5"),
+    ("binary", "debian:bookworm-slim"),
+    ("c", "gcc:13"),
+    ("cpp", "gcc:13"),
+    ("go", "golang:1.22"),
+    ("java", "eclipse-temurin:21"),
+    ("javascript", "node:22"),
+    ("python", "python:3.12"),
+    ("ruby", "ruby:3.3"),
+    ("rust", "rust:1.79"),
+];
+const HOOK_KEYS: &[&str] = &["pre_build", "post_test", "on_accept"];
+const QUEST_META_KEYS: &[&str] = &[
+    "input_file",
+    "output_file",
+    "time_limit_ms",
+    "memory_limit_mib",
+    "encoding",
+    "filters",
+];
+const MANIFEST_KEYS: &[&str] = &[
+    "version",
+    "timestamp",
+    "ai_sdk",
+    "api_key",
+    "model",
+    "ollama_url",
+    "github_token",
+    "update_interval_hours",
+];
+
+pub const ENV_MANIFEST_URL: &str = "OWLGO_MANIFEST_URL";
+pub const ENV_AI_SDK: &str = "OWLGO_AI_SDK";
+pub const ENV_API_KEY: &str = "OWLGO_API_KEY";
+pub const ENV_HEADER_URL: &str = "OWLGO_HEADER_URL";
+pub const ENV_JOBS: &str = "OWLGO_JOBS";
+
+/// Settings loaded from the nearest `.owlgo.toml`, with hooks and the TUI falling back to the manifest.
+/// Settings that can be given without editing any file (e.g., on CI or lab machines) are
+/// resolved with flags first, then `OWLGO_*` environment variables, then `.owlgo.toml`, then
+/// the manifest.
+#[derive(Clone, Debug, Default)]
+pub struct OwlConfig {
+    pub quest: Option<String>,
+    pub lang: Option<String>,
+    /// The language given with `--lang`, overriding detection.
+    pub force_lang: Option<String>,
+    pub compare: CompareMode,
+    pub filters: Vec<OutputFilter>,
+    pub format: OutputFormat,
+    pub shard: Option<Shard>,
+    pub jobs: usize,
+    pub show_top: bool,
+    pub show_intro: bool,
+    pub show_build: bool,
+    pub pin: Option<PinAction>,
+    pub build_args: Vec<String>,
+    pub jvm_args: Vec<String>,
+    pub output_limit: Option<u64>,
+    pub scratch: bool,
+    pub aux_files: Vec<PathBuf>,
+    pub script: Option<String>,
+    pub hooks: OwlHooks,
+    pub keymap: KeyMap,
+    pub theme: TuiTheme,
+    /// Commit and tag accepted solutions in the stash (`git.tag_accepted` in the manifest).
+    pub tag_accepted: bool,
+    /// Build and run programs in Docker (`docker.enabled` in the manifest, or `--docker`).
+    pub docker: bool,
+    /// Images pinned by language name in the manifest's `[docker]` table.
+    pub docker_images: Vec<(String, String)>,
+    pub sandbox: Option<Sandbox>,
+    pub formatters: Formatters,
+    pub build_cache: Option<PathBuf>,
+    pub failure_log: Option<PathBuf>,
+    /// Where `quest --results` writes the run's results as JSON.
+    pub results: Option<PathBuf>,
+    /// The quest's time (wall clock) and memory (peak RSS, in bytes) limits for each run.
+    pub time_limit: Option<Duration>,
+    pub memory_limit: Option<u64>,
+    /// Decodes outputs when previewing them (e.g. Latin-1 answers from legacy quests).
+    pub encoding: Option<&'static Encoding>,
+    /// Where `update` downloads the manifest and its header from, in place of the official ones.
+    pub manifest_url: Option<Url>,
+    pub header_url: Option<Url>,
+    /// The LLM backend and its API key, in place of the manifest's.
+    pub ai_sdk: Option<String>,
+    pub api_key: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OwlHooks {
+    pub pre_build: Option<String>,
+    pub post_test: Option<String>,
+    pub on_accept: Option<String>,
+}
+
+/// Per-quest settings from the quest's `.quest.toml`, e.g. for problems that read and write
+/// named files instead of stdin and stdout.
+#[derive(Clone, Debug, Default)]
+pub struct QuestMeta {
+    pub input_file: Option<String>,
+    pub output_file: Option<String>,
+    pub time_limit: Option<Duration>,
+    /// Peak memory in bytes.
+    pub memory_limit: Option<u64>,
+    /// How expected outputs are encoded, for display only; judging compares raw bytes.
+    pub encoding: Option<&'static Encoding>,
+    pub filters: Vec<OutputFilter>,
+}
+
+impl QuestMeta {
+    pub fn load(quest_dir: &Path) -> Result<Self> {
+        let meta_path = quest_dir.join(QUEST_META);
+
+        if !meta_path.is_file() {
+            return Ok(QuestMeta::default());
+        }
+
+        let meta_doc = toml_utils::read_toml(&meta_path)?;
+        warn_unknown_keys(&meta_path, meta_doc.as_table(), "", QUEST_META_KEYS);
+
+        let get_file_name = |key: &str| match get_str(&meta_path, &meta_doc, key)? {
+            Some(file_name) if Path::new(&file_name).file_name() != Some(file_name.as_ref()) => {
+                Err(OwlError::TomlError(
+                    format!(
+                        "Invalid entry '{}' in '{}'",
+                        key,
+                        meta_path.to_string_lossy()
+                    ),
+                    "expected a plain file name".into(),
+                ))
+            }
+            file_name => Ok(file_name),
+        };
+
+        let time_limit = match meta_doc.get("time_limit_ms") {
+            Some(item) => Some(
+                item.as_integer()
+                    .filter(|&ms| ms > 0)
+                    .map(|ms| Duration::from_millis(ms as u64))
+                    .ok_or(OwlError::TomlError(
+                        format!(
+                            "Invalid entry 'time_limit_ms' in '{}'",
+                            meta_path.to_string_lossy()
+                        ),
+                        "expected a positive integer".into(),
+                    ))?,
+            ),
+            None => None,
+        };
+
+        let memory_limit = match meta_doc.get("memory_limit_mib") {
+            Some(item) => Some(
+                item.as_integer()
+                    .filter(|&mib| mib > 0)
+                    .map(|mib| mib as u64 * 1024 * 1024)
+                    .ok_or(OwlError::TomlError(
+                        format!(
+                            "Invalid entry 'memory_limit_mib' in '{}'",
+                            meta_path.to_string_lossy()
+                        ),
+                        "expected a positive integer".into(),
+                    ))?,
+            ),
+            None => None,
+        };
+
+        let encoding = match get_str(&meta_path, &meta_doc, "encoding")? {
+            Some(label) => Some(Encoding::for_label(label.as_bytes()).ok_or(
+                OwlError::TomlError(
+                    format!(
+                        "Invalid entry 'encoding' in '{}'",
+                        meta_path.to_string_lossy()
+                    ),
+                    format!("'{}': unknown encoding", label),
+                ),
+            )?),
+            None => None,
+        };
+
+        Ok(QuestMeta {
+            input_file: get_file_name("input_file")?,
+            output_file: get_file_name("output_file")?,
+            time_limit,
+            memory_limit,
+            encoding,
+            filters: get_filters(&meta_path, &meta_doc)?,
+        })
+    }
+}
+
+impl OwlConfig {
+    pub fn load(config_name: &str) -> Result<Self> {
+        let cwd = env::current_dir().map_err(|e| {
+            OwlError::FileError(
+                "Failed to determine current working directory".into(),
+                e.to_string(),
+            )
+        })?;
+
+        match find_config(&cwd, config_name) {
+            Some(config_path) => {
+                let config_doc = toml_utils::read_toml(&config_path)?;
+                OwlConfig::from_doc(&config_path, &config_doc)
+            }
+            None => Ok(OwlConfig::default()),
+        }
+    }
+
+    /// This config with the `OWLGO_*` environment variables applied over `.owlgo.toml`.
+    pub fn with_env(mut self) -> Result<Self> {
+        let get_var = |key: &str| env::var(key).ok().filter(|var| !var.trim().is_empty());
+
+        let invalid = |key: &str, var: &str, expected: &str| {
+            OwlError::TomlError(
+                format!("Invalid environment variable {}='{}'", key, var),
+                format!("expected {}", expected),
+            )
+        };
+
+        if let Some(var) = get_var(ENV_MANIFEST_URL) {
+            self.manifest_url =
+                Some(Url::parse(var.trim()).map_err(|_| invalid(ENV_MANIFEST_URL, &var, "a URL"))?);
+        }
+
+        if let Some(var) = get_var(ENV_HEADER_URL) {
+            self.header_url =
+                Some(Url::parse(var.trim()).map_err(|_| invalid(ENV_HEADER_URL, &var, "a URL"))?);
+        }
+
+        if let Some(var) = get_var(ENV_AI_SDK) {
+            if !AI_SDKS.contains(&var.trim()) {
+                return Err(invalid(
+                    ENV_AI_SDK,
+                    &var,
+                    &format!("one of {}", AI_SDKS.join(", ")),
+                ));
+            }
+
+            self.ai_sdk = Some(var.trim().to_string());
+        }
+
+        if let Some(var) = get_var(ENV_API_KEY) {
+            self.api_key = Some(var);
+        }
+
+        if let Some(var) = get_var(ENV_JOBS) {
+            self.jobs = var
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|&jobs| jobs > 0)
+                .ok_or_else(|| invalid(ENV_JOBS, &var, "a positive integer"))?;
+        }
+
+        Ok(self)
+    }
+
+    pub fn with_manifest(mut self, manifest_path: &Path) -> Result<Self> {
+        if !manifest_path.exists() {
+            return Ok(self);
+        }
+
+        let manifest_doc = toml_utils::read_toml(manifest_path)?;
+
+        if let Some(manifest_table) = manifest_doc.get("manifest").and_then(Item::as_table_like) {
+            warn_unknown_keys(manifest_path, manifest_table, "manifest.", MANIFEST_KEYS);
+        }
+
+        let fallback = OwlHooks::from_item(manifest_path, manifest_doc.get("hooks"))?;
+
+        self.hooks = OwlHooks {
+            pre_build: self.hooks.pre_build.or(fallback.pre_build),
+            post_test: self.hooks.post_test.or(fallback.post_test),
+            on_accept: self.hooks.on_accept.or(fallback.on_accept),
+        };
+        self.keymap = KeyMap::from_item(manifest_path, manifest_doc.get("tui"))?;
+        self.theme = TuiTheme::from_item(manifest_path, manifest_doc.get("appearance"))?;
+        self.sandbox = Sandbox::from_item(manifest_path, manifest_doc.get("sandbox"))?;
+        self.formatters = Formatters::from_item(manifest_path, manifest_doc.get("formatters"))?;
+        self.tag_accepted = manifest_doc
+            .get("git")
+            .and_then(|git| git.get("tag_accepted"))
+            .and_then(Item::as_bool)
+            .unwrap_or(false);
+
+        if let Some(docker) = manifest_doc.get("docker").and_then(Item::as_table_like) {
+            self.docker = docker
+                .get("enabled")
+                .and_then(Item::as_bool)
+                .unwrap_or(false);
+            self.docker_images = docker
+                .iter()
+                .filter_map(|(lang_name, image)| {
+                    image
+                        .as_str()
+                        .map(|image| (lang_name.to_string(), image.to_string()))
+                })
+                .collect();
+        }
+
+        Ok(self)
+    }
+
+    pub fn check_manifest(manifest_path: &Path, manifest_doc: &DocumentMut) -> Result<()> {
+        OwlHooks::from_item(manifest_path, manifest_doc.get("hooks"))?;
+        KeyMap::from_item(manifest_path, manifest_doc.get("tui"))?;
+        TuiTheme::from_item(manifest_path, manifest_doc.get("appearance"))?;
+        Sandbox::from_item(manifest_path, manifest_doc.get("sandbox"))?;
+        Formatters::from_item(manifest_path, manifest_doc.get("formatters"))?;
+
+        Ok(())
+    }
+
+    fn from_doc(config_path: &Path, config_doc: &DocumentMut) -> Result<Self> {
+        warn_unknown_keys(config_path, config_doc.as_table(), "", CONFIG_KEYS);
+
+        let compare = match get_str(config_path, config_doc, "compare")? {
+            Some(mode) => CompareMode::try_from(mode.as_str())?,
+            None => CompareMode::default(),
+        };
+
+        let format = match get_str(config_path, config_doc, "format")? {
+            Some(format) => OutputFormat::try_from(format.as_str())?,
+            None => OutputFormat::default(),
+        };
+
+        let build_args = get_str_array(config_path, config_doc, "build_args")?;
+        let jvm_args = get_str_array(config_path, config_doc, "jvm_args")?;
+
+        let output_limit = match config_doc.get("output_limit_mib") {
+            Some(item) => Some(
+                item.as_integer()
+                    .filter(|&mib| mib > 0)
+                    .map(|mib| mib as u64 * 1024 * 1024)
+                    .ok_or(OwlError::TomlError(
+                        format!(
+                            "Invalid entry 'output_limit_mib' in '{}'",
+                            config_path.to_string_lossy()
+                        ),
+                        "expected a positive integer".into(),
+                    ))?,
+            ),
+            None => None,
+        };
+
+        let scratch = match config_doc.get("scratch") {
+            Some(item) => item.as_bool().ok_or(OwlError::TomlError(
+                format!(
+                    "Invalid entry 'scratch' in '{}'",
+                    config_path.to_string_lossy()
+                ),
+                "expected a boolean".into(),
+            ))?,
+            None => false,
+        };
+
+        let jobs = match config_doc.get("jobs") {
+            Some(item) => item
+                .as_integer()
+                .filter(|&jobs| jobs > 0)
+                .map(|jobs| jobs as usize)
+                .ok_or(OwlError::TomlError(
+                    format!(
+                        "Invalid entry 'jobs' in '{}'",
+                        config_path.to_string_lossy()
+                    ),
+                    "expected a positive integer".into(),
+                ))?,
+            None => 1,
+        };
+
+        let ai_sdk = match get_str(config_path, config_doc, "ai_sdk")? {
+            Some(ai_sdk) if !AI_SDKS.contains(&ai_sdk.as_str()) => {
+                return Err(OwlError::TomlError(
+                    format!(
+                        "Invalid entry 'ai_sdk' in '{}'",
+                        config_path.to_string_lossy()
+                    ),
+                    format!("expected one of {}", AI_SDKS.join(", ")),
+                ));
+            }
+            ai_sdk => ai_sdk,
+        };
+
+        let config_dir = config_path.parent().unwrap_or(Path::new("."));
+        let aux_files = get_str_array(config_path, config_doc, "aux_files")?
+            .into_iter()
+            .map(|aux_file| config_dir.join(aux_file))
+            .collect();
+
+        Ok(OwlConfig {
+            quest: get_str(config_path, config_doc, "quest")?,
+            lang: get_str(config_path, config_doc, "lang")?,
+            force_lang: None,
+            compare,
+            filters: get_filters(config_path, config_doc)?,
+            format,
+            shard: None,
+            jobs,
+            show_top: false,
+            show_intro: false,
+            show_build: false,
+            pin: None,
+            build_args,
+            jvm_args,
+            output_limit,
+            scratch,
+            aux_files,
+            script: get_str(config_path, config_doc, "script")?,
+            hooks: OwlHooks::from_item(config_path, config_doc.get("hooks"))?,
+            keymap: KeyMap::default(),
+            theme: TuiTheme::default(),
+            tag_accepted: false,
+            docker: false,
+            docker_images: Vec::new(),
+            sandbox: None,
+            formatters: Formatters::default(),
+            build_cache: None,
+            failure_log: None,
+            results: None,
+            time_limit: None,
+            memory_limit: None,
+            encoding: None,
+            manifest_url: get_url(config_path, config_doc, "manifest_url")?,
+            header_url: get_url(config_path, config_doc, "header_url")?,
+            ai_sdk,
+            api_key: get_str(config_path, config_doc, "api_key")?,
+        })
+    }
+
+    /// This config with a quest's `.quest.toml` settings applied for judging it, its filters
+    /// running before the configured ones.
+    pub fn for_quest(&self, meta: &QuestMeta) -> OwlConfig {
+        OwlConfig {
+            filters: meta.filters.iter().chain(&self.filters).cloned().collect(),
+            time_limit: meta.time_limit,
+            memory_limit: meta.memory_limit,
+            encoding: meta.encoding,
+            ..self.clone()
+        }
+    }
+
+    /// The image a language is built and run in, or `None` to use the host's toolchain.
+    pub fn docker_image(&self, lang_name: &str) -> Option<String> {
+        if !self.docker {
+            return None;
+        }
+
+        self.docker_images
+            .iter()
+            .find(|(name, _)| name == lang_name)
+            .map(|(_, image)| image.clone())
+            .or_else(|| {
+                DEFAULT_DOCKER_IMAGES
+                    .iter()
+                    .find(|(name, _)| *name == lang_name)
+                    .map(|(_, image)| image.to_string())
+            })
+    }
+
+    /// How programs in `lang_name` are run under this config.
+    pub fn run_options(&self, lang_name: &str) -> RunOptions {
+        RunOptions {
+            docker_image: self.docker_image(lang_name),
+            sandbox: self.sandbox.clone(),
+            output_limit: self.output_limit,
+            // twice the time limit, so a run just over it is still timed rather than killed
+            deadline: self
+                .time_limit
+                .map(|limit| limit * 2 + Duration::from_secs(1)),
+        }
+    }
+}
+
+impl OwlHooks {
+    fn from_item(config_path: &Path, item: Option<&Item>) -> Result<Self> {
+        let Some(item) = item else {
+            return Ok(OwlHooks::default());
+        };
+
+        let hooks_table = item.as_table_like().ok_or(OwlError::TomlError(
+            format!(
+                "Invalid entry 'hooks' in '{}'",
+                config_path.to_string_lossy()
+            ),
+            "expected a table".into(),
+        ))?;
+
+        warn_unknown_keys(config_path, hooks_table, "hooks.", HOOK_KEYS);
+
+        let get_hook = |key: &str| match hooks_table.get(key) {
+            Some(hook) => hook
+                .as_str()
+                .map(|s| Some(s.to_string()))
+                .ok_or(OwlError::TomlError(
+                    format!(
+                        "Invalid entry 'hooks.{}' in '{}'",
+                        key,
+                        config_path.to_string_lossy()
+                    ),
+                    "expected a string".into(),
+                )),
+            None => Ok(None),
+        };
+
+        Ok(OwlHooks {
+            pre_build: get_hook("pre_build")?,
+            post_test: get_hook("post_test")?,
+            on_accept: get_hook("on_accept")?,
+        })
+    }
+}
+
+fn find_config(cwd: &Path, config_name: &str) -> Option<PathBuf> {
+    cwd.ancestors()
+        .map(|dir| dir.join(config_name))
+        .find(|config_path| config_path.is_file())
+}
+
+fn warn_unknown_keys(config_path: &Path, table: &dyn TableLike, prefix: &str, known: &[&str]) {
+    for (key, _) in table.iter().filter(|(key, _)| !known.contains(key)) {
+        warn(OwlWarning::UnknownKey(
+            format!("{}{}", prefix, key),
+            config_path.to_string_lossy().to_string(),
+        ));
+    }
+}
+
+fn get_str_array(config_path: &Path, config_doc: &DocumentMut, key: &str) -> Result<Vec<String>> {
+    let invalid_entry = || {
+        OwlError::TomlError(
+            format!(
+                "Invalid entry '{}' in '{}'",
+                key,
+                config_path.to_string_lossy()
+            ),
+            "expected an array of strings".into(),
+        )
+    };
+
+    match config_doc.get(key) {
+        Some(item) => item
+            .as_array()
+            .ok_or_else(invalid_entry)?
+            .iter()
+            .map(|arg| arg.as_str().map(String::from).ok_or_else(invalid_entry))
+            .collect(),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn get_filters(config_path: &Path, config_doc: &DocumentMut) -> Result<Vec<OutputFilter>> {
+    get_str_array(config_path, config_doc, "filters")?
+        .iter()
+        .map(|filter| {
+            OutputFilter::try_from(filter.as_str()).map_err(|e| {
+                OwlError::TomlError(
+                    format!(
+                        "Invalid entry 'filters' in '{}'",
+                        config_path.to_string_lossy()
+                    ),
+                    e.to_string(),
+                )
+            })
+        })
+        .collect()
+}
+
+fn get_url(config_path: &Path, config_doc: &DocumentMut, key: &str) -> Result<Option<Url>> {
+    match get_str(config_path, config_doc, key)? {
+        Some(url) => Url::parse(url.trim()).map(Some).map_err(|_| {
+            OwlError::TomlError(
+                format!(
+                    "Invalid entry '{}' in '{}'",
+                    key,
+                    config_path.to_string_lossy()
+                ),
+                "expected a URL".into(),
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+fn get_str(config_path: &Path, config_doc: &DocumentMut, key: &str) -> Result<Option<String>> {
+    match config_doc.get(key) {
+        Some(item) => item
+            .as_str()
+            .map(|s| Some(s.to_string()))
+            .ok_or(OwlError::TomlError(
+                format!(
+                    "Invalid entry '{}' in '{}'",
+                    key,
+                    config_path.to_string_lossy()
+                ),
+                "expected a string".into(),
+            )),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,225 @@
+use super::fs_utils;
+use crate::common::{OwlError, Result};
+use crate::{OWL_DIR, POOL_DIR};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::copy;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+/// The hashes of the blobs the pool hardlinked into quests, the only ones `gc_pool` may remove.
+const LINKED_INDEX: &str = ".linked";
+
+pub struct GcSummary {
+    pub blobs: usize,
+    pub removed: usize,
+    pub freed: u64,
+}
+
+pub fn hash_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not open '{}'", path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    let mut hasher = Sha256::new();
+
+    copy(&mut file, &mut hasher).map_err(|e| {
+        OwlError::FileError(
+            format!("could not hash '{}'", path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Replaces every file in the quest dir with a hardlink into the content-addressed pool, so
+/// quests that share test data share the bytes on disk.
+pub fn pool_quest_dir(quest_dir: &Path) -> Result<usize> {
+    let pool_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, POOL_DIR], None)?;
+    let mut linked = BTreeSet::new();
+
+    for file in fs_utils::dir_tree(quest_dir)? {
+        let is_hidden = file
+            .file_name()
+            .and_then(OsStr::to_str)
+            .is_some_and(|name| name.starts_with('.'));
+
+        if is_hidden || !file.is_file() {
+            continue;
+        }
+
+        let hash = hash_file(&file)?;
+        let blob = blob_path(&pool_dir, &hash);
+
+        if blob.exists() {
+            if !is_same_file(&file, &blob) && !relink(&blob, &file)? {
+                continue;
+            }
+        } else {
+            if let Some(blob_dir) = blob.parent() {
+                fs::create_dir_all(blob_dir).map_err(|e| {
+                    OwlError::FileError(
+                        format!("could not create dir '{}'", blob_dir.to_string_lossy()),
+                        e.to_string(),
+                    )
+                })?;
+            }
+
+            // filesystems without hardlinks simply keep the quest's own copy
+            if fs::hard_link(&file, &blob).is_err() {
+                continue;
+            }
+        }
+
+        linked.insert(hash);
+    }
+
+    let pooled = linked.len();
+
+    if pooled > 0 {
+        let mut index = read_index(&pool_dir)?;
+        index.append(&mut linked);
+        write_index(&pool_dir, &index)?;
+    }
+
+    Ok(pooled)
+}
+
+/// Removes blobs that no quest links to anymore.
+#[cfg(unix)]
+pub fn gc_pool(dry_run: bool) -> Result<GcSummary> {
+    let pool_dir = fs_utils::ensure_path_from_home(&[OWL_DIR, POOL_DIR], None)?;
+
+    let mut summary = GcSummary {
+        blobs: 0,
+        removed: 0,
+        freed: 0,
+    };
+
+    if !pool_dir.exists() {
+        return Ok(summary);
+    }
+
+    let mut index = read_index(&pool_dir)?;
+
+    for blob in fs_utils::dir_tree(&pool_dir)? {
+        let Some(hash) = blob.file_name().and_then(OsStr::to_str) else {
+            continue;
+        };
+
+        if !index.contains(hash) {
+            continue;
+        }
+
+        let metadata = fs::metadata(&blob).map_err(|e| {
+            OwlError::FileError(
+                format!("could not read '{}'", blob.to_string_lossy()),
+                e.to_string(),
+            )
+        })?;
+
+        summary.blobs += 1;
+
+        if metadata.nlink() > 1 {
+            continue;
+        }
+
+        if !dry_run {
+            fs_utils::remove_path(&blob)?;
+            index.remove(hash);
+        }
+
+        summary.removed += 1;
+        summary.freed += metadata.len();
+    }
+
+    if !dry_run {
+        write_index(&pool_dir, &index)?;
+    }
+
+    Ok(summary)
+}
+
+/// Without link counts there's no telling which blobs are still in use.
+#[cfg(not(unix))]
+pub fn gc_pool(_dry_run: bool) -> Result<GcSummary> {
+    Err(OwlError::Unsupported(
+        "garbage collecting the pool needs hardlink counts, which are only available on unix"
+            .into(),
+    ))
+}
+
+fn read_index(pool_dir: &Path) -> Result<BTreeSet<String>> {
+    let index_path = pool_dir.join(LINKED_INDEX);
+
+    if !index_path.exists() {
+        return Ok(BTreeSet::new());
+    }
+
+    fs::read_to_string(&index_path)
+        .map(|index| index.lines().map(String::from).collect())
+        .map_err(|e| {
+            OwlError::FileError(
+                format!("could not read '{}'", index_path.to_string_lossy()),
+                e.to_string(),
+            )
+        })
+}
+
+fn write_index(pool_dir: &Path, index: &BTreeSet<String>) -> Result<()> {
+    let index_path = pool_dir.join(LINKED_INDEX);
+    let contents = index
+        .iter()
+        .map(|hash| format!("{}\n", hash))
+        .collect::<String>();
+
+    fs::write(&index_path, contents).map_err(|e| {
+        OwlError::FileError(
+            format!("could not write '{}'", index_path.to_string_lossy()),
+            e.to_string(),
+        )
+    })
+}
+
+fn blob_path(pool_dir: &Path, hash: &str) -> PathBuf {
+    pool_dir.join(&hash[..2]).join(hash)
+}
+
+#[cfg(unix)]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+/// Relinking a file that's already the blob is harmless, so everything counts as a copy.
+#[cfg(not(unix))]
+fn is_same_file(_: &Path, _: &Path) -> bool {
+    false
+}
+
+fn relink(blob: &Path, file: &Path) -> Result<bool> {
+    let tmp_path = file.with_file_name(format!(
+        ".{}.pool",
+        file.file_name().and_then(OsStr::to_str).unwrap_or("blob")
+    ));
+
+    if fs::hard_link(blob, &tmp_path).is_err() {
+        return Ok(false);
+    }
+
+    fs::rename(&tmp_path, file).map(|_| true).map_err(|e| {
+        OwlError::FileError(
+            format!("could not link '{}' into the pool", file.to_string_lossy()),
+            e.to_string(),
+        )
+    })
+}
@@ -0,0 +1,171 @@
+use super::{fs_utils, toml_utils};
+use crate::common::{OwlError, Result};
+use crate::{OWL_DIR, PROGRESS};
+use chrono::Local;
+use std::fs;
+use std::path::PathBuf;
+use toml_edit::{Array, DocumentMut, Item, Table, value};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PinAction {
+    Pin,
+    Unpin,
+}
+
+/// How a quest's toolchain for a language is tracked in the progress store.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ToolchainPin {
+    Pinned(String),
+    Unpinned,
+    Unset,
+}
+
+pub fn progress_path() -> Result<PathBuf> {
+    fs_utils::ensure_path_from_home(&[OWL_DIR], Some(PROGRESS))
+}
+
+pub fn read_progress() -> Result<DocumentMut> {
+    let progress_path = progress_path()?;
+
+    if progress_path.exists() {
+        toml_utils::read_toml(&progress_path)
+    } else {
+        Ok(DocumentMut::new())
+    }
+}
+
+pub fn write_progress(progress_doc: &DocumentMut) -> Result<()> {
+    let progress_path = progress_path()?;
+    let tmp_path = progress_path.with_extension("tmp");
+
+    fs::write(&tmp_path, progress_doc.to_string())
+        .and_then(|_| fs::rename(&tmp_path, &progress_path))
+        .map_err(|e| {
+            OwlError::FileError(
+                format!("could not write '{}'", progress_path.to_string_lossy()),
+                e.to_string(),
+            )
+        })
+}
+
+pub fn toolchain_pin(
+    progress_doc: &DocumentMut,
+    quest_name: &str,
+    lang_name: &str,
+) -> ToolchainPin {
+    let pin = progress_doc
+        .get("quests")
+        .and_then(|quests| quests.get(quest_name))
+        .and_then(|quest| quest.get("toolchains"))
+        .and_then(|toolchains| toolchains.get(lang_name));
+
+    match pin.map(|pin| (pin.as_str(), pin.as_bool())) {
+        Some((Some(version), _)) => ToolchainPin::Pinned(version.into()),
+        Some((_, Some(false))) => ToolchainPin::Unpinned,
+        _ => ToolchainPin::Unset,
+    }
+}
+
+/// Pins (or with `None`, explicitly unpins) the toolchain version for a quest and language.
+pub fn set_toolchain_pin(
+    progress_doc: &mut DocumentMut,
+    quest_name: &str,
+    lang_name: &str,
+    version: Option<&str>,
+) {
+    let quest = quest_table(progress_doc, quest_name);
+
+    if !quest.contains_key("toolchains") {
+        quest["toolchains"] = Item::Table(Table::new());
+    }
+
+    quest["toolchains"][lang_name] = match version {
+        Some(version) => value(version),
+        None => value(false),
+    };
+}
+
+/// Counts an accepted run, pinning the toolchain the first time a language is accepted.
+pub fn record_accepted(
+    progress_doc: &mut DocumentMut,
+    quest_name: &str,
+    lang_name: &str,
+    version: Option<&str>,
+) -> bool {
+    let now = Local::now().to_rfc3339();
+    let quest = quest_table(progress_doc, quest_name);
+
+    if !quest.contains_key("first_accepted") {
+        quest["first_accepted"] = value(&now);
+    }
+
+    quest["last_accepted"] = value(&now);
+
+    let accepted_runs = quest
+        .get("accepted_runs")
+        .and_then(Item::as_integer)
+        .unwrap_or(0);
+    quest["accepted_runs"] = value(accepted_runs + 1);
+
+    match version {
+        Some(version)
+            if toolchain_pin(progress_doc, quest_name, lang_name) == ToolchainPin::Unset =>
+        {
+            set_toolchain_pin(progress_doc, quest_name, lang_name, Some(version));
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Cases marked in `owlgo debug` to come back to.
+pub fn revisit_cases(progress_doc: &DocumentMut, quest_name: &str) -> Vec<String> {
+    progress_doc
+        .get("quests")
+        .and_then(|quests| quests.get(quest_name))
+        .and_then(|quest| quest.get("revisit"))
+        .and_then(Item::as_array)
+        .map(|cases| {
+            cases
+                .iter()
+                .filter_map(|case| case.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn set_revisit_cases(progress_doc: &mut DocumentMut, quest_name: &str, cases: &[String]) {
+    if cases.is_empty() {
+        if let Some(quest) = progress_doc
+            .get_mut("quests")
+            .and_then(|quests| quests.get_mut(quest_name))
+            .and_then(Item::as_table_like_mut)
+        {
+            quest.remove("revisit");
+        }
+
+        return;
+    }
+
+    quest_table(progress_doc, quest_name)["revisit"] = value(cases.iter().collect::<Array>());
+}
+
+fn quest_table<'a>(progress_doc: &'a mut DocumentMut, quest_name: &str) -> &'a mut Table {
+    if !progress_doc.contains_key("quests") {
+        let mut quests = Table::new();
+        quests.set_implicit(true);
+        progress_doc["quests"] = Item::Table(quests);
+    }
+
+    let quests = progress_doc["quests"]
+        .as_table_mut()
+        .expect("quests is a table");
+
+    if !quests.contains_key(quest_name) {
+        quests[quest_name] = Item::Table(Table::new());
+    }
+
+    quests[quest_name]
+        .as_table_mut()
+        .expect("quest progress is a table")
+}
@@ -1,48 +1,197 @@
-use super::{Uri, fs_utils};
+use super::manifest::{self, Manifest, ManifestHeader};
+use super::{OwlConfig, Uri, fs_utils};
 use crate::common::{OwlError, Result};
-use reqwest;
+use crate::owl_utils::cmd::{git_utils, progress_utils, term_utils};
+use chrono::Local;
+use minisign_verify::{PublicKey, Signature};
+use reqwest::StatusCode;
+use reqwest::header::{ETAG, IF_NONE_MATCH};
 use std::cmp::Ordering;
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
+use std::process;
+use std::time::Duration;
 use toml_edit::{DocumentMut, Item, Table, value};
 use url::Url;
 
+pub const UPDATE_INTERVAL_HOURS: i64 = 24;
+
+#[derive(Clone, Debug, Default)]
+pub struct QuestEntry {
+    pub name: String,
+    pub url: String,
+    pub desc: Option<String>,
+    pub difficulty: Option<String>,
+    pub source: Option<String>,
+    /// The checksum the quest's archive must match, required by signed extensions.
+    pub sha256: Option<String>,
+}
+
+impl QuestEntry {
+    pub fn from_item(quest_name: &str, item: &Item) -> Result<Self> {
+        let invalid_entry = |info: &str| {
+            OwlError::TomlError(
+                format!("Invalid entry '{}' in manifest", quest_name),
+                info.into(),
+            )
+        };
+
+        if let Some(url) = item.as_str() {
+            return Ok(QuestEntry {
+                name: quest_name.to_string(),
+                url: url.to_string(),
+                ..QuestEntry::default()
+            });
+        }
+
+        let quest_table = item
+            .as_table_like()
+            .ok_or_else(|| invalid_entry("expected a URL or a table"))?;
+
+        let get_str = |key: &str| match quest_table.get(key) {
+            Some(field) => field
+                .as_str()
+                .map(|s| Some(s.to_string()))
+                .ok_or_else(|| invalid_entry(&format!("'{}': expected a string", key))),
+            None => Ok(None),
+        };
+
+        Ok(QuestEntry {
+            name: quest_name.to_string(),
+            url: get_str("url")?.ok_or_else(|| invalid_entry("missing 'url'"))?,
+            desc: get_str("desc")?,
+            difficulty: get_str("difficulty")?,
+            source: get_str("source")?,
+            sha256: get_str("sha256")?,
+        })
+    }
+
+    pub fn describe(&self) -> String {
+        let mut details = Vec::new();
+
+        if let Some(difficulty) = &self.difficulty {
+            details.push(difficulty.clone());
+        }
+        if let Some(source) = &self.source {
+            details.push(source.clone());
+        }
+
+        let mut desc = format!("'{}'", self.name);
+
+        if !details.is_empty() {
+            desc.push_str(&format!(" [{}]", details.join(", ")));
+        }
+        if let Some(quest_desc) = &self.desc {
+            desc.push_str(&format!(" - {}", quest_desc));
+        }
+
+        desc
+    }
+}
+
+/// Quests an extension added or changed at `timestamp`, from its `[changelog]` table.
+#[derive(Clone, Debug, Default)]
+pub struct ChangelogEntry {
+    pub timestamp: String,
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub note: Option<String>,
+}
+
+impl ChangelogEntry {
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+
+        if !self.added.is_empty() {
+            parts.push(format!("added {}", self.added.join(", ")));
+        }
+        if !self.changed.is_empty() {
+            parts.push(format!("changed {}", self.changed.join(", ")));
+        }
+        if let Some(note) = &self.note {
+            parts.push(note.clone());
+        }
+
+        format!("{}: {}", self.timestamp, parts.join("; "))
+    }
+}
+
+/// The changelog entries of an extension newer than `since`, oldest first.
+pub fn extension_changelog(ext_doc: &DocumentMut, since: &str) -> Result<Vec<ChangelogEntry>> {
+    let Some(changelog) = ext_doc.get("changelog").and_then(Item::as_table_like) else {
+        return Ok(Vec::new());
+    };
+
+    let get_list = |item: &Item, key: &str| {
+        item.get(key)
+            .and_then(Item::as_array)
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| name.as_str().map(String::from))
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut entries = Vec::new();
+
+    for (timestamp, item) in changelog.iter() {
+        if compare_stamps(since, timestamp)? != Ordering::Less {
+            continue;
+        }
+
+        entries.push(ChangelogEntry {
+            timestamp: timestamp.to_string(),
+            added: get_list(item, "added"),
+            changed: get_list(item, "changed"),
+            note: item.get("note").and_then(Item::as_str).map(String::from),
+        });
+    }
+
+    entries.sort_by(|a, b| compare_stamps(&a.timestamp, &b.timestamp).unwrap_or(Ordering::Equal));
+
+    Ok(entries)
+}
+
 pub async fn check_updates(
     remote_manifest_url: &Url,
     manifest_path: &Path,
+    header_cache: &Path,
 ) -> Result<(Ordering, Ordering)> {
-    let (local_version, local_timestamp) = get_manifest_version_timestamp(manifest_path)?;
+    let remote_doc = request_remote_header(remote_manifest_url, header_cache).await?;
 
-    let remote_doc = request_toml(remote_manifest_url).await?;
+    compare_remote_header(&remote_doc, manifest_path)
+}
 
-    let remote_version = remote_doc["manifest"]["version"]
-        .as_str()
-        .map(String::from)
-        .ok_or(OwlError::TomlError(
-            format!(
-                "Failed to extract manifest version from remote '{}'",
-                remote_manifest_url
-            ),
-            "None".into(),
-        ))?;
-    let remote_timestamp = remote_doc["manifest"]["timestamp"]
-        .as_str()
-        .map(String::from)
-        .ok_or(OwlError::TomlError(
-            format!(
-                "Failed to extract manifest timestamp from remote '{}'",
-                remote_manifest_url
-            ),
-            "None".into(),
-        ))?;
+pub fn compare_remote_header(
+    remote_doc: &DocumentMut,
+    manifest_path: &Path,
+) -> Result<(Ordering, Ordering)> {
+    let (local_version, local_timestamp) = get_manifest_version_timestamp(manifest_path)?;
+
+    let remote_header = ManifestHeader::from_doc(remote_doc)?;
 
     Ok((
-        compare_stamps(&local_version, &remote_version)?,
-        compare_stamps(&local_timestamp, &remote_timestamp)?,
+        compare_stamps(&local_version, remote_header.version()?)?,
+        compare_stamps(&local_timestamp, remote_header.timestamp()?)?,
     ))
 }
 
+pub fn cached_remote_header(
+    header_cache: &Path,
+    max_age_hours: Option<i64>,
+) -> Option<DocumentMut> {
+    let cached_doc = read_toml(header_cache).ok()?;
+    let checked = cached_doc.get("cache")?.get("checked")?.as_integer()?;
+
+    match max_age_hours {
+        Some(hours) if Local::now().timestamp() - checked >= hours * 3600 => None,
+        _ => Some(cached_doc),
+    }
+}
+
 pub async fn commit_doc(
     manifest_path: &Path,
     prompt_dir: &Path,
@@ -51,45 +200,53 @@ pub async fn commit_doc(
     local_doc: &mut DocumentMut,
     and_fetch_to_tmp: Option<&Path>,
 ) -> Result<()> {
-    if let Some(quests_table) = remote_doc["quests"].as_table() {
+    if let Some(quests_table) = remote_doc.get("quests").and_then(Item::as_table) {
         let mut quest_path = manifest_path
             .parent()
             .expect("manifest file to have parent owlgo directory")
             .to_path_buf();
 
         for (quest_name, quest_uri) in quests_table.iter() {
-            local_doc["quests"][quest_name] = quest_uri.clone();
+            manifest::set_entry(local_doc, "quests", quest_name, quest_uri.clone());
 
             if let Some(tmp_archive) = and_fetch_to_tmp {
                 quest_path.push(quest_name);
 
-                let quest_uri_str = quest_uri.as_str().ok_or(OwlError::TomlError(
-                    format!(
-                        "Invalid entry for '{}' in table 'quests' in extension '{}'",
-                        quest_name, ext_name
-                    ),
-                    "None".into(),
-                ))?;
-
-                match Uri::try_from(quest_uri_str)? {
-                    Uri::Local(path) => {
-                        fs_utils::extract_archive(&path, &quest_path, false).await?;
-                    }
-                    Uri::Remote(url) => {
-                        fs_utils::download_archive(&url, tmp_archive, &quest_path).await?
+                let quest_entry = QuestEntry::from_item(quest_name, quest_uri).map_err(|e| {
+                    OwlError::TomlError(
+                        format!(
+                            "Invalid entry for '{}' in table 'quests' in extension '{}'",
+                            quest_name, ext_name
+                        ),
+                        e.to_string(),
+                    )
+                })?;
+
+                let fetch = async {
+                    match Uri::try_from(quest_entry.url.as_str())? {
+                        Uri::Local(path) => {
+                            let sha256 = quest_entry.sha256.as_deref();
+                            fs_utils::extract_archive(&path, &quest_path, false, sha256).await
+                        }
+                        Uri::Remote(url) => {
+                            let sha256 = quest_entry.sha256.as_deref();
+                            fs_utils::download_archive(&url, tmp_archive, &quest_path, sha256).await
+                        }
                     }
                 };
 
+                progress_utils::track("download", quest_name, fetch).await?;
+
                 quest_path.pop();
             }
         }
     }
 
-    if let Some(prompt_table) = remote_doc["prompts"].as_table() {
+    if let Some(prompt_table) = remote_doc.get("prompts").and_then(Item::as_table) {
         let mut prompt_path = prompt_dir.to_path_buf();
 
         for (prompt_name, prompt_uri) in prompt_table.iter() {
-            local_doc["prompts"][prompt_name] = prompt_uri.clone();
+            manifest::set_entry(local_doc, "prompts", prompt_name, prompt_uri.clone());
 
             if and_fetch_to_tmp.is_some() {
                 let prompt_uri_str = prompt_uri.as_str().ok_or(OwlError::TomlError(
@@ -116,27 +273,41 @@ pub async fn commit_doc(
 }
 
 pub async fn commit_extension(
-    manifest_path: &Path,
+    manifest: &mut Manifest,
     prompt_dir: &Path,
     ext_name: &str,
     ext_uri: &Uri,
     ext_doc: &DocumentMut,
-    manifest_doc: &mut DocumentMut,
     and_fetch_to_tmp: Option<&Path>,
 ) -> Result<()> {
-    manifest_doc["extensions"][ext_name] = ext_doc["manifest"]["timestamp"].clone();
+    let ext_timestamp = ext_doc
+        .get("manifest")
+        .and_then(|header| header.get("timestamp"))
+        .ok_or(OwlError::TomlError(
+            format!(
+                "Invalid entry for 'timestamp' in table 'manifest' in extension '{}'",
+                ext_name
+            ),
+            "None".into(),
+        ))?
+        .clone();
 
-    match ext_uri {
-        Uri::Local(ext_path) => {
-            manifest_doc["ext_uri"][ext_name] = value(ext_path.to_str().ok_or(
-                OwlError::FileError("Invalid extension path".into(), "None".into()),
-            )?)
-        }
-        Uri::Remote(ext_url) => manifest_doc["ext_uri"][ext_name] = value(ext_url.as_str()),
-    }
+    let ext_uri_str = match ext_uri {
+        Uri::Local(ext_path) => ext_path.to_str().ok_or(OwlError::FileError(
+            "Invalid extension path".into(),
+            "None".into(),
+        ))?,
+        Uri::Remote(ext_url) => ext_url.as_str(),
+    };
+
+    let manifest_path = manifest.path().to_path_buf();
+    let manifest_doc = manifest.doc_mut();
+
+    manifest::set_entry(manifest_doc, "extensions", ext_name, ext_timestamp);
+    manifest::set_entry(manifest_doc, "ext_uri", ext_name, value(ext_uri_str));
 
     commit_doc(
-        manifest_path,
+        &manifest_path,
         prompt_dir,
         ext_name,
         ext_doc,
@@ -145,7 +316,7 @@ pub async fn commit_extension(
     )
     .await?;
 
-    write_manifest(manifest_doc, manifest_path)
+    manifest.write()
 }
 
 pub fn compare_stamps(s1: &str, s2: &str) -> Result<Ordering> {
@@ -175,6 +346,22 @@ pub fn compare_stamps(s1: &str, s2: &str) -> Result<Ordering> {
     }
 }
 
+/// The timestamp after `stamp`, with its last component bumped (e.g. `0.1.4` to `0.1.5`).
+pub fn next_stamp(stamp: &str) -> Result<String> {
+    let (head, last) = stamp.rsplit_once('.').unwrap_or(("", stamp));
+    let last_num = last.parse::<usize>().map_err(|e| {
+        OwlError::TomlError(
+            format!("Failed to parse timestamp '{}' as (usize,,)", stamp),
+            e.to_string(),
+        )
+    })?;
+
+    Ok(match head {
+        "" => (last_num + 1).to_string(),
+        head => format!("{}.{}", head, last_num + 1),
+    })
+}
+
 pub fn create_toml(path: &Path, toml_template: &str) -> Result<()> {
     let toml_file = OpenOptions::new()
         .create(true)
@@ -213,35 +400,131 @@ pub fn get_embedded_version(toml_str: &str) -> Result<String> {
         .parse::<DocumentMut>()
         .map_err(|e| OwlError::TomlError("Failed to parse str as TOML".into(), e.to_string()))?;
 
-    doc["manifest"]["version"]
-        .as_str()
-        .map(String::from)
-        .ok_or(OwlError::TomlError(
-            "Failed to extract embedded TOML version".into(),
-            "None".into(),
-        ))
+    ManifestHeader::from_doc(&doc)?.version().map(String::from)
 }
 
-pub fn get_manifest_ai_sdk(manifest_path: &Path) -> Result<(String, String)> {
-    let doc = get_manifest_header_doc(manifest_path)?;
+/// The LLM backend and its API key, each from the config if set there.
+pub fn get_manifest_ai_sdk(manifest_path: &Path, config: &OwlConfig) -> Result<(String, String)> {
+    if let (Some(ai_sdk), Some(api_key)) = (&config.ai_sdk, &config.api_key) {
+        return Ok((ai_sdk.clone(), api_key.clone()));
+    }
+
+    let header = ManifestHeader::from_doc(&get_manifest_header_doc(manifest_path)?)?;
 
-    let ai_sdk =
-        doc["manifest"]["ai_sdk"]
+    let ai_sdk = match &config.ai_sdk {
+        Some(ai_sdk) => ai_sdk.clone(),
+        None => header.ai_sdk()?.to_string(),
+    };
+    let api_key = match &config.api_key {
+        Some(api_key) => api_key.clone(),
+        None => header.api_key()?.to_string(),
+    };
+
+    Ok((ai_sdk, api_key))
+}
+
+pub fn get_manifest_model(manifest_path: &Path) -> Result<Option<String>> {
+    let doc = read_toml(manifest_path)?;
+
+    match doc
+        .get("manifest")
+        .and_then(|manifest| manifest.get("model"))
+    {
+        Some(model) => model
             .as_str()
-            .map(String::from)
+            .filter(|model| !model.is_empty())
+            .map(|model| Some(model.to_string()))
             .ok_or(OwlError::TomlError(
-                "Failed not extract entry 'ai_sdk' in table 'manifest'".into(),
-                "None".into(),
-            ))?;
-    let api_key = doc["manifest"]["api_key"]
-        .as_str()
-        .map(String::from)
-        .ok_or(OwlError::TomlError(
-            "Failed not extract entry 'api_key' in table 'manifest'".into(),
-            "None".into(),
-        ))?;
+                "Invalid entry 'model' in table 'manifest'".into(),
+                "expected a non-empty string".into(),
+            )),
+        None => Ok(None),
+    }
+}
 
-    Ok((ai_sdk, api_key))
+pub fn get_manifest_ollama_url(manifest_path: &Path) -> Result<Option<String>> {
+    let doc = read_toml(manifest_path)?;
+
+    match doc
+        .get("manifest")
+        .and_then(|manifest| manifest.get("ollama_url"))
+    {
+        Some(url) => url
+            .as_str()
+            .filter(|url| !url.is_empty())
+            .map(|url| Some(url.to_string()))
+            .ok_or(OwlError::TomlError(
+                "Invalid entry 'ollama_url' in table 'manifest'".into(),
+                "expected a non-empty string".into(),
+            )),
+        None => Ok(None),
+    }
+}
+
+pub fn get_manifest_github_token(manifest_path: &Path) -> Result<Option<String>> {
+    let doc = read_toml(manifest_path)?;
+
+    match doc
+        .get("manifest")
+        .and_then(|manifest| manifest.get("github_token"))
+    {
+        Some(token) => token
+            .as_str()
+            .map(|token| Some(token.to_string()).filter(|token| !token.is_empty()))
+            .ok_or(OwlError::TomlError(
+                "Invalid entry 'github_token' in table 'manifest'".into(),
+                "expected a string".into(),
+            )),
+        None => Ok(None),
+    }
+}
+
+/// How much LLM chat history to keep, from the manifest's `[chat]` table (unset keeps all).
+#[derive(Clone, Debug, Default)]
+pub struct ChatRetention {
+    pub max_count: Option<usize>,
+    pub max_age: Option<Duration>,
+    pub max_bytes: Option<u64>,
+}
+
+pub fn get_manifest_chat_retention(manifest_path: &Path) -> Result<ChatRetention> {
+    let doc = read_toml(manifest_path)?;
+
+    let get_limit = |key: &str| match doc.get("chat").and_then(|chat| chat.get(key)) {
+        Some(limit) => limit
+            .as_integer()
+            .filter(|&limit| limit >= 0)
+            .map(|limit| Some(limit as u64))
+            .ok_or(OwlError::TomlError(
+                format!("Invalid entry '{}' in table 'chat'", key),
+                "expected a non-negative integer".into(),
+            )),
+        None => Ok(None),
+    };
+
+    Ok(ChatRetention {
+        max_count: get_limit("max_count")?.map(|count| count as usize),
+        max_age: get_limit("max_age_days")?.map(|days| Duration::from_secs(days * 24 * 60 * 60)),
+        max_bytes: get_limit("max_size_mib")?.map(|mib| mib * 1024 * 1024),
+    })
+}
+
+pub fn get_manifest_update_interval(manifest_path: &Path) -> Result<i64> {
+    let doc = read_toml(manifest_path)?;
+
+    match doc
+        .get("manifest")
+        .and_then(|manifest| manifest.get("update_interval_hours"))
+    {
+        Some(hours) => hours
+            .as_integer()
+            .filter(|&hours| hours >= 0)
+            .ok_or(OwlError::TomlError(
+                "Invalid entry 'update_interval_hours' in table 'manifest'".into(),
+                "expected a non-negative integer".into(),
+            )),
+        None => Ok(UPDATE_INTERVAL_HOURS),
+    }
 }
 
 pub fn get_manifest_header_doc(manifest_path: &Path) -> Result<DocumentMut> {
@@ -275,24 +558,12 @@ pub fn get_manifest_header_doc(manifest_path: &Path) -> Result<DocumentMut> {
 }
 
 pub fn get_manifest_version_timestamp(manifest_path: &Path) -> Result<(String, String)> {
-    let doc = get_manifest_header_doc(manifest_path)?;
-
-    let version = doc["manifest"]["version"]
-        .as_str()
-        .map(String::from)
-        .ok_or(OwlError::TomlError(
-            "Failed to extract 'version' from table 'manifest'".into(),
-            "None".into(),
-        ))?;
-    let timestamp = doc["manifest"]["timestamp"]
-        .as_str()
-        .map(String::from)
-        .ok_or(OwlError::TomlError(
-            "Failed to extract 'timestamp' from table 'manifest'".into(),
-            "None".into(),
-        ))?;
+    let header = ManifestHeader::from_doc(&get_manifest_header_doc(manifest_path)?)?;
 
-    Ok((version, timestamp))
+    Ok((
+        header.version()?.to_string(),
+        header.timestamp()?.to_string(),
+    ))
 }
 
 pub fn read_toml(path: &Path) -> Result<DocumentMut> {
@@ -312,9 +583,78 @@ pub fn read_toml(path: &Path) -> Result<DocumentMut> {
         })
 }
 
-pub async fn request_toml(url: &Url) -> Result<DocumentMut> {
+pub async fn request_remote_header(header_url: &Url, header_cache: &Path) -> Result<DocumentMut> {
+    let cached_doc = read_toml(header_cache).ok();
+    let etag = cached_doc
+        .as_ref()
+        .and_then(|doc| doc.get("cache")?.get("etag")?.as_str().map(String::from));
+
+    let mut request = reqwest::Client::new().get(header_url.as_str());
+
+    if let Some(etag) = etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+
+    let resp = request
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|e| {
+            OwlError::NetworkError(
+                format!("Failed to request '{}'", header_url.as_str()),
+                e.to_string(),
+            )
+        })?;
+
+    let mut header_doc = match cached_doc {
+        Some(cached_doc) if resp.status() == StatusCode::NOT_MODIFIED => cached_doc,
+        _ => {
+            let etag = resp
+                .headers()
+                .get(ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .map(String::from);
+
+            let mut header_doc = resp
+                .text()
+                .await
+                .map_err(|e| {
+                    OwlError::NetworkError(
+                        format!("Failed to read response from '{}'", header_url.as_str()),
+                        e.to_string(),
+                    )
+                })?
+                .parse::<DocumentMut>()
+                .map_err(|e| {
+                    OwlError::TomlError(
+                        format!(
+                            "Failed to parse response from '{}' as TOML",
+                            header_url.as_str()
+                        ),
+                        e.to_string(),
+                    )
+                })?;
+
+            header_doc["cache"] = Table::new().into();
+
+            if let Some(etag) = etag {
+                header_doc["cache"]["etag"] = value(etag);
+            }
+
+            header_doc
+        }
+    };
+
+    header_doc["cache"]["checked"] = value(Local::now().timestamp());
+    write_remote_header(&header_doc, header_cache)?;
+
+    Ok(header_doc)
+}
+
+pub async fn request_text(url: &Url) -> Result<String> {
     reqwest::get(url.as_str())
         .await
+        .and_then(|resp| resp.error_for_status())
         .map_err(|e| {
             OwlError::NetworkError(
                 format!("Failed to request '{}'", url.as_str()),
@@ -328,7 +668,12 @@ pub async fn request_toml(url: &Url) -> Result<DocumentMut> {
                 format!("Failed to read response from '{}'", url.as_str()),
                 e.to_string(),
             )
-        })?
+        })
+}
+
+pub async fn request_toml(url: &Url) -> Result<DocumentMut> {
+    request_text(url)
+        .await?
         .parse::<DocumentMut>()
         .map_err(|e| {
             OwlError::TomlError(
@@ -338,118 +683,314 @@ pub async fn request_toml(url: &Url) -> Result<DocumentMut> {
         })
 }
 
+/// Reads an extension manifest. When the extension has a pinned public key, the manifest
+/// must come with a valid minisign signature at `<uri>.minisig`, and every quest it lists must
+/// pin its archive's `sha256` so the signature covers the test data too.
+pub async fn read_extension(ext_uri: &Uri, pub_key: Option<&str>) -> Result<DocumentMut> {
+    let (ext_str, sig_uri) = match ext_uri {
+        Uri::Local(path) => {
+            let mut sig_path = path.clone().into_os_string();
+            sig_path.push(".minisig");
+            (
+                path.to_string_lossy().to_string(),
+                Uri::Local(sig_path.into()),
+            )
+        }
+        Uri::Remote(url) => {
+            // keeps any query or fragment, e.g. `ext.toml?raw=1` -> `ext.toml.minisig?raw=1`
+            let mut sig_url = url.clone();
+            sig_url.set_path(&format!("{}.minisig", url.path()));
+            (url.to_string(), Uri::Remote(sig_url))
+        }
+    };
+
+    let text = read_uri_text(ext_uri).await?;
+
+    if let Some(pub_key) = pub_key {
+        let sig_text = read_uri_text(&sig_uri).await.map_err(|e| {
+            OwlError::UriError(
+                format!("'{}': extension is pinned but has no signature", ext_str),
+                e.to_string(),
+            )
+        })?;
+
+        verify_signature(&text, &sig_text, pub_key).map_err(|e| {
+            OwlError::UriError(
+                format!("'{}': signature verification failed", ext_str),
+                e.to_string(),
+            )
+        })?;
+    }
+
+    let mut ext_doc = text.parse::<DocumentMut>().map_err(|e| {
+        OwlError::TomlError(
+            format!("Failed to parse '{}' as TOML", ext_str),
+            e.to_string(),
+        )
+    })?;
+
+    if pub_key.is_some() {
+        let unpinned = manifest::entries(&ext_doc, "quests")
+            .filter(|(quest_name, quest_uri)| {
+                !QuestEntry::from_item(quest_name, quest_uri)
+                    .is_ok_and(|quest_entry| quest_entry.sha256.is_some())
+            })
+            .map(|(quest_name, _)| quest_name.to_string())
+            .collect::<Vec<String>>();
+
+        if !unpinned.is_empty() {
+            return Err(OwlError::UriError(
+                format!("'{}': signed extension has unpinned quests", ext_str),
+                format!("no 'sha256' for {}", unpinned.join(", ")),
+            ));
+        }
+    }
+
+    if let Uri::Local(path) = ext_uri
+        && let Some(ext_dir) = path.parent()
+    {
+        resolve_relative_uris(&mut ext_doc, ext_dir);
+    }
+
+    Ok(ext_doc)
+}
+
+/// Rewrites relative paths in a local extension's quests and prompts to be relative to the
+/// extension itself, so a cloned extension can point at the archives beside it.
+fn resolve_relative_uris(ext_doc: &mut DocumentMut, ext_dir: &Path) {
+    let resolve = |uri: &mut Item| {
+        let Some(uri_str) = uri.as_str() else {
+            return;
+        };
+
+        if let Ok(Uri::Local(path)) = Uri::try_from(uri_str)
+            && path.is_relative()
+        {
+            *uri = value(ext_dir.join(path).to_string_lossy().as_ref());
+        }
+    };
+
+    for table_name in ["quests", "prompts"] {
+        let Some(table) = ext_doc
+            .get_mut(table_name)
+            .and_then(Item::as_table_like_mut)
+        else {
+            continue;
+        };
+
+        for (_, entry) in table.iter_mut() {
+            match entry
+                .as_table_like_mut()
+                .and_then(|entry| entry.get_mut("url"))
+            {
+                Some(url) => resolve(url),
+                None => resolve(entry),
+            }
+        }
+    }
+}
+
+/// Pulls the latest commits into the clone a team extension is read from.
+pub fn pull_git_extension(ext_path: &Path) -> Result<()> {
+    let clone_dir = ext_path.parent().unwrap_or(Path::new("."));
+    let branch = git_utils::git_branch(clone_dir)?;
+
+    git_utils::git_pull(clone_dir, "origin", &branch).map(|_| ())
+}
+
+async fn read_uri_text(uri: &Uri) -> Result<String> {
+    match uri {
+        Uri::Local(path) => fs::read_to_string(path).map_err(|e| {
+            OwlError::FileError(
+                format!("Failed to read '{}'", path.to_string_lossy()),
+                e.to_string(),
+            )
+        }),
+        Uri::Remote(url) => request_text(url).await,
+    }
+}
+
+pub fn parse_public_key(pub_key: &str) -> Result<PublicKey> {
+    PublicKey::from_base64(pub_key.trim()).map_err(|e| {
+        OwlError::UriError(
+            format!("'{}': invalid minisign public key", pub_key),
+            e.to_string(),
+        )
+    })
+}
+
+pub fn verify_signature(text: &str, sig_text: &str, pub_key: &str) -> Result<()> {
+    let signature = Signature::decode(sig_text)
+        .map_err(|e| OwlError::UriError("Invalid minisign signature".into(), e.to_string()))?;
+
+    parse_public_key(pub_key)?
+        .verify(text.as_bytes(), &signature, false)
+        .map_err(|e| OwlError::UriError("Signature does not match".into(), e.to_string()))
+}
+
+/// The public key pinned for an extension in the manifest, if any.
+pub fn extension_key(manifest_doc: &DocumentMut, ext_name: &str) -> Option<String> {
+    manifest_doc
+        .get("ext_keys")?
+        .get(ext_name)?
+        .as_str()
+        .map(String::from)
+}
+
 pub async fn update_extensions(
-    manifest_path: &Path,
+    manifest: &mut Manifest,
     prompt_path: &Path,
-    manifest_doc: &mut DocumentMut,
     and_fetch_to_tmp: &Path,
+    review: bool,
 ) -> Result<()> {
-    if let Some(ext_table) = manifest_doc.get("extensions").and_then(Item::as_table) {
-        let mut tmp_doc = DocumentMut::new();
-        tmp_doc["extensions"] = Table::new().into();
-        tmp_doc["prompts"] = Table::new().into();
-        tmp_doc["quests"] = Table::new().into();
-
-        for (ext_name, ext_timestamp) in ext_table.iter() {
-            let ext_uri_str = manifest_doc
-                .get("ext_uri")
-                .and_then(Item::as_table)
-                .ok_or(OwlError::TomlError(
-                    "Invalid table 'ext_uri' in manifest".into(),
-                    "None".into(),
-                ))?
-                .get(ext_name)
-                .and_then(|item| item.as_str())
-                .ok_or(OwlError::TomlError(
-                    format!(
-                        "Invalid entry for '{}' in table 'ext_uri' in manifest",
-                        ext_name
-                    ),
-                    "None".into(),
-                ))?;
+    let extensions = manifest.iter_extensions().collect::<Result<Vec<_>>>()?;
+
+    let mut tmp_doc = DocumentMut::new();
+    tmp_doc["extensions"] = Table::new().into();
+    tmp_doc["prompts"] = Table::new().into();
+    tmp_doc["quests"] = Table::new().into();
+
+    for ext in extensions.iter() {
+        if ext.git.is_some()
+            && let Uri::Local(ext_path) = &ext.uri
+        {
+            pull_git_extension(ext_path)?;
+        }
 
-            let remote_doc = match Uri::try_from(ext_uri_str)? {
-                Uri::Local(path) => read_toml(&path)?,
-                Uri::Remote(url) => request_toml(&url).await?,
-            };
+        let mut remote_doc = read_extension(&ext.uri, ext.pub_key.as_deref()).await?;
 
-            let remote_ext_timestamp =
-                remote_doc["manifest"]["timestamp"]
-                    .as_str()
-                    .ok_or(OwlError::TomlError(
-                        format!(
-                    "Invalid entry for 'timestamp' in table 'manifest' from remote extension '{}'",
-                    ext_name
-                ),
-                        "None".into(),
-                    ))?;
-
-            let ext_timestamp_str = ext_timestamp.as_str().ok_or(OwlError::TomlError(
+        let remote_ext_timestamp = remote_doc
+            .get("manifest")
+            .and_then(|header| header.get("timestamp"))
+            .and_then(Item::as_str)
+            .ok_or(OwlError::TomlError(
                 format!(
-                    "Invalid entry for 'timestamp' in table 'manifest' in local extension '{}'",
-                    ext_name
+                    "Invalid entry for 'timestamp' in table 'manifest' from remote extension '{}'",
+                    ext.name
                 ),
                 "None".into(),
-            ))?;
-
-            if compare_stamps(ext_timestamp_str, remote_ext_timestamp)? == Ordering::Less {
-                tmp_doc["extensions"][ext_name] = value(remote_ext_timestamp);
-
-                commit_doc(
-                    manifest_path,
-                    prompt_path,
-                    ext_name,
-                    &remote_doc,
-                    &mut tmp_doc,
-                    Some(and_fetch_to_tmp),
-                )
-                .await?;
-            }
-        }
+            ))?
+            .to_string();
 
-        if let Some(tmp_ext_table) = tmp_doc["extensions"].as_table() {
-            for (key, item) in tmp_ext_table.iter() {
-                manifest_doc["extensions"][key] = item.clone();
-            }
-        }
+        if compare_stamps(&ext.timestamp, &remote_ext_timestamp)? == Ordering::Less {
+            tmp_doc["extensions"][ext.name.as_str()] = value(&remote_ext_timestamp);
+
+            let changelog = extension_changelog(&remote_doc, &ext.timestamp)?;
 
-        if let Some(tmp_prompt_table) = tmp_doc["prompts"].as_table() {
-            for (key, item) in tmp_prompt_table.iter() {
-                manifest_doc["prompts"][key] = item.clone();
+            if !changelog.is_empty() {
+                eprintln!("extension '{}' changed since {}:", ext.name, ext.timestamp);
+
+                for entry in changelog.iter() {
+                    eprintln!("  {}", entry.describe());
+                }
             }
-        }
 
-        if let Some(tmp_quests_table) = tmp_doc["quests"].as_table() {
-            for (key, item) in tmp_quests_table.iter() {
-                manifest_doc["quests"][key] = item.clone();
+            if review {
+                review_quests(manifest.doc(), &mut remote_doc, &changelog);
             }
+
+            commit_doc(
+                manifest.path(),
+                prompt_path,
+                &ext.name,
+                &remote_doc,
+                &mut tmp_doc,
+                Some(and_fetch_to_tmp),
+            )
+            .await?;
         }
     }
 
-    write_manifest(manifest_doc, manifest_path)
+    for table_name in ["extensions", "prompts", "quests"] {
+        manifest::merge_table(manifest.doc_mut(), &tmp_doc, table_name);
+    }
+
+    manifest.write()
+}
+
+/// Asks which of an extension's new or changed quests to accept, dropping the rest from
+/// `remote_doc` so the local entries are kept.
+fn review_quests(
+    manifest_doc: &DocumentMut,
+    remote_doc: &mut DocumentMut,
+    changelog: &[ChangelogEntry],
+) {
+    let Some(quests_table) = remote_doc
+        .get_mut("quests")
+        .and_then(Item::as_table_like_mut)
+    else {
+        return;
+    };
+
+    let quest_url = |quest_name: &str, item: &Item| {
+        QuestEntry::from_item(quest_name, item)
+            .ok()
+            .map(|quest| quest.url)
+    };
+    let local_url = |quest_name: &str| {
+        manifest_doc
+            .get("quests")
+            .and_then(|quests| quests.get(quest_name))
+            .and_then(|item| quest_url(quest_name, item))
+    };
+
+    let candidates = quests_table
+        .iter()
+        .filter(|(quest_name, item)| match changelog.is_empty() {
+            true => local_url(quest_name) != quest_url(quest_name, item),
+            false => changelog.iter().any(|entry| {
+                entry
+                    .added
+                    .iter()
+                    .chain(&entry.changed)
+                    .any(|name| name == quest_name)
+            }),
+        })
+        .map(|(quest_name, _)| quest_name.to_string())
+        .collect::<Vec<String>>();
+
+    let declined = candidates
+        .into_iter()
+        .filter(|quest_name| {
+            let verb = match local_url(quest_name) {
+                Some(_) => "update",
+                None => "add",
+            };
+
+            !term_utils::confirm(&format!("{} quest '{}'?", verb, quest_name))
+        })
+        .collect::<Vec<String>>();
+
+    for quest_name in declined {
+        eprintln!("skipped '{}'", quest_name);
+        quests_table.remove(&quest_name);
+    }
 }
 
 pub async fn update_manifest(
     header_url: &Url,
+    header_cache: &Path,
     manifest_url: &Url,
     manifest_path: &Path,
     prompt_dir: &Path,
     tmp_archive: &Path,
+    review: bool,
 ) -> Result<()> {
     if !manifest_path.exists() {
         eprintln!("no manifest...");
         eprintln!("downloading manifest...");
 
-        let mut remote_doc = request_toml(manifest_url).await?;
+        let mut manifest = Manifest::from_doc(manifest_path, request_toml(manifest_url).await?);
 
         eprintln!("updating extensions...");
 
-        return update_extensions(manifest_path, prompt_dir, &mut remote_doc, tmp_archive).await;
+        return update_extensions(&mut manifest, prompt_dir, tmp_archive, review).await;
     }
 
-    let mut manifest_doc = read_toml(manifest_path)?;
+    let mut manifest = Manifest::from_doc(manifest_path, read_toml(manifest_path)?);
 
-    let (version_order, timestamp_order) = check_updates(header_url, manifest_path).await?;
+    let (version_order, timestamp_order) =
+        check_updates(header_url, manifest_path, header_cache).await?;
 
     if timestamp_order == Ordering::Less {
         eprintln!("manifest out of date...");
@@ -457,38 +998,28 @@ pub async fn update_manifest(
 
         let remote_doc = request_toml(manifest_url).await?;
 
-        manifest_doc["manifest"]["timestamp"] = remote_doc["manifest"]["timestamp"].clone();
-
-        if let Some(ext_table) = remote_doc["extensions"].as_table() {
-            for (key, item) in ext_table.iter() {
-                manifest_doc["extensions"][key] = item.clone();
-            }
+        if let Some(remote_timestamp) = remote_doc
+            .get("manifest")
+            .and_then(|header| header.get("timestamp"))
+        {
+            manifest::set_entry(
+                manifest.doc_mut(),
+                "manifest",
+                "timestamp",
+                remote_timestamp.clone(),
+            );
         }
 
-        if let Some(ext_uri_table) = remote_doc["ext_uri"].as_table() {
-            for (key, item) in ext_uri_table.iter() {
-                manifest_doc["ext_uri"][key] = item.clone();
-            }
+        for table_name in ["extensions", "ext_uri", "prompts", "quests"] {
+            manifest::merge_table(manifest.doc_mut(), &remote_doc, table_name);
         }
 
-        if let Some(prompt_table) = remote_doc["prompts"].as_table() {
-            for (key, item) in prompt_table.iter() {
-                manifest_doc["prompts"][key] = item.clone();
-            }
-        }
-
-        if let Some(quests_table) = remote_doc["quests"].as_table() {
-            for (key, item) in quests_table.iter() {
-                manifest_doc["quests"][key] = item.clone();
-            }
-        }
-
-        write_manifest(&manifest_doc, manifest_path)?;
+        manifest.write()?;
     }
 
     eprintln!("updating extensions...");
 
-    update_extensions(manifest_path, prompt_dir, &mut manifest_doc, tmp_archive).await?;
+    update_extensions(&mut manifest, prompt_dir, tmp_archive, review).await?;
 
     if version_order == Ordering::Less {
         eprintln!("owlgo out of date...");
@@ -498,6 +1029,22 @@ pub async fn update_manifest(
     Ok(())
 }
 
+pub fn write_remote_header(header_doc: &DocumentMut, header_cache: &Path) -> Result<()> {
+    let tmp_path = header_cache.with_extension(format!("{}.tmp", process::id()));
+
+    fs::write(&tmp_path, header_doc.to_string())
+        .and_then(|_| fs::rename(&tmp_path, header_cache))
+        .map_err(|e| {
+            OwlError::FileError(
+                format!(
+                    "Failed to cache remote header '{}'",
+                    header_cache.to_string_lossy()
+                ),
+                e.to_string(),
+            )
+        })
+}
+
 pub fn write_manifest(manifest_doc: &DocumentMut, manifest_path: &Path) -> Result<()> {
     let manifest_file = OpenOptions::new()
         .create(true)
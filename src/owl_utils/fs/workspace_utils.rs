@@ -0,0 +1,339 @@
+use crate::common::{OwlError, Result};
+use regex::Regex;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub const DEFAULT_TOKEN_BUDGET: usize = 32_000;
+
+/// A rough average for source code, good enough to budget what fits in a model's context.
+const BYTES_PER_TOKEN: usize = 4;
+/// Anything larger is data or generated code rather than something worth reviewing.
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+const GITIGNORE: &str = ".gitignore";
+
+/// Skipped even without a `.gitignore`: VCS metadata, build output, and dependencies.
+const DEFAULT_IGNORES: &[&str] = &[
+    ".git/",
+    "target/",
+    "build/",
+    "dist/",
+    "node_modules/",
+    "__pycache__/",
+    ".venv/",
+    "*.o",
+    "*.obj",
+    "*.a",
+    "*.so",
+    "*.dylib",
+    "*.dll",
+    "*.exe",
+    "*.class",
+    "*.jar",
+    "*.pyc",
+    "*.lock",
+    "*.min.js",
+];
+
+/// What to gather for a review: files and dirs, and how many tokens they may take up.
+#[derive(Clone, Debug)]
+pub struct WorkspaceScope {
+    pub paths: Vec<PathBuf>,
+    pub token_budget: usize,
+}
+
+/// The files picked for a review, and how many were left out and why.
+#[derive(Debug, Default)]
+pub struct WorkspaceContext {
+    pub files: Vec<(String, String)>,
+    pub tokens: usize,
+    pub ignored: usize,
+    pub too_large: usize,
+    pub over_budget: usize,
+}
+
+#[derive(Clone)]
+struct IgnoreRule {
+    base: PathBuf,
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+struct Candidate {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+impl WorkspaceScope {
+    pub fn new(paths: Vec<PathBuf>, token_budget: Option<usize>) -> Self {
+        WorkspaceScope {
+            paths,
+            token_budget: token_budget.unwrap_or(DEFAULT_TOKEN_BUDGET),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Walks the scope (minus `exclude`, e.g. the program under review), then keeps the most
+    /// recently modified text files that fit in the token budget.
+    pub fn gather(&self, exclude: Option<&Path>) -> Result<WorkspaceContext> {
+        let mut context = WorkspaceContext::default();
+        let mut candidates = Vec::new();
+
+        // unanchored, so they apply at any depth whatever their base
+        let defaults = DEFAULT_IGNORES
+            .iter()
+            .filter_map(|pattern| IgnoreRule::parse(Path::new(""), pattern))
+            .collect::<Vec<IgnoreRule>>();
+
+        for path in self.paths.iter() {
+            if path.is_dir() {
+                walk(path, &defaults, &mut candidates, &mut context)?;
+            } else {
+                candidates.push(candidate(path)?);
+            }
+        }
+
+        let mut seen = exclude
+            .and_then(|exclude| fs::canonicalize(exclude).ok())
+            .into_iter()
+            .collect::<HashSet<PathBuf>>();
+
+        // a file reached through overlapping paths (or the program itself) is only sent once
+        candidates.retain(|candidate| match fs::canonicalize(&candidate.path) {
+            Ok(path) => seen.insert(path),
+            Err(_) => true,
+        });
+        candidates.sort_by_key(|candidate| Reverse(candidate.modified));
+
+        for candidate in candidates {
+            if candidate.size > MAX_FILE_BYTES {
+                context.too_large += 1;
+                continue;
+            }
+
+            let tokens = (candidate.size as usize).div_ceil(BYTES_PER_TOKEN);
+
+            if context.tokens + tokens > self.token_budget {
+                context.over_budget += 1;
+                continue;
+            }
+
+            // binary files aren't worth a reviewer's (or a model's) time
+            let Ok(contents) = fs::read_to_string(&candidate.path) else {
+                context.ignored += 1;
+                continue;
+            };
+
+            if contents.contains('\0') {
+                context.ignored += 1;
+                continue;
+            }
+
+            context.tokens += tokens;
+            context
+                .files
+                .push((candidate.path.to_string_lossy().to_string(), contents));
+        }
+
+        Ok(context)
+    }
+}
+
+impl WorkspaceContext {
+    pub fn summary(&self) -> String {
+        format!(
+            "included {} files (~{} tokens), skipped {} ignored, {} too large, {} over budget",
+            self.files.len(),
+            self.tokens,
+            self.ignored,
+            self.too_large,
+            self.over_budget
+        )
+    }
+}
+
+impl IgnoreRule {
+    /// Parses a `.gitignore` line relative to `base`, the dir the `.gitignore` is in.
+    fn parse(base: &Path, line: &str) -> Option<Self> {
+        let line = line.trim_end();
+
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, pattern) = match line.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+
+        let (dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+
+        let anchored = pattern.contains('/');
+        let pattern = pattern.trim_start_matches('/');
+
+        let regex = Regex::new(&format!("^{}$", glob_regex(pattern))).ok()?;
+
+        Some(IgnoreRule {
+            base: base.to_path_buf(),
+            regex,
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        let rel_path = match path.strip_prefix(&self.base) {
+            Ok(rel_path) => rel_path,
+            Err(_) => return false,
+        };
+
+        if self.anchored {
+            self.regex
+                .is_match(&rel_path.to_string_lossy().replace('\\', "/"))
+        } else {
+            rel_path
+                .file_name()
+                .is_some_and(|name| self.regex.is_match(&name.to_string_lossy()))
+        }
+    }
+}
+
+/// Translates a gitignore glob (`*`, `?`, `**`, and `[...]` classes) into a regex.
+fn glob_regex(pattern: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                regex.push('[');
+
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    regex.push('^');
+                }
+
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+
+                    if c == '\\' {
+                        regex.push('\\');
+                    }
+
+                    regex.push(c);
+                }
+
+                regex.push(']');
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex
+}
+
+fn is_ignored(rules: &[IgnoreRule], path: &Path, is_dir: bool) -> bool {
+    // the last matching rule wins, so a later `!pattern` can bring a file back
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule.matches(path, is_dir))
+        .is_some_and(|rule| !rule.negated)
+}
+
+fn walk(
+    dir: &Path,
+    parent_rules: &[IgnoreRule],
+    candidates: &mut Vec<Candidate>,
+    context: &mut WorkspaceContext,
+) -> Result<()> {
+    let mut rules = parent_rules.to_vec();
+
+    if let Ok(gitignore) = fs::read_to_string(dir.join(GITIGNORE)) {
+        rules.extend(
+            gitignore
+                .lines()
+                .filter_map(|line| IgnoreRule::parse(dir, line)),
+        );
+    }
+
+    let mut entries = fs::read_dir(dir)
+        .map_err(|e| {
+            OwlError::FileError(
+                format!("Failed to read dir '{}'", dir.to_string_lossy()),
+                e.to_string(),
+            )
+        })?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<PathBuf>>();
+
+    entries.sort();
+
+    for path in entries {
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+
+        if metadata.is_symlink() || is_ignored(&rules, &path, metadata.is_dir()) {
+            context.ignored += 1;
+            continue;
+        }
+
+        if metadata.is_dir() {
+            walk(&path, &rules, candidates, context)?;
+        } else if path.file_name().is_some_and(|name| name != GITIGNORE) {
+            candidates.push(Candidate {
+                path,
+                size: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn candidate(path: &Path) -> Result<Candidate> {
+    let metadata = fs::metadata(path).map_err(|e| {
+        OwlError::FileError(
+            format!("could not read '{}'", path.to_string_lossy()),
+            e.to_string(),
+        )
+    })?;
+
+    Ok(Candidate {
+        path: path.to_path_buf(),
+        size: metadata.len(),
+        modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+    })
+}
@@ -1,6 +1,25 @@
-use crate::{common::OwlError, common::Result, owl_utils::toml_utils};
+use crate::{common::OwlError, common::Result, owl_utils::OwlConfig, owl_utils::toml_utils};
 use anthropic_sdk::{Anthropic, ContentBlock, MessageCreateBuilder};
+use reqwest::header::CONTENT_TYPE;
+use serde_json::{Value, json};
+use std::env;
 use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use url::Url;
+
+pub const AI_SDKS: &[&str] = &["claude", "ollama"];
+pub const DEFAULT_MODEL: &str = "claude-sonnet-4-5";
+pub const DEFAULT_OLLAMA_MODEL: &str = "llama3.2";
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+static LLM_MODEL: Mutex<Option<String>> = Mutex::new(None);
+
+/// A connection to the selected LLM backend.
+pub enum LlmClient {
+    Claude(Anthropic),
+    Ollama(Url),
+}
 
 #[derive(Debug, PartialEq)]
 pub enum PromptMode {
@@ -22,6 +41,62 @@ Consider:
 Suggest improvements and explain your reasoning for each suggestion.
 "#;
 
+const BUILD_ERRORS_PROMPT: &str = r#"
+The last attempt to build it failed with these compiler errors:
+[paste]
+"#;
+
+const FAILURE_REPORT_PROMPT: &str = r#"
+The last attempt to build or run it failed. Here is the failure report:
+[paste]
+"#;
+
+const LANG_PROMPT: &str = r#"
+It is written in [paste].
+"#;
+
+const BUILD_LINE_PROMPT: &str = r#"
+It is built with `[paste]`, so tailor any advice to that compiler and those flags.
+"#;
+
+const TIME_LIMIT_PROMPT: &str = r#"
+It must finish each test case within a time limit of [paste], so target optimizations at that budget.
+"#;
+
+const WORKSPACE_PROMPT: &str = r#"
+For context, here are other files from the same workspace (most recently modified first):
+[paste]
+"#;
+
+const BRAINSTORM_PROMPT: &str = r#"
+I haven't written any code yet. Could you help me brainstorm?
+Consider:
+1. Important concepts and constraints
+2. Candidate approaches and their complexity
+3. Edge cases worth testing
+Please do not solve the problem for me or provide me with code.
+"#;
+
+const COMPARE_PROMPT: &str = r#"
+Here are several programs I have written to solve the same problem:
+[paste]
+Please compare these approaches. For each program, consider:
+1. Strengths of the approach
+2. Weaknesses, potential bugs, or edge cases
+3. Time and space complexity
+Conclude with which approach you would prefer and explain your reasoning.
+"#;
+
+const DIFF_PROMPT: &str = r#"
+Here are the changes I made to my program since I last stashed it, as a unified diff:
+[paste]
+Please review only these changes. Consider:
+1. Whether the changes introduce bugs or miss edge cases
+2. Whether they achieve what they appear to be aiming for
+3. Performance or readability regressions
+Keep the review focused on the changed lines and explain your reasoning for each suggestion.
+"#;
+
 const DEFAULT_PROMPT: &str = r#"
 Please review the following code:
 [paste]
@@ -67,6 +142,8 @@ Here's a piece of code that needs optimization:
 Please suggest optimizations to improve its performance. For each suggestion, explain the expected improvement and any trade-offs.
 "#;
 
+const MIN_TODO_WORDS: usize = 3;
+
 const PLACEHOLDER: &str = "[paste]";
 
 const TEST_PROMPT: &str = r#"
@@ -78,44 +155,84 @@ Include tests for:
 All inputs will be valid. Please explain your reasoning for each suggestion.
 "#;
 
-pub async fn llm_query_client(
+pub async fn llm_compare_with_client(
     ai_sdk: &str,
-    client: &Anthropic,
-    ai_responses: &[String],
-    user_queries: &[String],
+    client: &LlmClient,
+    progs: &[(String, String)],
+    check_prompt: Option<&str>,
 ) -> Result<String> {
-    let mut builder = MessageCreateBuilder::new("claude-sonnet-4-5", 1024);
+    let progs_str = progs
+        .iter()
+        .map(|(prog_name, prog_str)| format!("### {}\n```\n{}\n```", prog_name, prog_str))
+        .collect::<Vec<String>>()
+        .join("\n\n");
 
-    for (ai_response, user_query) in ai_responses.iter().zip(user_queries.iter()) {
-        builder = builder.assistant(ai_response.as_str());
-        builder = builder.user(user_query.as_str());
-    }
+    let compare_prompt = COMPARE_PROMPT.replace(PLACEHOLDER, &progs_str);
 
-    let response = client
-        .messages()
-        .create(builder.build())
-        .await
-        .map_err(|e| {
-            OwlError::LlmError(
-                format!("Failed to send prompt to '{}' for review", ai_sdk),
-                e.to_string(),
-            )
-        })?;
+    let user_prompt = match check_prompt {
+        Some(prompt_str) => format!(
+            "{}\n{}",
+            DESC_PROMPT.replace(PLACEHOLDER, prompt_str),
+            compare_prompt
+        ),
+        None => compare_prompt,
+    };
 
-    let mut buffer = String::new();
-    for content_block in response.content {
-        if let ContentBlock::Text { text } = content_block {
-            buffer.push_str(&format!("\n{}: ", ai_sdk));
-            buffer.push_str(&text);
-        }
-    }
+    send_messages(ai_sdk, client, &[("user", &user_prompt)]).await
+}
 
-    Ok(buffer)
+pub async fn llm_diff_with_client(
+    ai_sdk: &str,
+    client: &LlmClient,
+    diff_str: &str,
+    lang_context: Option<&str>,
+    check_prompt: Option<&str>,
+) -> Result<String> {
+    let diff_block = format!("```diff\n{}```", diff_str);
+
+    let diff_prompt = DIFF_PROMPT.replace(
+        PLACEHOLDER,
+        &match lang_context {
+            Some(lang_context) => with_lang_context(&diff_block, lang_context),
+            None => diff_block,
+        },
+    );
+
+    let user_prompt = match check_prompt {
+        Some(prompt_str) => format!(
+            "{}\n{}",
+            DESC_PROMPT.replace(PLACEHOLDER, prompt_str),
+            diff_prompt
+        ),
+        None => diff_prompt,
+    };
+
+    send_messages(ai_sdk, client, &[("user", &user_prompt)]).await
+}
+
+pub async fn llm_query_client(
+    ai_sdk: &str,
+    client: &LlmClient,
+    ai_responses: &[String],
+    user_queries: &[String],
+) -> Result<String> {
+    let messages = ai_responses
+        .iter()
+        .zip(user_queries.iter())
+        .flat_map(|(ai_response, user_query)| {
+            [
+                ("assistant", ai_response.as_str()),
+                ("user", user_query.as_str()),
+            ]
+        })
+        .collect::<Vec<(&str, &str)>>();
+
+    send_messages(ai_sdk, client, &messages).await
 }
 
 pub async fn llm_review_with_client(
     ai_sdk: &str,
-    client: &Anthropic,
+    client: &LlmClient,
     check_prog: Option<&str>,
     check_prompt: Option<&str>,
     mode: PromptMode,
@@ -145,7 +262,11 @@ pub async fn llm_review_with_client(
             } else if let Some(text) = &suggested_prompt {
                 format!("{}\n{}", DESC_PROMPT.replace(PLACEHOLDER, prompt_str), text)
             } else {
-                DESC_PROMPT.replace(PLACEHOLDER, prompt_str)
+                format!(
+                    "{}\n{}",
+                    DESC_PROMPT.replace(PLACEHOLDER, prompt_str),
+                    BRAINSTORM_PROMPT
+                )
             }
         })
         .or(suggested_prompt)
@@ -154,34 +275,110 @@ pub async fn llm_review_with_client(
             "None".into(),
         ))?;
 
-    let response = client
-        .messages()
-        .create(
-            MessageCreateBuilder::new("claude-sonnet-4-5", 1024)
-                .user(user_prompt)
-                .build(),
-        )
-        .await
-        .map_err(|e| {
-            OwlError::LlmError(
-                format!("Failed to send prompt to '{}' for review", ai_sdk),
-                e.to_string(),
-            )
-        })?;
+    send_messages(ai_sdk, client, &[("user", &user_prompt)]).await
+}
 
-    let mut buffer = String::new();
-    for content_block in response.content {
-        if let ContentBlock::Text { text } = content_block {
-            buffer.push_str(&format!("\n{}: ", ai_sdk));
-            buffer.push_str(&text);
+/// Describes the language, build flags, and time limit a program is judged under.
+pub fn lang_context(
+    lang_name: &str,
+    build_line: Option<&str>,
+    time_limit: Option<Duration>,
+) -> String {
+    let mut context = LANG_PROMPT.replace(PLACEHOLDER, lang_name);
+
+    if let Some(build_line) = build_line {
+        context.push_str(&BUILD_LINE_PROMPT.replace(PLACEHOLDER, build_line));
+    }
+
+    if let Some(time_limit) = time_limit {
+        context.push_str(
+            &TIME_LIMIT_PROMPT.replace(PLACEHOLDER, &format!("{:.2}s", time_limit.as_secs_f64())),
+        );
+    }
+
+    context
+}
+
+pub fn with_lang_context(prog_str: &str, lang_context: &str) -> String {
+    format!("{}\n{}", prog_str, lang_context)
+}
+
+pub fn with_build_errors(prog_str: &str, build_errors: &str) -> String {
+    format!(
+        "{}\n{}",
+        prog_str,
+        BUILD_ERRORS_PROMPT.replace(PLACEHOLDER, build_errors)
+    )
+}
+
+pub fn with_failure_report(prog_str: &str, failure_report: &str) -> String {
+    format!(
+        "{}\n{}",
+        prog_str,
+        FAILURE_REPORT_PROMPT.replace(PLACEHOLDER, failure_report)
+    )
+}
+
+/// Each file as its name followed by its contents in a code block.
+pub fn workspace_files(files: &[(String, String)]) -> String {
+    files
+        .iter()
+        .map(|(name, contents)| format!("`{}`:\n```\n{}\n```\n", name, contents.trim_end()))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn with_workspace(prog_str: &str, files: &[(String, String)]) -> String {
+    format!(
+        "{}\n{}",
+        prog_str,
+        WORKSPACE_PROMPT.replace(PLACEHOLDER, &workspace_files(files))
+    )
+}
+
+/// Pulls the list items out of a review, skipping code blocks and one- or two-word bullets.
+pub fn extract_todos(response: &str) -> Vec<String> {
+    let mut in_code = false;
+    let mut todos = Vec::new();
+
+    for line in response.lines() {
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+
+        if in_code || line.len() - trimmed.len() > 3 {
+            continue;
+        }
+
+        let item = match trimmed.split_once(' ') {
+            Some(("-" | "*" | "+", item)) => item,
+            Some((marker, item))
+                if marker.len() > 1
+                    && (marker.ends_with('.') || marker.ends_with(')'))
+                    && marker[..marker.len() - 1]
+                        .chars()
+                        .all(|c| c.is_ascii_digit()) =>
+            {
+                item
+            }
+            _ => continue,
+        };
+
+        let item = item.trim().replace("**", "");
+
+        if item.split_whitespace().count() >= MIN_TODO_WORDS {
+            todos.push(item);
         }
     }
 
-    Ok(buffer)
+    todos
 }
 
-pub fn try_llm_client(manifest_path: &Path) -> Result<(String, Anthropic)> {
-    let (ai_sdk, api_key) = toml_utils::get_manifest_ai_sdk(manifest_path)?;
+pub fn try_llm_client(manifest_path: &Path, config: &OwlConfig) -> Result<(String, LlmClient)> {
+    let (ai_sdk, api_key) = toml_utils::get_manifest_ai_sdk(manifest_path, config)?;
 
     if ai_sdk.is_empty() {
         return Err(OwlError::LlmError(
@@ -190,15 +387,21 @@ pub fn try_llm_client(manifest_path: &Path) -> Result<(String, Anthropic)> {
         ));
     }
 
-    if api_key.is_empty() {
+    if api_key.is_empty() && ai_sdk != "ollama" {
         return Err(OwlError::LlmError(
             "Failed to determine API key".into(),
             "'api_key' in manifest is None".into(),
         ));
     }
 
-    match ai_sdk.as_str() {
-        "claude" => println!("Sending code review to {}...", ai_sdk),
+    let client = match ai_sdk.as_str() {
+        "claude" => LlmClient::Claude(Anthropic::new(api_key).map_err(|e| {
+            OwlError::LlmError(
+                format!("Failed to connect to '{}' for code review", ai_sdk),
+                e.to_string(),
+            )
+        })?),
+        "ollama" => LlmClient::Ollama(ollama_url(manifest_path)?),
         _ => {
             return Err(OwlError::Unsupported(format!(
                 "'{}': not supported",
@@ -207,12 +410,128 @@ pub fn try_llm_client(manifest_path: &Path) -> Result<(String, Anthropic)> {
         }
     };
 
-    let client = Anthropic::new(api_key).map_err(|e| {
-        OwlError::LlmError(
-            format!("Failed to connect to '{}' for code review", ai_sdk),
-            e.to_string(),
-        )
-    })?;
+    match &client {
+        LlmClient::Claude(_) => println!("Sending code review to {}...", ai_sdk),
+        LlmClient::Ollama(url) => println!("Sending code review to {} at {}...", ai_sdk, url),
+    }
+
+    *LLM_MODEL.lock().expect("llm model lock is not poisoned") =
+        toml_utils::get_manifest_model(manifest_path)?;
 
     Ok((ai_sdk, client))
 }
+
+async fn send_messages(
+    ai_sdk: &str,
+    client: &LlmClient,
+    messages: &[(&str, &str)],
+) -> Result<String> {
+    let texts = match client {
+        LlmClient::Claude(client) => {
+            let mut builder = MessageCreateBuilder::new(llm_model(DEFAULT_MODEL), 1024);
+
+            for &(role, content) in messages {
+                builder = match role {
+                    "assistant" => builder.assistant(content),
+                    _ => builder.user(content),
+                };
+            }
+
+            let response = client
+                .messages()
+                .create(builder.build())
+                .await
+                .map_err(|e| {
+                    OwlError::LlmError(
+                        format!("Failed to send prompt to '{}' for review", ai_sdk),
+                        e.to_string(),
+                    )
+                })?;
+
+            response
+                .content
+                .into_iter()
+                .filter_map(|content_block| match content_block {
+                    ContentBlock::Text { text } => Some(text),
+                    _ => None,
+                })
+                .collect::<Vec<String>>()
+        }
+        LlmClient::Ollama(url) => vec![ollama_chat(ai_sdk, url, messages).await?],
+    };
+
+    let mut buffer = String::new();
+    for text in texts {
+        buffer.push_str(&format!("\n{}: ", ai_sdk));
+        buffer.push_str(&text);
+    }
+
+    Ok(buffer)
+}
+
+async fn ollama_chat(ai_sdk: &str, url: &Url, messages: &[(&str, &str)]) -> Result<String> {
+    let chat_url = format!("{}/api/chat", url.as_str().trim_end_matches('/'));
+
+    let body = json!({
+        "model": llm_model(DEFAULT_OLLAMA_MODEL),
+        "messages": messages
+            .iter()
+            .map(|(role, content)| json!({ "role": role, "content": content }))
+            .collect::<Vec<_>>(),
+        "stream": false,
+    });
+
+    let response_text = reqwest::Client::new()
+        .post(&chat_url)
+        .header(CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|e| {
+            OwlError::LlmError(
+                format!("Failed to send prompt to '{}' at '{}'", ai_sdk, chat_url),
+                e.to_string(),
+            )
+        })?
+        .text()
+        .await
+        .map_err(|e| {
+            OwlError::LlmError(
+                format!("Failed to read response from '{}'", chat_url),
+                e.to_string(),
+            )
+        })?;
+
+    serde_json::from_str::<Value>(&response_text)
+        .ok()
+        .and_then(|response| response["message"]["content"].as_str().map(String::from))
+        .ok_or(OwlError::LlmError(
+            format!("Unexpected response from '{}'", chat_url),
+            response_text,
+        ))
+}
+
+/// The Ollama endpoint from the manifest, then `OLLAMA_HOST`, then the local default.
+fn ollama_url(manifest_path: &Path) -> Result<Url> {
+    let url_str = match toml_utils::get_manifest_ollama_url(manifest_path)? {
+        Some(url_str) => url_str,
+        None => match env::var("OLLAMA_HOST") {
+            Ok(host) if host.contains("://") => host,
+            Ok(host) if !host.is_empty() => format!("http://{}", host),
+            _ => DEFAULT_OLLAMA_URL.into(),
+        },
+    };
+
+    Url::parse(&url_str).map_err(|e| {
+        OwlError::UriError(format!("'{}': invalid ollama url", url_str), e.to_string())
+    })
+}
+
+fn llm_model(default_model: &str) -> String {
+    LLM_MODEL
+        .lock()
+        .expect("llm model lock is not poisoned")
+        .clone()
+        .unwrap_or(default_model.into())
+}
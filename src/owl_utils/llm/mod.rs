@@ -1,3 +1,3 @@
 pub mod llm_utils;
 
-pub use llm_utils::PromptMode;
+pub use llm_utils::{LlmClient, PromptMode};
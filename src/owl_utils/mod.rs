@@ -3,7 +3,13 @@ pub mod fs;
 pub mod llm;
 pub mod tui;
 
-pub use cmd::{cmd_utils, git_utils, prog_utils};
-pub use fs::{Uri, fs_utils, toml_utils};
-pub use llm::{PromptMode, llm_utils};
-pub use tui::{FileApp, FileExplorerApp, LlmApp, tui_utils};
+pub use cmd::{
+    cmd_utils, fmt_utils, git_utils, hook_utils, judge_utils, prog_utils, progress_utils,
+    results_utils, sandbox_utils, script_utils, signal_utils, term_utils, vscode_utils,
+};
+pub use fs::{
+    Manifest, ManifestHeader, OwlConfig, QuestMeta, Uri, WorkspaceScope, cloud_utils, fs_utils,
+    owl_config, pool_utils, solve_utils, toml_utils, workspace_utils,
+};
+pub use llm::{LlmClient, PromptMode, llm_utils};
+pub use tui::{FileApp, FileExplorerApp, LlmApp, TopApp, TopState, TuiTheme, tui_utils};
@@ -1,4 +1,10 @@
+pub mod tui_keymap;
 pub mod tui_markdown;
+pub mod tui_theme;
 pub mod tui_utils;
 
-pub use tui_utils::{FileApp, FileExplorerApp, LlmApp};
+pub use tui_keymap::KeyMap;
+pub use tui_theme::TuiTheme;
+pub use tui_utils::{
+    DebugApp, DebugCase, DebugRun, FileApp, FileExplorerApp, LlmApp, QuestCase, TopApp, TopState,
+};
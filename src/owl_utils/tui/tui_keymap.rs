@@ -0,0 +1,369 @@
+use crate::common::{OwlError, Result};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::Path;
+use toml_edit::Item;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyAction {
+    Quit,
+    ScrollUp,
+    ScrollDown,
+    ScrollPageUp,
+    ScrollPageDown,
+    ScrollTop,
+    ScrollBottom,
+    FocusNext,
+    NavUp,
+    NavDown,
+    NavIn,
+    NavOut,
+    NavFirst,
+    NavLast,
+    NavPageUp,
+    NavPageDown,
+    Send,
+    Recall,
+    LoadFull,
+    RunCase,
+    RunAll,
+    ToggleMark,
+    ToggleSplit,
+}
+
+impl KeyAction {
+    const ALL: [KeyAction; 23] = [
+        KeyAction::Quit,
+        KeyAction::ScrollUp,
+        KeyAction::ScrollDown,
+        KeyAction::ScrollPageUp,
+        KeyAction::ScrollPageDown,
+        KeyAction::ScrollTop,
+        KeyAction::ScrollBottom,
+        KeyAction::FocusNext,
+        KeyAction::NavUp,
+        KeyAction::NavDown,
+        KeyAction::NavIn,
+        KeyAction::NavOut,
+        KeyAction::NavFirst,
+        KeyAction::NavLast,
+        KeyAction::NavPageUp,
+        KeyAction::NavPageDown,
+        KeyAction::Send,
+        KeyAction::Recall,
+        KeyAction::LoadFull,
+        KeyAction::RunCase,
+        KeyAction::RunAll,
+        KeyAction::ToggleMark,
+        KeyAction::ToggleSplit,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            KeyAction::Quit => "quit",
+            KeyAction::ScrollUp => "scroll_up",
+            KeyAction::ScrollDown => "scroll_down",
+            KeyAction::ScrollPageUp => "scroll_page_up",
+            KeyAction::ScrollPageDown => "scroll_page_down",
+            KeyAction::ScrollTop => "scroll_top",
+            KeyAction::ScrollBottom => "scroll_bottom",
+            KeyAction::FocusNext => "focus_next",
+            KeyAction::NavUp => "nav_up",
+            KeyAction::NavDown => "nav_down",
+            KeyAction::NavIn => "nav_in",
+            KeyAction::NavOut => "nav_out",
+            KeyAction::NavFirst => "nav_first",
+            KeyAction::NavLast => "nav_last",
+            KeyAction::NavPageUp => "nav_page_up",
+            KeyAction::NavPageDown => "nav_page_down",
+            KeyAction::Send => "send",
+            KeyAction::Recall => "recall",
+            KeyAction::LoadFull => "load_full",
+            KeyAction::RunCase => "run_case",
+            KeyAction::RunAll => "run_all",
+            KeyAction::ToggleMark => "toggle_mark",
+            KeyAction::ToggleSplit => "toggle_split",
+        }
+    }
+
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            KeyAction::Quit => &["q", "esc"],
+            KeyAction::ScrollUp => &["up"],
+            KeyAction::ScrollDown => &["down"],
+            KeyAction::ScrollPageUp => &["pageup"],
+            KeyAction::ScrollPageDown => &["pagedown"],
+            KeyAction::ScrollTop => &["home"],
+            KeyAction::ScrollBottom => &["end"],
+            KeyAction::FocusNext => &["tab", "shift-tab"],
+            KeyAction::NavUp => &["k"],
+            KeyAction::NavDown => &["j"],
+            KeyAction::NavIn => &["l", "right", "enter"],
+            KeyAction::NavOut => &["h", "left", "backspace"],
+            KeyAction::NavFirst => &["home"],
+            KeyAction::NavLast => &["end"],
+            KeyAction::NavPageUp => &["pageup"],
+            KeyAction::NavPageDown => &["pagedown"],
+            KeyAction::Send => &["ctrl-s"],
+            KeyAction::Recall => &["up"],
+            KeyAction::LoadFull => &["f"],
+            KeyAction::RunCase => &["r"],
+            KeyAction::RunAll => &["R"],
+            KeyAction::ToggleMark => &["m"],
+            KeyAction::ToggleSplit => &["tab"],
+        }
+    }
+
+    fn vim_keys(&self) -> &'static [&'static str] {
+        match self {
+            KeyAction::ScrollUp => &["k", "ctrl-k"],
+            KeyAction::ScrollDown => &["j", "ctrl-j"],
+            KeyAction::ScrollPageUp => &["ctrl-u"],
+            KeyAction::ScrollPageDown => &["ctrl-d"],
+            KeyAction::ScrollTop => &["g"],
+            KeyAction::ScrollBottom => &["G"],
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let modifiers = match key.code {
+            KeyCode::Char(_) | KeyCode::BackTab => key.modifiers - KeyModifiers::SHIFT,
+            _ => key.modifiers,
+        };
+
+        self.code == key.code && self.modifiers == modifiers
+    }
+
+    pub fn is_plain_char(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_)) && self.modifiers.is_empty()
+    }
+
+    pub fn label(&self) -> String {
+        let key_str = match self.code {
+            KeyCode::Up => "▲".into(),
+            KeyCode::Down => "▼".into(),
+            KeyCode::Left => "◀".into(),
+            KeyCode::Right => "▶".into(),
+            KeyCode::Char(' ') => "space".into(),
+            KeyCode::BackTab => "shift-tab".into(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::F(n) => format!("f{}", n),
+            code => code.to_string().to_lowercase(),
+        };
+
+        match (
+            self.modifiers.contains(KeyModifiers::CONTROL),
+            self.modifiers.contains(KeyModifiers::ALT),
+        ) {
+            (true, true) => format!("ctrl-alt-{}", key_str),
+            (true, false) => format!("ctrl-{}", key_str),
+            (false, true) => format!("alt-{}", key_str),
+            (false, false) => key_str,
+        }
+    }
+}
+
+impl TryFrom<&str> for KeyBinding {
+    type Error = OwlError;
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key_str = s.trim();
+
+        loop {
+            if let Some(rest) = key_str.strip_prefix("ctrl-") {
+                modifiers |= KeyModifiers::CONTROL;
+                key_str = rest;
+            } else if let Some(rest) = key_str.strip_prefix("alt-") {
+                modifiers |= KeyModifiers::ALT;
+                key_str = rest;
+            } else {
+                break;
+            }
+        }
+
+        let code = match key_str.to_lowercase().as_str() {
+            "esc" | "escape" => KeyCode::Esc,
+            "enter" | "return" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "shift-tab" | "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            lower => {
+                let mut chars = key_str.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                        Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                        _ => {
+                            return Err(OwlError::Unsupported(format!(
+                                "'{}': unknown key binding",
+                                s
+                            )));
+                        }
+                    },
+                }
+            }
+        };
+
+        Ok(KeyBinding { code, modifiers })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct KeyMap {
+    bindings: Vec<(KeyAction, KeyBinding)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let bindings = KeyAction::ALL
+            .iter()
+            .flat_map(|action| {
+                action
+                    .default_keys()
+                    .iter()
+                    .map(|key_str| (*action, KeyBinding::try_from(*key_str)))
+            })
+            .map(|(action, binding)| (action, binding.expect("default key binding is valid")))
+            .collect();
+
+        KeyMap { bindings }
+    }
+}
+
+impl KeyMap {
+    pub fn from_item(config_path: &Path, item: Option<&Item>) -> Result<Self> {
+        let Some(item) = item else {
+            return Ok(KeyMap::default());
+        };
+
+        let tui_table = item.as_table_like().ok_or(OwlError::TomlError(
+            format!("Invalid entry 'tui' in '{}'", config_path.to_string_lossy()),
+            "expected a table".into(),
+        ))?;
+
+        let use_vim = match tui_table.get("vim") {
+            Some(vim) => vim.as_bool().ok_or(OwlError::TomlError(
+                format!(
+                    "Invalid entry 'tui.vim' in '{}'",
+                    config_path.to_string_lossy()
+                ),
+                "expected a boolean".into(),
+            ))?,
+            None => false,
+        };
+
+        let mut bindings = Vec::new();
+
+        for action in KeyAction::ALL {
+            let key_strs = match tui_table.get(action.name()) {
+                Some(keys) => parse_keys(config_path, action, keys)?,
+                None => action
+                    .default_keys()
+                    .iter()
+                    .map(|key_str| key_str.to_string())
+                    .collect(),
+            };
+
+            let vim_strs = if use_vim { action.vim_keys() } else { &[] };
+
+            for key_str in key_strs
+                .iter()
+                .map(String::as_str)
+                .chain(vim_strs.iter().copied())
+            {
+                bindings.push((action, KeyBinding::try_from(key_str)?));
+            }
+        }
+
+        Ok(KeyMap { bindings })
+    }
+
+    pub fn action(&self, key: &KeyEvent, actions: &[KeyAction]) -> Option<KeyAction> {
+        actions.iter().copied().find(|action| {
+            self.bindings
+                .iter()
+                .any(|(bound, binding)| bound == action && binding.matches(key))
+        })
+    }
+
+    pub fn label(&self, action: KeyAction) -> String {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, binding)| binding.label())
+            .unwrap_or("?".into())
+    }
+
+    /// The label of a key for `action` that isn't also bound to `shadowed`, which takes precedence.
+    pub fn label_besides(&self, action: KeyAction, shadowed: KeyAction) -> String {
+        self.bindings
+            .iter()
+            .find(|(bound, binding)| {
+                *bound == action
+                    && !self.bindings.iter().any(|(other, other_binding)| {
+                        *other == shadowed && other_binding == binding
+                    })
+            })
+            .map(|(_, binding)| binding.label())
+            .unwrap_or("?".into())
+    }
+
+    pub fn scroll_help(&self) -> String {
+        format!(
+            "Use {} {} to scroll ",
+            self.label(KeyAction::ScrollUp),
+            self.label(KeyAction::ScrollDown)
+        )
+    }
+
+    pub fn text_action(&self, key: &KeyEvent, actions: &[KeyAction]) -> Option<KeyAction> {
+        actions.iter().copied().find(|action| {
+            self.bindings.iter().any(|(bound, binding)| {
+                bound == action && !binding.is_plain_char() && binding.matches(key)
+            })
+        })
+    }
+}
+
+fn parse_keys(config_path: &Path, action: KeyAction, item: &Item) -> Result<Vec<String>> {
+    let invalid_entry = || {
+        OwlError::TomlError(
+            format!(
+                "Invalid entry 'tui.{}' in '{}'",
+                action.name(),
+                config_path.to_string_lossy()
+            ),
+            "expected a key or an array of keys".into(),
+        )
+    };
+
+    if let Some(key_str) = item.as_str() {
+        return Ok(vec![key_str.to_string()]);
+    }
+
+    item.as_array()
+        .ok_or_else(invalid_entry)?
+        .iter()
+        .map(|key| key.as_str().map(String::from).ok_or_else(invalid_entry))
+        .collect()
+}
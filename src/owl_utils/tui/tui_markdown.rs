@@ -5,14 +5,17 @@
 //! rendered to the terminal using the 'Ratatui' library.
 
 use std::sync::LazyLock;
+use std::thread;
 use std::vec;
 
+use super::tui_theme::TuiTheme;
 use ansi_to_tui::IntoText;
 use itertools::{Itertools, Position};
 use pulldown_cmark::{
-    BlockQuoteKind, CodeBlockKind, CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd,
+    Alignment, BlockQuoteKind, CodeBlockKind, CowStr, Event, HeadingLevel, Options, Parser, Tag,
+    TagEnd,
 };
-use ratatui::style::{Style, Stylize};
+use ratatui::style::{Modifier, Style, Stylize};
 use ratatui::text::{Line, Span, Text};
 use syntect::{
     easy::HighlightLines,
@@ -21,11 +24,12 @@ use syntect::{
     util::{LinesWithEndings, as_24_bit_terminal_escaped},
 };
 
-pub fn from_str<'a>(input: &'a str) -> Text<'a> {
+pub fn from_str<'a>(input: &'a str, theme: &TuiTheme) -> Text<'a> {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
     let parser = Parser::new_ext(input, options);
-    let mut writer = TextWriter::new(parser);
+    let mut writer = TextWriter::new(parser, theme.clone());
     writer.run();
     writer.text
 }
@@ -57,17 +61,41 @@ struct TextWriter<'a, I> {
     /// A link which will be appended to the current line when the link tag is closed.
     link: Option<CowStr<'a>>,
 
+    /// An image source and title which will be appended when the image tag is closed.
+    image: Option<(CowStr<'a>, CowStr<'a>)>,
+
+    /// Table being collected, rendered all at once when the table tag is closed.
+    table: Option<TableBuffer>,
+
     needs_newline: bool,
+
+    /// Colors used for headings, code, quotes, and links.
+    theme: TuiTheme,
 }
 
-static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
-static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+#[derive(Default)]
+struct TableBuffer {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+    row: Vec<String>,
+    cell: String,
+}
+
+pub static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+pub static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+
+pub fn preload_syntax() {
+    thread::spawn(|| {
+        LazyLock::force(&SYNTAX_SET);
+        LazyLock::force(&THEME_SET);
+    });
+}
 
 impl<'a, I> TextWriter<'a, I>
 where
     I: Iterator<Item = Event<'a>>,
 {
-    fn new(iter: I) -> Self {
+    fn new(iter: I, theme: TuiTheme) -> Self {
         Self {
             iter,
             text: Text::default(),
@@ -78,6 +106,9 @@ where
             needs_newline: false,
             code_highlighter: None,
             link: None,
+            image: None,
+            table: None,
+            theme,
         }
     }
 
@@ -111,6 +142,10 @@ where
             Tag::Strong => self.push_inline_style(Style::new().bold()),
             Tag::Strikethrough => self.push_inline_style(Style::new().crossed_out()),
             Tag::Link { dest_url, .. } => self.push_link(dest_url),
+            Tag::Image {
+                dest_url, title, ..
+            } => self.start_image(dest_url, title),
+            Tag::Table(alignments) => self.start_table(alignments),
             _ => {}
         }
     }
@@ -125,17 +160,17 @@ where
             TagEnd::List(_is_ordered) => self.end_list(),
             TagEnd::Item => {}
             TagEnd::FootnoteDefinition => {}
-            TagEnd::Table => {}
-            TagEnd::TableHead => {}
-            TagEnd::TableRow => {}
-            TagEnd::TableCell => {}
+            TagEnd::Table => self.end_table(),
+            TagEnd::TableHead => self.end_table_row(),
+            TagEnd::TableRow => self.end_table_row(),
+            TagEnd::TableCell => self.end_table_cell(),
             TagEnd::Emphasis => self.pop_inline_style(),
             TagEnd::Strong => self.pop_inline_style(),
             TagEnd::Strikethrough => self.pop_inline_style(),
             TagEnd::Subscript => {}
             TagEnd::Superscript => {}
             TagEnd::Link => self.pop_link(),
-            TagEnd::Image => {}
+            TagEnd::Image => self.end_image(),
             TagEnd::MetadataBlock(_) => {}
             TagEnd::DefinitionList => {}
             TagEnd::DefinitionListTitle => {}
@@ -160,13 +195,20 @@ where
         if self.needs_newline {
             self.push_line(Line::default());
         }
+        let accent = self.theme.accent;
         let style = match level {
-            HeadingLevel::H1 => styles::H1,
-            HeadingLevel::H2 => styles::H2,
-            HeadingLevel::H3 => styles::H3,
-            HeadingLevel::H4 => styles::H4,
-            HeadingLevel::H5 => styles::H5,
-            HeadingLevel::H6 => styles::H6,
+            HeadingLevel::H1 => Style::new()
+                .bg(accent)
+                .add_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::UNDERLINED),
+            HeadingLevel::H2 => Style::new().fg(accent).add_modifier(Modifier::BOLD),
+            HeadingLevel::H3 => Style::new()
+                .fg(accent)
+                .add_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::ITALIC),
+            HeadingLevel::H4 | HeadingLevel::H5 | HeadingLevel::H6 => {
+                Style::new().fg(accent).add_modifier(Modifier::ITALIC)
+            }
         };
         let content = format!("{} ", "#".repeat(level as usize));
         self.push_line(Line::styled(content, style));
@@ -183,7 +225,7 @@ where
             self.needs_newline = false;
         }
         self.line_prefixes.push(Span::from(">"));
-        self.line_styles.push(styles::BLOCKQUOTE);
+        self.line_styles.push(Style::new().fg(self.theme.quote));
     }
 
     fn end_blockquote(&mut self) {
@@ -193,6 +235,11 @@ where
     }
 
     fn text(&mut self, text: CowStr<'a>) {
+        if let Some(table) = &mut self.table {
+            table.cell.push_str(&text);
+            return;
+        }
+
         if let Some(highlighter) = &mut self.code_highlighter {
             let text: Text = LinesWithEndings::from(&text)
                 .filter_map(|line| highlighter.highlight_line(line, &SYNTAX_SET).ok())
@@ -226,7 +273,7 @@ where
     }
 
     fn code(&mut self, code: CowStr<'a>) {
-        let span = Span::styled(code, styles::CODE);
+        let span = Span::styled(code, self.code_style());
         self.push_span(span);
     }
 
@@ -275,7 +322,7 @@ where
             CodeBlockKind::Indented => "",
         };
 
-        self.line_styles.push(styles::CODE);
+        self.line_styles.push(self.code_style());
 
         self.set_code_highlighter(lang);
 
@@ -295,13 +342,18 @@ where
     }
 
     fn set_code_highlighter(&mut self, lang: &str) {
-        if let Some(syntax) = SYNTAX_SET.find_syntax_by_token(lang) {
-            let theme = &THEME_SET.themes["base16-ocean.dark"];
+        if let Some(syntax) = SYNTAX_SET.find_syntax_by_token(lang)
+            && let Some(theme) = THEME_SET.themes.get(&self.theme.syntax_theme)
+        {
             let highlighter = HighlightLines::new(syntax, theme);
             self.code_highlighter = Some(highlighter);
         }
     }
 
+    fn code_style(&self) -> Style {
+        Style::new().fg(self.theme.code_fg).bg(self.theme.code_bg)
+    }
+
     fn clear_code_highlighter(&mut self) {
         self.code_highlighter = None;
     }
@@ -333,6 +385,11 @@ where
     }
 
     fn push_span(&mut self, span: Span<'a>) {
+        if let Some(table) = &mut self.table {
+            table.cell.push_str(&span.content);
+            return;
+        }
+
         if let Some(line) = self.text.lines.last_mut() {
             line.push_span(span);
         } else {
@@ -340,6 +397,125 @@ where
         }
     }
 
+    fn start_image(&mut self, dest_url: CowStr<'a>, title: CowStr<'a>) {
+        self.push_inline_style(Style::new().italic());
+        self.push_span("[image: ".into());
+        self.image = Some((dest_url, title));
+    }
+
+    fn end_image(&mut self) {
+        self.push_span("]".into());
+        self.pop_inline_style();
+
+        if let Some((dest_url, title)) = self.image.take() {
+            if !title.is_empty() {
+                self.push_span(format!(" \"{}\"", title).into());
+            }
+            self.push_span(" (".into());
+            self.push_span(Span::styled(dest_url, Style::new().fg(self.theme.link)));
+            self.push_span(")".into());
+        }
+    }
+
+    fn start_table(&mut self, alignments: Vec<Alignment>) {
+        if self.needs_newline {
+            self.push_line(Line::default());
+        }
+        self.table = Some(TableBuffer {
+            alignments,
+            ..Default::default()
+        });
+    }
+
+    fn end_table_cell(&mut self) {
+        if let Some(table) = &mut self.table {
+            let cell = std::mem::take(&mut table.cell);
+            table.row.push(cell.trim().to_string());
+        }
+    }
+
+    fn end_table_row(&mut self) {
+        if let Some(table) = &mut self.table {
+            let row = std::mem::take(&mut table.row);
+            table.rows.push(row);
+        }
+    }
+
+    fn end_table(&mut self) {
+        let Some(table) = self.table.take() else {
+            return;
+        };
+
+        let num_cols = table.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..num_cols)
+            .map(|col| {
+                table
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(col))
+                    .map(|cell| Span::raw(cell.as_str()).width())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let border = |left: &str, mid: &str, right: &str| {
+            let segments = widths
+                .iter()
+                .map(|width| "─".repeat(width + 2))
+                .collect::<Vec<String>>();
+            Line::styled(
+                format!("{}{}{}", left, segments.join(mid), right),
+                Style::new().fg(self.theme.accent),
+            )
+        };
+
+        let mut lines = vec![border("┌", "┬", "┐")];
+
+        for (i, row) in table.rows.iter().enumerate() {
+            let mut spans: Vec<Span<'a>> =
+                vec![Span::styled("│", Style::new().fg(self.theme.accent))];
+
+            for (col, width) in widths.iter().enumerate() {
+                let cell = row.get(col).map(String::as_str).unwrap_or("");
+                let padding = width.saturating_sub(Span::raw(cell).width());
+
+                let (pad_left, pad_right) = match table.alignments.get(col) {
+                    Some(Alignment::Right) => (padding, 0),
+                    Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+                    _ => (0, padding),
+                };
+
+                let content = format!(
+                    " {}{}{} ",
+                    " ".repeat(pad_left),
+                    cell,
+                    " ".repeat(pad_right)
+                );
+
+                spans.push(if i == 0 {
+                    Span::styled(content, Style::new().bold())
+                } else {
+                    Span::raw(content)
+                });
+                spans.push(Span::styled("│", Style::new().fg(self.theme.accent)));
+            }
+
+            lines.push(Line::from(spans));
+
+            if i == 0 && table.rows.len() > 1 {
+                lines.push(border("├", "┼", "┤"));
+            }
+        }
+
+        lines.push(border("└", "┴", "┘"));
+
+        for line in lines {
+            self.push_line(line);
+        }
+        self.needs_newline = true;
+    }
+
     /// Store the link to be appended to the link text
     fn push_link(&mut self, dest_url: CowStr<'a>) {
         self.link = Some(dest_url);
@@ -349,36 +525,13 @@ where
     fn pop_link(&mut self) {
         if let Some(link) = self.link.take() {
             self.push_span(" (".into());
-            self.push_span(Span::styled(link, styles::LINK));
+            self.push_span(Span::styled(
+                link,
+                Style::new()
+                    .fg(self.theme.link)
+                    .add_modifier(Modifier::UNDERLINED),
+            ));
             self.push_span(")".into());
         }
     }
 }
-
-mod styles {
-    use ratatui::style::{Color, Modifier, Style};
-
-    pub const H1: Style = Style::new()
-        .bg(Color::Cyan)
-        .add_modifier(Modifier::BOLD)
-        .add_modifier(Modifier::UNDERLINED);
-    pub const H2: Style = Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD);
-    pub const H3: Style = Style::new()
-        .fg(Color::Cyan)
-        .add_modifier(Modifier::BOLD)
-        .add_modifier(Modifier::ITALIC);
-    pub const H4: Style = Style::new()
-        .fg(Color::LightCyan)
-        .add_modifier(Modifier::ITALIC);
-    pub const H5: Style = Style::new()
-        .fg(Color::LightCyan)
-        .add_modifier(Modifier::ITALIC);
-    pub const H6: Style = Style::new()
-        .fg(Color::LightCyan)
-        .add_modifier(Modifier::ITALIC);
-    pub const BLOCKQUOTE: Style = Style::new().fg(Color::Green);
-    pub const CODE: Style = Style::new().fg(Color::White).bg(Color::Black);
-    pub const LINK: Style = Style::new()
-        .fg(Color::Blue)
-        .add_modifier(Modifier::UNDERLINED);
-}
@@ -0,0 +1,158 @@
+use super::tui_markdown::THEME_SET;
+use crate::common::{OwlError, Result};
+use ratatui::style::Color;
+use std::path::Path;
+use std::str::FromStr;
+use toml_edit::Item;
+
+const PRESETS: &[&str] = &["dark", "light"];
+
+#[derive(Clone, Debug)]
+pub struct TuiTheme {
+    pub syntax_theme: String,
+    pub accent: Color,
+    pub text: Color,
+    pub selection: Color,
+    pub code_fg: Color,
+    pub code_bg: Color,
+    pub quote: Color,
+    pub link: Color,
+}
+
+impl Default for TuiTheme {
+    fn default() -> Self {
+        TuiTheme {
+            syntax_theme: "base16-ocean.dark".into(),
+            accent: Color::Cyan,
+            text: Color::White,
+            selection: Color::DarkGray,
+            code_fg: Color::White,
+            code_bg: Color::Black,
+            quote: Color::Green,
+            link: Color::Blue,
+        }
+    }
+}
+
+impl TryFrom<&str> for TuiTheme {
+    type Error = OwlError;
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        match s {
+            "dark" => Ok(TuiTheme::default()),
+            "light" => Ok(TuiTheme {
+                syntax_theme: "InspiredGitHub".into(),
+                accent: Color::Blue,
+                text: Color::Black,
+                selection: Color::Gray,
+                code_fg: Color::Black,
+                code_bg: Color::Gray,
+                quote: Color::DarkGray,
+                link: Color::Magenta,
+            }),
+            _ => {
+                check_syntax_theme(s)?;
+
+                Ok(TuiTheme {
+                    syntax_theme: s.into(),
+                    ..TuiTheme::default()
+                })
+            }
+        }
+    }
+}
+
+impl TuiTheme {
+    pub fn from_item(config_path: &Path, item: Option<&Item>) -> Result<Self> {
+        let Some(item) = item else {
+            return Ok(TuiTheme::default());
+        };
+
+        let appearance_table = item.as_table_like().ok_or(OwlError::TomlError(
+            format!(
+                "Invalid entry 'appearance' in '{}'",
+                config_path.to_string_lossy()
+            ),
+            "expected a table".into(),
+        ))?;
+
+        let get_str = |key: &str| match appearance_table.get(key) {
+            Some(value) => value
+                .as_str()
+                .map(|s| Some(s.to_string()))
+                .ok_or(OwlError::TomlError(
+                    format!(
+                        "Invalid entry 'appearance.{}' in '{}'",
+                        key,
+                        config_path.to_string_lossy()
+                    ),
+                    "expected a string".into(),
+                )),
+            None => Ok(None),
+        };
+
+        let mut theme = match get_str("preset")? {
+            Some(preset) if PRESETS.contains(&preset.as_str()) => {
+                TuiTheme::try_from(preset.as_str())?
+            }
+            Some(preset) => {
+                return Err(OwlError::TomlError(
+                    format!(
+                        "Invalid entry 'appearance.preset' in '{}'",
+                        config_path.to_string_lossy()
+                    ),
+                    format!("'{}': expected one of {}", preset, PRESETS.join(", ")),
+                ));
+            }
+            None => TuiTheme::default(),
+        };
+
+        if let Some(syntax_theme) = get_str("syntax_theme")? {
+            check_syntax_theme(&syntax_theme)?;
+            theme.syntax_theme = syntax_theme;
+        }
+
+        for (key, color) in [
+            ("accent", &mut theme.accent),
+            ("text", &mut theme.text),
+            ("selection", &mut theme.selection),
+            ("code_fg", &mut theme.code_fg),
+            ("code_bg", &mut theme.code_bg),
+            ("quote", &mut theme.quote),
+            ("link", &mut theme.link),
+        ] {
+            if let Some(color_str) = get_str(key)? {
+                *color = Color::from_str(&color_str).map_err(|e| {
+                    OwlError::TomlError(
+                        format!(
+                            "Invalid entry 'appearance.{}' in '{}'",
+                            key,
+                            config_path.to_string_lossy()
+                        ),
+                        e.to_string(),
+                    )
+                })?;
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+fn check_syntax_theme(name: &str) -> Result<()> {
+    if THEME_SET.themes.contains_key(name) {
+        Ok(())
+    } else {
+        Err(OwlError::Unsupported(format!(
+            "'{}': unknown theme (expected {}, or one of {})",
+            name,
+            PRESETS.join(", "),
+            THEME_SET
+                .themes
+                .keys()
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ")
+        )))
+    }
+}
@@ -1,41 +1,54 @@
+use super::tui_keymap::{KeyAction, KeyMap};
 use super::tui_markdown;
+use super::tui_theme::TuiTheme;
 use crate::common::{OwlError, Result};
-use crate::owl_utils::{PromptMode, fs_utils, llm_utils, prog_utils};
+use crate::owl_utils::{LlmClient, PromptMode, fs_utils, llm_utils, prog_utils, term_utils};
 use ansi_to_tui::IntoText;
-use anthropic_sdk::Anthropic;
+use chrono::{DateTime, Local};
 use crossterm::{
     ExecutableCommand,
+    event::{DisableMouseCapture, EnableMouseCapture},
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{
     backend::CrosstermBackend,
     crossterm,
-    crossterm::event::{Event, KeyCode, read},
+    crossterm::event::{Event, MouseButton, MouseEvent, MouseEventKind, read},
     prelude::*,
     widgets::*,
 };
-use ratatui_explorer::{FileExplorer, Theme};
+use ratatui_explorer::{FileExplorer, Input, Theme};
 use std::ffi::OsStr;
+use std::fs;
 use std::io::stdout;
-use std::path::Path;
-use std::time::{Duration, Instant};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
 use syntect::easy::HighlightLines;
-use syntect::highlighting::ThemeSet;
-use syntect::parsing::SyntaxSet;
 use syntect::util::LinesWithEndings;
 use tui_textarea::TextArea;
 
 pub fn enter_raw_mode() -> Result<()> {
+    term_utils::require_interactive("the TUI")?;
+
+    tui_markdown::preload_syntax();
+
     enable_raw_mode()
         .map_err(|e| OwlError::TuiError("Failed to enter raw mode".into(), e.to_string()))?;
     stdout()
         .execute(EnterAlternateScreen)
         .map_err(|e| OwlError::TuiError("Failed to enable alt screen".into(), e.to_string()))?;
+    stdout()
+        .execute(EnableMouseCapture)
+        .map_err(|e| OwlError::TuiError("Failed to enable mouse".into(), e.to_string()))?;
 
     Ok(())
 }
 
 pub fn exit_raw_mode() -> Result<()> {
+    stdout()
+        .execute(DisableMouseCapture)
+        .map_err(|e| OwlError::TuiError("Failed to disable mouse".into(), e.to_string()))?;
     disable_raw_mode()
         .map_err(|e| OwlError::TuiError("Failed to disable raw mode".into(), e.to_string()))?;
     stdout()
@@ -45,31 +58,117 @@ pub fn exit_raw_mode() -> Result<()> {
     Ok(())
 }
 
-pub fn get_tui_theme() -> Theme {
+const WHEEL_STEP: usize = 3;
+
+pub fn get_tui_theme(theme: &TuiTheme, focused: bool) -> Theme {
     Theme::default()
-        .with_block(Block::default().borders(Borders::ALL))
-        .with_dir_style(
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        )
+        .with_block(focus_block(theme, focused, BorderType::Plain))
+        .with_dir_style(Style::default().fg(theme.text).add_modifier(Modifier::BOLD))
         .with_highlight_dir_style(
             Style::default()
-                .fg(Color::White)
+                .fg(theme.text)
                 .add_modifier(Modifier::BOLD)
-                .bg(Color::DarkGray),
+                .bg(theme.selection),
         )
         .with_scroll_padding(1)
 }
 
-pub fn highlight_content(path: &Path, content: String, ps: &SyntaxSet, ts: &ThemeSet) -> String {
+fn focus_block(theme: &TuiTheme, focused: bool, border_type: BorderType) -> Block<'static> {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(border_type);
+
+    if focused {
+        block.border_style(Style::default().fg(theme.accent))
+    } else {
+        block
+    }
+}
+
+trait ScrollView {
+    fn scroll_state(&mut self) -> (&mut usize, &mut ScrollbarState);
+    fn content_length(&self) -> usize;
+    fn viewport(&self) -> Rect;
+
+    fn scroll_to(&mut self, position: usize) {
+        let (vertical_scroll, vertical_scroll_state) = self.scroll_state();
+        *vertical_scroll = position;
+        *vertical_scroll_state = vertical_scroll_state.position(position);
+    }
+
+    fn scroll_with(&mut self, action: KeyAction) -> bool {
+        let page = self.viewport().height.saturating_sub(2).max(1) as usize;
+        let current = *self.scroll_state().0;
+
+        let position = match action {
+            KeyAction::ScrollUp => current.saturating_sub(1),
+            KeyAction::ScrollDown => current.saturating_add(1),
+            KeyAction::ScrollPageUp => current.saturating_sub(page),
+            KeyAction::ScrollPageDown => current.saturating_add(page),
+            KeyAction::ScrollTop => 0,
+            KeyAction::ScrollBottom => self.content_length().saturating_sub(page),
+            _ => return false,
+        };
+
+        self.scroll_to(position);
+        true
+    }
+
+    fn scroll_with_mouse(&mut self, mouse: &MouseEvent) {
+        let current = *self.scroll_state().0;
+
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.scroll_to(current.saturating_sub(WHEEL_STEP)),
+            MouseEventKind::ScrollDown => self.scroll_to(current.saturating_add(WHEEL_STEP)),
+            _ => {}
+        }
+    }
+}
+
+const SCROLL_ACTIONS: [KeyAction; 6] = [
+    KeyAction::ScrollDown,
+    KeyAction::ScrollUp,
+    KeyAction::ScrollPageDown,
+    KeyAction::ScrollPageUp,
+    KeyAction::ScrollTop,
+    KeyAction::ScrollBottom,
+];
+
+const NAV_ACTIONS: [KeyAction; 8] = [
+    KeyAction::NavUp,
+    KeyAction::NavDown,
+    KeyAction::NavIn,
+    KeyAction::NavOut,
+    KeyAction::NavFirst,
+    KeyAction::NavLast,
+    KeyAction::NavPageUp,
+    KeyAction::NavPageDown,
+];
+
+/// `actions` followed by the pane keys, where the split view takes the pane key (tab) first
+/// when there is a statement to show.
+fn with_pane_actions(actions: &[KeyAction], has_statement: bool) -> Vec<KeyAction> {
+    let mut actions = actions.to_vec();
+
+    if has_statement {
+        actions.push(KeyAction::ToggleSplit);
+    }
+
+    actions.push(KeyAction::FocusNext);
+    actions
+}
+
+pub fn highlight_content(path: &Path, content: String, theme: &TuiTheme) -> String {
+    let ps = &*tui_markdown::SYNTAX_SET;
+    let ts = &*tui_markdown::THEME_SET;
+
     if path.is_file()
         && let Some(prog_ext) = path.extension().and_then(OsStr::to_str)
         && prog_ext != "md"
         && let Some(syntax) = ps.find_syntax_by_extension(prog_ext)
+        && let Some(syntax_theme) = ts.themes.get(&theme.syntax_theme)
     {
-        let theme = &ts.themes["base16-ocean.dark"];
-        let mut h = HighlightLines::new(syntax, theme);
+        let mut h = HighlightLines::new(syntax, syntax_theme);
 
         let mut buffer = String::new();
         for line in LinesWithEndings::from(&content) {
@@ -86,13 +185,112 @@ pub fn highlight_content(path: &Path, content: String, ps: &SyntaxSet, ts: &Them
     }
 }
 
+#[derive(Debug, Default)]
+struct ContentCache {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    content: String,
+    num_lines: usize,
+    load_hint: String,
+    show_full: bool,
+    full_path: Option<PathBuf>,
+}
+
+impl ContentCache {
+    fn new(keymap: &KeyMap, show_full: bool) -> Self {
+        ContentCache {
+            load_hint: format!(
+                "press {} to load the full file",
+                keymap.label(KeyAction::LoadFull)
+            ),
+            show_full,
+            ..Default::default()
+        }
+    }
+
+    fn load_full(&mut self) {
+        self.full_path = Some(self.path.clone());
+        self.modified = None;
+    }
+
+    fn refresh(&mut self, path: &Path, should_highlight: bool, theme: &TuiTheme) -> bool {
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        if self.path == path && modified.is_some() && self.modified == modified {
+            return false;
+        }
+
+        if fs_utils::is_hidden_case(path) {
+            self.content = "Hidden test case.".into();
+            self.num_lines = 1;
+            self.path = path.to_path_buf();
+            self.modified = modified;
+
+            return true;
+        }
+
+        let preview = if self.show_full || self.full_path.as_deref() == Some(path) {
+            None
+        } else {
+            fs_utils::read_preview(path, &self.load_hint).ok().flatten()
+        };
+
+        let content = match preview.map_or_else(|| fs_utils::read_contents(path), Ok) {
+            Ok(file_content) if should_highlight => highlight_content(path, file_content, theme),
+            Ok(file_content) => file_content,
+            _ => "Failed to load file.".into(),
+        };
+
+        self.num_lines = content.split('\n').count();
+        self.content = content;
+        self.path = path.to_path_buf();
+        self.modified = modified;
+
+        true
+    }
+}
+
+impl ScrollView for FileApp {
+    fn scroll_state(&mut self) -> (&mut usize, &mut ScrollbarState) {
+        (&mut self.vertical_scroll, &mut self.vertical_scroll_state)
+    }
+
+    fn content_length(&self) -> usize {
+        self.content_length
+    }
+
+    fn viewport(&self) -> Rect {
+        self.viewport
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct FileApp {
     pub vertical_scroll_state: ScrollbarState,
     pub vertical_scroll: usize,
+    pub content_length: usize,
+    pub viewport: Rect,
+    pub show_full: bool,
+    pub keymap: KeyMap,
+    pub theme: TuiTheme,
 }
 
 impl FileApp {
+    pub fn new(keymap: KeyMap, theme: TuiTheme) -> Self {
+        FileApp {
+            keymap,
+            theme,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_full(mut self, show_full: bool) -> Self {
+        self.show_full = show_full;
+        self
+    }
+
     pub fn run(mut self, path: &Path) -> Result<()> {
         let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))
             .map_err(|e| OwlError::TuiError("Failed to setup terminal".into(), e.to_string()))?;
@@ -103,93 +301,94 @@ impl FileApp {
             Constraint::Min(1),
         ]);
 
-        let ps = SyntaxSet::load_defaults_newlines();
-        let ts = ThemeSet::load_defaults();
         let should_use_syntax_highlighting = prog_utils::check_prog_lang(path).is_some();
+        let scroll_help = self.keymap.scroll_help();
 
         let tick_rate = Duration::from_millis(250);
         let mut last_tick = Instant::now();
 
-        loop {
-            terminal
-                .draw(|f| {
-                    let chunks = layout.split(f.area());
-
-                    let (file_content, num_lines) = match fs_utils::read_contents(path) {
-                        Ok(file_content) => {
-                            if should_use_syntax_highlighting {
-                                let content = highlight_content(path, file_content, &ps, &ts);
-                                let n = content.split('\n').count();
-                                (content, n)
-                            } else {
-                                let n = file_content.split('\n').count();
-                                (file_content, n)
-                            }
-                        }
-                        _ => ("Failed to load file.".into(), 1),
-                    };
-
-                    self.vertical_scroll_state =
-                        self.vertical_scroll_state.content_length(num_lines);
+        let mut cache = ContentCache::new(&self.keymap, self.show_full);
+        let mut should_draw = true;
 
-                    let filename = path
-                        .to_str()
-                        .map(|s| s.to_string())
-                        .unwrap_or(path.to_string_lossy().to_string());
-
-                    let title = Block::new()
-                        .title_alignment(Alignment::Center)
-                        .title(filename.italic());
-                    f.render_widget(title, chunks[0]);
+        loop {
+            should_draw |= cache.refresh(path, should_use_syntax_highlighting, &self.theme);
 
-                    let paragraph = if let Some(ext) = path.extension().and_then(OsStr::to_str)
-                        && ext == "md"
-                    {
-                        Paragraph::new(tui_markdown::from_str(&file_content))
-                            .block(
-                                Block::default()
-                                    .borders(Borders::ALL)
-                                    .border_type(BorderType::Double),
-                            )
-                            .wrap(Wrap { trim: false })
-                            .scroll((self.vertical_scroll as u16, 0))
-                    } else if should_use_syntax_highlighting
-                        && let Ok(text) = file_content.into_text()
-                    {
-                        Paragraph::new(text)
-                            .block(
-                                Block::default()
-                                    .borders(Borders::ALL)
-                                    .border_type(BorderType::Double),
-                            )
-                            .wrap(Wrap { trim: false })
-                            .scroll((self.vertical_scroll as u16, 0))
-                    } else {
-                        Paragraph::new(file_content)
-                            .block(
-                                Block::default()
-                                    .borders(Borders::ALL)
-                                    .border_type(BorderType::Double),
-                            )
-                            .scroll((self.vertical_scroll as u16, 0))
-                    };
+            if should_draw {
+                terminal
+                    .draw(|f| {
+                        let chunks = layout.split(f.area());
+
+                        let file_content = cache.content.as_str();
+                        let num_lines = cache.num_lines;
+
+                        self.content_length = num_lines;
+                        self.viewport = chunks[1];
+                        self.vertical_scroll_state =
+                            self.vertical_scroll_state.content_length(num_lines);
+
+                        let filename = path
+                            .to_str()
+                            .map(|s| s.to_string())
+                            .unwrap_or(path.to_string_lossy().to_string());
+
+                        let title = Block::new()
+                            .title_alignment(Alignment::Center)
+                            .title(filename.italic());
+                        f.render_widget(title, chunks[0]);
+
+                        let paragraph = if let Some(ext) = path.extension().and_then(OsStr::to_str)
+                            && ext == "md"
+                        {
+                            Paragraph::new(tui_markdown::from_str(file_content, &self.theme))
+                                .block(
+                                    Block::default()
+                                        .borders(Borders::ALL)
+                                        .border_type(BorderType::Double),
+                                )
+                                .wrap(Wrap { trim: false })
+                                .scroll((self.vertical_scroll as u16, 0))
+                        } else if should_use_syntax_highlighting
+                            && let Ok(text) = file_content.into_text()
+                        {
+                            Paragraph::new(text)
+                                .block(
+                                    Block::default()
+                                        .borders(Borders::ALL)
+                                        .border_type(BorderType::Double),
+                                )
+                                .wrap(Wrap { trim: false })
+                                .scroll((self.vertical_scroll as u16, 0))
+                        } else {
+                            Paragraph::new(file_content)
+                                .block(
+                                    Block::default()
+                                        .borders(Borders::ALL)
+                                        .border_type(BorderType::Double),
+                                )
+                                .scroll((self.vertical_scroll as u16, 0))
+                        };
 
-                    f.render_widget(Clear, chunks[1]);
-                    f.render_widget(paragraph, chunks[1]);
-                    f.render_stateful_widget(
-                        Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                            .begin_symbol(Some("↑"))
-                            .end_symbol(Some("↓")),
-                        chunks[1],
-                        &mut self.vertical_scroll_state,
-                    );
+                        f.render_widget(Clear, chunks[1]);
+                        f.render_widget(paragraph, chunks[1]);
+                        f.render_stateful_widget(
+                            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                                .begin_symbol(Some("↑"))
+                                .end_symbol(Some("↓")),
+                            chunks[1],
+                            &mut self.vertical_scroll_state,
+                        );
+
+                        let helpbar = Block::new()
+                            .title_alignment(Alignment::Center)
+                            .title(scroll_help.as_str().bold());
+                        f.render_widget(helpbar, chunks[2]);
+                    })
+                    .map_err(|e| {
+                        OwlError::TuiError("Failed to draw frame".into(), e.to_string())
+                    })?;
 
-                    let helpbar = Block::new()
-                        .title_alignment(Alignment::Center)
-                        .title("Use ▲ ▼ to scroll ".bold());
-                    f.render_widget(helpbar, chunks[2]);
-                })
-                .map_err(|e| OwlError::TuiError("Failed to draw frame".into(), e.to_string()))?;
+                should_draw = false;
+            }
 
             let timeout = tick_rate.saturating_sub(last_tick.elapsed());
 
@@ -200,25 +399,24 @@ impl FileApp {
                     OwlError::TuiError("Failed to read event".into(), e.to_string())
                 })?;
 
-                if let Event::Key(key) = event {
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => break,
-                        KeyCode::Down => {
-                            self.vertical_scroll = self.vertical_scroll.saturating_add(1);
-                            self.vertical_scroll_state =
-                                self.vertical_scroll_state.position(self.vertical_scroll);
-                        }
-                        KeyCode::Up => {
-                            self.vertical_scroll = self.vertical_scroll.saturating_sub(1);
-                            self.vertical_scroll_state =
-                                self.vertical_scroll_state.position(self.vertical_scroll);
-                        }
-                        _ => {
-                            self.vertical_scroll = 0;
-                            self.vertical_scroll_state =
-                                self.vertical_scroll_state.position(self.vertical_scroll);
-                        }
-                    };
+                should_draw = true;
+
+                match event {
+                    Event::Key(key) => {
+                        let mut actions = vec![KeyAction::Quit, KeyAction::LoadFull];
+                        actions.extend(SCROLL_ACTIONS);
+
+                        match self.keymap.action(&key, &actions) {
+                            Some(KeyAction::Quit) => break,
+                            Some(KeyAction::LoadFull) => cache.load_full(),
+                            Some(action) => {
+                                self.scroll_with(action);
+                            }
+                            None => self.scroll_to(0),
+                        };
+                    }
+                    Event::Mouse(mouse) => self.scroll_with_mouse(&mouse),
+                    _ => {}
                 }
             }
 
@@ -231,22 +429,245 @@ impl FileApp {
     }
 }
 
+/// The kind, size, and mtime of the highlighted entry, as columns under the explorer.
+fn entry_footer(path: &Path) -> String {
+    let Ok(entry) = fs_utils::TreeEntry::load(path) else {
+        return String::new();
+    };
+
+    let size = match entry.kind {
+        fs_utils::EntryKind::File => match entry.size {
+            size if size < 1024 => format!("{}B", size),
+            size if size < 1024 * 1024 => format!("{:.1}KiB", size as f64 / 1024.0),
+            size => format!("{:.1}MiB", size as f64 / (1024.0 * 1024.0)),
+        },
+        _ => "-".into(),
+    };
+
+    let modified = entry
+        .modified
+        .map(|modified| {
+            DateTime::<Local>::from(modified)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or("-".into());
+
+    format!(" {:<7} {:>9}  {} ", entry.kind.name(), size, modified)
+}
+
+/// A case shown beside the statement in the quest explorer's split view.
+#[derive(Debug, Default)]
+pub struct QuestCase {
+    pub name: String,
+    pub input: PathBuf,
+    pub answer: Option<PathBuf>,
+}
+
+impl ScrollView for FileExplorerApp {
+    // the statement scrolls on its own in the split view, the case panes share the preview's
+    fn scroll_state(&mut self) -> (&mut usize, &mut ScrollbarState) {
+        match self.split && !self.preview_focused {
+            true => (&mut self.statement_scroll, &mut self.statement_scroll_state),
+            false => (&mut self.vertical_scroll, &mut self.vertical_scroll_state),
+        }
+    }
+
+    fn content_length(&self) -> usize {
+        match self.split && !self.preview_focused {
+            true => self.statement_length,
+            false => self.content_length,
+        }
+    }
+
+    fn viewport(&self) -> Rect {
+        match self.split && !self.preview_focused {
+            true => self.statement_area,
+            false => self.viewport,
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct FileExplorerApp {
     pub vertical_scroll_state: ScrollbarState,
     pub vertical_scroll: usize,
+    pub content_length: usize,
+    pub viewport: Rect,
+    pub explorer_area: Rect,
+    pub preview_focused: bool,
+    pub show_full: bool,
+    pub statement: Option<String>,
+    pub cases: Vec<QuestCase>,
+    pub split: bool,
+    pub selected: usize,
+    pub statement_scroll_state: ScrollbarState,
+    pub statement_scroll: usize,
+    pub statement_length: usize,
+    pub statement_area: Rect,
+    pub case_area: Rect,
+    pub keymap: KeyMap,
+    pub theme: TuiTheme,
 }
 
 impl FileExplorerApp {
+    pub fn new(keymap: KeyMap, theme: TuiTheme) -> Self {
+        FileExplorerApp {
+            keymap,
+            theme,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_full(mut self, show_full: bool) -> Self {
+        self.show_full = show_full;
+        self
+    }
+
+    /// The quest's statement (markdown) and cases, shown side by side in the split view.
+    pub fn with_statement(mut self, statement: Option<String>, cases: Vec<QuestCase>) -> Self {
+        self.statement = statement;
+        self.cases = cases;
+        self
+    }
+
+    /// Switches between the explorer and the split view, which opens on the case under `cursor`
+    /// (if it is one) with the statement focused.
+    fn toggle_split(&mut self, cursor: &Path) {
+        self.split = !self.split;
+        self.preview_focused = false;
+
+        if self.split
+            && let Some(stem) = fs_utils::case_stem(cursor)
+            && let Some(i) = self.cases.iter().position(|case| case.name == stem)
+        {
+            self.selected = i;
+        }
+
+        self.vertical_scroll = 0;
+        self.vertical_scroll_state = self.vertical_scroll_state.position(0);
+    }
+
+    /// Steps through the cases in the split view, showing each from its top.
+    fn select_case(&mut self, action: KeyAction) {
+        let last = self.cases.len().saturating_sub(1);
+
+        self.selected = match action {
+            KeyAction::NavUp => self.selected.saturating_sub(1),
+            KeyAction::NavDown => (self.selected + 1).min(last),
+            KeyAction::NavFirst => 0,
+            KeyAction::NavLast => last,
+            KeyAction::NavPageUp => self.selected.saturating_sub(10),
+            KeyAction::NavPageDown => (self.selected + 10).min(last),
+            _ => return,
+        };
+
+        self.vertical_scroll = 0;
+        self.vertical_scroll_state = self.vertical_scroll_state.position(0);
+    }
+
+    /// Draws the statement on the left, and the selected case's input above its answer on the
+    /// right.
+    fn draw_split(&mut self, f: &mut Frame, input: &str, answer: &str) {
+        let chunks =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)]).split(f.area());
+        let body =
+            Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).split(chunks[0]);
+        let case_panes =
+            Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).split(body[1]);
+
+        let statement = self.statement.clone().unwrap_or_default();
+        let statement = tui_markdown::from_str(&statement, &self.theme);
+
+        self.statement_length = statement.lines.len();
+        self.statement_area = body[0];
+        self.statement_scroll_state = self
+            .statement_scroll_state
+            .content_length(self.statement_length);
+
+        f.render_widget(Clear, body[0]);
+        f.render_widget(
+            Paragraph::new(statement)
+                .block(
+                    focus_block(&self.theme, !self.preview_focused, BorderType::Rounded)
+                        .title(" statement "),
+                )
+                .wrap(Wrap { trim: false })
+                .scroll((self.statement_scroll as u16, 0)),
+            body[0],
+        );
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(Some("↑"))
+                .end_symbol(Some("↓")),
+            body[0],
+            &mut self.statement_scroll_state,
+        );
+
+        let case_title = match self.cases.get(self.selected) {
+            Some(case) => format!(
+                " input · {} ({}/{}) ",
+                case.name,
+                self.selected + 1,
+                self.cases.len()
+            ),
+            None => " input · no cases ".into(),
+        };
+
+        self.content_length = input.lines().count().max(answer.lines().count());
+        self.viewport = case_panes[0];
+        self.case_area = body[1];
+        self.vertical_scroll_state = self
+            .vertical_scroll_state
+            .content_length(self.content_length);
+
+        for (text, area, pane_title) in [
+            (input, case_panes[0], case_title),
+            (answer, case_panes[1], " answer ".to_string()),
+        ] {
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(text)
+                    .block(
+                        focus_block(&self.theme, self.preview_focused, BorderType::Rounded)
+                            .title(pane_title),
+                    )
+                    .scroll((self.vertical_scroll as u16, 0)),
+                area,
+            );
+            f.render_stateful_widget(
+                Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                    .begin_symbol(Some("↑"))
+                    .end_symbol(Some("↓")),
+                area,
+                &mut self.vertical_scroll_state,
+            );
+        }
+
+        let help = format!(
+            " {} {} case | {} files | {} pane | {} ",
+            self.keymap.label(KeyAction::NavUp),
+            self.keymap.label(KeyAction::NavDown),
+            self.keymap.label(KeyAction::ToggleSplit),
+            self.keymap
+                .label_besides(KeyAction::FocusNext, KeyAction::ToggleSplit),
+            self.keymap.label(KeyAction::Quit),
+        );
+        f.render_widget(
+            Paragraph::new(Line::from(help.bold())).alignment(Alignment::Center),
+            chunks[1],
+        );
+    }
+
     pub fn run(mut self, cwd: &Path) -> Result<()> {
         let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))
             .map_err(|e| OwlError::TuiError("Failed to setup terminal".into(), e.to_string()))?;
 
         let layout = Layout::horizontal([Constraint::Ratio(1, 3), Constraint::Ratio(2, 3)]);
 
-        let theme = get_tui_theme();
+        let explorer_theme = get_tui_theme(&self.theme, !self.preview_focused);
 
-        let mut file_explorer = FileExplorer::with_theme(theme).map_err(|e| {
+        let mut file_explorer = FileExplorer::with_theme(explorer_theme).map_err(|e| {
             OwlError::TuiError("Failed to start file explorer".into(), e.to_string())
         })?;
 
@@ -257,107 +678,158 @@ impl FileExplorerApp {
             )
         })?;
 
-        let ps = SyntaxSet::load_defaults_newlines();
-        let ts = ThemeSet::load_defaults();
+        let scroll_help = match self.statement {
+            Some(_) => format!(
+                "{}| {} statement ",
+                self.keymap.scroll_help(),
+                self.keymap.label(KeyAction::ToggleSplit)
+            ),
+            None => self.keymap.scroll_help(),
+        };
+        let nav_help = format!(
+            "Use {} {} {} {} to scroll ",
+            self.keymap.label(KeyAction::NavOut),
+            self.keymap.label(KeyAction::NavDown),
+            self.keymap.label(KeyAction::NavUp),
+            self.keymap.label(KeyAction::NavIn)
+        );
 
         let tick_rate = Duration::from_millis(250);
         let mut last_tick = Instant::now();
 
+        let mut cache = ContentCache::new(&self.keymap, self.show_full);
+        let mut input_cache = ContentCache::new(&self.keymap, self.show_full);
+        let mut answer_cache = ContentCache::new(&self.keymap, self.show_full);
+        let mut should_draw = true;
+
         loop {
             let file_cursor = file_explorer.current();
 
             let should_use_syntax_highlighting =
                 prog_utils::check_prog_lang(file_cursor.path()).is_some();
 
-            terminal
-                .draw(|f| {
-                    let h_chunks = layout.split(f.area());
-                    let l_chunks =
-                        Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
-                            .split(h_chunks[0]);
-                    let r_chunks =
-                        Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
-                            .split(h_chunks[1]);
-
-                    let (file_content, num_lines) =
-                        match fs_utils::read_contents(file_cursor.path()) {
-                            Ok(file_content) => {
-                                if should_use_syntax_highlighting {
-                                    let content = highlight_content(
-                                        file_cursor.path(),
-                                        file_content,
-                                        &ps,
-                                        &ts,
-                                    );
-                                    let n = content.split('\n').count();
-                                    (content, n)
-                                } else {
-                                    let n = file_content.split('\n').count();
-                                    (file_content, n)
-                                }
-                            }
-                            _ => ("Failed to load file.".into(), 1),
-                        };
+            should_draw |= cache.refresh(
+                file_cursor.path(),
+                should_use_syntax_highlighting,
+                &self.theme,
+            );
 
-                    self.vertical_scroll_state =
-                        self.vertical_scroll_state.content_length(num_lines);
+            let case = self.cases.get(self.selected).filter(|_| self.split);
 
-                    f.render_widget(&file_explorer.widget(), l_chunks[0]);
+            if let Some(case) = case {
+                should_draw |= input_cache.refresh(&case.input, false, &self.theme);
 
-                    let l_helpbar = Block::new()
-                        .title_alignment(Alignment::Center)
-                        .title("Use h j k l to scroll ".bold());
-                    f.render_widget(l_helpbar, l_chunks[1]);
+                if let Some(answer) = &case.answer {
+                    should_draw |= answer_cache.refresh(answer, false, &self.theme);
+                }
+            }
 
-                    let paragraph = if let Some(ext) =
-                        file_cursor.path().extension().and_then(OsStr::to_str)
-                        && ext == "md"
-                    {
-                        Paragraph::new(tui_markdown::from_str(&file_content))
-                            .block(
-                                Block::default()
-                                    .borders(Borders::ALL)
-                                    .border_type(BorderType::Double),
-                            )
-                            .wrap(Wrap { trim: false })
-                            .scroll((self.vertical_scroll as u16, 0))
-                    } else if should_use_syntax_highlighting
-                        && let Ok(text) = file_content.into_text()
-                    {
-                        Paragraph::new(text)
-                            .block(
-                                Block::default()
-                                    .borders(Borders::ALL)
-                                    .border_type(BorderType::Double),
-                            )
-                            .wrap(Wrap { trim: false })
-                            .scroll((self.vertical_scroll as u16, 0))
-                    } else {
-                        Paragraph::new(file_content)
-                            .block(
-                                Block::default()
-                                    .borders(Borders::ALL)
-                                    .border_type(BorderType::Double),
-                            )
-                            .scroll((self.vertical_scroll as u16, 0))
-                    };
+            let answer = match case {
+                Some(QuestCase { answer: None, .. }) => "(no answer file)",
+                Some(_) => answer_cache.content.as_str(),
+                None => "",
+            };
+            let input = case.map_or("", |_| input_cache.content.as_str());
 
-                    f.render_widget(Clear, r_chunks[0]);
-                    f.render_widget(paragraph, r_chunks[0]);
-                    f.render_stateful_widget(
-                        Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                            .begin_symbol(Some("↑"))
-                            .end_symbol(Some("↓")),
-                        r_chunks[0],
-                        &mut self.vertical_scroll_state,
-                    );
+            if should_draw && self.split {
+                terminal
+                    .draw(|f| self.draw_split(f, input, answer))
+                    .map_err(|e| {
+                        OwlError::TuiError("Failed to draw frame".into(), e.to_string())
+                    })?;
 
-                    let r_helpbar = Block::new()
-                        .title_alignment(Alignment::Center)
-                        .title("Use ▲ ▼ to scroll ".bold());
-                    f.render_widget(r_helpbar, r_chunks[1]);
-                })
-                .map_err(|e| OwlError::TuiError("Failed to draw frame".into(), e.to_string()))?;
+                should_draw = false;
+            }
+
+            if should_draw {
+                terminal
+                    .draw(|f| {
+                        let h_chunks = layout.split(f.area());
+                        let l_chunks = Layout::vertical([
+                            Constraint::Percentage(100),
+                            Constraint::Min(1),
+                            Constraint::Min(1),
+                        ])
+                        .split(h_chunks[0]);
+                        let r_chunks =
+                            Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
+                                .split(h_chunks[1]);
+
+                        let file_content = cache.content.as_str();
+                        let num_lines = cache.num_lines;
+
+                        self.content_length = num_lines;
+                        self.viewport = r_chunks[0];
+                        self.explorer_area = l_chunks[0];
+                        self.vertical_scroll_state =
+                            self.vertical_scroll_state.content_length(num_lines);
+
+                        f.render_widget(&file_explorer.widget(), l_chunks[0]);
+
+                        let entry_bar = Block::new()
+                            .title_alignment(Alignment::Center)
+                            .title(entry_footer(file_cursor.path()).italic());
+                        f.render_widget(entry_bar, l_chunks[1]);
+
+                        let l_helpbar = Block::new()
+                            .title_alignment(Alignment::Center)
+                            .title(nav_help.as_str().bold());
+                        f.render_widget(l_helpbar, l_chunks[2]);
+
+                        let paragraph = if let Some(ext) =
+                            file_cursor.path().extension().and_then(OsStr::to_str)
+                            && ext == "md"
+                        {
+                            Paragraph::new(tui_markdown::from_str(file_content, &self.theme))
+                                .block(focus_block(
+                                    &self.theme,
+                                    self.preview_focused,
+                                    BorderType::Double,
+                                ))
+                                .wrap(Wrap { trim: false })
+                                .scroll((self.vertical_scroll as u16, 0))
+                        } else if should_use_syntax_highlighting
+                            && let Ok(text) = file_content.into_text()
+                        {
+                            Paragraph::new(text)
+                                .block(focus_block(
+                                    &self.theme,
+                                    self.preview_focused,
+                                    BorderType::Double,
+                                ))
+                                .wrap(Wrap { trim: false })
+                                .scroll((self.vertical_scroll as u16, 0))
+                        } else {
+                            Paragraph::new(file_content)
+                                .block(focus_block(
+                                    &self.theme,
+                                    self.preview_focused,
+                                    BorderType::Double,
+                                ))
+                                .scroll((self.vertical_scroll as u16, 0))
+                        };
+
+                        f.render_widget(Clear, r_chunks[0]);
+                        f.render_widget(paragraph, r_chunks[0]);
+                        f.render_stateful_widget(
+                            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                                .begin_symbol(Some("↑"))
+                                .end_symbol(Some("↓")),
+                            r_chunks[0],
+                            &mut self.vertical_scroll_state,
+                        );
+
+                        let r_helpbar = Block::new()
+                            .title_alignment(Alignment::Center)
+                            .title(scroll_help.as_str().bold());
+                        f.render_widget(r_helpbar, r_chunks[1]);
+                    })
+                    .map_err(|e| {
+                        OwlError::TuiError("Failed to draw frame".into(), e.to_string())
+                    })?;
+
+                should_draw = false;
+            }
 
             let timeout = tick_rate.saturating_sub(last_tick.elapsed());
 
@@ -368,32 +840,112 @@ impl FileExplorerApp {
                     OwlError::TuiError("Failed to read event".into(), e.to_string())
                 })?;
 
-                if let Event::Key(key) = event {
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => break,
-                        KeyCode::Down => {
-                            self.vertical_scroll = self.vertical_scroll.saturating_add(1);
-                            self.vertical_scroll_state =
-                                self.vertical_scroll_state.position(self.vertical_scroll);
+                should_draw = true;
+
+                let input = match event {
+                    Event::Key(key) => {
+                        let mut actions =
+                            with_pane_actions(&[KeyAction::Quit], self.statement.is_some());
+                        actions.push(KeyAction::LoadFull);
+
+                        if self.preview_focused && !self.split {
+                            actions.extend(SCROLL_ACTIONS);
+                            actions.extend(NAV_ACTIONS);
+                        } else {
+                            actions.extend(NAV_ACTIONS);
+                            actions.extend(SCROLL_ACTIONS);
+                        }
+
+                        match self.keymap.action(&key, &actions) {
+                            Some(KeyAction::Quit) => break,
+                            Some(KeyAction::ToggleSplit) => {
+                                self.toggle_split(file_cursor.path());
+                                file_explorer.set_theme(get_tui_theme(&self.theme, true));
+                                None
+                            }
+                            Some(KeyAction::FocusNext) => {
+                                self.preview_focused = !self.preview_focused;
+                                file_explorer
+                                    .set_theme(get_tui_theme(&self.theme, !self.preview_focused));
+                                None
+                            }
+                            Some(KeyAction::LoadFull) if self.split => {
+                                input_cache.load_full();
+                                answer_cache.load_full();
+                                None
+                            }
+                            Some(KeyAction::LoadFull) => {
+                                cache.load_full();
+                                None
+                            }
+                            Some(action) if self.split && NAV_ACTIONS.contains(&action) => {
+                                self.select_case(action);
+                                None
+                            }
+                            Some(KeyAction::NavUp) => Some(Input::Up),
+                            Some(KeyAction::NavDown) => Some(Input::Down),
+                            Some(KeyAction::NavIn) => Some(Input::Right),
+                            Some(KeyAction::NavOut) => Some(Input::Left),
+                            Some(KeyAction::NavFirst) => Some(Input::Home),
+                            Some(KeyAction::NavLast) => Some(Input::End),
+                            Some(KeyAction::NavPageUp) => Some(Input::PageUp),
+                            Some(KeyAction::NavPageDown) => Some(Input::PageDown),
+                            Some(action) => {
+                                self.scroll_with(action);
+                                None
+                            }
+                            None => Some(Input::None),
                         }
-                        KeyCode::Up => {
-                            self.vertical_scroll = self.vertical_scroll.saturating_sub(1);
-                            self.vertical_scroll_state =
-                                self.vertical_scroll_state.position(self.vertical_scroll);
+                    }
+                    Event::Mouse(mouse) if self.split => {
+                        let position = Position::new(mouse.column, mouse.row);
+
+                        // the pane under the pointer takes focus, so the wheel scrolls it
+                        if self.statement_area.contains(position) {
+                            self.preview_focused = false;
+                            self.scroll_with_mouse(&mouse);
+                        } else if self.case_area.contains(position) {
+                            self.preview_focused = true;
+                            self.scroll_with_mouse(&mouse);
                         }
-                        _ => {
-                            self.vertical_scroll = 0;
-                            self.vertical_scroll_state =
-                                self.vertical_scroll_state.position(self.vertical_scroll);
-
-                            file_explorer.handle(&event).map_err(|e| {
-                                OwlError::TuiError(
-                                    "Failed to handle key event".into(),
-                                    e.to_string(),
-                                )
-                            })?;
+
+                        None
+                    }
+                    Event::Mouse(mouse) => {
+                        let position = Position::new(mouse.column, mouse.row);
+
+                        if let MouseEventKind::Down(MouseButton::Left) = mouse.kind
+                            && (self.explorer_area.contains(position)
+                                || self.viewport.contains(position))
+                        {
+                            self.preview_focused = self.viewport.contains(position);
+                            file_explorer
+                                .set_theme(get_tui_theme(&self.theme, !self.preview_focused));
                         }
-                    };
+
+                        if self.explorer_area.contains(position) {
+                            match mouse.kind {
+                                MouseEventKind::ScrollUp => Some(Input::Up),
+                                MouseEventKind::ScrollDown => Some(Input::Down),
+                                _ => None,
+                            }
+                        } else {
+                            if self.viewport.contains(position) {
+                                self.scroll_with_mouse(&mouse);
+                            }
+
+                            None
+                        }
+                    }
+                    _ => None,
+                };
+
+                if let Some(input) = input {
+                    self.scroll_to(0);
+
+                    file_explorer.handle(input).map_err(|e| {
+                        OwlError::TuiError("Failed to handle key event".into(), e.to_string())
+                    })?;
                 }
             }
 
@@ -406,26 +958,58 @@ impl FileExplorerApp {
     }
 }
 
+impl ScrollView for LlmApp {
+    fn scroll_state(&mut self) -> (&mut usize, &mut ScrollbarState) {
+        (&mut self.vertical_scroll, &mut self.vertical_scroll_state)
+    }
+
+    fn content_length(&self) -> usize {
+        self.content_length
+    }
+
+    fn viewport(&self) -> Rect {
+        self.viewport
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct LlmApp {
     pub vertical_scroll_state: ScrollbarState,
     pub vertical_scroll: usize,
+    pub content_length: usize,
+    pub viewport: Rect,
+    pub input_area: Rect,
+    pub chat_focused: bool,
+    pub sending: bool,
+    pub keymap: KeyMap,
+    pub theme: TuiTheme,
 }
 
 impl LlmApp {
+    pub fn new(keymap: KeyMap, theme: TuiTheme) -> Self {
+        LlmApp {
+            keymap,
+            theme,
+            ..Default::default()
+        }
+    }
+
     pub fn draw(
         &mut self,
         ai_sdk: &str,
         layout: &Layout,
         markdown_str: &str,
         lines_len: usize,
-        textarea: &TextArea,
+        textarea: &mut TextArea,
         f: &mut Frame,
     ) {
         let chunks = layout.split(f.area());
 
-        let markdown_text = tui_markdown::from_str(markdown_str);
+        let markdown_text = tui_markdown::from_str(markdown_str, &self.theme);
 
+        self.content_length = lines_len;
+        self.viewport = chunks[1];
+        self.input_area = chunks[3];
         self.vertical_scroll_state = self.vertical_scroll_state.content_length(lines_len);
 
         let title = Block::new()
@@ -436,11 +1020,11 @@ impl LlmApp {
         f.render_widget(Clear, chunks[1]);
         f.render_widget(
             Paragraph::new(markdown_text)
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .border_type(BorderType::Double),
-                )
+                .block(focus_block(
+                    &self.theme,
+                    self.chat_focused,
+                    BorderType::Double,
+                ))
                 .wrap(Wrap { trim: false })
                 .scroll((self.vertical_scroll as u16, 0)),
             chunks[1],
@@ -453,18 +1037,31 @@ impl LlmApp {
             &mut self.vertical_scroll_state,
         );
 
-        let helpbar = Block::new()
-            .title_alignment(Alignment::Center)
-            .title("Use ▲ ▼ to scroll ".bold());
+        let helpbar = Block::new().title_alignment(Alignment::Center).title(
+            format!(
+                "{}| {} to send | {} to recall ",
+                self.keymap.scroll_help(),
+                self.keymap.label(KeyAction::Send),
+                self.keymap.label(KeyAction::Recall)
+            )
+            .bold(),
+        );
         f.render_widget(helpbar, chunks[2]);
 
-        f.render_widget(textarea, chunks[3]);
+        let input_block = focus_block(&self.theme, !self.chat_focused, BorderType::Double);
+
+        if self.sending {
+            textarea.set_block(input_block.title(" sending... ".italic()));
+        } else {
+            textarea.set_block(input_block);
+        }
+        f.render_widget(&*textarea, chunks[3]);
     }
 
     pub async fn run(
         mut self,
         ai_sdk: &str,
-        client: &Anthropic,
+        client: &LlmClient,
         check_prog: Option<&str>,
         check_prompt: Option<&str>,
         mode: PromptMode,
@@ -483,11 +1080,6 @@ impl LlmApp {
         let mut last_tick = Instant::now();
 
         let mut textarea = TextArea::default();
-        textarea.set_block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Double),
-        );
 
         let mut ai_responses: Vec<String> = Vec::new();
         let mut user_queries: Vec<String> = Vec::new();
@@ -496,7 +1088,7 @@ impl LlmApp {
         let mut lines_len = 1;
 
         terminal
-            .draw(|f| self.draw(ai_sdk, &layout, &markdown_str, lines_len, &textarea, f))
+            .draw(|f| self.draw(ai_sdk, &layout, &markdown_str, lines_len, &mut textarea, f))
             .map_err(|e| OwlError::TuiError("Failed to draw frame".into(), e.to_string()))?;
 
         let response =
@@ -508,10 +1100,11 @@ impl LlmApp {
         ai_responses.push(response);
 
         let mut user_has_query = false;
+        let mut recall_index: Option<usize> = None;
 
         loop {
             terminal
-                .draw(|f| self.draw(ai_sdk, &layout, &markdown_str, lines_len, &textarea, f))
+                .draw(|f| self.draw(ai_sdk, &layout, &markdown_str, lines_len, &mut textarea, f))
                 .map_err(|e| OwlError::TuiError("Failed to draw frame".into(), e.to_string()))?;
 
             if user_has_query {
@@ -523,9 +1116,12 @@ impl LlmApp {
                 lines_len += response.split('\n').count();
                 ai_responses.push(response);
                 user_has_query = false;
+                self.sending = false;
 
                 terminal
-                    .draw(|f| self.draw(ai_sdk, &layout, &markdown_str, lines_len, &textarea, f))
+                    .draw(|f| {
+                        self.draw(ai_sdk, &layout, &markdown_str, lines_len, &mut textarea, f)
+                    })
                     .map_err(|e| {
                         OwlError::TuiError("Failed to draw frame".into(), e.to_string())
                     })?;
@@ -540,20 +1136,63 @@ impl LlmApp {
                     OwlError::TuiError("Failed to read event".into(), e.to_string())
                 })?;
 
+                if let Event::Mouse(mouse) = event {
+                    let position = Position::new(mouse.column, mouse.row);
+
+                    if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
+                        if self.viewport.contains(position) {
+                            self.chat_focused = true;
+                        } else if self.input_area.contains(position) {
+                            self.chat_focused = false;
+                        }
+                    }
+
+                    if self.viewport.contains(position) {
+                        self.scroll_with_mouse(&mouse);
+                    }
+                }
+
                 if let Event::Key(key) = event {
-                    match key.code {
-                        KeyCode::Esc => break,
-                        KeyCode::Down => {
-                            self.vertical_scroll = self.vertical_scroll.saturating_add(1);
-                            self.vertical_scroll_state =
-                                self.vertical_scroll_state.position(self.vertical_scroll);
+                    let mut actions = vec![KeyAction::Quit, KeyAction::FocusNext, KeyAction::Send];
+
+                    let key_action = if self.chat_focused {
+                        actions.extend(SCROLL_ACTIONS);
+                        self.keymap.action(&key, &actions)
+                    } else {
+                        let can_recall = textarea.is_empty()
+                            || (recall_index.is_some() && textarea.cursor().0 == 0);
+
+                        if can_recall {
+                            actions.push(KeyAction::Recall);
+                        }
+                        actions.extend([KeyAction::ScrollPageDown, KeyAction::ScrollPageUp]);
+
+                        self.keymap.text_action(&key, &actions)
+                    };
+
+                    match key_action {
+                        Some(KeyAction::Quit) => break,
+                        Some(KeyAction::FocusNext) => {
+                            self.chat_focused = !self.chat_focused;
                         }
-                        KeyCode::Up => {
-                            self.vertical_scroll = self.vertical_scroll.saturating_sub(1);
-                            self.vertical_scroll_state =
-                                self.vertical_scroll_state.position(self.vertical_scroll);
+                        Some(KeyAction::Recall) => {
+                            let prev_index = match recall_index {
+                                Some(i) => i.saturating_sub(1),
+                                None => user_queries.len().saturating_sub(1),
+                            };
+
+                            if let Some(prev_query) = user_queries.get(prev_index) {
+                                textarea.select_all();
+                                textarea.cut();
+                                textarea.insert_str(prev_query);
+                                recall_index = Some(prev_index);
+                            }
                         }
-                        KeyCode::Enter => {
+                        Some(KeyAction::Send) if textarea.is_empty() => {}
+                        Some(KeyAction::Send) => {
+                            self.chat_focused = false;
+                            self.sending = true;
+                            recall_index = None;
                             self.vertical_scroll = lines_len;
                             self.vertical_scroll_state.last();
 
@@ -572,8 +1211,14 @@ impl LlmApp {
                             markdown_str
                                 .push_str(&format!("\n\n**# {}**: Thinking...\n\n", ai_sdk));
                         }
-                        _ => {
-                            textarea.input(key);
+                        Some(action) => {
+                            self.scroll_with(action);
+                        }
+                        None => {
+                            self.chat_focused = false;
+                            if textarea.input(key) {
+                                recall_index = None;
+                            }
                         }
                     };
                 }
@@ -587,3 +1232,627 @@ impl LlmApp {
         Ok(markdown_str)
     }
 }
+
+/// Live counters shared between the workers of a `--jobs` run and the `TopApp` drawing them.
+#[derive(Debug, Default)]
+pub struct TopState {
+    pub running: Vec<(String, Instant)>,
+    pub queued: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub workers: usize,
+}
+
+impl TopState {
+    pub fn new(queued: usize, workers: usize) -> Self {
+        TopState {
+            queued,
+            workers,
+            ..Default::default()
+        }
+    }
+
+    pub fn start(&mut self, case_name: &str) {
+        self.queued = self.queued.saturating_sub(1);
+        self.running.push((case_name.to_string(), Instant::now()));
+    }
+
+    pub fn finish(&mut self, case_name: &str, passed: bool) {
+        self.running.retain(|(name, _)| name != case_name);
+
+        if passed {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TopApp {
+    pub theme: TuiTheme,
+}
+
+impl TopApp {
+    pub fn new(theme: TuiTheme) -> Self {
+        TopApp { theme }
+    }
+
+    pub fn draw(&self, title: &str, state: &TopState, f: &mut Frame) {
+        let chunks = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .split(f.area());
+
+        let title = Block::new()
+            .title_alignment(Alignment::Center)
+            .title(title.to_string().bold());
+        f.render_widget(title, chunks[0]);
+
+        let done = state.passed + state.failed;
+        let total = done + state.running.len() + state.queued;
+
+        f.render_widget(
+            Gauge::default()
+                .block(focus_block(&self.theme, true, BorderType::Rounded))
+                .gauge_style(Style::default().fg(self.theme.accent))
+                .label(format!("{}/{}", done, total))
+                .ratio(done as f64 / total.max(1) as f64),
+            chunks[1],
+        );
+
+        let mut running = state.running.iter().collect::<Vec<_>>();
+        running.sort_by_key(|(_, started)| *started);
+
+        let rows = running.into_iter().map(|(name, started)| {
+            Row::new([name.clone(), format!("{}ms", started.elapsed().as_millis())])
+        });
+
+        f.render_widget(
+            Table::new(
+                rows,
+                [Constraint::Percentage(70), Constraint::Percentage(30)],
+            )
+            .header(Row::new(["running", "elapsed"]).bold())
+            .block(focus_block(&self.theme, false, BorderType::Rounded)),
+            chunks[2],
+        );
+
+        let counts = Line::from(vec![
+            format!(" queued: {} ", state.queued).into(),
+            format!("| running: {} ", state.running.len()).into(),
+            format!("| passed: {} ", state.passed).green(),
+            format!("| failed: {} ", state.failed).red(),
+        ]);
+        f.render_widget(
+            Paragraph::new(counts).alignment(Alignment::Center),
+            chunks[3],
+        );
+    }
+
+    /// Redraws until every worker has exited.
+    pub fn run(self, title: &str, state: &Mutex<TopState>) -> Result<()> {
+        stdout()
+            .execute(EnterAlternateScreen)
+            .map_err(|e| OwlError::TuiError("Failed to enable alt screen".into(), e.to_string()))?;
+
+        let run_result = self.run_view(title, state);
+
+        stdout()
+            .execute(LeaveAlternateScreen)
+            .map_err(|e| OwlError::TuiError("Failed to leave alt screen".into(), e.to_string()))?;
+
+        run_result
+    }
+
+    fn run_view(&self, title: &str, state: &Mutex<TopState>) -> Result<()> {
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))
+            .map_err(|e| OwlError::TuiError("Failed to setup terminal".into(), e.to_string()))?;
+
+        terminal
+            .hide_cursor()
+            .map_err(|e| OwlError::TuiError("Failed to hide cursor".into(), e.to_string()))?;
+
+        let tick_rate = Duration::from_millis(100);
+        let mut last_failed = 0;
+
+        loop {
+            let (failed, workers) = {
+                let state = state.lock().expect("top state lock is not poisoned");
+
+                terminal
+                    .draw(|f| self.draw(title, &state, f))
+                    .map_err(|e| {
+                        OwlError::TuiError("Failed to draw frame".into(), e.to_string())
+                    })?;
+
+                (state.failed, state.workers)
+            };
+
+            if workers == 0 {
+                break;
+            }
+
+            // runtime errors may write to the terminal, so repaint fully after a failure
+            if failed != last_failed {
+                last_failed = failed;
+                terminal.clear().map_err(|e| {
+                    OwlError::TuiError("Failed to clear terminal".into(), e.to_string())
+                })?;
+            }
+
+            std::thread::sleep(tick_rate);
+        }
+
+        terminal
+            .show_cursor()
+            .map_err(|e| OwlError::TuiError("Failed to show cursor".into(), e.to_string()))
+    }
+}
+
+/// What happened when the debugger ran the program on a case.
+#[derive(Debug, Default)]
+pub struct DebugRun {
+    pub passed: bool,
+    pub elapsed: Duration,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// A case as shown by the debugger, with its latest run and whether it is marked to revisit.
+#[derive(Debug, Default)]
+pub struct DebugCase {
+    pub name: String,
+    pub input: String,
+    pub expected: String,
+    pub hidden: bool,
+    pub marked: bool,
+    pub run: Option<DebugRun>,
+}
+
+const DEBUG_PANES: [&str; 5] = ["input", "expected", "stdout", "stderr", "statement"];
+const STATEMENT_PANE: usize = 4;
+
+impl ScrollView for DebugApp {
+    fn scroll_state(&mut self) -> (&mut usize, &mut ScrollbarState) {
+        (
+            &mut self.scrolls[self.focused],
+            &mut self.scroll_states[self.focused],
+        )
+    }
+
+    fn content_length(&self) -> usize {
+        self.content_lengths[self.focused]
+    }
+
+    fn viewport(&self) -> Rect {
+        self.viewports[self.focused]
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DebugApp {
+    pub scroll_states: [ScrollbarState; 5],
+    pub scrolls: [usize; 5],
+    pub content_lengths: [usize; 5],
+    pub viewports: [Rect; 5],
+    pub focused: usize,
+    pub selected: usize,
+    pub status: String,
+    pub statement: Option<String>,
+    pub split: bool,
+    pub keymap: KeyMap,
+    pub theme: TuiTheme,
+}
+
+impl DebugApp {
+    pub fn new(keymap: KeyMap, theme: TuiTheme) -> Self {
+        DebugApp {
+            keymap,
+            theme,
+            ..Default::default()
+        }
+    }
+
+    /// The quest's statement (markdown), shown beside the selected case in the split view.
+    pub fn with_statement(mut self, statement: Option<String>) -> Self {
+        self.statement = statement;
+        self
+    }
+
+    /// Resets the case panes, keeping the statement where it was scrolled to.
+    fn reset_scrolls(&mut self) {
+        for i in 0..STATEMENT_PANE {
+            self.scrolls[i] = 0;
+            self.scroll_states[i] = Default::default();
+        }
+    }
+
+    fn visible_panes(&self) -> &'static [usize] {
+        match self.split {
+            true => &[STATEMENT_PANE, 0, 1],
+            false => &[0, 1, 2, 3],
+        }
+    }
+
+    fn focus_next(&mut self) {
+        let panes = self.visible_panes();
+
+        self.focused = match panes.iter().position(|&pane| pane == self.focused) {
+            Some(i) => panes[(i + 1) % panes.len()],
+            None => panes[0],
+        };
+    }
+
+    /// Draws the case list, and lays out the input, expected, stdout, and stderr panes beside it.
+    fn case_panes<'a>(
+        &self,
+        cases: &'a [DebugCase],
+        area: Rect,
+        f: &mut Frame,
+        input: Text<'a>,
+        expected: Text<'a>,
+    ) -> Vec<(usize, Text<'a>, Rect, String)> {
+        let body = Layout::horizontal([Constraint::Length(28), Constraint::Min(20)]).split(area);
+
+        let items = cases.iter().map(|case| {
+            let (verdict, style) = match &case.run {
+                Some(run) if run.passed => ("✓", Style::default().fg(Color::Green)),
+                Some(_) => ("✗", Style::default().fg(Color::Red)),
+                None => ("·", Style::default()),
+            };
+            let mark = if case.marked { "★" } else { " " };
+
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", verdict), style),
+                format!("{} {}", mark, case.name).into(),
+            ]))
+        });
+
+        let mut list_state = ListState::default().with_selected(Some(self.selected));
+
+        f.render_stateful_widget(
+            List::new(items)
+                .block(focus_block(&self.theme, false, BorderType::Rounded).title(" cases "))
+                .highlight_style(
+                    Style::default()
+                        .bg(self.theme.selection)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            body[0],
+            &mut list_state,
+        );
+
+        let panes = Layout::vertical([
+            Constraint::Percentage(30),
+            Constraint::Percentage(45),
+            Constraint::Percentage(25),
+        ])
+        .split(body[1]);
+        let outputs =
+            Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).split(panes[1]);
+
+        let case = &cases[self.selected];
+
+        let hidden = "Hidden test case.";
+        let (stdout, stderr, run_title) = match &case.run {
+            _ if case.hidden => (hidden, hidden, None),
+            Some(run) => (
+                run.stdout.as_str(),
+                run.stderr.as_str(),
+                Some(format!(
+                    "{}ms, {}",
+                    run.elapsed.as_millis(),
+                    if run.passed { "passed" } else { "failed" }
+                )),
+            ),
+            None => ("", "", None),
+        };
+
+        let expected_lines = case.expected.lines().collect::<Vec<&str>>();
+        let stdout_text = Text::from(
+            stdout
+                .lines()
+                .enumerate()
+                .map(|(i, line)| {
+                    if case.hidden
+                        || expected_lines.get(i).map(|l| l.trim_end()) == Some(line.trim_end())
+                    {
+                        Line::from(line)
+                    } else {
+                        Line::styled(line, Style::default().fg(Color::Red))
+                    }
+                })
+                .collect::<Vec<Line>>(),
+        );
+
+        vec![
+            (
+                0,
+                input,
+                panes[0],
+                format!(" {} · {} ", DEBUG_PANES[0], case.name),
+            ),
+            (1, expected, outputs[0], format!(" {} ", DEBUG_PANES[1])),
+            (
+                2,
+                stdout_text,
+                outputs[1],
+                match run_title {
+                    Some(run_title) => format!(" {} ({}) ", DEBUG_PANES[2], run_title),
+                    None => format!(" {} ", DEBUG_PANES[2]),
+                },
+            ),
+            (
+                3,
+                Text::from(stderr),
+                panes[2],
+                format!(" {} ", DEBUG_PANES[3]),
+            ),
+        ]
+    }
+
+    pub fn draw(&mut self, title: &str, cases: &[DebugCase], f: &mut Frame) {
+        let chunks = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .split(f.area());
+
+        let title = Block::new()
+            .title_alignment(Alignment::Center)
+            .title(title.to_string().bold());
+        f.render_widget(title, chunks[0]);
+
+        self.viewports = Default::default();
+
+        let Some(case) = cases.get(self.selected) else {
+            return;
+        };
+
+        let hidden = "Hidden test case.";
+        let input = Text::from(if case.hidden {
+            hidden
+        } else {
+            case.input.as_str()
+        });
+        let expected = Text::from(if case.hidden {
+            hidden
+        } else {
+            case.expected.as_str()
+        });
+
+        let statement = self.statement.clone().unwrap_or_default();
+
+        let panes = if self.split {
+            let body = Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+                .split(chunks[1]);
+            let case_panes =
+                Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).split(body[1]);
+
+            vec![
+                (
+                    STATEMENT_PANE,
+                    tui_markdown::from_str(&statement, &self.theme),
+                    body[0],
+                    format!(" {} ", DEBUG_PANES[STATEMENT_PANE]),
+                ),
+                (
+                    0,
+                    input,
+                    case_panes[0],
+                    format!(
+                        " {} · {} ({}/{}) ",
+                        DEBUG_PANES[0],
+                        case.name,
+                        self.selected + 1,
+                        cases.len()
+                    ),
+                ),
+                (1, expected, case_panes[1], format!(" {} ", DEBUG_PANES[1])),
+            ]
+        } else {
+            self.case_panes(cases, chunks[1], f, input, expected)
+        };
+
+        for (i, text, area, pane_title) in panes {
+            self.content_lengths[i] = text.lines.len();
+            self.viewports[i] = area;
+            self.scroll_states[i] = self.scroll_states[i].content_length(text.lines.len());
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(text)
+                    .block(
+                        focus_block(&self.theme, self.focused == i, BorderType::Rounded)
+                            .title(pane_title),
+                    )
+                    .scroll((self.scrolls[i] as u16, 0)),
+                area,
+            );
+            f.render_stateful_widget(
+                Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                    .begin_symbol(Some("↑"))
+                    .end_symbol(Some("↓")),
+                area,
+                &mut self.scroll_states[i],
+            );
+        }
+
+        let help = match self.statement {
+            Some(_) => format!(
+                " {} {} step | {} run | {} run all | {} mark | {} statement | {} pane | {} ",
+                self.keymap.label(KeyAction::NavUp),
+                self.keymap.label(KeyAction::NavDown),
+                self.keymap.label(KeyAction::RunCase),
+                self.keymap.label(KeyAction::RunAll),
+                self.keymap.label(KeyAction::ToggleMark),
+                self.keymap.label(KeyAction::ToggleSplit),
+                self.keymap
+                    .label_besides(KeyAction::FocusNext, KeyAction::ToggleSplit),
+                self.keymap.label(KeyAction::Quit),
+            ),
+            None => format!(
+                " {} {} step | {} run | {} run all | {} mark | {} pane | {} ",
+                self.keymap.label(KeyAction::NavUp),
+                self.keymap.label(KeyAction::NavDown),
+                self.keymap.label(KeyAction::RunCase),
+                self.keymap.label(KeyAction::RunAll),
+                self.keymap.label(KeyAction::ToggleMark),
+                self.keymap.label(KeyAction::FocusNext),
+                self.keymap.label(KeyAction::Quit),
+            ),
+        };
+        let helpbar = match self.status.is_empty() {
+            true => Line::from(help.bold()),
+            false => Line::from(vec![help.bold(), format!("· {} ", self.status).italic()]),
+        };
+        f.render_widget(
+            Paragraph::new(helpbar).alignment(Alignment::Center),
+            chunks[2],
+        );
+    }
+
+    /// Steps through `cases`, running one with `run_case` (by index) whenever asked, until quit.
+    /// Marks are left on `cases` for the caller to keep.
+    pub fn run(
+        mut self,
+        title: &str,
+        cases: &mut [DebugCase],
+        mut run_case: impl FnMut(usize) -> DebugRun,
+    ) -> Result<()> {
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))
+            .map_err(|e| OwlError::TuiError("Failed to setup terminal".into(), e.to_string()))?;
+
+        loop {
+            terminal
+                .draw(|f| self.draw(title, cases, f))
+                .map_err(|e| OwlError::TuiError("Failed to draw frame".into(), e.to_string()))?;
+
+            let event = read()
+                .map_err(|e| OwlError::TuiError("Failed to read event".into(), e.to_string()))?;
+
+            let to_run = match event {
+                Event::Key(key) => {
+                    let mut actions = with_pane_actions(
+                        &[
+                            KeyAction::Quit,
+                            KeyAction::RunCase,
+                            KeyAction::RunAll,
+                            KeyAction::ToggleMark,
+                        ],
+                        self.statement.is_some(),
+                    );
+                    actions.extend(NAV_ACTIONS);
+                    actions.extend(SCROLL_ACTIONS);
+
+                    let last = cases.len().saturating_sub(1);
+                    let before = self.selected;
+
+                    let to_run = match self.keymap.action(&key, &actions) {
+                        Some(KeyAction::Quit) => break,
+                        Some(KeyAction::RunCase) => vec![self.selected],
+                        Some(KeyAction::RunAll) => (0..cases.len()).collect(),
+                        Some(KeyAction::ToggleMark) => {
+                            if let Some(case) = cases.get_mut(self.selected) {
+                                case.marked = !case.marked;
+                            }
+                            Vec::new()
+                        }
+                        Some(KeyAction::ToggleSplit) => {
+                            self.split = !self.split;
+                            self.focused = self.visible_panes()[0];
+                            Vec::new()
+                        }
+                        Some(KeyAction::FocusNext) => {
+                            self.focus_next();
+                            Vec::new()
+                        }
+                        Some(KeyAction::NavUp) => {
+                            self.selected = self.selected.saturating_sub(1);
+                            Vec::new()
+                        }
+                        Some(KeyAction::NavDown) => {
+                            self.selected = (self.selected + 1).min(last);
+                            Vec::new()
+                        }
+                        Some(KeyAction::NavFirst) => {
+                            self.selected = 0;
+                            Vec::new()
+                        }
+                        Some(KeyAction::NavLast) => {
+                            self.selected = last;
+                            Vec::new()
+                        }
+                        Some(KeyAction::NavPageUp) => {
+                            self.selected = self.selected.saturating_sub(10);
+                            Vec::new()
+                        }
+                        Some(KeyAction::NavPageDown) => {
+                            self.selected = (self.selected + 10).min(last);
+                            Vec::new()
+                        }
+                        Some(action) => {
+                            self.scroll_with(action);
+                            Vec::new()
+                        }
+                        None => Vec::new(),
+                    };
+
+                    if self.selected != before {
+                        self.reset_scrolls();
+                    }
+
+                    to_run
+                }
+                Event::Mouse(mouse) => {
+                    let position = Position::new(mouse.column, mouse.row);
+
+                    if let Some(pane) = self
+                        .viewports
+                        .iter()
+                        .position(|viewport| viewport.contains(position))
+                    {
+                        self.focused = pane;
+                        self.scroll_with_mouse(&mouse);
+                    }
+
+                    Vec::new()
+                }
+                _ => Vec::new(),
+            };
+
+            let total = to_run.len();
+
+            for (n, i) in to_run.into_iter().enumerate() {
+                self.status = match total {
+                    1 => format!("running {}…", cases[i].name),
+                    _ => format!("running {}/{}: {}…", n + 1, total, cases[i].name),
+                };
+
+                terminal.draw(|f| self.draw(title, cases, f)).map_err(|e| {
+                    OwlError::TuiError("Failed to draw frame".into(), e.to_string())
+                })?;
+
+                cases[i].run = Some(run_case(i));
+            }
+
+            if total > 0 {
+                let passed = cases
+                    .iter()
+                    .filter(|case| case.run.as_ref().is_some_and(|run| run.passed))
+                    .count();
+                let ran = cases.iter().filter(|case| case.run.is_some()).count();
+
+                self.status = format!("{}/{} run cases passed", passed, ran);
+                self.reset_scrolls();
+            }
+        }
+
+        Ok(())
+    }
+}